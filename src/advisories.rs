@@ -0,0 +1,193 @@
+//! Sun protection and hydration advisories.
+//!
+//! UV index, daily highs and humidity are combined into a few standard lines
+//! appended to the block summary. UV and humidity come from an extra
+//! Open‑Meteo forecast request; historic proxies have neither, so only the
+//! temperature-based advice applies to them.
+
+use crate::variables::{self, Endpoint};
+use crate::{DayTemp, Units, celcius_to_farenheit, farenheit_to_celcius, fmt_temp, http};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Per-day values the advisories need beyond temperature.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Exposure {
+    pub uv_index: Option<f64>,
+    /// Mean relative humidity, percent.
+    pub humidity: Option<f64>,
+}
+
+/// When advisories kick in. Temperatures are °C.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Thresholds {
+    /// UV index at which to advise sunscreen ("high" on the WHO scale).
+    pub uv: f64,
+    /// Daily high at which to advise carrying water.
+    pub hot: f64,
+    /// Heat index at which to advise carrying water even below `hot`.
+    pub heat_index: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds {
+            uv: 6.0,
+            hot: 30.0,
+            heat_index: 32.0,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Parse `uv=6, hot=30, heat-index=32`; omitted keys keep their default
+    /// and temperatures accept an `F` suffix.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut t = Thresholds::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected key=value, got '{pair}'"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let temperature = key != "uv";
+            let number = crate::packing::parse_value(value, temperature)
+                .ok_or_else(|| anyhow!("bad number '{value}' for {key}"))?;
+            match key {
+                "uv" => t.uv = number,
+                "hot" => t.hot = number,
+                "heat-index" => t.heat_index = number,
+                other => {
+                    return Err(anyhow!(
+                        "unknown threshold '{other}' (expected uv, hot or heat-index)"
+                    ));
+                }
+            }
+        }
+        Ok(t)
+    }
+
+    /// Canonical form, as accepted by [`Thresholds::parse`].
+    pub fn name(&self) -> String {
+        format!(
+            "uv={}, hot={}, heat-index={}",
+            self.uv, self.hot, self.heat_index
+        )
+    }
+}
+
+/// NWS heat index (Rothfusz regression with Steadman's simple formula below
+/// 80°F), in °C.
+pub fn heat_index(temp_c: f64, humidity: f64) -> f64 {
+    let t = celcius_to_farenheit(temp_c);
+    let rh = humidity;
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return farenheit_to_celcius(simple);
+    }
+    let hi = -42.379 + 2.04901523 * t + 10.14333127 * rh
+        - 0.22475541 * t * rh
+        - 0.00683783 * t * t
+        - 0.05481717 * rh * rh
+        + 0.00122874 * t * t * rh
+        + 0.00085282 * t * rh * rh
+        - 0.00000199 * t * t * rh * rh;
+    farenheit_to_celcius(hi)
+}
+
+/// Advisory lines for the trip, in the order UV, then heat.
+pub fn advisories(
+    data: &[DayTemp],
+    exposure: &BTreeMap<NaiveDate, Exposure>,
+    thresholds: &Thresholds,
+    units: Units,
+) -> Vec<String> {
+    let show = |c: f64| match units {
+        Units::Celsius => format!("{}°C", fmt_temp(c)),
+        Units::Fahrenheit => format!("{}°F", fmt_temp(celcius_to_farenheit(c))),
+    };
+    let mut lines = Vec::new();
+    let uv = exposure
+        .values()
+        .filter_map(|e| e.uv_index)
+        .reduce(f64::max);
+    if let Some(uv) = uv
+        && uv >= thresholds.uv
+    {
+        lines.push(format!(
+            "**Advisory**: UV index up to {} — use SPF 30+ and reapply sunscreen every 2 hours",
+            fmt_temp(uv)
+        ));
+    }
+    let Some(high) = data.iter().map(|d| d.tmax).reduce(f64::max) else {
+        return lines;
+    };
+    let feels = data
+        .iter()
+        .filter_map(|d| Some(heat_index(d.tmax, exposure.get(&d.date)?.humidity?)))
+        .reduce(f64::max);
+    if high >= thresholds.hot || feels.is_some_and(|f| f >= thresholds.heat_index) {
+        let feels = feels
+            .filter(|f| f.round() > high.round())
+            .map(|f| format!(" (feels like {})", show(f)))
+            .unwrap_or_default();
+        lines.push(format!(
+            "**Advisory**: highs up to {}{feels} — carry 2 L of water per person per day",
+            show(high)
+        ));
+    }
+    lines
+}
+
+#[derive(Deserialize, Debug)]
+struct ExposureResp {
+    daily: Option<ExposureDaily>,
+}
+#[derive(Deserialize, Debug)]
+struct ExposureDaily {
+    time: Vec<NaiveDate>,
+    #[serde(default)]
+    uv_index_max: Vec<Option<f64>>,
+    #[serde(default)]
+    relative_humidity_2m_mean: Vec<Option<f64>>,
+}
+
+/// UV index and humidity for `start..=end` from the Open‑Meteo forecast API.
+pub async fn fetch_exposure(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &str,
+) -> Result<BTreeMap<NaiveDate, Exposure>> {
+    let url = format!(
+        "{}/forecast?latitude={lat}&longitude={lon}&daily={}&start_date={start}&end_date={end}&timezone={}",
+        crate::forecast_base(),
+        variables::daily_param(
+            &["uv_index_max", "relative_humidity_2m_mean"],
+            Endpoint::Forecast
+        ),
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let resp: ExposureResp = client.get_json(&url).await?;
+    let Some(daily) = resp.daily else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(daily
+        .time
+        .iter()
+        .enumerate()
+        .map(|(i, date)| {
+            let at = |v: &Vec<Option<f64>>| v.get(i).copied().flatten();
+            (
+                *date,
+                Exposure {
+                    uv_index: at(&daily.uv_index_max),
+                    humidity: at(&daily.relative_humidity_2m_mean),
+                },
+            )
+        })
+        .collect())
+}
@@ -0,0 +1,46 @@
+//! IATA/ICAO airport code resolution from a bundled dataset.
+//!
+//! Flight-centric notes often only carry an airport code (`city: FCO` or
+//! `airport: LIRF`). The dataset in `data/airports.csv` covers Italian
+//! airports and major international hubs; codes resolve without a network
+//! call.
+
+/// A bundled airport record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Airport {
+    pub iata: &'static str,
+    pub icao: &'static str,
+    pub name: &'static str,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: &'static str,
+}
+
+const AIRPORTS_CSV: &str = include_str!("../data/airports.csv");
+
+/// Whether `s` has the shape of an IATA (3 letters) or ICAO (4 letters) code.
+pub fn looks_like_code(s: &str) -> bool {
+    matches!(s.len(), 3 | 4) && s.chars().all(|c| c.is_ascii_uppercase())
+}
+
+/// Look up an airport by IATA or ICAO code (case-insensitive).
+pub fn lookup(code: &str) -> Option<Airport> {
+    let code = code.trim().to_ascii_uppercase();
+    AIRPORTS_CSV
+        .lines()
+        .skip(1)
+        .filter_map(parse_row)
+        .find(|a| a.iata == code || a.icao == code)
+}
+
+fn parse_row(line: &'static str) -> Option<Airport> {
+    let mut cols = line.split(',');
+    Some(Airport {
+        iata: cols.next()?,
+        icao: cols.next()?,
+        name: cols.next()?,
+        latitude: cols.next()?.parse().ok()?,
+        longitude: cols.next()?.parse().ok()?,
+        timezone: cols.next()?,
+    })
+}
@@ -0,0 +1,339 @@
+//! User-defined weather alerts.
+//!
+//! `alert-if: tmax_f > 95, rain_prob > 60` (in the config or a note's
+//! frontmatter) is a list of rules, any one of which flags a day. Rules are
+//! checked against the forecast days of each note; days that cross one get
+//! a banner at the top of the block, a line in the end-of-run summary and,
+//! with `on-alert`, a notification command. Past days and last year's proxy
+//! are never flagged: there is nothing left to act on.
+
+use crate::variables::{self, Endpoint};
+use crate::{DayTemp, http};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+/// `95`, `97.2`: whole numbers without decimals, others with one.
+fn number(v: f64) -> String {
+    match v.fract() == 0.0 {
+        true => format!("{v:.0}"),
+        false => format!("{v:.1}"),
+    }
+}
+
+/// A daily value a rule can test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Variable {
+    /// Daily high, °C.
+    Tmax,
+    /// Daily low, °C.
+    Tmin,
+    /// Daily high, °F.
+    TmaxF,
+    /// Daily low, °F.
+    TminF,
+    /// Highest chance of precipitation, percent.
+    RainProb,
+    /// Highest UV index.
+    Uv,
+    /// Mean relative humidity, percent.
+    Humidity,
+}
+
+impl Variable {
+    const ALL: [Variable; 7] = [
+        Variable::Tmax,
+        Variable::Tmin,
+        Variable::TmaxF,
+        Variable::TminF,
+        Variable::RainProb,
+        Variable::Uv,
+        Variable::Humidity,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variable::Tmax => "tmax",
+            Variable::Tmin => "tmin",
+            Variable::TmaxF => "tmax_f",
+            Variable::TminF => "tmin_f",
+            Variable::RainProb => "rain_prob",
+            Variable::Uv => "uv",
+            Variable::Humidity => "humidity",
+        }
+    }
+
+    /// The Open‑Meteo daily variable for values not in the temperature
+    /// table.
+    fn daily(self) -> Option<&'static str> {
+        match self {
+            Variable::RainProb => Some("precipitation_probability_max"),
+            Variable::Uv => Some("uv_index_max"),
+            Variable::Humidity => Some("relative_humidity_2m_mean"),
+            _ => None,
+        }
+    }
+
+    fn of_day(self, day: &DayTemp, extras: Option<&BTreeMap<Variable, f64>>) -> Option<f64> {
+        match self {
+            Variable::Tmax => Some(day.tmax),
+            Variable::Tmin => Some(day.tmin),
+            Variable::TmaxF => Some(day.tmax_f),
+            Variable::TminF => Some(day.tmin_f),
+            _ => extras?.get(&self).copied(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Above => ">",
+            Op::AtLeast => ">=",
+            Op::Below => "<",
+            Op::AtMost => "<=",
+        }
+    }
+
+    fn holds(self, value: f64, limit: f64) -> bool {
+        match self {
+            Op::Above => value > limit,
+            Op::AtLeast => value >= limit,
+            Op::Below => value < limit,
+            Op::AtMost => value <= limit,
+        }
+    }
+}
+
+/// One `variable op limit` comparison.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rule {
+    pub variable: Variable,
+    pub op: Op,
+    pub limit: f64,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.variable.name(),
+            self.op.symbol(),
+            number(self.limit)
+        )
+    }
+}
+
+impl Rule {
+    fn parse(s: &str) -> Result<Self> {
+        let at = s
+            .find(['<', '>'])
+            .ok_or_else(|| anyhow!("expected e.g. 'tmax_f > 95', got '{s}'"))?;
+        let (name, rest) = s.split_at(at);
+        let (op, limit) = match rest.split_at(1) {
+            (">", l) if l.starts_with('=') => (Op::AtLeast, &l[1..]),
+            ("<", l) if l.starts_with('=') => (Op::AtMost, &l[1..]),
+            (">", l) => (Op::Above, l),
+            (_, l) => (Op::Below, l),
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let variable = Variable::ALL
+            .into_iter()
+            .find(|v| v.name() == name)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Variable::ALL.iter().map(|v| v.name()).collect();
+                anyhow!(
+                    "unknown alert variable '{name}' (expected {})",
+                    known.join(", ")
+                )
+            })?;
+        let limit = limit
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("bad number '{}' in alert '{s}'", limit.trim()))?;
+        Ok(Rule {
+            variable,
+            op,
+            limit,
+        })
+    }
+}
+
+/// The rules of one `alert-if` setting; a day alerts if any rule holds.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rules(pub Vec<Rule>);
+
+impl Rules {
+    /// Parse `tmax_f > 95, rain_prob >= 60`.
+    pub fn parse(s: &str) -> Result<Self> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(Rule::parse)
+            .collect::<Result<_>>()
+            .map(Rules)
+    }
+
+    /// Canonical form, as accepted by [`Rules::parse`].
+    pub fn name(&self) -> String {
+        let rules: Vec<String> = self.0.iter().map(Rule::to_string).collect();
+        rules.join(", ")
+    }
+
+    /// Open‑Meteo daily variables the rules need beyond temperatures.
+    fn extra_variables(&self) -> Vec<Variable> {
+        let mut vars: Vec<Variable> = self
+            .0
+            .iter()
+            .map(|r| r.variable)
+            .filter(|v| v.daily().is_some())
+            .collect();
+        vars.sort();
+        vars.dedup();
+        vars
+    }
+}
+
+/// A day that crossed a rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alert {
+    pub date: NaiveDate,
+    pub rule: Rule,
+    /// The day's value of the rule's variable.
+    pub value: f64,
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.date,
+            self.rule.variable.name(),
+            number(self.value),
+            self.rule.op.symbol(),
+            number(self.rule.limit)
+        )
+    }
+}
+
+/// Per-day values that are not in the temperature table.
+pub type Extras = BTreeMap<NaiveDate, BTreeMap<Variable, f64>>;
+
+/// Every rule each day of `data` crosses, in date then rule order.
+pub fn evaluate(rules: &Rules, data: &[DayTemp], extras: &Extras) -> Vec<Alert> {
+    let mut out = Vec::new();
+    for day in data {
+        for rule in &rules.0 {
+            let Some(value) = rule.variable.of_day(day, extras.get(&day.date)) else {
+                continue;
+            };
+            if rule.op.holds(value, rule.limit) {
+                out.push(Alert {
+                    date: day.date,
+                    rule: *rule,
+                    value,
+                });
+            }
+        }
+    }
+    out
+}
+
+/// The line at the top of the block, if any day alerts.
+pub fn banner(alerts: &[Alert]) -> Option<String> {
+    if alerts.is_empty() {
+        return None;
+    }
+    let list: Vec<String> = alerts.iter().map(Alert::to_string).collect();
+    Some(format!("**Alert**: {}", list.join("; ")))
+}
+
+/// The values `rules` need beyond temperatures for `start..=end`, from the
+/// Open‑Meteo forecast API. Nothing is fetched if no rule needs one.
+pub async fn fetch_extras(
+    client: &http::Client,
+    rules: &Rules,
+    (lat, lon): (f64, f64),
+    (start, end): (NaiveDate, NaiveDate),
+    tz: &str,
+) -> Result<Extras> {
+    let vars = rules.extra_variables();
+    if vars.is_empty() {
+        return Ok(Extras::new());
+    }
+    let names: Vec<&str> = vars.iter().filter_map(|v| v.daily()).collect();
+    let url = format!(
+        "{}/forecast?latitude={lat}&longitude={lon}&daily={}&start_date={start}&end_date={end}&timezone={}",
+        crate::forecast_base(),
+        variables::daily_param(&names, Endpoint::Forecast),
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let resp: Value = client.get_json(&url).await?;
+    let daily = &resp["daily"];
+    let dates = daily["time"].as_array().cloned().unwrap_or_default();
+    let mut out = Extras::new();
+    for (i, date) in dates.iter().enumerate() {
+        let Some(date) = date.as_str().and_then(|d| d.parse().ok()) else {
+            continue;
+        };
+        let values = vars
+            .iter()
+            .filter_map(|v| Some((*v, daily[v.daily()?][i].as_f64()?)))
+            .collect();
+        out.insert(date, values);
+    }
+    Ok(out)
+}
+
+static ALERTS: Mutex<Vec<(String, Vec<Alert>)>> = Mutex::new(Vec::new());
+
+/// Remember `alerts` for `note` for the end-of-run summary.
+pub fn record(note: &str, alerts: &[Alert]) {
+    if !alerts.is_empty() {
+        ALERTS
+            .lock()
+            .unwrap()
+            .push((note.to_string(), alerts.to_vec()));
+    }
+}
+
+/// Every note's alerts so far in this run, in the order they were recorded.
+pub fn snapshot() -> Vec<(String, Vec<Alert>)> {
+    ALERTS.lock().unwrap().clone()
+}
+
+/// Forget earlier runs' alerts, for a process that runs more than once.
+pub fn clear() {
+    ALERTS.lock().unwrap().clear();
+}
+
+/// The end-of-run summary, empty if nothing alerted.
+///
+/// ```text
+/// Alerts:
+///   Trips/Rome.md: 2025-08-21 tmax_f 97.2 > 95
+/// ```
+pub fn summary(alerts: &[(String, Vec<Alert>)]) -> String {
+    if alerts.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("Alerts:\n");
+    for (note, list) in alerts {
+        let list: Vec<String> = list.iter().map(Alert::to_string).collect();
+        out.push_str(&format!("  {note}: {}\n", list.join("; ")));
+    }
+    out
+}
@@ -0,0 +1,167 @@
+//! API request budgets.
+//!
+//! Every HTTP request is counted per service (Open‑Meteo, NWS, Nominatim,
+//! ...) for the run, and each note's requests are counted against its city.
+//! At the end of a run the per-service counts are added to a per-day total
+//! kept in `$XDG_STATE_HOME/weather-helper/usage.json`, so the warning about
+//! approaching a free-tier limit accounts for earlier runs the same day.
+
+use crate::io;
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Free-tier daily request limits of services that publish one.
+pub const DAILY_LIMITS: &[(&str, u64)] = &[("open-meteo", 10_000), ("visual-crossing", 1_000)];
+
+/// Share of a daily limit at which to warn.
+pub const WARN_AT: f64 = 0.8;
+
+static RUN_CALLS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+static CITY_CALLS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+static TODAY_CALLS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// Which service a request URL belongs to; unknown hosts count as themselves.
+pub fn service(url: &str) -> String {
+    let host = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .split(['/', '?'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let known = [
+        ("open-meteo", "open-meteo"),
+        ("weather.gov", "nws"),
+        ("met.no", "met-norway"),
+        ("visualcrossing", "visual-crossing"),
+        ("nominatim", "nominatim"),
+        ("photon", "photon"),
+        ("nager", "nager"),
+        ("staticmap", "static-map"),
+    ];
+    known
+        .iter()
+        .find(|(needle, _)| host.contains(needle))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or(host)
+}
+
+/// Count one request to `url`.
+pub fn record(url: &str) {
+    *RUN_CALLS.lock().unwrap().entry(service(url)).or_default() += 1;
+}
+
+/// Count `calls` requests made for a note about `city`.
+pub fn record_city(city: &str, calls: u64) {
+    if calls > 0 {
+        *CITY_CALLS
+            .lock()
+            .unwrap()
+            .entry(city.to_string())
+            .or_default() += calls;
+    }
+}
+
+/// Requests per service so far in this run.
+pub fn run_calls() -> BTreeMap<String, u64> {
+    RUN_CALLS.lock().unwrap().clone()
+}
+
+/// Requests per city so far in this run.
+pub fn city_calls() -> BTreeMap<String, u64> {
+    CITY_CALLS.lock().unwrap().clone()
+}
+
+/// Today's persisted totals per service, as of the last [`finish`].
+pub fn today_calls() -> BTreeMap<String, u64> {
+    TODAY_CALLS.lock().unwrap().clone()
+}
+
+/// Requests per service on one day, across runs.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: Option<NaiveDate>,
+    #[serde(default)]
+    pub calls: BTreeMap<String, u64>,
+}
+
+impl DailyUsage {
+    /// The saved usage for `today`; a missing, unreadable or older file
+    /// starts the day at zero.
+    pub fn load(path: &Path, today: NaiveDate) -> DailyUsage {
+        let saved: DailyUsage = io::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        if saved.date == Some(today) {
+            saved
+        } else {
+            DailyUsage {
+                date: Some(today),
+                calls: BTreeMap::new(),
+            }
+        }
+    }
+
+    pub fn add(&mut self, calls: &BTreeMap<String, u64>) {
+        for (service, n) in calls {
+            *self.calls.entry(service.clone()).or_default() += n;
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            io::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+        }
+        io::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("cannot write {}", path.display()))
+    }
+
+    /// One warning per service at or past [`WARN_AT`] of its daily limit.
+    pub fn warnings(&self) -> Vec<String> {
+        DAILY_LIMITS
+            .iter()
+            .filter_map(|(service, limit)| {
+                let used = *self.calls.get(*service)?;
+                (used as f64 >= *limit as f64 * WARN_AT)
+                    .then(|| format!("{service}: {used} of {limit} free daily requests used today"))
+            })
+            .collect()
+    }
+}
+
+/// `$WEATHER_HELPER_USAGE_FILE`, or `$XDG_STATE_HOME/weather-helper/usage.json`
+/// falling back to `~/.local/state`.
+pub fn usage_path() -> PathBuf {
+    if let Some(path) = env::var_os("WEATHER_HELPER_USAGE_FILE") {
+        return PathBuf::from(path);
+    }
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("weather-helper")
+        .join("usage.json")
+}
+
+/// Add this run's requests to the day's saved totals and return them.
+pub fn finish(today: NaiveDate) -> Result<DailyUsage> {
+    let path = usage_path();
+    let mut usage = DailyUsage::load(&path, today);
+    usage.add(&run_calls());
+    usage.save(&path)?;
+    *TODAY_CALLS.lock().unwrap() = usage.calls.clone();
+    Ok(usage)
+}
+
+/// Run report line, e.g. `API requests: 14 (open-meteo 12, nominatim 2)`.
+pub fn report(calls: &BTreeMap<String, u64>) -> String {
+    let total: u64 = calls.values().sum();
+    let parts: Vec<String> = calls.iter().map(|(s, n)| format!("{s} {n}")).collect();
+    format!("API requests: {total} ({})", parts.join(", "))
+}
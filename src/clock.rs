@@ -0,0 +1,85 @@
+//! Where "now" and "today" come from.
+//!
+//! Range planning, the choice of notes a run picks, fetch stamps and the age
+//! shown on stale data all ask a [`Clock`] rather than the system time, so
+//! tests and simulations can run at any instant in any zone, DST
+//! transitions included. [`SystemClock`] is the default; set
+//! [`crate::RunOptions::clock`] to a [`FixedClock`] to freeze time.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::fmt;
+
+/// A source of the current time and the zone local times are in.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// The current instant.
+    fn now_utc(&self) -> DateTime<Utc>;
+
+    /// The zone "today" and local timestamps are in; `None` is the
+    /// system's local zone.
+    fn zone(&self) -> Option<Tz>;
+
+    /// The current time in [`Clock::zone`].
+    fn now(&self) -> DateTime<FixedOffset> {
+        in_zone(self.now_utc(), self.zone())
+    }
+
+    /// The current date in [`Clock::zone`].
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
+
+/// `at` in `zone`, or in the system's local zone for `None`.
+pub fn in_zone(at: DateTime<Utc>, zone: Option<Tz>) -> DateTime<FixedOffset> {
+    match zone {
+        Some(zone) => at.with_timezone(&zone).fixed_offset(),
+        None => at.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// The system clock in the system's local zone.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn zone(&self) -> Option<Tz> {
+        None
+    }
+}
+
+/// A clock stopped at one instant.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedClock {
+    pub at: DateTime<Utc>,
+    /// Zone for local times; `None` is the system's local zone.
+    pub zone: Option<Tz>,
+}
+
+impl FixedClock {
+    pub fn new(at: DateTime<Utc>, zone: Option<Tz>) -> Self {
+        FixedClock { at, zone }
+    }
+
+    /// Stopped at wall-clock `time` on `date` in `zone`. An ambiguous time
+    /// (clocks going back) is its first occurrence; a time skipped when
+    /// clocks go forward is `None`.
+    pub fn at_local(date: NaiveDate, time: NaiveTime, zone: Tz) -> Option<Self> {
+        let local = zone.from_local_datetime(&date.and_time(time)).earliest()?;
+        Some(FixedClock::new(local.with_timezone(&Utc), Some(zone)))
+    }
+}
+
+impl Clock for FixedClock {
+    fn now_utc(&self) -> DateTime<Utc> {
+        self.at
+    }
+
+    fn zone(&self) -> Option<Tz> {
+        self.zone
+    }
+}
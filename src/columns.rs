@@ -0,0 +1,295 @@
+//! Extra daily variables for the day table.
+//!
+//! `weather-vars: [precipitation_sum, wind_speed_10m_max]` in a note (or
+//! `vars:` in the config) asks for those daily variables alongside the
+//! temperatures.
+//!
+//! Precipitation, rain chance, wind and UV are [`Measure`]s: they have
+//! short names (`weather-vars: [rain, wind, uv]`), are kept on each
+//! [`DayTemp`], get readable headers (`Rain (mm)`) in every table style and
+//! a part in the summary line (`Rain 12 mm in total • Wind up to 35 km/h`).
+//!
+//! Any other variable becomes a [`Column`], which
+//! [`crate::render_table_columns`] adds to the Markdown day table, headed
+//! by the variable's name and the unit the API reports:
+//!
+//! ```text
+//! | Date | High (°F) | Low (°F) | High (°C) | Low (°C) | wind_gusts_10m_max (km/h) |
+//! ```
+//!
+//! Names are passed to the API as written (older aliases are translated,
+//! see [`variables`]); a variable the endpoint does not have is left empty.
+//! Other table styles only carry the measures.
+
+use crate::variables::{self, Endpoint};
+use crate::{DayTemp, http};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// A daily variable with its own [`DayTemp`] field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Measure {
+    /// `precipitation_sum`, summed over the trip.
+    Rain,
+    /// `precipitation_probability_max`.
+    RainChance,
+    /// `wind_speed_10m_max`.
+    Wind,
+    /// `uv_index_max`.
+    Uv,
+}
+
+impl Measure {
+    /// Table and summary order.
+    pub const ALL: [Measure; 4] = [
+        Measure::Rain,
+        Measure::RainChance,
+        Measure::Wind,
+        Measure::Uv,
+    ];
+
+    /// Name accepted in `weather-vars`.
+    pub fn short(self) -> &'static str {
+        match self {
+            Measure::Rain => "rain",
+            Measure::RainChance => "rain-chance",
+            Measure::Wind => "wind",
+            Measure::Uv => "uv",
+        }
+    }
+
+    /// The Open‑Meteo daily variable.
+    pub fn variable(self) -> &'static str {
+        match self {
+            Measure::Rain => "precipitation_sum",
+            Measure::RainChance => "precipitation_probability_max",
+            Measure::Wind => "wind_speed_10m_max",
+            Measure::Uv => "uv_index_max",
+        }
+    }
+
+    pub fn from_variable(name: &str) -> Option<Self> {
+        Measure::ALL.into_iter().find(|m| m.variable() == name)
+    }
+
+    /// Unit of the values, as Open‑Meteo reports them by default.
+    pub fn unit(self) -> &'static str {
+        match self {
+            Measure::Rain => "mm",
+            Measure::RainChance => "%",
+            Measure::Wind => "km/h",
+            Measure::Uv => "",
+        }
+    }
+
+    /// `Rain (mm)`.
+    pub fn header(self) -> String {
+        let label = match self {
+            Measure::Rain => "Rain",
+            Measure::RainChance => "Rain chance",
+            Measure::Wind => "Wind",
+            Measure::Uv => "UV",
+        };
+        match self.unit() {
+            "" => label.to_string(),
+            unit => format!("{label} ({unit})"),
+        }
+    }
+
+    /// Shortcode and `data-` attribute name, e.g. `rain-mm`.
+    pub fn attribute(self) -> &'static str {
+        match self {
+            Measure::Rain => "rain-mm",
+            Measure::RainChance => "rain-chance",
+            Measure::Wind => "wind-kmh",
+            Measure::Uv => "uv",
+        }
+    }
+
+    /// Whether the summary reports the trip's total rather than its maximum.
+    pub fn is_total(self) -> bool {
+        self == Measure::Rain
+    }
+
+    pub fn of(self, day: &DayTemp) -> Option<f64> {
+        match self {
+            Measure::Rain => day.precipitation,
+            Measure::RainChance => day.precipitation_probability,
+            Measure::Wind => day.wind_speed,
+            Measure::Uv => day.uv_index,
+        }
+    }
+
+    fn slot(self, day: &mut DayTemp) -> &mut Option<f64> {
+        match self {
+            Measure::Rain => &mut day.precipitation,
+            Measure::RainChance => &mut day.precipitation_probability,
+            Measure::Wind => &mut day.wind_speed,
+            Measure::Uv => &mut day.uv_index,
+        }
+    }
+
+    /// `12.5 mm`, `85%` or `8`.
+    pub fn format(self, value: f64) -> String {
+        match self.unit() {
+            "" | "%" => format!("{}{}", number(value), self.unit()),
+            unit => format!("{} {unit}", number(value)),
+        }
+    }
+
+    /// The trip's total or maximum over the days that have a value.
+    pub fn over(self, data: &[DayTemp]) -> Option<f64> {
+        let values = data.iter().filter_map(|d| self.of(d));
+        match self.is_total() {
+            true => values
+                .reduce(|a, b| a + b)
+                .map(|sum| (sum * 10.0).round() / 10.0),
+            false => values.reduce(f64::max),
+        }
+    }
+}
+
+/// The measures any day in `data` has a value for, in [`Measure::ALL`] order.
+pub fn measures(data: &[DayTemp]) -> Vec<Measure> {
+    Measure::ALL
+        .into_iter()
+        .filter(|m| data.iter().any(|d| m.of(d).is_some()))
+        .collect()
+}
+
+/// Move the measures among `columns` onto the days of `data`, returning the
+/// other columns.
+pub fn merge(data: &mut [DayTemp], columns: Vec<Column>) -> Vec<Column> {
+    let (measured, rest): (Vec<Column>, Vec<Column>) = columns
+        .into_iter()
+        .partition(|c| Measure::from_variable(&c.name).is_some());
+    for column in measured {
+        let Some(measure) = Measure::from_variable(&column.name) else {
+            continue;
+        };
+        for day in data.iter_mut() {
+            *measure.slot(day) = column.values.get(&day.date).copied();
+        }
+    }
+    rest
+}
+
+/// `12.5` or `12`.
+pub(crate) fn number(v: f64) -> String {
+    match v.fract() == 0.0 {
+        true => format!("{v:.0}"),
+        false => format!("{v:.1}"),
+    }
+}
+
+/// One requested variable's values by day.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Column {
+    /// The variable as requested, e.g. `snowfall_sum`.
+    pub name: String,
+    /// Unit from the response's `daily_units`, e.g. `cm`.
+    pub unit: Option<String>,
+    pub values: BTreeMap<NaiveDate, f64>,
+}
+
+impl Column {
+    /// `snowfall_sum (cm)`.
+    pub fn header(&self) -> String {
+        match self.unit.as_deref().filter(|u| !u.is_empty()) {
+            Some(unit) => format!("{} ({unit})", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The day's value with at most one decimal, empty if there is none.
+    pub fn cell(&self, date: NaiveDate) -> String {
+        self.values.get(&date).map_or(String::new(), |v| number(*v))
+    }
+}
+
+/// Parse `precipitation_sum, wind_speed_10m_max` or `rain, wind`; short
+/// names become the Open‑Meteo variables, duplicates are dropped and an
+/// empty list requests nothing.
+pub fn parse_vars(s: &str) -> Result<Vec<String>> {
+    let mut vars: Vec<String> = Vec::new();
+    for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        let name = Measure::ALL
+            .into_iter()
+            .find(|m| name.eq_ignore_ascii_case(m.short()))
+            .map_or(name, |m| m.variable());
+        let valid = name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(anyhow!(
+                "'{name}' is not an Open-Meteo daily variable name (like precipitation_sum)"
+            ));
+        }
+        if !vars.iter().any(|v| v == name) {
+            vars.push(name.to_string());
+        }
+    }
+    Ok(vars)
+}
+
+/// Fetch `names` for `start..=end` from the forecast API, or the ERA5
+/// archive when the table is last year's proxy.
+pub async fn fetch(
+    client: &http::Client,
+    names: &[String],
+    (lat, lon): (f64, f64),
+    (start, end): (NaiveDate, NaiveDate),
+    tz: &str,
+    forecast: bool,
+) -> Result<Vec<Column>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (endpoint, source) = match forecast {
+        true => (
+            format!("{}/forecast", crate::forecast_base()),
+            Endpoint::Forecast,
+        ),
+        false => (format!("{}/era5", crate::archive_base()), Endpoint::Archive),
+    };
+    let requested: Vec<&str> = names.iter().map(String::as_str).collect();
+    let url = format!(
+        "{endpoint}?latitude={lat}&longitude={lon}&daily={}&start_date={start}&end_date={end}&timezone={}",
+        variables::daily_param(&requested, source),
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let mut resp: Value = client.get_json(&url).await?;
+    let translation = variables::translate(&requested, source);
+    let units: BTreeMap<&str, String> = translation
+        .names
+        .iter()
+        .filter_map(|(sent, name)| {
+            let unit = resp["daily_units"][sent].as_str()?;
+            Some((name.as_str(), unit.to_string()))
+        })
+        .collect();
+    variables::restore_names(&mut resp, &translation);
+    let daily = &resp["daily"];
+    let dates: Vec<Option<NaiveDate>> = daily["time"]
+        .as_array()
+        .map(|days| {
+            days.iter()
+                .map(|d| d.as_str().and_then(|d| d.parse().ok()))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(names
+        .iter()
+        .map(|name| Column {
+            name: name.clone(),
+            unit: units.get(name.as_str()).cloned(),
+            values: dates
+                .iter()
+                .enumerate()
+                .filter_map(|(i, date)| Some(((*date)?, daily[name][i].as_f64()?)))
+                .collect(),
+        })
+        .collect())
+}
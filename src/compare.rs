@@ -0,0 +1,165 @@
+//! Weather for destinations still being chosen between.
+//!
+//! A note with `candidates: [Lisbon, Porto]` and no `city` gets a
+//! comparison instead of the usual table: one row per trip day with each
+//! candidate side by side, then each candidate's average high and number
+//! of wet days. Candidates are written like `route:` stops (names,
+//! `"lat, lon"` or mappings) and fetched the same way, all at once. Once the
+//! note sets `city`, the candidates are ignored and the normal block
+//! replaces the comparison.
+
+use crate::range_planner::{self, FetchKind};
+use crate::roadtrip::RoadDay;
+use crate::route::{self, Stop};
+use crate::{
+    NoteMeta, RunOptions, Units, celcius_to_farenheit, fmt_temp, hooks, http, markers, outline,
+    stale, upsert_weather_block_under, width,
+};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Each candidate's days, in `candidates:` order.
+pub type Candidates = Vec<(Stop, Vec<RoadDay>)>;
+
+/// Whether a WMO weather code brings rain, snow or storms.
+fn is_wet(code: u8) -> bool {
+    matches!(code, 51..=67 | 71..=77 | 80..=86 | 95..=99)
+}
+
+/// Geocode and fetch every candidate for the trip. Returns the block's
+/// header line with the weather.
+pub async fn fetch(
+    meta: &NoteMeta,
+    client: &http::Client,
+    opts: &RunOptions,
+) -> Result<(String, Candidates)> {
+    let today = opts.today();
+    let ranges = range_planner::plan_ranges(
+        meta.arrival,
+        meta.departure,
+        today,
+        range_planner::horizon(today),
+    );
+    let (s, e) = match (ranges.first(), ranges.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return Err(anyhow!("no dates to fetch for the trip")),
+    };
+    let has = |kind| ranges.iter().any(|r| r.kind == kind);
+    let kind = match (has(FetchKind::Actuals), has(FetchKind::Forecast)) {
+        (true, true) => "Actuals + forecast",
+        (true, false) => "Actuals",
+        (false, true) => "Forecast",
+        (false, false) => "Historic (proxy)",
+    };
+    let stops = route::resolve(
+        client,
+        &meta.candidates,
+        opts.geocoder,
+        opts.language.as_deref(),
+    )
+    .await?;
+    let weather = route::fetch_route(client, &stops, s, e, !has(FetchKind::HistoricProxy)).await?;
+    let names: Vec<String> = stops.iter().map(|s| width::isolate(&s.name)).collect();
+    Ok((format!("{kind}: {} {s} → {e}", names.join(" vs ")), weather))
+}
+
+/// The side-by-side table: a column per candidate, a row per day, then
+/// average highs and wet-day counts.
+pub fn render_table(candidates: &[(Stop, Vec<RoadDay>)], units: Units) -> String {
+    let temp = |c: f64| match units {
+        Units::Celsius => format!("{}°C", fmt_temp(c)),
+        Units::Fahrenheit => format!("{}°F", fmt_temp(celcius_to_farenheit(c))),
+    };
+    let dates: BTreeSet<NaiveDate> = candidates
+        .iter()
+        .flat_map(|(_, days)| days.iter().map(|d| d.date))
+        .collect();
+    if dates.is_empty() {
+        return "_(no rows)_".into();
+    }
+    let names: Vec<String> = candidates
+        .iter()
+        .map(|(stop, _)| width::isolate(&stop.name))
+        .collect();
+    let mut s = format!(
+        "| Date | {} |\n|---|{}\n",
+        names.join(" | "),
+        "---|".repeat(names.len())
+    );
+    for date in &dates {
+        let cells: Vec<String> = candidates
+            .iter()
+            .map(|(_, days)| {
+                let Some(day) = days.iter().find(|d| d.date == *date) else {
+                    return "–".to_string();
+                };
+                let range = match (day.tmax, day.tmin) {
+                    (Some(hi), Some(lo)) => format!("{} / {}", temp(hi), temp(lo)),
+                    (Some(t), None) | (None, Some(t)) => temp(t),
+                    (None, None) => "–".to_string(),
+                };
+                match day.weather_code.map(route::describe) {
+                    Some(what) if !what.is_empty() => format!("{range}, {what}"),
+                    _ => range,
+                }
+            })
+            .collect();
+        s.push_str(&format!("| {date} | {} |\n", cells.join(" | ")));
+    }
+    let average: Vec<String> = candidates
+        .iter()
+        .map(|(_, days)| {
+            let highs: Vec<f64> = days.iter().filter_map(|d| d.tmax).collect();
+            match highs.len() {
+                0 => "–".to_string(),
+                n => temp(highs.iter().sum::<f64>() / n as f64),
+            }
+        })
+        .collect();
+    let wet: Vec<String> = candidates
+        .iter()
+        .map(|(_, days)| {
+            let n = days
+                .iter()
+                .filter(|d| d.weather_code.is_some_and(is_wet))
+                .count();
+            n.to_string()
+        })
+        .collect();
+    s.push_str(&format!("| **Avg high** | {} |\n", average.join(" | ")));
+    s.push_str(&format!("| **Wet days** | {} |\n", wet.join(" | ")));
+    s
+}
+
+/// The comparison block, with the usual heading and markers so a later run
+/// with a chosen `city` replaces it.
+pub fn render_block(label: &str, candidates: &[(Stop, Vec<RoadDay>)], units: Units) -> String {
+    format!(
+        "## Weather Forecast\n{}\n**{label}**  \n\n{}\n{}\n",
+        markers::BEGIN,
+        render_table(candidates, units),
+        markers::END
+    )
+}
+
+/// Fetch the candidates and write the comparison into the note. Returns
+/// whether more than the fetch stamp changed.
+pub async fn process(meta: &NoteMeta, client: &http::Client, opts: &RunOptions) -> Result<bool> {
+    let meta = &meta.occurrence_on(opts.today());
+    let opts = &opts.for_note(meta)?;
+    let (label, candidates) = fetch(meta, client, opts).await?;
+    let mut block = render_block(&label, &candidates, opts.units);
+    if opts.align_tables {
+        block = width::align_tables(&block);
+    }
+    let block = stale::stamp(&block, opts.now());
+    let path = Path::new(&meta.path);
+    let content = hooks::read_note(path)?;
+    let mut updated = content.clone();
+    upsert_weather_block_under(&mut updated, &block, opts.under_heading.as_deref())?;
+    outline::check_untouched(&content, &updated, false)?;
+    hooks::write_note(path, &updated)?;
+    Ok(stale::without_stamp(&content) != stale::without_stamp(&updated))
+}
@@ -0,0 +1,560 @@
+//! Layered configuration.
+//!
+//! Every setting resolves through the same layers, later ones winning:
+//! built-in defaults < config file < `WEATHER_HELPER_*` environment variables
+//! < CLI flags (including `--set key=value`) < `weather-<key>` frontmatter in
+//! an individual note. Each value remembers which layer set it so
+//! `weather-helper config show --origin` can answer "why is it using °F?".
+
+use crate::advisories::Thresholds;
+use crate::alerts::Rules;
+use crate::columns;
+use crate::grid;
+use crate::history::{self, HistoryMode};
+use crate::i18n::Languages;
+use crate::io;
+use crate::output::ColorChoice;
+use crate::provider::Provider;
+use crate::shortcode::TableStyle;
+use crate::stale::Refresh;
+use crate::window;
+use crate::{Geocoder, OnError, RunOptions, Units, parse_duration};
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
+use serde_yaml::Value as YamlValue;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Every configurable key, in display order.
+pub const KEYS: &[&str] = &[
+    "timeout",
+    "deadline",
+    "max-notes",
+    "today",
+    "polite",
+    "grid-radius",
+    "geocoder",
+    "language",
+    "write-coords",
+    "color",
+    "units",
+    "summary-lang",
+    "vars",
+    "provider",
+    "model",
+    "packing",
+    "packing-rules",
+    "outfit",
+    "outfit-rules",
+    "advisories",
+    "alert-if",
+    "window-score",
+    "home-timezone",
+    "dst-note",
+    "holidays",
+    "facts",
+    "map",
+    "show-location",
+    "align-tables",
+    "table-style",
+    "sidecar",
+    "embed-data",
+    "day-property",
+    "separate-note",
+    "under-heading",
+    "on-error",
+    "refresh",
+    "tags",
+    "only-upcoming",
+    "retention",
+    "include-past",
+    "links",
+    "fsync",
+    "pre-read",
+    "post-write",
+    "on-alert",
+    "after-note",
+    "after-run",
+    "history",
+    "history-limit",
+];
+
+/// Keys a note may override with `weather-<key>` frontmatter.
+pub const NOTE_KEYS: &[&str] = &[
+    "geocoder",
+    "language",
+    "write-coords",
+    "units",
+    "summary-lang",
+    "vars",
+    "provider",
+    "model",
+    "packing",
+    "outfit",
+    "advisories",
+    "alert-if",
+    "window-score",
+    "dst-note",
+    "holidays",
+    "facts",
+    "map",
+    "show-location",
+    "align-tables",
+    "table-style",
+    "sidecar",
+    "embed-data",
+    "day-property",
+    "separate-note",
+    "under-heading",
+    "on-error",
+    "refresh",
+];
+
+/// Where an effective value came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    File(PathBuf),
+    Env(String),
+    Flag,
+    Frontmatter(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::File(path) => write!(f, "config file {}", path.display()),
+            Origin::Env(var) => write!(f, "env {var}"),
+            Origin::Flag => write!(f, "command line"),
+            Origin::Frontmatter(path) => write!(f, "frontmatter of {path}"),
+        }
+    }
+}
+
+/// Raw setting values with their origins.
+#[derive(Clone, Debug)]
+pub struct Config {
+    values: BTreeMap<&'static str, (String, Origin)>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let opts = RunOptions::default();
+        let values = KEYS
+            .iter()
+            .map(|key| (*key, (display_value(&opts, key), Origin::Default)))
+            .collect();
+        Config { values }
+    }
+}
+
+impl Config {
+    /// Defaults overlaid with the config file and environment.
+    ///
+    /// `file` overrides the usual location (`WEATHER_HELPER_CONFIG`, then
+    /// `$XDG_CONFIG_HOME/weather-helper/config.yaml`); a missing default file
+    /// is not an error.
+    pub fn load(file: Option<&Path>) -> Result<Self> {
+        let mut config = Config::default();
+        let (path, required) = match file {
+            Some(path) => (path.to_path_buf(), true),
+            None => match env::var_os("WEATHER_HELPER_CONFIG") {
+                Some(path) => (PathBuf::from(path), true),
+                None => (default_config_path(), false),
+            },
+        };
+        if required || path.exists() {
+            let text = io::read_to_string(&path)
+                .with_context(|| format!("cannot read config file {}", path.display()))?;
+            config
+                .apply_yaml(&text, Origin::File(path.clone()))
+                .with_context(|| format!("invalid config file {}", path.display()))?;
+        }
+        for key in KEYS {
+            let var = env_var(key);
+            if let Ok(value) = env::var(&var) {
+                config.set(key, &value, Origin::Env(var))?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// Set `key` from `origin`, validating both key and value.
+    pub fn set(&mut self, key: &str, value: &str, origin: Origin) -> Result<()> {
+        let key = KEYS.iter().find(|k| **k == key).ok_or_else(|| {
+            anyhow!(
+                "unknown setting '{key}' (expected one of {})",
+                KEYS.join(", ")
+            )
+        })?;
+        apply(&mut RunOptions::default(), key, value)?;
+        self.values.insert(key, (value.trim().to_string(), origin));
+        Ok(())
+    }
+
+    /// Apply a flat YAML mapping of settings.
+    pub fn apply_yaml(&mut self, text: &str, origin: Origin) -> Result<()> {
+        let yaml: YamlValue = serde_yaml::from_str(text)?;
+        let Some(map) = yaml.as_mapping() else {
+            return if yaml.is_null() {
+                Ok(())
+            } else {
+                Err(anyhow!("expected a mapping of settings"))
+            };
+        };
+        for (key, value) in map {
+            let key = key
+                .as_str()
+                .ok_or_else(|| anyhow!("setting names must be strings"))?;
+            self.set(key, &yaml_scalar(value), origin.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Apply a note's `weather-<key>` frontmatter overrides.
+    pub fn apply_note(&mut self, overrides: &BTreeMap<String, String>, path: &str) -> Result<()> {
+        for (key, value) in overrides {
+            if !NOTE_KEYS.contains(&key.as_str()) {
+                return Err(anyhow!(
+                    "'weather-{key}' cannot be set per note (allowed: {})",
+                    NOTE_KEYS.join(", ")
+                ));
+            }
+            self.set(key, value, Origin::Frontmatter(path.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Effective value and origin of `key`.
+    pub fn get(&self, key: &str) -> Option<(&str, &Origin)> {
+        self.values.get(key).map(|(v, o)| (v.as_str(), o))
+    }
+
+    /// Typed options for a run, with `verbosity` (not a config key) kept from `base`.
+    pub fn run_options(&self, base: &RunOptions) -> Result<RunOptions> {
+        let mut opts = base.clone();
+        for key in KEYS {
+            let (value, origin) = &self.values[key];
+            apply(&mut opts, key, value).with_context(|| format!("{key} from {origin}"))?;
+        }
+        Ok(opts)
+    }
+
+    /// `key = value` lines, optionally followed by each value's origin.
+    pub fn show(&self, with_origin: bool) -> String {
+        let width = KEYS.iter().map(|k| k.len()).max().unwrap_or(0);
+        let mut out = String::new();
+        for key in KEYS {
+            let (value, origin) = &self.values[key];
+            let value = if value.is_empty() { "(unset)" } else { value };
+            if with_origin {
+                out.push_str(&format!("{key:<width$} = {value:<12} # {origin}\n"));
+            } else {
+                out.push_str(&format!("{key:<width$} = {value}\n"));
+            }
+        }
+        // API keys are never printed, only whether and where they are set.
+        for (provider, source) in crate::secrets::configured() {
+            let key = format!("{provider}-api-key");
+            if with_origin {
+                out.push_str(&format!("{key:<width$} = {:<12} # {source}\n", "***"));
+            } else {
+                out.push_str(&format!("{key:<width$} = ***\n"));
+            }
+        }
+        out
+    }
+}
+
+/// Set one typed option from its string form. Empty or `none` clears
+/// optional settings.
+pub fn apply(opts: &mut RunOptions, key: &str, value: &str) -> Result<()> {
+    let value = value.trim();
+    let unset = value.is_empty() || value.eq_ignore_ascii_case("none");
+    match key {
+        "timeout" => opts.timeout = (!unset).then(|| parse_duration(value)).transpose()?,
+        "deadline" => opts.deadline = (!unset).then(|| parse_duration(value)).transpose()?,
+        "max-notes" => {
+            opts.max_notes = match value.to_ascii_lowercase().as_str() {
+                "off" => Some(0),
+                _ if unset => None,
+                _ => Some(value.parse().map_err(|_| {
+                    anyhow!("max-notes must be a number of notes or off, got '{value}'")
+                })?),
+            }
+        }
+        "today" => {
+            opts.today = (!unset)
+                .then(|| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+                .transpose()
+                .map_err(|_| anyhow!("today must be a date like 2025-08-01, got '{value}'"))?
+        }
+        "polite" => {
+            opts.polite = value
+                .parse()
+                .map_err(|_| anyhow!("polite must be true or false, got '{value}'"))?
+        }
+        "grid-radius" => {
+            opts.grid_radius = (!unset).then(|| grid::parse_radius(value)).transpose()?
+        }
+        "geocoder" => opts.geocoder = Geocoder::parse(value)?,
+        "language" => opts.language = (!unset).then(|| value.to_string()),
+        "write-coords" => {
+            opts.write_coords = value
+                .parse()
+                .map_err(|_| anyhow!("write-coords must be true or false, got '{value}'"))?
+        }
+        "color" => opts.color = ColorChoice::parse(value)?,
+        "units" => opts.units = Units::parse(value)?,
+        "summary-lang" => {
+            opts.summary_lang = match unset {
+                true => Languages::default(),
+                false => Languages::parse(value)?,
+            }
+        }
+        "vars" => opts.vars = columns::parse_vars(value)?,
+        "on-error" => opts.on_error = OnError::parse(value)?,
+        "refresh" => opts.refresh = Refresh::parse(value)?,
+        "only-upcoming" => {
+            opts.only_upcoming = (!unset)
+                .then(|| parse_duration(value))
+                .transpose()?
+                .map(|d| d.as_secs().div_ceil(86400) as u32)
+        }
+        "retention" => {
+            opts.retention = (!unset)
+                .then(|| parse_duration(value))
+                .transpose()?
+                .map(|d| d.as_secs().div_ceil(86400) as u32)
+        }
+        "include-past" => {
+            opts.include_past = value
+                .parse()
+                .map_err(|_| anyhow!("include-past must be true or false, got '{value}'"))?
+        }
+        "tags" => {
+            opts.tags = value
+                .split(',')
+                .map(|t| t.trim().trim_start_matches('#').to_string())
+                .filter(|t| !t.is_empty())
+                .collect()
+        }
+        "provider" => opts.provider = Provider::parse(value)?,
+        "model" => opts.model = (!unset).then(|| value.to_string()),
+        "packing" => {
+            opts.packing = value
+                .parse()
+                .map_err(|_| anyhow!("packing must be true or false, got '{value}'"))?
+        }
+        "packing-rules" => opts.packing_rules = (!unset).then(|| PathBuf::from(value)),
+        "outfit" => {
+            opts.outfit = value
+                .parse()
+                .map_err(|_| anyhow!("outfit must be true or false, got '{value}'"))?
+        }
+        "outfit-rules" => opts.outfit_rules = (!unset).then(|| PathBuf::from(value)),
+        "facts" => {
+            opts.facts = value
+                .parse()
+                .map_err(|_| anyhow!("facts must be true or false, got '{value}'"))?
+        }
+        "map" => opts.map = crate::map::MapMode::parse(value)?,
+        "show-location" => {
+            opts.show_location = value
+                .parse()
+                .map_err(|_| anyhow!("show-location must be true or false, got '{value}'"))?
+        }
+        "align-tables" => {
+            opts.align_tables = value
+                .parse()
+                .map_err(|_| anyhow!("align-tables must be true or false, got '{value}'"))?
+        }
+        "table-style" => opts.table_style = TableStyle::parse(value)?,
+        "pre-read" => opts.pre_read = (!unset).then(|| value.to_string()),
+        "post-write" => opts.post_write = (!unset).then(|| value.to_string()),
+        "on-alert" => opts.on_alert = (!unset).then(|| value.to_string()),
+        "after-note" => opts.after_note = (!unset).then(|| value.to_string()),
+        "after-run" => opts.after_run = (!unset).then(|| value.to_string()),
+        "history" => opts.history = HistoryMode::parse(value)?,
+        "history-limit" => {
+            opts.history_limit = (!unset).then(|| history::parse_size(value)).transpose()?
+        }
+        "alert-if" => opts.alert_if = (!unset).then(|| Rules::parse(value)).transpose()?,
+        "window-score" => {
+            opts.window_score = match unset {
+                true => window::Score::default(),
+                false => window::Score::parse(value)?,
+            }
+        }
+        "fsync" => opts.fsync = io::SyncPolicy::parse(value)?,
+        "sidecar" => {
+            opts.sidecar = value
+                .parse()
+                .map_err(|_| anyhow!("sidecar must be true or false, got '{value}'"))?
+        }
+        "embed-data" => {
+            opts.embed_data = value
+                .parse()
+                .map_err(|_| anyhow!("embed-data must be true or false, got '{value}'"))?
+        }
+        "day-property" => opts.day_property = (!unset).then(|| value.to_string()),
+        "separate-note" => {
+            opts.separate_note = value
+                .parse()
+                .map_err(|_| anyhow!("separate-note must be true or false, got '{value}'"))?
+        }
+        "under-heading" => opts.under_heading = (!unset).then(|| value.to_string()),
+        "links" => {
+            opts.links = value
+                .parse()
+                .map_err(|_| anyhow!("links must be true or false, got '{value}'"))?
+        }
+        "holidays" => {
+            opts.holidays = value
+                .parse()
+                .map_err(|_| anyhow!("holidays must be true or false, got '{value}'"))?
+        }
+        "home-timezone" => {
+            opts.home_timezone = (!unset).then(|| crate::tz::parse_zone(value)).transpose()?
+        }
+        "dst-note" => {
+            opts.dst_note = value
+                .parse()
+                .map_err(|_| anyhow!("dst-note must be true or false, got '{value}'"))?
+        }
+        "advisories" => {
+            opts.advisories = match value.to_ascii_lowercase().as_str() {
+                "true" | "on" => Some(Thresholds::default()),
+                "false" | "off" => None,
+                _ if unset => None,
+                _ => Some(Thresholds::parse(value)?),
+            }
+        }
+        other => return Err(anyhow!("unknown setting '{other}'")),
+    }
+    Ok(())
+}
+
+/// String form of an option, as `config show` prints it.
+fn display_value(opts: &RunOptions, key: &str) -> String {
+    let duration = |d: Option<std::time::Duration>| {
+        d.map(|d| format!("{}s", d.as_secs_f64()))
+            .unwrap_or_default()
+    };
+    match key {
+        "timeout" => duration(opts.timeout),
+        "deadline" => duration(opts.deadline),
+        "max-notes" => opts
+            .max_notes()
+            .map_or("off".to_string(), |n| n.to_string()),
+        "today" => opts.today.map(|d| d.to_string()).unwrap_or_default(),
+        "polite" => opts.polite.to_string(),
+        "grid-radius" => opts
+            .grid_radius
+            .map(grid::format_radius)
+            .unwrap_or_default(),
+        "geocoder" => opts.geocoder.name().to_string(),
+        "language" => opts.language.clone().unwrap_or_default(),
+        "write-coords" => opts.write_coords.to_string(),
+        "color" => opts.color.name().to_string(),
+        "units" => opts.units.name().to_string(),
+        "summary-lang" => opts.summary_lang.name(),
+        "vars" => opts.vars.join(", "),
+        "provider" => opts.provider.name().to_string(),
+        "model" => opts.model.clone().unwrap_or_default(),
+        "packing" => opts.packing.to_string(),
+        "packing-rules" => opts
+            .packing_rules
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        "outfit" => opts.outfit.to_string(),
+        "holidays" => opts.holidays.to_string(),
+        "facts" => opts.facts.to_string(),
+        "map" => opts.map.name().to_string(),
+        "show-location" => opts.show_location.to_string(),
+        "align-tables" => opts.align_tables.to_string(),
+        "table-style" => opts.table_style.name().to_string(),
+        "links" => opts.links.to_string(),
+        "sidecar" => opts.sidecar.to_string(),
+        "embed-data" => opts.embed_data.to_string(),
+        "day-property" => opts.day_property.clone().unwrap_or_default(),
+        "separate-note" => opts.separate_note.to_string(),
+        "under-heading" => opts.under_heading.clone().unwrap_or_default(),
+        "on-error" => opts.on_error.name().to_string(),
+        "refresh" => opts.refresh.name().to_string(),
+        "tags" => opts.tags.join(","),
+        "only-upcoming" => opts
+            .only_upcoming
+            .map(|d| format!("{d}d"))
+            .unwrap_or_default(),
+        "retention" => format!(
+            "{}d",
+            opts.retention
+                .unwrap_or(crate::priority::DEFAULT_RETENTION_DAYS)
+        ),
+        "include-past" => opts.include_past.to_string(),
+        "fsync" => opts.fsync.name().to_string(),
+        "pre-read" => opts.pre_read.clone().unwrap_or_default(),
+        "post-write" => opts.post_write.clone().unwrap_or_default(),
+        "on-alert" => opts.on_alert.clone().unwrap_or_default(),
+        "after-note" => opts.after_note.clone().unwrap_or_default(),
+        "after-run" => opts.after_run.clone().unwrap_or_default(),
+        "history" => opts.history.name().to_string(),
+        "history-limit" => history::format_size(opts.history_limit()),
+        "alert-if" => opts.alert_if.as_ref().map(Rules::name).unwrap_or_default(),
+        "window-score" => opts.window_score.name(),
+        "home-timezone" => opts
+            .home_timezone
+            .map(|z| z.name().to_string())
+            .unwrap_or_default(),
+        "dst-note" => opts.dst_note.to_string(),
+        "advisories" => match opts.advisories {
+            None => "off".to_string(),
+            Some(t) if t == Thresholds::default() => "on".to_string(),
+            Some(t) => t.name(),
+        },
+        "outfit-rules" => opts
+            .outfit_rules
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Environment variable for `key`, e.g. `WEATHER_HELPER_WRITE_COORDS`.
+pub fn env_var(key: &str) -> String {
+    format!(
+        "WEATHER_HELPER_{}",
+        key.to_ascii_uppercase().replace('-', "_")
+    )
+}
+
+/// `$XDG_CONFIG_HOME/weather-helper/config.yaml`, falling back to `~/.config`.
+pub fn default_config_path() -> PathBuf {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("weather-helper")
+        .join("config.yaml")
+}
+
+/// Scalar YAML value as text (`true`, `10`, `de`); other shapes are empty.
+pub fn yaml_scalar(value: &YamlValue) -> String {
+    match value {
+        YamlValue::String(s) => s.clone(),
+        YamlValue::Bool(b) => b.to_string(),
+        YamlValue::Number(n) => n.to_string(),
+        // `weather-vars: [a, b]` reads like `weather-vars: a, b`.
+        YamlValue::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(yaml_scalar).collect();
+            items.join(", ")
+        }
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,94 @@
+//! Lenient parsing of trip dates from frontmatter.
+//!
+//! Notes written by hand use whatever date style the author is used to, so
+//! besides ISO `YYYY-MM-DD` we accept dotted dates, slashed/dashed day-first or
+//! month-first dates, and ISO datetimes. Ambiguous day/month orders are
+//! resolved by a `date-order` frontmatter key.
+
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// How to read ambiguous `NN/NN/YYYY` and `NN-NN-YYYY` dates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `DD/MM/YYYY` (default).
+    #[default]
+    DayFirst,
+    /// `MM/DD/YYYY`.
+    MonthFirst,
+}
+
+impl DateOrder {
+    /// Parse a `date-order` value (`dmy`/`mdy`).
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "dmy" | "day-first" | "dd/mm/yyyy" => Ok(DateOrder::DayFirst),
+            "mdy" | "month-first" | "mm/dd/yyyy" => Ok(DateOrder::MonthFirst),
+            other => Err(anyhow!(
+                "unknown date-order '{other}' (expected dmy or mdy)"
+            )),
+        }
+    }
+}
+
+/// A date read from frontmatter together with the format that matched.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedDate {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    /// Human-readable format, e.g. `DD/MM/YYYY`.
+    pub format: &'static str,
+}
+
+impl ParsedDate {
+    /// Whether the value used the canonical `YYYY-MM-DD` form.
+    pub fn is_iso(&self) -> bool {
+        self.format == "YYYY-MM-DD"
+    }
+}
+
+/// Parse a trip date in any supported format.
+pub fn parse_trip_date(s: &str, order: DateOrder) -> Result<ParsedDate> {
+    let s = s.trim();
+    let date_only = |fmt: &str, format: &'static str| {
+        NaiveDate::parse_from_str(s, fmt)
+            .ok()
+            .map(|date| ParsedDate {
+                date,
+                time: None,
+                format,
+            })
+    };
+    let date_time = |fmt: &str, format: &'static str| {
+        NaiveDateTime::parse_from_str(s, fmt)
+            .ok()
+            .map(|dt| ParsedDate {
+                date: dt.date(),
+                time: Some(dt.time()),
+                format,
+            })
+    };
+
+    let (slashed, dashed, slashed_name, dashed_name) = match order {
+        DateOrder::DayFirst => ("%d/%m/%Y", "%d-%m-%Y", "DD/MM/YYYY", "DD-MM-YYYY"),
+        DateOrder::MonthFirst => ("%m/%d/%Y", "%m-%d-%Y", "MM/DD/YYYY", "MM-DD-YYYY"),
+    };
+
+    date_only("%Y-%m-%d", "YYYY-MM-DD")
+        .or_else(|| date_time("%Y-%m-%dT%H:%M:%S", "YYYY-MM-DDTHH:MM:SS"))
+        .or_else(|| date_time("%Y-%m-%dT%H:%M", "YYYY-MM-DDTHH:MM"))
+        .or_else(|| date_time("%Y-%m-%d %H:%M", "YYYY-MM-DD HH:MM"))
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .ok()
+                .map(|dt| ParsedDate {
+                    date: dt.naive_local().date(),
+                    time: Some(dt.naive_local().time()),
+                    format: "RFC 3339",
+                })
+        })
+        .or_else(|| date_only("%Y.%m.%d", "YYYY.MM.DD"))
+        .or_else(|| date_only(slashed, slashed_name))
+        .or_else(|| date_only(dashed, dashed_name))
+        .ok_or_else(|| anyhow!("unrecognized date '{s}' (expected YYYY-MM-DD)"))
+}
@@ -0,0 +1,73 @@
+//! Per-day weather as a frontmatter property.
+//!
+//! With `day-property: forecast` every run writes the table's days into the
+//! note's frontmatter as parallel arrays, so Obsidian Bases or Dataview can
+//! chart them across trips (e.g. a calendar heatmap of highs):
+//!
+//! ```yaml
+//! forecast:
+//!   dates: [2025-08-20, 2025-08-21]
+//!   high: [31.4, 33.0]
+//!   low: [20.1, 21.7]
+//!   unit: °C
+//! ```
+//!
+//! The layout is fixed (key order, one flow sequence per line, one decimal)
+//! so re-runs only change the numbers. Each edit is checked by parsing the
+//! frontmatter back: the property must read as written and every other key
+//! must be unchanged, or the note is not written.
+
+use crate::{DayTemp, Units, frontmatter};
+use anyhow::{Result, anyhow};
+use serde_yaml::Value;
+
+/// Most days written; longer trips keep their first days.
+pub const MAX_DAYS: usize = 62;
+
+/// The property's value lines for `data`, in `units`.
+pub fn render(data: &[DayTemp], units: Units) -> String {
+    let days = &data[..data.len().min(MAX_DAYS)];
+    let list = |values: Vec<String>| format!("[{}]", values.join(", "));
+    let (highs, lows): (Vec<String>, Vec<String>) = days
+        .iter()
+        .map(|d| match units {
+            Units::Celsius => (d.tmax, d.tmin),
+            Units::Fahrenheit => (d.tmax_f, d.tmin_f),
+        })
+        .map(|(high, low)| (format!("{high:.1}"), format!("{low:.1}")))
+        .unzip();
+    let unit = match units {
+        Units::Celsius => "°C",
+        Units::Fahrenheit => "°F",
+    };
+    format!(
+        "  dates: {}\n  high: {}\n  low: {}\n  unit: {unit}\n",
+        list(days.iter().map(|d| d.date.to_string()).collect()),
+        list(highs),
+        list(lows),
+    )
+}
+
+/// `content` with property `key` set to `data`.
+pub fn apply(content: &str, key: &str, data: &[DayTemp], units: Units) -> Result<String> {
+    if key.starts_with("weather-") {
+        return Err(anyhow!(
+            "day-property '{key}' would be read as a per-note setting; pick a name without the weather- prefix"
+        ));
+    }
+    let value = render(data, units);
+    let out = frontmatter::set_block(content, key, &value)?;
+
+    let mut before = frontmatter::parse(content)?;
+    let mut after = frontmatter::parse(&out)?;
+    let expected: Value = serde_yaml::from_str(&format!("{key}:\n{value}"))?;
+    let expected = expected.get(key).cloned();
+    let written = after.remove(key);
+    before.remove(key);
+    if written != expected || before != after {
+        return Err(anyhow!(
+            "refusing to write: the frontmatter would not read back as intended (is '{key}' written in an unusual style?)"
+        ));
+    }
+    Ok(out)
+}
@@ -0,0 +1,67 @@
+//! Destination facts from a bundled dataset.
+//!
+//! `data/countries.csv` holds, per ISO country code, the plug types, mains
+//! voltage, emergency number and whether tap water is generally safe to
+//! drink. The footer under the weather table and the packing adapter rule
+//! both read it; lookups need no network call.
+
+/// A bundled country record.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CountryInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+    /// IEC plug types, e.g. `["C", "F"]`.
+    pub plugs: Vec<&'static str>,
+    /// Mains voltage, e.g. `230` or `127/220`.
+    pub voltage: &'static str,
+    /// Emergency numbers, most general first (`112`, `110 / 119`).
+    pub emergency: &'static str,
+    /// `safe`, `varies` or `unsafe`.
+    pub tap_water: &'static str,
+}
+
+const COUNTRIES_CSV: &str = include_str!("../data/countries.csv");
+
+/// Look up a country by ISO 3166-1 alpha-2 code (case-insensitive).
+pub fn lookup(code: &str) -> Option<CountryInfo> {
+    let code = code.trim().to_ascii_uppercase();
+    COUNTRIES_CSV
+        .lines()
+        .skip(1)
+        .filter_map(parse_row)
+        .find(|c| c.code == code)
+}
+
+fn parse_row(line: &'static str) -> Option<CountryInfo> {
+    let mut cols = line.split(',');
+    Some(CountryInfo {
+        code: cols.next()?,
+        name: cols.next()?,
+        plugs: cols.next()?.split_whitespace().collect(),
+        voltage: cols.next()?,
+        emergency: cols.next()?,
+        tap_water: cols.next()?,
+    })
+}
+
+/// Plug types used in `country`; empty when it is not in the dataset.
+pub fn plug_types(country: &str) -> Vec<&'static str> {
+    lookup(country).map(|c| c.plugs).unwrap_or_default()
+}
+
+/// One-line footer, e.g.
+/// `_Italy: plugs C/F/L, 230 V • emergency 112 • tap water safe to drink_`.
+pub fn render_footer(info: &CountryInfo) -> String {
+    let water = match info.tap_water {
+        "safe" => "tap water safe to drink",
+        "unsafe" => "drink bottled or treated water",
+        _ => "tap water safety varies by area",
+    };
+    format!(
+        "_{}: plugs {}, {} V • emergency {} • {water}_\n",
+        info.name,
+        info.plugs.join("/"),
+        info.voltage,
+        info.emergency
+    )
+}
@@ -0,0 +1,94 @@
+//! In-place edits to a note's YAML frontmatter.
+//!
+//! Edits are line-based so the rest of the frontmatter (comments, key order,
+//! quoting) is left exactly as the author wrote it.
+
+use anyhow::{Result, anyhow};
+
+/// Set top-level `key: value` pairs in the frontmatter of `content`.
+///
+/// Existing keys are replaced on their own line; new keys are appended just
+/// before the closing `---`.
+pub fn set_keys(content: &str, pairs: &[(&str, String)]) -> Result<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut close = closing_fence(&lines)?;
+    for (key, value) in pairs {
+        let line = format!("{key}: {value}");
+        let existing = lines[1..close].iter().position(|l| {
+            l.strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with(':'))
+        });
+        match existing {
+            Some(i) => lines[i + 1] = line,
+            None => {
+                lines.insert(close, line);
+                close += 1;
+            }
+        }
+    }
+    let mut out = lines.join("\n");
+    if content.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Line index of the `---` closing the frontmatter.
+fn closing_fence(lines: &[String]) -> Result<usize> {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return Err(anyhow!("no YAML frontmatter"));
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|l| l.trim_end() == "---")
+        .map(|i| i + 1)
+        .ok_or_else(|| anyhow!("unterminated YAML frontmatter"))
+}
+
+/// Set top-level `key` to a nested value: `value` holds the lines below
+/// `key:`, already indented.
+///
+/// An existing `key` is replaced together with its indented or list-item
+/// continuation lines; otherwise the key is appended before the closing
+/// `---`.
+pub fn set_block(content: &str, key: &str, value: &str) -> Result<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let close = closing_fence(&lines)?;
+    let mut new = vec![format!("{key}:")];
+    new.extend(value.lines().map(str::to_string));
+    let existing = lines[1..close].iter().position(|l| {
+        l.strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with(':'))
+    });
+    match existing {
+        Some(i) => {
+            let start = i + 1;
+            let end = lines[start + 1..close]
+                .iter()
+                .position(|l| !(l.starts_with([' ', '\t']) || l.starts_with("- ")))
+                .map_or(close, |n| start + 1 + n);
+            lines.splice(start..end, new);
+        }
+        None => {
+            lines.splice(close..close, new);
+        }
+    }
+    let mut out = lines.join("\n");
+    if content.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// The frontmatter of `content` parsed as a YAML mapping.
+pub fn parse(content: &str) -> Result<serde_yaml::Mapping> {
+    let lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let close = closing_fence(&lines)?;
+    let yaml = lines[1..close].join("\n");
+    match serde_yaml::from_str(&yaml)? {
+        serde_yaml::Value::Mapping(map) => Ok(map),
+        serde_yaml::Value::Null => Ok(serde_yaml::Mapping::new()),
+        _ => Err(anyhow!("frontmatter is not a YAML mapping")),
+    }
+}
@@ -0,0 +1,79 @@
+//! Coordinates and distances.
+//!
+//! Great-circle distances, and the one way coordinates are written and read
+//! across the tool (`41.90, 12.50`), for grid snapping, reverse-geocoded
+//! headers, waypoints and library consumers alike.
+//!
+//! Open‑Meteo answers for the model grid cell nearest the requested point
+//! and reports where that is. The reported point is remembered for the run,
+//! so the header (with `show-location`) and `-v` logs can say how far the
+//! weather's source is from the destination.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Mean radius of the Earth, km.
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle (haversine) distance between two points, km.
+pub fn distance_km((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    let (p1, p2) = (lat1.to_radians(), lat2.to_radians());
+    let dp = p2 - p1;
+    let dl = (lon2 - lon1).to_radians();
+    let a = (dp / 2.0).sin().powi(2) + p1.cos() * p2.cos() * (dl / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().min(1.0).asin()
+}
+
+/// `41.90, 12.50` with `decimals` places.
+pub fn format_coords(lat: f64, lon: f64, decimals: usize) -> String {
+    format!("{lat:.decimals$}, {lon:.decimals$}")
+}
+
+/// `850 m`, `3.2 km` or `41 km`.
+pub fn format_distance(km: f64) -> String {
+    match km {
+        km if km < 1.0 => format!("{:.0} m", km * 1000.0),
+        km if km < 10.0 => format!("{km:.1} km"),
+        km => format!("{km:.0} km"),
+    }
+}
+
+/// Read `41.9, 12.5`, also with hemisphere letters (`41.9N, 12.5E`,
+/// `33.87 S, 151.21 E`); `None` unless both parts are numbers in range.
+pub fn parse_coords(s: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = s.split_once(',')?;
+    let part = |s: &str, positive: char, negative: char| -> Option<f64> {
+        let s = s.trim();
+        let (number, sign) = match s.chars().last()?.to_ascii_uppercase() {
+            c if c == positive => (&s[..s.len() - 1], 1.0),
+            c if c == negative => (&s[..s.len() - 1], -1.0),
+            _ => (s, 1.0),
+        };
+        let value: f64 = number.trim().trim_end_matches('°').parse().ok()?;
+        // `41.9°S` is fine; `-41.9S` says the same thing twice.
+        (sign > 0.0 || value >= 0.0).then_some(value * sign)
+    };
+    let lat = part(lat, 'N', 'S')?;
+    let lon = part(lon, 'E', 'W')?;
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+/// Reported grid points by requested coordinates, for this run.
+static GRID_POINTS: Mutex<BTreeMap<String, (f64, f64)>> = Mutex::new(BTreeMap::new());
+
+/// Remember that a request for `requested` was answered for `reported`.
+pub fn record_grid_point(requested: (f64, f64), reported: (f64, f64)) {
+    GRID_POINTS
+        .lock()
+        .unwrap()
+        .insert(format_coords(requested.0, requested.1, 4), reported);
+}
+
+/// Where the weather requested for `requested` came from, if the API said.
+pub fn grid_point(requested: (f64, f64)) -> Option<(f64, f64)> {
+    GRID_POINTS
+        .lock()
+        .unwrap()
+        .get(&format_coords(requested.0, requested.1, 4))
+        .copied()
+}
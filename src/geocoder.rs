@@ -0,0 +1,468 @@
+//! Geocoding backends.
+//!
+//! Open‑Meteo's geocoder is the default, but it misses many small places and
+//! POIs that OSM-based geocoders find, so Nominatim and Photon can be selected
+//! instead (`--geocoder` or `WEATHER_HELPER_GEOCODER`). OSM services are
+//! queried with the tool's User-Agent and throttled to one request per second.
+//! A configured language (`--language`) localizes the returned place names.
+//! Names that find nothing are retried in a normalized form, and a final miss
+//! lists near-matches in the error.
+
+use crate::{http, width};
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Results are limited to this country (ISO 3166-1 alpha-2).
+const COUNTRY: &str = "IT";
+
+/// Minimum spacing between requests to the OSM-based services.
+const OSM_MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+static OSM_LAST_REQUEST: Mutex<Option<Instant>> = Mutex::const_new(None);
+
+/// A resolved location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    /// IANA timezone, or `auto` to let Open‑Meteo derive it from coordinates.
+    pub timezone: String,
+    pub country_code: Option<String>,
+    /// Metres above sea level, when the geocoder reports it.
+    pub elevation: Option<f64>,
+}
+
+/// Which geocoding service resolves city names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Geocoder {
+    #[default]
+    OpenMeteo,
+    Nominatim,
+    Photon,
+}
+
+impl Geocoder {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open-meteo" | "openmeteo" => Ok(Geocoder::OpenMeteo),
+            "nominatim" => Ok(Geocoder::Nominatim),
+            "photon" => Ok(Geocoder::Photon),
+            other => Err(anyhow!(
+                "unknown geocoder '{other}' (expected open-meteo, nominatim or photon)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`Geocoder::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Geocoder::OpenMeteo => "open-meteo",
+            Geocoder::Nominatim => "nominatim",
+            Geocoder::Photon => "photon",
+        }
+    }
+
+    /// Resolve `query` to the best matching place, with the place name
+    /// localized to `language` (ISO 639-1, e.g. `de`) when given.
+    pub async fn search(
+        self,
+        client: &http::Client,
+        query: &str,
+        language: Option<&str>,
+    ) -> Result<Place> {
+        if let Some(place) = self.search_once(client, query, language).await? {
+            return Ok(place);
+        }
+        // Retry with accents, punctuation and "city of" prefixes removed.
+        let normalized = normalize_query(query);
+        if !normalized.is_empty()
+            && normalized != query
+            && let Some(place) = self.search_once(client, &normalized, language).await?
+        {
+            return Ok(place);
+        }
+        let suggestions = suggest(client, query).await;
+        match suggestions.as_slice() {
+            [] => Err(anyhow!("geocoding failed for city: {}", query)),
+            names => Err(anyhow!(
+                "geocoding failed for city: {} (did you mean {}?)",
+                query,
+                names
+                    .iter()
+                    .map(|n| format!("'{n}'"))
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            )),
+        }
+    }
+
+    async fn search_once(
+        self,
+        client: &http::Client,
+        query: &str,
+        language: Option<&str>,
+    ) -> Result<Option<Place>> {
+        match self {
+            Geocoder::OpenMeteo => open_meteo_search(client, query, language).await,
+            Geocoder::Nominatim => nominatim_search(client, query, language).await,
+            Geocoder::Photon => photon_search(client, query, language).await,
+        }
+    }
+}
+
+/// Leading phrases that geocoders do not expect in a place name.
+const QUERY_PREFIXES: &[&str] = &["city of ", "town of ", "città di ", "comune di "];
+
+/// Most suggestions listed in a "did you mean" hint.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Simplify a city name for a second geocoding attempt: lowercase, drop
+/// diacritics and punctuation, strip "city of"-style prefixes, collapse spaces.
+pub fn normalize_query(s: &str) -> String {
+    let mut out = s.trim().to_lowercase();
+    for prefix in QUERY_PREFIXES {
+        if let Some(rest) = out.strip_prefix(prefix) {
+            out = rest.to_string();
+            break;
+        }
+    }
+    // Vowel points and accents attach to the letter before them; dropping
+    // them keeps the word whole ("תֵּל" → "תל") instead of splitting it.
+    let folded: String = out
+        .chars()
+        .filter(|c| !width::is_combining_mark(*c) && !width::is_bidi_control(*c))
+        .map(fold_char)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    folded.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Map a Latin letter with diacritics to its base letter.
+fn fold_char(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' | 'ń' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ś' | 'š' => 's',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ð' => 'd',
+        'ł' => 'l',
+        other => other,
+    }
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(cur)
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// Pick the `candidates` close enough to `query` to be a likely typo, best first.
+pub fn rank_suggestions(query: &str, candidates: &[String]) -> Vec<String> {
+    let wanted = normalize_query(query);
+    let limit = (wanted.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &String)> = candidates
+        .iter()
+        .map(|c| (edit_distance(&wanted, &normalize_query(c)), c))
+        .filter(|(d, _)| *d <= limit)
+        .collect();
+    scored.sort_by_key(|(d, _)| *d);
+    let mut names: Vec<String> = Vec::new();
+    for (_, name) in scored {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names.truncate(MAX_SUGGESTIONS);
+    names
+}
+
+/// Near-matches for a failed query, looked up worldwide by name prefix.
+/// Best effort: lookup errors just mean no suggestions.
+async fn suggest(client: &http::Client, query: &str) -> Vec<String> {
+    let normalized = normalize_query(query);
+    // Typos tend to be later in the word, so search on the leading part.
+    let keep = (normalized.chars().count() / 2).max(3);
+    let prefix: String = normalized.chars().take(keep).collect();
+    if prefix.chars().count() < 3 {
+        return Vec::new();
+    }
+    let url = format!(
+        "{}/search?name={}&count=20",
+        geocode_base(),
+        urlencoding::encode(&prefix)
+    );
+    match client.get_json::<OpenMeteoResp>(&url).await {
+        Ok(resp) => {
+            let names: Vec<String> = resp
+                .results
+                .unwrap_or_default()
+                .into_iter()
+                .map(|r| r.name)
+                .collect();
+            rank_suggestions(query, &names)
+        }
+        Err(e) => {
+            tracing::debug!("suggestion lookup failed: {e:#}");
+            Vec::new()
+        }
+    }
+}
+
+/// Gets the url for the geocode
+fn geocode_base() -> String {
+    env::var("OPEN_METEO_GEOCODE_BASE")
+        .unwrap_or_else(|_| "https://geocoding-api.open-meteo.com/v1".to_string())
+}
+
+/// Gets the url for Nominatim
+fn nominatim_base() -> String {
+    env::var("NOMINATIM_BASE").unwrap_or_else(|_| "https://nominatim.openstreetmap.org".to_string())
+}
+
+/// Gets the url for Photon
+fn photon_base() -> String {
+    env::var("PHOTON_BASE").unwrap_or_else(|_| "https://photon.komoot.io".to_string())
+}
+
+/// Wait until the OSM rate limit allows another request.
+async fn osm_throttle() {
+    let mut last = OSM_LAST_REQUEST.lock().await;
+    if let Some(prev) = *last {
+        tokio::time::sleep_until(prev + OSM_MIN_INTERVAL).await;
+    }
+    *last = Some(Instant::now());
+}
+
+#[derive(Deserialize, Debug)]
+struct OpenMeteoResp {
+    results: Option<Vec<OpenMeteoItem>>,
+}
+#[derive(Deserialize, Debug)]
+struct OpenMeteoItem {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+    #[serde(default)]
+    timezone: Option<String>,
+    #[serde(default)]
+    country_code: Option<String>,
+    #[serde(default)]
+    elevation: Option<f64>,
+}
+
+/// `&<param>=<lang>` when a language is configured.
+fn language_param(param: &str, language: Option<&str>) -> String {
+    language
+        .map(|l| format!("&{param}={}", urlencoding::encode(l)))
+        .unwrap_or_default()
+}
+
+async fn open_meteo_search(
+    client: &http::Client,
+    query: &str,
+    language: Option<&str>,
+) -> Result<Option<Place>> {
+    let url = format!(
+        "{}/search?name={}&country={COUNTRY}&count=1{}",
+        geocode_base(),
+        urlencoding::encode(query),
+        language_param("language", language)
+    );
+    let resp: OpenMeteoResp = client.get_json(&url).await?;
+    Ok(resp.results.and_then(|mut v| v.pop()).map(|item| Place {
+        name: item.name,
+        latitude: item.latitude,
+        longitude: item.longitude,
+        timezone: item.timezone.unwrap_or_else(|| "Europe/Rome".to_string()),
+        country_code: item.country_code,
+        elevation: item.elevation,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct NominatimItem {
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    address: BTreeMap<String, String>,
+}
+
+async fn nominatim_search(
+    client: &http::Client,
+    query: &str,
+    language: Option<&str>,
+) -> Result<Option<Place>> {
+    let url = format!(
+        "{}/search?format=jsonv2&addressdetails=1&limit=1&countrycodes={}&q={}{}",
+        nominatim_base(),
+        COUNTRY.to_ascii_lowercase(),
+        urlencoding::encode(query),
+        language_param("accept-language", language)
+    );
+    osm_throttle().await;
+    let items: Vec<NominatimItem> = client.get_json(&url).await?;
+    let Some(item) = items.into_iter().next() else {
+        return Ok(None);
+    };
+    Ok(Some(Place {
+        name: item
+            .name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| query.to_string()),
+        latitude: item.lat.parse()?,
+        longitude: item.lon.parse()?,
+        timezone: "auto".to_string(),
+        country_code: item.address.get("country_code").map(|c| c.to_uppercase()),
+        elevation: None,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct PhotonResp {
+    features: Vec<PhotonFeature>,
+}
+#[derive(Deserialize, Debug)]
+struct PhotonFeature {
+    geometry: PhotonGeometry,
+    properties: PhotonProps,
+}
+#[derive(Deserialize, Debug)]
+struct PhotonGeometry {
+    /// `[lon, lat]`
+    coordinates: (f64, f64),
+}
+#[derive(Deserialize, Debug)]
+struct PhotonProps {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    countrycode: Option<String>,
+}
+
+async fn photon_search(
+    client: &http::Client,
+    query: &str,
+    language: Option<&str>,
+) -> Result<Option<Place>> {
+    let url = format!(
+        "{}/api/?limit=10&q={}{}",
+        photon_base(),
+        urlencoding::encode(query),
+        language_param("lang", language)
+    );
+    osm_throttle().await;
+    let resp: PhotonResp = client.get_json(&url).await?;
+    // Photon has no country parameter, so filter client-side.
+    Ok(resp
+        .features
+        .into_iter()
+        .find(|f| {
+            f.properties
+                .countrycode
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(COUNTRY))
+        })
+        .map(|f| Place {
+            name: f.properties.name.unwrap_or_else(|| query.to_string()),
+            latitude: f.geometry.coordinates.1,
+            longitude: f.geometry.coordinates.0,
+            timezone: "auto".to_string(),
+            country_code: f.properties.countrycode.map(|c| c.to_uppercase()),
+            elevation: None,
+        }))
+}
+
+#[derive(Deserialize, Debug)]
+struct ReverseResp {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    address: BTreeMap<String, String>,
+}
+
+/// Name the nearest town to a coordinate using Nominatim reverse geocoding.
+pub async fn reverse_geocode(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    language: Option<&str>,
+) -> Result<String> {
+    let url = format!(
+        "{}/reverse?format=jsonv2&zoom=10&lat={lat}&lon={lon}{}",
+        nominatim_base(),
+        language_param("accept-language", language)
+    );
+    osm_throttle().await;
+    let resp: ReverseResp = client.get_json(&url).await?;
+    ["city", "town", "village", "hamlet", "municipality"]
+        .iter()
+        .find_map(|k| resp.address.get(*k).cloned())
+        .or(resp.name.filter(|n| !n.is_empty()))
+        .ok_or_else(|| anyhow!("no place name near ({lat}, {lon})"))
+}
+
+#[derive(Deserialize, Debug)]
+struct ElevationResp {
+    #[serde(default)]
+    elevation: Vec<f64>,
+}
+
+/// Terrain height at a coordinate from Open‑Meteo's elevation API, for
+/// places whose geocoder does not report one.
+pub async fn fetch_elevation(client: &http::Client, lat: f64, lon: f64) -> Result<Option<f64>> {
+    let url = format!(
+        "{}/elevation?latitude={lat}&longitude={lon}",
+        crate::forecast_base()
+    );
+    let resp: ElevationResp = client.get_json(&url).await?;
+    Ok(resp.elevation.first().copied())
+}
+
+/// Where a place resolved to, for the block header: `41.90, 12.50 · 21 m ·
+/// Europe/Rome`. Coordinates are left out when the header already shows
+/// them; unknown parts are skipped.
+pub fn location_details(place: &Place, coords: bool) -> String {
+    let mut parts = Vec::new();
+    if coords {
+        parts.push(crate::geo::format_coords(
+            place.latitude,
+            place.longitude,
+            2,
+        ));
+    }
+    if let Some(m) = place.elevation {
+        parts.push(format!("{m:.0} m"));
+    }
+    if !place.timezone.is_empty() && place.timezone != "auto" {
+        parts.push(place.timezone.clone());
+    }
+    parts.join(" · ")
+}
@@ -0,0 +1,47 @@
+//! Sharing fetches between nearby destinations.
+//!
+//! A vault with a note per neighborhood of one metro area asks the weather
+//! APIs for almost the same place over and over. With `grid-radius` set,
+//! each destination's weather is fetched for the centre of the grid cell it
+//! falls in instead of its own coordinates. Cells are `grid-radius` km on a
+//! side, so a destination is never more than about 0.7 × the radius from
+//! the point its weather comes from, and notes in the same cell with the
+//! same dates send identical requests that are made only once per run.
+//!
+//! Geocoding, coordinates written back, maps and the block header still use
+//! the destination itself; the approximation is logged with `-v`.
+
+use anyhow::{Result, anyhow};
+
+/// Length of one degree of latitude, km.
+const KM_PER_DEGREE: f64 = 111.32;
+
+/// Parse a cell size: `5`, `5km` or `2.5 km`.
+pub fn parse_radius(s: &str) -> Result<f64> {
+    let number = s.trim().trim_end_matches("km").trim_end();
+    match number.parse::<f64>() {
+        Ok(km) if km.is_finite() && km > 0.0 => Ok(km),
+        _ => Err(anyhow!(
+            "grid-radius must be a distance in km like 5km, got '{s}'"
+        )),
+    }
+}
+
+/// Canonical form, as accepted by [`parse_radius`].
+pub fn format_radius(km: f64) -> String {
+    format!("{km}km")
+}
+
+/// The centre of the `km`-wide grid cell holding (`lat`, `lon`), rounded
+/// to four decimals so every note in the cell sends the same request.
+pub fn snap(lat: f64, lon: f64, km: f64) -> (f64, f64) {
+    let round = |v: f64| (v * 1e4).round() / 1e4;
+    let lat_step = km / KM_PER_DEGREE;
+    let row = (lat / lat_step).floor();
+    let centre_lat = ((row + 0.5) * lat_step).clamp(-90.0, 90.0);
+    // Cells in a row share one width, so a row never straddles two sizes.
+    let lon_step = (lat_step / centre_lat.to_radians().cos().max(0.01)).min(360.0);
+    let centre_lon = ((lon / lon_step).floor() + 0.5) * lon_step;
+    let centre_lon = (centre_lon + 180.0).rem_euclid(360.0) - 180.0;
+    (round(centre_lat), round(centre_lon))
+}
@@ -0,0 +1,151 @@
+//! How each API behaved during the run.
+//!
+//! The shared HTTP client records every attempt here, per service (as
+//! [`budget::service`] names them): how many requests went out, how many
+//! were retries, how long they took and how the failures broke down. The
+//! summary printed at the end of a run says whether failures look like the
+//! user's configuration (requests the API rejected), their network
+//! (timeouts, connection errors) or the API itself (server errors, rate
+//! limiting).
+
+use crate::budget;
+use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// What kind of failure a request ended in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorClass {
+    /// The request timed out.
+    Timeout,
+    /// No response: DNS, connection refused or reset, TLS.
+    Network,
+    /// A 4xx other than 429: the request itself was wrong.
+    Rejected,
+    /// 429 Too Many Requests.
+    RateLimited,
+    /// A 5xx.
+    ServerError,
+    /// A response that could not be read or decoded.
+    BadResponse,
+}
+
+impl ErrorClass {
+    /// Class of a failed response's status.
+    pub fn of_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => ErrorClass::RateLimited,
+            StatusCode::REQUEST_TIMEOUT => ErrorClass::Timeout,
+            s if s.is_server_error() => ErrorClass::ServerError,
+            _ => ErrorClass::Rejected,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Network => "network error",
+            ErrorClass::Rejected => "rejected request (4xx)",
+            ErrorClass::RateLimited => "rate limited (429)",
+            ErrorClass::ServerError => "server error (5xx)",
+            ErrorClass::BadResponse => "bad response",
+        }
+    }
+
+    /// Who is most likely at fault for failures of this class.
+    pub fn blame(self) -> &'static str {
+        match self {
+            ErrorClass::Timeout | ErrorClass::Network => "your network",
+            ErrorClass::Rejected => "your configuration",
+            ErrorClass::RateLimited | ErrorClass::ServerError | ErrorClass::BadResponse => {
+                "the API"
+            }
+        }
+    }
+}
+
+/// One service's requests this run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ServiceHealth {
+    /// Attempts sent, retries included.
+    pub requests: u64,
+    /// Attempts that were retries of a failed one.
+    pub retries: u64,
+    /// Total time spent waiting for responses.
+    pub latency: Duration,
+    pub errors: BTreeMap<ErrorClass, u64>,
+}
+
+impl ServiceHealth {
+    pub fn average_latency(&self) -> Option<Duration> {
+        (self.requests > 0).then(|| self.latency / self.requests as u32)
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.errors.values().sum()
+    }
+}
+
+static HEALTH: Mutex<BTreeMap<String, ServiceHealth>> = Mutex::new(BTreeMap::new());
+
+/// Record one attempt at `url` (`attempt` counts from 1).
+pub fn record_attempt(url: &str, attempt: u32, elapsed: Duration) {
+    let mut health = HEALTH.lock().unwrap();
+    let entry = health.entry(budget::service(url)).or_default();
+    entry.requests += 1;
+    entry.retries += u64::from(attempt > 1);
+    entry.latency += elapsed;
+}
+
+/// Record a failed attempt at `url`.
+pub fn record_error(url: &str, class: ErrorClass) {
+    let mut health = HEALTH.lock().unwrap();
+    let entry = health.entry(budget::service(url)).or_default();
+    *entry.errors.entry(class).or_default() += 1;
+}
+
+/// Every service's health so far in this run.
+pub fn snapshot() -> BTreeMap<String, ServiceHealth> {
+    HEALTH.lock().unwrap().clone()
+}
+
+/// The end-of-run summary: one line per service, then where most failures
+/// point, if there were any.
+///
+/// ```text
+/// Provider health:
+///   open-meteo: 12 requests (1 retry), avg 240 ms, 1 server error (5xx)
+///   nominatim: 3 requests, avg 610 ms
+/// Most failures point at the API.
+/// ```
+pub fn summary(health: &BTreeMap<String, ServiceHealth>) -> String {
+    let mut out = String::from("Provider health:\n");
+    let mut by_class: BTreeMap<ErrorClass, u64> = BTreeMap::new();
+    for (service, h) in health {
+        let retries = match h.retries {
+            0 => String::new(),
+            1 => " (1 retry)".to_string(),
+            n => format!(" ({n} retries)"),
+        };
+        let mut parts = vec![format!("{} requests{retries}", h.requests)];
+        if let Some(avg) = h.average_latency() {
+            parts.push(format!("avg {} ms", avg.as_millis()));
+        }
+        for (class, n) in &h.errors {
+            parts.push(format!("{n} {}", class.label()));
+            *by_class.entry(*class).or_default() += n;
+        }
+        out.push_str(&format!("  {service}: {}\n", parts.join(", ")));
+    }
+    // Ties go to the class listed first, i.e. the user's side.
+    let worst = by_class
+        .iter()
+        .rev()
+        .max_by_key(|(_, n)| **n)
+        .map(|(class, _)| *class);
+    if let Some(class) = worst {
+        out.push_str(&format!("Most failures point at {}.\n", class.blame()));
+    }
+    out
+}
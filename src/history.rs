@@ -0,0 +1,233 @@
+//! An append-only log of everything fetched.
+//!
+//! With `history: global` every note written adds one JSON line to
+//! `$XDG_STATE_HOME/weather-helper/history.jsonl` (`history: per-note`
+//! keeps one `<note>.history.jsonl` beside each note instead): when it was
+//! fetched, the block's header and each day's high and low. Lines are never
+//! rewritten, so the log shows how a forecast for a given day moved as the
+//! trip approached, and can be compared with what was later observed.
+//!
+//! A log that has grown past `history-limit` is renamed to `<log>.1`
+//! (replacing an older one) before the next line is added, so at most about
+//! twice the limit is kept. `weather-helper history show` reads both.
+
+use crate::{PreparedNote, Units, io};
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Default `history-limit`: 5 MiB.
+pub const DEFAULT_LIMIT: u64 = 5 * 1024 * 1024;
+
+/// Where fetches are logged, if anywhere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HistoryMode {
+    #[default]
+    Off,
+    /// One log for every note.
+    Global,
+    /// A log beside each note.
+    PerNote,
+}
+
+impl HistoryMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "none" | "" => Ok(HistoryMode::Off),
+            "global" | "on" | "true" => Ok(HistoryMode::Global),
+            "per-note" | "note" => Ok(HistoryMode::PerNote),
+            other => Err(anyhow!(
+                "unknown history mode '{other}' (expected off, global or per-note)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`HistoryMode::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            HistoryMode::Off => "off",
+            HistoryMode::Global => "global",
+            HistoryMode::PerNote => "per-note",
+        }
+    }
+
+    /// The log a fetch for the note at `md_path` goes to.
+    pub fn log_path(self, md_path: &str) -> Option<PathBuf> {
+        match self {
+            HistoryMode::Off => None,
+            HistoryMode::Global => Some(global_path()),
+            HistoryMode::PerNote => Some(note_path(md_path)),
+        }
+    }
+}
+
+/// Parse a size such as `5MB`, `512k` or `1048576` (bytes); units are
+/// powers of 1024.
+pub fn parse_size(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num
+        .parse()
+        .map_err(|_| anyhow!("invalid size '{s}' (expected e.g. 5MB, 512KB)"))?;
+    let scale = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        other => return Err(anyhow!("unknown size unit '{other}' in '{s}'")),
+    };
+    Ok((value * scale as f64) as u64)
+}
+
+/// `5MB`, `300KB` or plain bytes, as [`parse_size`] reads them back.
+pub fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 && b % (1024 * 1024) == 0 => format!("{}MB", b / (1024 * 1024)),
+        b if b >= 1024 && b % 1024 == 0 => format!("{}KB", b / 1024),
+        b => format!("{b}"),
+    }
+}
+
+/// `$WEATHER_HELPER_HISTORY_FILE`, or
+/// `$XDG_STATE_HOME/weather-helper/history.jsonl` falling back to
+/// `~/.local/state`.
+pub fn global_path() -> PathBuf {
+    if let Some(path) = env::var_os("WEATHER_HELPER_HISTORY_FILE") {
+        return PathBuf::from(path);
+    }
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".local").join("state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("weather-helper")
+        .join("history.jsonl")
+}
+
+/// `Trips/Rome.md` → `Trips/Rome.history.jsonl`.
+pub fn note_path(md_path: &str) -> PathBuf {
+    let p = Path::new(md_path);
+    let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+    p.with_file_name(format!("{stem}.history.jsonl"))
+}
+
+/// `history.jsonl` → `history.jsonl.1`.
+pub fn rotated_path(log: &Path) -> PathBuf {
+    let mut name = log.as_os_str().to_owned();
+    name.push(".1");
+    PathBuf::from(name)
+}
+
+/// One fetch, as one line of the log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// When the data was fetched (RFC 3339).
+    pub fetched: String,
+    pub note: String,
+    pub city: String,
+    /// The block's header line.
+    pub label: String,
+    pub days: Vec<Day>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub tmax_c: f64,
+    pub tmin_c: f64,
+}
+
+pub fn entry(note: &PreparedNote, fetched: String) -> Entry {
+    Entry {
+        fetched,
+        note: note.meta.path.clone(),
+        city: note.meta.city.clone(),
+        label: note.label.clone(),
+        days: note
+            .data
+            .iter()
+            .map(|d| Day {
+                date: d.date,
+                tmax_c: d.tmax,
+                tmin_c: d.tmin,
+            })
+            .collect(),
+    }
+}
+
+/// Which entries of a log concern `note` (a path, or its end such as
+/// `Rome.md`).
+pub fn for_note<'a>(entries: &'a [Entry], note: &str) -> Vec<&'a Entry> {
+    let want = Path::new(note);
+    entries
+        .iter()
+        .filter(|e| Path::new(&e.note).ends_with(want) || want.ends_with(&e.note))
+        .collect()
+}
+
+/// Add `entry` to `log`, first rotating a log of `limit` bytes or more.
+pub fn append(log: &Path, entry: &Entry, limit: u64) -> Result<()> {
+    if let Some(dir) = log.parent().filter(|d| !d.as_os_str().is_empty()) {
+        io::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    }
+    if io::metadata(log).is_ok_and(|m| m.len() >= limit) {
+        io::rename_file(log, rotated_path(log))
+            .with_context(|| format!("cannot rotate {}", log.display()))?;
+    }
+    let line = serde_json::to_string(entry)? + "\n";
+    io::append(log, line).with_context(|| format!("cannot write {}", log.display()))
+}
+
+/// Every entry in `log` and its rotated predecessor, oldest first. Lines
+/// that do not parse (a write cut short) are skipped.
+pub fn read(log: &Path) -> Vec<Entry> {
+    [rotated_path(log), log.to_path_buf()]
+        .iter()
+        .filter_map(|path| io::read_to_string(path).ok())
+        .flat_map(|text| {
+            text.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect::<Vec<Entry>>()
+        })
+        .collect()
+}
+
+/// The `history show` listing: one line per fetch with each day's high and
+/// low, or with `date` one line per fetch of that day only, to see how its
+/// forecast moved.
+pub fn show(entries: &[&Entry], date: Option<NaiveDate>, units: Units) -> String {
+    let temp = |c: f64| match units {
+        Units::Celsius => format!("{c:.1}"),
+        Units::Fahrenheit => format!("{:.1}", crate::celcius_to_farenheit(c)),
+    };
+    let unit = match units {
+        Units::Celsius => "°C",
+        Units::Fahrenheit => "°F",
+    };
+    let mut out = String::new();
+    for entry in entries {
+        let days: Vec<String> = entry
+            .days
+            .iter()
+            .filter(|d| date.is_none_or(|want| d.date == want))
+            .map(|d| format!("{} {}/{}", d.date, temp(d.tmax_c), temp(d.tmin_c)))
+            .collect();
+        if days.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "{} {}: {} {unit}\n",
+            entry.fetched,
+            entry.note,
+            days.join(", ")
+        ));
+    }
+    if out.is_empty() {
+        out.push_str("No matching history.\n");
+    }
+    out
+}
@@ -0,0 +1,82 @@
+//! Public holidays at the destination.
+//!
+//! Holidays come from the Nager.Date API (no key) for the destination's
+//! country and are listed under the table, since closures matter as much as
+//! the weather when planning a day.
+
+use crate::http;
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::env;
+
+/// Gets the url for Nager.Date
+fn nager_base() -> String {
+    env::var("NAGER_BASE").unwrap_or_else(|_| "https://date.nager.at/api/v3".to_string())
+}
+
+/// One entry of `/PublicHolidays/{year}/{country}`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub local_name: String,
+    pub name: String,
+    /// `false` when only some regions observe it.
+    #[serde(default = "nationwide")]
+    pub global: bool,
+}
+
+fn nationwide() -> bool {
+    true
+}
+
+/// Holidays in `country` (ISO alpha-2) between `start` and `end` inclusive.
+pub async fn fetch_holidays(
+    client: &http::Client,
+    country: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<Holiday>> {
+    let mut found = Vec::new();
+    for year in start.year()..=end.year() {
+        let url = format!(
+            "{}/PublicHolidays/{year}/{}",
+            nager_base(),
+            urlencoding::encode(&country.to_ascii_uppercase())
+        );
+        let holidays: Vec<Holiday> = client.get_json(&url).await?;
+        found.extend(holidays);
+    }
+    Ok(during(found, start, end))
+}
+
+/// Holidays within `start..=end`, sorted, one entry per date and name.
+pub fn during(mut holidays: Vec<Holiday>, start: NaiveDate, end: NaiveDate) -> Vec<Holiday> {
+    holidays.retain(|h| (start..=end).contains(&h.date));
+    holidays.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.name.cmp(&b.name)));
+    holidays.dedup_by(|a, b| a.date == b.date && a.name == b.name);
+    holidays
+}
+
+/// `**Public holidays**` list, or empty when there are none.
+pub fn render_holidays(holidays: &[Holiday]) -> String {
+    if holidays.is_empty() {
+        return String::new();
+    }
+    let mut s = String::from("**Public holidays**\n\n");
+    for h in holidays {
+        let local = crate::width::isolate(&h.local_name);
+        let name = if h.local_name == h.name {
+            local
+        } else {
+            format!("{local} ({})", h.name)
+        };
+        let scope = if h.global { "" } else { ", some regions" };
+        s.push_str(&format!(
+            "- {} {name} — expect closures{scope}\n",
+            h.date.format("%b %-d")
+        ));
+    }
+    s
+}
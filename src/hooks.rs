@@ -0,0 +1,182 @@
+//! Commands run around note reads and writes, for encrypted or otherwise
+//! transformed vaults.
+//!
+//! `pre-read` runs instead of reading a note directly; its stdout is taken
+//! as the note's text (e.g. `gpg --decrypt "$1"`). `post-write` runs after a
+//! note has been written (e.g. re-encrypting it in place, or syncing a
+//! Cryptomator mount). Both get the note's path as `$1` and in
+//! `WEATHER_HELPER_NOTE`, and are run with `sh -c` (`cmd /C` on Windows).
+//! A hook that exits non-zero fails that note.
+//!
+//! `on-alert` runs after a note was written with [`crate::alerts`] that
+//! fired, with the alert lines in `WEATHER_HELPER_ALERTS` (e.g.
+//! `notify-send "Trip weather" "$WEATHER_HELPER_ALERTS"`). It cannot fail
+//! the note, which is already written; a failure is only logged.
+//!
+//! `after-note` runs once each note has been processed, whatever the
+//! outcome, and `after-run` once at the end of the run (e.g. syncing the
+//! vault, sending a chat message, rebuilding a static site). They get the
+//! outcome as JSON on stdin (see [`crate::outcome`]): one note's, with its
+//! path as `$1`, or the whole run's, with the vault root as `$1`. Like
+//! `on-alert`, their failures are only logged.
+//!
+//! Hooks come only from the command line, environment or config file, never
+//! from a note's frontmatter.
+
+use crate::io;
+use anyhow::{Result, anyhow};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::RwLock;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hooks {
+    pub pre_read: Option<String>,
+    pub post_write: Option<String>,
+    pub on_alert: Option<String>,
+    pub after_note: Option<String>,
+    pub after_run: Option<String>,
+}
+
+static HOOKS: RwLock<Hooks> = RwLock::new(Hooks {
+    pre_read: None,
+    post_write: None,
+    on_alert: None,
+    after_note: None,
+    after_run: None,
+});
+
+/// Set the hooks for every later note read and write in this process.
+pub fn set(hooks: Hooks) {
+    *HOOKS.write().unwrap() = hooks;
+}
+
+fn current() -> Hooks {
+    HOOKS.read().unwrap().clone()
+}
+
+/// Read a note, through the `pre-read` hook if one is set.
+pub fn read_note(path: &Path) -> Result<String> {
+    let Some(cmd) = current().pre_read else {
+        return Ok(io::read_to_string(path)?);
+    };
+    let output = run(
+        "pre-read",
+        &cmd,
+        path,
+        &note_env(path),
+        None,
+        Stdio::piped(),
+    )?;
+    String::from_utf8(output.stdout)
+        .map_err(|_| anyhow!("pre-read hook for {} printed invalid UTF-8", path.display()))
+}
+
+/// Write a note, then run the `post-write` hook if one is set.
+pub fn write_note(path: &Path, content: &str) -> Result<()> {
+    io::write(path, content)?;
+    if let Some(cmd) = current().post_write {
+        run(
+            "post-write",
+            &cmd,
+            path,
+            &note_env(path),
+            None,
+            Stdio::inherit(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Run the `on-alert` hook, if one is set, for `alerts` (one per line) in
+/// the note at `path`.
+pub fn notify_alerts(path: &Path, alerts: &str) {
+    if let Some(cmd) = current().on_alert {
+        let mut env = note_env(path);
+        env.push(("WEATHER_HELPER_ALERTS", alerts.into()));
+        if let Err(err) = run("on-alert", &cmd, path, &env, None, Stdio::inherit()) {
+            tracing::warn!(%err, "alert notification failed");
+        }
+    }
+}
+
+/// Run the `after-note` hook, if one is set, with the note's `outcome` JSON.
+pub fn after_note(path: &Path, outcome: &str) {
+    if let Some(cmd) = current().after_note {
+        let env = note_env(path);
+        if let Err(err) = run(
+            "after-note",
+            &cmd,
+            path,
+            &env,
+            Some(outcome),
+            Stdio::inherit(),
+        ) {
+            tracing::warn!(%err, "after-note hook failed");
+        }
+    }
+}
+
+/// Run the `after-run` hook, if one is set, with the run's `report` JSON.
+pub fn after_run(root: &Path, report: &str) {
+    if let Some(cmd) = current().after_run
+        && let Err(err) = run("after-run", &cmd, root, &[], Some(report), Stdio::inherit())
+    {
+        tracing::warn!(%err, "after-run hook failed");
+    }
+}
+
+fn note_env(path: &Path) -> Vec<(&'static str, std::ffi::OsString)> {
+    vec![("WEATHER_HELPER_NOTE", path.as_os_str().to_owned())]
+}
+
+fn run(
+    name: &str,
+    cmd: &str,
+    path: &Path,
+    env: &[(&'static str, std::ffi::OsString)],
+    input: Option<&str>,
+    stdout: Stdio,
+) -> Result<Output> {
+    let cannot = |e: std::io::Error| anyhow!("cannot run {name} hook '{cmd}': {e}");
+    let mut child = shell(cmd, path)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(match input {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(stdout)
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(cannot)?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A hook that ignores its input may exit before reading it all.
+        let _ = stdin.write_all(input.as_bytes());
+    }
+    let output = child.wait_with_output().map_err(cannot)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "{name} hook failed for {} ({}): {}",
+            path.display(),
+            output.status,
+            stderr.trim()
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(not(windows))]
+fn shell(cmd: &str, path: &Path) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(cmd).arg("weather-helper").arg(path);
+    command
+}
+
+#[cfg(windows)]
+fn shell(cmd: &str, _path: &Path) -> Command {
+    let mut command = Command::new("cmd");
+    command.arg("/C").arg(cmd);
+    command
+}
@@ -0,0 +1,525 @@
+//! Shared HTTP client used for every API call.
+//!
+//! Wraps a `reqwest::Client` so per-request settings (timeouts today) are
+//! configured once per run and applied to geocoding and weather fetches alike.
+//! Responses from providers that send validators (`ETag`, `Last-Modified`)
+//! can be kept on disk between runs and revalidated with conditional
+//! requests, so a refresh that finds nothing new costs a 304. Every attempt
+//! is recorded in [`health`] for the end-of-run provider summary.
+//!
+//! In polite mode (`--polite`, for community-hosted mirrors and shared
+//! networks) every clone of a client shares one pace: at most one request
+//! per [`POLITE_INTERVAL`], plus a random delay so runs started by cron on
+//! many machines do not arrive in lockstep.
+//!
+//! A client can also share responses: identical GETs within one run are
+//! answered from memory, which is what makes [`crate::grid`] snapping pay.
+
+use crate::health::{self, ErrorClass};
+use crate::io;
+use crate::{budget, metrics};
+use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
+use reqwest::header::{
+    ETAG, EXPIRES, HeaderMap, HeaderValue, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// Identifies the tool to APIs that require it (e.g. Nominatim).
+pub const USER_AGENT: &str = concat!(
+    "weather-helper/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/ckugel/weather-helper)"
+);
+
+/// Smallest gap between two requests in polite mode.
+pub const POLITE_INTERVAL: Duration = Duration::from_secs(1);
+/// Upper bound of the random delay added to each polite request.
+pub const POLITE_JITTER: Duration = Duration::from_millis(500);
+
+/// Largest response body accepted by default; bigger bodies are an error
+/// rather than an unbounded allocation.
+pub const MAX_BODY_BYTES: usize = 32 * 1024 * 1024;
+
+/// HTTP client with retry, timeout and response-size policy.
+#[derive(Clone, Debug)]
+pub struct Client {
+    inner: reqwest::Client,
+    max_body: usize,
+    /// Responses kept for [`Client::get_json_revalidated`], shared by clones.
+    cache: Arc<Mutex<HashMap<String, Cached>>>,
+    /// Where revalidated responses persist between runs, if anywhere.
+    cache_dir: Option<PathBuf>,
+    /// In polite mode, when the last request went out, shared by clones.
+    pace: Option<Arc<tokio::sync::Mutex<Option<tokio::time::Instant>>>>,
+    /// Bodies kept for [`Client::get_json`] when responses are shared,
+    /// shared by clones.
+    shared: Option<Arc<Mutex<Bodies>>>,
+}
+
+/// Response bodies by URL.
+type Bodies = HashMap<String, Vec<u8>>;
+
+/// A response body with its freshness metadata and validators.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Cached {
+    /// The request URL with credentials masked, for anyone inspecting the
+    /// cache directory.
+    url: String,
+    body: String,
+    expires: Option<SystemTime>,
+    last_modified: Option<String>,
+    etag: Option<String>,
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Client {
+            inner: reqwest::Client::default(),
+            max_body: MAX_BODY_BYTES,
+            cache: Arc::default(),
+            cache_dir: None,
+            pace: None,
+            shared: None,
+        }
+    }
+}
+
+impl Client {
+    /// Build a client; `timeout` bounds each individual request.
+    pub fn new(timeout: Option<Duration>) -> Result<Self> {
+        let mut builder = reqwest::Client::builder().user_agent(USER_AGENT);
+        if let Some(t) = timeout {
+            builder = builder.timeout(t);
+        }
+        let inner = builder.build().context("failed to build HTTP client")?;
+        Ok(Client {
+            inner,
+            max_body: MAX_BODY_BYTES,
+            cache: Arc::default(),
+            cache_dir: None,
+            pace: None,
+            shared: None,
+        })
+    }
+
+    /// Persist revalidated responses in `dir` so later runs can send
+    /// conditional requests.
+    pub fn with_cache_dir(mut self, dir: PathBuf) -> Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
+    /// Pace requests politely (see the module docs) when `polite` is set.
+    pub fn with_polite(mut self, polite: bool) -> Self {
+        self.pace = polite.then(Arc::default);
+        self
+    }
+
+    /// Answer repeated GETs for the same URL from memory for the life of
+    /// the client when `shared` is set.
+    pub fn with_shared(mut self, shared: bool) -> Self {
+        self.shared = shared.then(Arc::default);
+        self
+    }
+
+    /// Whether requests are paced politely.
+    pub fn is_polite(&self) -> bool {
+        self.pace.is_some()
+    }
+
+    /// In polite mode, wait until this client may send another request.
+    async fn wait_turn(&self) {
+        let Some(pace) = &self.pace else {
+            return;
+        };
+        let mut last = pace.lock().await;
+        let jitter = Duration::from_millis(fastrand::u64(0..=POLITE_JITTER.as_millis() as u64));
+        let earliest = last.map_or_else(tokio::time::Instant::now, |prev| prev + POLITE_INTERVAL);
+        tokio::time::sleep_until(earliest + jitter).await;
+        *last = Some(tokio::time::Instant::now());
+    }
+
+    /// Cap response bodies at `bytes` instead of [`MAX_BODY_BYTES`].
+    pub fn with_max_body(mut self, bytes: usize) -> Self {
+        self.max_body = bytes;
+        self
+    }
+
+    /// GET `url` and parse the JSON body, retrying transient failures.
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        if let Some(shared) = &self.shared
+            && let Some(body) = shared.lock().unwrap().get(url)
+        {
+            metrics::CACHE_HITS.inc();
+            tracing::debug!(url = %redact_url(url), "shared response");
+            return decode_json(body);
+        }
+        let (_, _, body) = self.send(url, HeaderMap::new()).await?;
+        let parsed = decode_json(&body)
+            .inspect_err(|_| health::record_error(url, ErrorClass::BadResponse))
+            .with_context(|| format!("bad response from {}", redact_url(url)))?;
+        if let Some(shared) = &self.shared {
+            shared.lock().unwrap().insert(url.to_string(), body);
+        }
+        Ok(parsed)
+    }
+
+    /// GET `url` and return the raw body, e.g. an image.
+    pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let (_, _, body) = self.send(url, HeaderMap::new()).await?;
+        Ok(body)
+    }
+
+    /// Like [`Client::get_json`], but honors the server's `Expires` header and
+    /// revalidates with `If-None-Match`/`If-Modified-Since`, as MET Norway's
+    /// terms require. A 304 counts as a cache hit.
+    ///
+    /// Responses are remembered for the lifetime of the client (one run), so
+    /// notes sharing a location do not refetch it, and in the cache directory
+    /// when one is set.
+    pub async fn get_json_revalidated<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let cached = self.cached(url);
+        if let Some(c) = &cached
+            && c.expires.is_some_and(|e| SystemTime::now() < e)
+        {
+            metrics::CACHE_HITS.inc();
+            tracing::debug!(url = %redact_url(url), "cache fresh");
+            return decode_json(c.body.as_bytes());
+        }
+        let mut headers = HeaderMap::new();
+        let validator =
+            |v: &Option<String>| v.as_deref().and_then(|v| HeaderValue::from_str(v).ok());
+        if let Some(tag) = cached.as_ref().and_then(|c| validator(&c.etag)) {
+            headers.insert(IF_NONE_MATCH, tag);
+        }
+        if let Some(since) = cached.as_ref().and_then(|c| validator(&c.last_modified)) {
+            headers.insert(IF_MODIFIED_SINCE, since);
+        }
+        let (status, resp_headers, body) = self.send(url, headers).await?;
+        let body = match (status, cached) {
+            (StatusCode::NOT_MODIFIED, Some(c)) => {
+                metrics::CACHE_HITS.inc();
+                tracing::debug!(url = %redact_url(url), "cache revalidated");
+                c.body
+            }
+            (StatusCode::NOT_MODIFIED, None) => {
+                return Err(anyhow!(
+                    "unexpected 304 Not Modified from {}",
+                    redact_url(url)
+                ));
+            }
+            _ => String::from_utf8_lossy(&body).into_owned(),
+        };
+        let header = |name| {
+            resp_headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let entry = Cached {
+            url: redact_url(url),
+            expires: header(EXPIRES).as_deref().and_then(parse_http_date),
+            last_modified: header(LAST_MODIFIED),
+            etag: header(ETAG),
+            body,
+        };
+        let parsed = decode_json(entry.body.as_bytes())
+            .inspect_err(|_| health::record_error(url, ErrorClass::BadResponse))
+            .with_context(|| format!("bad response from {}", redact_url(url)))?;
+        self.store(url, entry);
+        Ok(parsed)
+    }
+
+    /// The remembered response for `url`, from memory or the cache directory.
+    fn cached(&self, url: &str) -> Option<Cached> {
+        if let Some(c) = self.cache.lock().unwrap().get(url) {
+            return Some(c.clone());
+        }
+        let path = self.cache_dir.as_ref()?.join(cache_file_name(url));
+        let entry: Cached = serde_json::from_str(&io::read_to_string(path).ok()?).ok()?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), entry.clone());
+        Some(entry)
+    }
+
+    /// Remember a response; failing to persist it only costs a full refetch.
+    fn store(&self, url: &str, entry: Cached) {
+        if let Some(dir) = &self.cache_dir {
+            let written = io::create_dir_all(dir).and_then(|_| {
+                let json = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+                io::write(dir.join(cache_file_name(url)), json)
+            });
+            if let Err(err) = written {
+                tracing::warn!(%err, dir = %dir.display(), "could not persist HTTP cache entry");
+            }
+        }
+        self.cache.lock().unwrap().insert(url.to_string(), entry);
+    }
+
+    /// Send a GET with retries; returns the status, headers and body of the
+    /// first 2xx or 304 response.
+    #[tracing::instrument(name = "api_call", skip_all, fields(url = %redact_url(url)))]
+    async fn send(
+        &self,
+        url: &str,
+        headers: HeaderMap,
+    ) -> Result<(StatusCode, HeaderMap, Vec<u8>)> {
+        // Errors and logs only ever show the URL with credentials masked.
+        let shown = redact_url(url);
+        // Polite retries back off from the pacing interval, not 100ms.
+        let mut delay_ms = match self.is_polite() {
+            true => POLITE_INTERVAL.as_millis() as u64,
+            false => 100,
+        };
+        let attempts = 3;
+        for attempt in 1..=attempts {
+            if attempt > 1 {
+                metrics::API_RETRIES.inc();
+            }
+            self.wait_turn().await;
+            metrics::API_CALLS.inc();
+            budget::record(url);
+            let started = std::time::Instant::now();
+            let resp = self.inner.get(url).headers(headers.clone()).send().await;
+            metrics::REQUEST_LATENCY.observe(started.elapsed());
+            health::record_attempt(url, attempt, started.elapsed());
+            tracing::debug!(
+                attempt,
+                url = %shown,
+                status = resp.as_ref().ok().map(|r| r.status().as_u16()),
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "GET"
+            );
+            match resp {
+                Ok(r) => {
+                    let status = r.status();
+                    let resp_headers = r.headers().clone();
+                    let body = read_capped(r, self.max_body).await;
+                    tracing::trace!(bytes = body.as_ref().map_or(0, Vec::len), "read body");
+                    if status.is_success() || status == StatusCode::NOT_MODIFIED {
+                        if body.is_err() {
+                            health::record_error(url, ErrorClass::BadResponse);
+                        }
+                        let body = body.with_context(|| format!("failed to read body: {shown}"))?;
+                        return Ok((status, resp_headers, body));
+                    }
+                    metrics::API_FAILURES.inc();
+                    health::record_error(url, ErrorClass::of_status(status));
+                    let reason = body
+                        .ok()
+                        .and_then(|b| serde_json::from_slice::<Value>(&b).ok())
+                        .and_then(|v| api_error_reason(&v));
+                    tracing::warn!(attempt, %status, reason = reason.as_deref(), "request failed");
+                    // A 4xx with a reason is a bug in the request (e.g. an invalid
+                    // date range); retrying cannot fix it.
+                    if attempt == attempts || !is_retryable(status) {
+                        let err = match reason {
+                            Some(reason) => anyhow!("HTTP {status}, API said: '{reason}'"),
+                            None => anyhow!("HTTP {status}"),
+                        };
+                        let context = format!("request failed: {shown}");
+                        return Err(match is_retryable(status) {
+                            true => err.context(Unavailable(context)),
+                            false => err.context(context),
+                        });
+                    }
+                }
+                Err(e) => {
+                    metrics::API_FAILURES.inc();
+                    let class = match e.is_timeout() {
+                        true => ErrorClass::Timeout,
+                        false => ErrorClass::Network,
+                    };
+                    health::record_error(url, class);
+                    // The error's message would repeat the URL, credentials
+                    // included.
+                    let e = e.without_url();
+                    tracing::warn!(attempt, error = %e, "network error");
+                    if attempt == attempts {
+                        let kind = match class {
+                            ErrorClass::Timeout => "request timed out",
+                            _ => "network error",
+                        };
+                        return Err(anyhow!(e))
+                            .with_context(|| Unavailable(format!("{kind}: {shown}")));
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            delay_ms *= 2;
+        }
+        Err(anyhow!("unreachable retry loop"))
+    }
+}
+
+/// `$WEATHER_HELPER_CACHE_DIR`, or `$XDG_CACHE_HOME/weather-helper/http`
+/// falling back to `~/.cache`.
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("WEATHER_HELPER_CACHE_DIR") {
+        return PathBuf::from(dir);
+    }
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("weather-helper")
+        .join("http")
+}
+
+/// Cache entries are named by a hash of the URL, which may hold an API key.
+fn cache_file_name(url: &str) -> String {
+    format!("{:x}.json", Sha256::digest(url.as_bytes()))
+}
+
+/// Parse an HTTP date such as `Tue, 01 Jul 2025 10:00:00 GMT`.
+pub fn parse_http_date(s: &str) -> Option<SystemTime> {
+    chrono::DateTime::parse_from_rfc2822(s.trim())
+        .ok()
+        .map(SystemTime::from)
+}
+
+/// Read a response body chunk by chunk, failing once it exceeds `max` bytes
+/// so a misbehaving endpoint cannot exhaust memory.
+async fn read_capped(mut resp: reqwest::Response, max: usize) -> Result<Vec<u8>> {
+    if let Some(len) = resp.content_length()
+        && len > max as u64
+    {
+        return Err(anyhow!(
+            "response body of {len} bytes exceeds the {max} byte limit"
+        ));
+    }
+    let mut body = Vec::with_capacity(resp.content_length().unwrap_or(0) as usize);
+    while let Some(chunk) = resp.chunk().await.map_err(reqwest::Error::without_url)? {
+        if body.len() + chunk.len() > max {
+            return Err(anyhow!("response body exceeds the {max} byte limit"));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Query parameters whose values are credentials.
+const SECRET_PARAMS: &[&str] = &["key", "apikey", "api_key", "token", "access_token"];
+
+/// `url` with credential query values replaced by `***`, for logging.
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((k, _)) if SECRET_PARAMS.contains(&k.to_ascii_lowercase().as_str()) => {
+                format!("{k}=***")
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{base}?{}", query.join("&"))
+}
+
+/// Whether a failed status is worth retrying: server errors, request
+/// timeouts and rate limiting. Other client errors are returned immediately.
+pub fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Context on the error of a request that got no usable answer: no
+/// connection, a timeout, rate limiting or a server error.
+#[derive(Debug)]
+struct Unavailable(String);
+
+impl std::fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Whether `err` comes from a service that was down or out of reach (see
+/// [`is_retryable`]), rather than from a request the API rejected or a
+/// problem with the note itself.
+pub fn is_unavailable(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Unavailable>().is_some()
+}
+
+/// Extract the `reason` from an Open‑Meteo style `{"error": true, "reason": ...}` body.
+pub fn api_error_reason(body: &Value) -> Option<String> {
+    let obj = body.as_object()?;
+    if !obj.get("error").and_then(Value::as_bool).unwrap_or(false) {
+        return None;
+    }
+    let reason = obj
+        .get("reason")
+        .or_else(|| obj.get("message"))
+        .and_then(Value::as_str)
+        .unwrap_or("unspecified error");
+    Some(reason.to_string())
+}
+
+/// Just the error fields of an API body; everything else is skipped without
+/// being materialized.
+#[derive(Deserialize)]
+struct ErrorProbe {
+    #[serde(default)]
+    error: bool,
+    reason: Option<String>,
+    message: Option<String>,
+}
+
+/// Decode a JSON body into `T`, explaining unexpected payload shapes.
+///
+/// The body is decoded straight into `T` so large responses are not held
+/// twice. API error objects are detected first, and only when `T` does not
+/// fit is the body parsed into a raw value to produce a targeted message
+/// about renamed/missing fields instead of a bare serde error.
+pub fn decode_json<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    if let Ok(probe) = serde_json::from_slice::<ErrorProbe>(body)
+        && probe.error
+    {
+        let reason = probe
+            .reason
+            .or(probe.message)
+            .unwrap_or_else(|| "unspecified error".to_string());
+        return Err(anyhow!("API said: '{reason}'"));
+    }
+    if let Ok(parsed) = serde_json::from_slice(body) {
+        return Ok(parsed);
+    }
+    let value: Value = serde_json::from_slice(body).map_err(|e| {
+        let preview: String = String::from_utf8_lossy(body).chars().take(120).collect();
+        anyhow!("response is not valid JSON ({e}): {preview}")
+    })?;
+    if let Some(reason) = api_error_reason(&value) {
+        return Err(anyhow!("API said: '{reason}'"));
+    }
+    let keys = match &value {
+        Value::Object(map) => map.keys().cloned().collect::<Vec<_>>().join(", "),
+        other => format!("<{}>", json_kind(other)),
+    };
+    serde_json::from_value(value)
+        .map_err(|e| anyhow!("unexpected payload shape: {e} (top-level keys: {keys})"))
+}
+
+fn json_kind(v: &Value) -> &'static str {
+    match v {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
@@ -0,0 +1,215 @@
+//! Translations of the block's summary line.
+//!
+//! The catalog holds the summary line's words for each supported language.
+//! `summary-lang` picks one or more of them; with several (`en, it` for a
+//! family vault shared across languages) the line is written once per
+//! language, in that order, one under the other:
+//!
+//! ```text
+//! _5 days • High range 24° → 29° • Low range 15° → 18°_
+//! _5 giorni • Massime 24° → 29° • Minime 15° → 18°_
+//! ```
+//!
+//! Numbers are the same in every line; only the words change. Rain, wind
+//! and UV requested with `weather-vars` go at the end of each line:
+//! `_… • Rain 12.5 mm in total • Wind up to 35 km/h_`.
+
+use crate::columns::Measure;
+use anyhow::{Result, anyhow};
+
+/// The words of the summary line in one language.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Messages {
+    /// ISO 639-1 code.
+    pub lang: &'static str,
+    pub days: &'static str,
+    pub highs: &'static str,
+    pub lows: &'static str,
+    pub no_data: &'static str,
+    pub rain: &'static str,
+    pub rain_chance: &'static str,
+    pub wind: &'static str,
+    /// After a trip total: `Rain 12 mm in total`.
+    pub in_total: &'static str,
+    /// Before a trip maximum: `Wind up to 35 km/h`.
+    pub up_to: &'static str,
+}
+
+/// Every supported language; English first.
+pub const CATALOG: &[Messages] = &[
+    Messages {
+        lang: "en",
+        days: "days",
+        highs: "High range",
+        lows: "Low range",
+        no_data: "No data returned",
+        rain: "Rain",
+        rain_chance: "Rain chance",
+        wind: "Wind",
+        in_total: "in total",
+        up_to: "up to",
+    },
+    Messages {
+        lang: "it",
+        days: "giorni",
+        highs: "Massime",
+        lows: "Minime",
+        no_data: "Nessun dato ricevuto",
+        rain: "Pioggia",
+        rain_chance: "Probabilità di pioggia",
+        wind: "Vento",
+        in_total: "in totale",
+        up_to: "fino a",
+    },
+    Messages {
+        lang: "de",
+        days: "Tage",
+        highs: "Höchstwerte",
+        lows: "Tiefstwerte",
+        no_data: "Keine Daten erhalten",
+        rain: "Regen",
+        rain_chance: "Regenwahrscheinlichkeit",
+        wind: "Wind",
+        in_total: "insgesamt",
+        up_to: "bis zu",
+    },
+    Messages {
+        lang: "fr",
+        days: "jours",
+        highs: "Maximales",
+        lows: "Minimales",
+        no_data: "Aucune donnée reçue",
+        rain: "Pluie",
+        rain_chance: "Risque de pluie",
+        wind: "Vent",
+        in_total: "au total",
+        up_to: "jusqu'à",
+    },
+    Messages {
+        lang: "es",
+        days: "días",
+        highs: "Máximas",
+        lows: "Mínimas",
+        no_data: "No se recibieron datos",
+        rain: "Lluvia",
+        rain_chance: "Probabilidad de lluvia",
+        wind: "Viento",
+        in_total: "en total",
+        up_to: "hasta",
+    },
+    Messages {
+        lang: "pt",
+        days: "dias",
+        highs: "Máximas",
+        lows: "Mínimas",
+        no_data: "Nenhum dado recebido",
+        rain: "Chuva",
+        rain_chance: "Chance de chuva",
+        wind: "Vento",
+        in_total: "no total",
+        up_to: "até",
+    },
+    Messages {
+        lang: "nl",
+        days: "dagen",
+        highs: "Maxima",
+        lows: "Minima",
+        no_data: "Geen gegevens ontvangen",
+        rain: "Regen",
+        rain_chance: "Kans op regen",
+        wind: "Wind",
+        in_total: "in totaal",
+        up_to: "tot",
+    },
+];
+
+/// The catalog entry for `lang` (`it`, `it-IT`, `IT`).
+pub fn messages(lang: &str) -> Option<&'static Messages> {
+    let (lang, _) = crate::locale::parse_locale(lang)?;
+    CATALOG.iter().find(|m| m.lang == lang)
+}
+
+/// The figures a summary line reports, already formatted.
+#[derive(Clone, Copy, Debug)]
+pub struct Figures<'a> {
+    pub days: usize,
+    pub highs: (&'a str, &'a str),
+    pub lows: (&'a str, &'a str),
+    /// Trip totals or maxima of the measured variables, formatted.
+    pub measures: &'a [(Measure, String)],
+}
+
+/// `summary-lang` setting: the languages the summary line is written in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Languages(Vec<&'static Messages>);
+
+impl Default for Languages {
+    fn default() -> Self {
+        Languages(vec![&CATALOG[0]])
+    }
+}
+
+impl Languages {
+    /// Parse `en`, `en, it` or `en+it`.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut langs = Vec::new();
+        for code in s
+            .split([',', '+', '/'])
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+        {
+            let m = messages(code).ok_or_else(|| {
+                let known: Vec<&str> = CATALOG.iter().map(|m| m.lang).collect();
+                anyhow!(
+                    "unknown summary language '{code}' (expected {})",
+                    known.join(", ")
+                )
+            })?;
+            if !langs.contains(&m) {
+                langs.push(m);
+            }
+        }
+        if langs.is_empty() {
+            return Err(anyhow!("summary-lang needs at least one language"));
+        }
+        Ok(Languages(langs))
+    }
+
+    /// Canonical form, as accepted by [`Languages::parse`].
+    pub fn name(&self) -> String {
+        let codes: Vec<&str> = self.0.iter().map(|m| m.lang).collect();
+        codes.join(", ")
+    }
+
+    /// The summary line in every language, one per line; `None` figures
+    /// mean there was no data.
+    pub fn summary(&self, figures: Option<Figures>) -> String {
+        let line = |m: &Messages| match figures {
+            Some(f) => {
+                let measures: String = f
+                    .measures
+                    .iter()
+                    .map(|(measure, value)| {
+                        let label = match measure {
+                            Measure::Rain => m.rain,
+                            Measure::RainChance => m.rain_chance,
+                            Measure::Wind => m.wind,
+                            Measure::Uv => "UV",
+                        };
+                        match measure.is_total() {
+                            true => format!(" • {label} {value} {}", m.in_total),
+                            false => format!(" • {label} {} {value}", m.up_to),
+                        }
+                    })
+                    .collect();
+                format!(
+                    "_{} {} • {} {}° → {}° • {} {}° → {}°{measures}_",
+                    f.days, m.days, m.highs, f.highs.0, f.highs.1, m.lows, f.lows.0, f.lows.1
+                )
+            }
+            None => format!("_{}_", m.no_data),
+        };
+        let lines: Vec<String> = self.0.iter().map(|m| line(m)).collect();
+        lines.join("  \n")
+    }
+}
@@ -0,0 +1,206 @@
+//! All file reads and writes go through here.
+//!
+//! Writes are atomic: the new contents go to a hidden temp file next to the
+//! target (unique per process and call, so concurrent notes never share one),
+//! take over the existing file's permissions, are synced according to the
+//! `fsync` setting, and are then renamed over the target. A crash or a full
+//! disk leaves either the old note or the new one, never half of each.
+
+use anyhow::{Result, anyhow};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+/// `fsync` setting: how hard a write tries to reach the disk before the
+/// rename makes it visible.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Leave flushing to the OS.
+    Off,
+    /// Sync the new file's contents before renaming it into place.
+    #[default]
+    File,
+    /// Also sync the directory, so the rename itself survives a power cut.
+    Full,
+}
+
+impl SyncPolicy {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "none" => Ok(SyncPolicy::Off),
+            "file" | "on" | "true" => Ok(SyncPolicy::File),
+            "full" => Ok(SyncPolicy::Full),
+            other => Err(anyhow!(
+                "unknown fsync policy '{other}' (expected off, file or full)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`SyncPolicy::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            SyncPolicy::Off => "off",
+            SyncPolicy::File => "file",
+            SyncPolicy::Full => "full",
+        }
+    }
+}
+
+static SYNC: AtomicU8 = AtomicU8::new(SyncPolicy::File as u8);
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Set the sync policy for every later write in this process.
+pub fn set_sync_policy(policy: SyncPolicy) {
+    SYNC.store(policy as u8, Ordering::Relaxed);
+}
+
+pub fn sync_policy() -> SyncPolicy {
+    match SYNC.load(Ordering::Relaxed) {
+        0 => SyncPolicy::Off,
+        2 => SyncPolicy::Full,
+        _ => SyncPolicy::File,
+    }
+}
+
+pub fn read_to_string(path: impl AsRef<Path>) -> std::io::Result<String> {
+    fs::read_to_string(path)
+}
+
+pub fn read(path: impl AsRef<Path>) -> std::io::Result<Vec<u8>> {
+    fs::read(path)
+}
+
+pub fn read_dir(path: impl AsRef<Path>) -> std::io::Result<fs::ReadDir> {
+    fs::read_dir(path)
+}
+
+pub fn create_dir_all(path: impl AsRef<Path>) -> std::io::Result<()> {
+    fs::create_dir_all(path)
+}
+
+pub fn metadata(path: impl AsRef<Path>) -> std::io::Result<fs::Metadata> {
+    fs::metadata(path)
+}
+
+pub fn remove_file(path: impl AsRef<Path>) -> std::io::Result<()> {
+    fs::remove_file(path)
+}
+
+/// A fresh hidden path next to `path` for staging its new contents.
+///
+/// The extension is kept (`.Rome.weather-helper-123-4.png`) for writers that
+/// pick a format from it.
+pub fn temp_path(path: &Path) -> PathBuf {
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut name = format!(".{stem}.weather-helper-{}-{n}", std::process::id());
+    if let Some(ext) = path.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    path.with_file_name(name)
+}
+
+/// Atomically replace `path` with `contents`.
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let tmp = temp_path(path);
+    let written = File::create(&tmp).and_then(|mut f| {
+        f.write_all(contents.as_ref())?;
+        if sync_policy() != SyncPolicy::Off {
+            f.sync_all()?;
+        }
+        Ok(())
+    });
+    match written.and_then(|_| persist(&tmp, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// Create `path` with `contents`, failing if it already exists.
+pub fn write_new(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_ref())?;
+    if sync_policy() != SyncPolicy::Off {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Append `contents` to `path`, creating it if needed.
+///
+/// Unlike [`write`] this is not atomic: a crash can leave a partial last
+/// line, which readers of append-only logs must skip.
+pub fn append(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path.as_ref())?;
+    file.write_all(contents.as_ref())?;
+    if sync_policy() != SyncPolicy::Off {
+        file.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Rename `from` to `to`, replacing `to` if it exists.
+pub fn rename_file(from: impl AsRef<Path>, to: impl AsRef<Path>) -> std::io::Result<()> {
+    rename(from.as_ref(), to.as_ref())
+}
+
+/// Move a finished temp file over `path`, keeping `path`'s permissions
+/// (the mode bits on Unix, the read-only flag on Windows).
+pub fn persist(tmp: &Path, path: &Path) -> std::io::Result<()> {
+    if let Ok(existing) = fs::metadata(path) {
+        fs::set_permissions(tmp, existing.permissions())?;
+    }
+    rename(tmp, path)?;
+    if sync_policy() == SyncPolicy::Full {
+        sync_dir(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn rename(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::rename(from, to)
+}
+
+/// Windows replaces the target on rename, but refuses while another process
+/// (a sync client, the editor's indexer) briefly holds it open; retry a few
+/// times before giving up.
+#[cfg(windows)]
+fn rename(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut attempt = 0;
+    loop {
+        match fs::rename(from, to) {
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied && attempt < 5 => {
+                attempt += 1;
+                std::thread::sleep(std::time::Duration::from_millis(20 * attempt));
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn sync_dir(path: &Path) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
+    File::open(dir)?.sync_all()
+}
+
+/// Directory handles cannot be synced outside Unix; the rename is as
+/// durable as the platform makes it.
+#[cfg(not(unix))]
+fn sync_dir(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
@@ -7,57 +7,183 @@
 //! See README for usage. The binary crate calls `run`.
 
 use anyhow::{Context, Result, anyhow};
-use chrono::{Datelike, Duration, Local, NaiveDate};
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
 use plotters::prelude::{
     BLACK, BLUE, BitMapBackend, ChartBuilder, IntoDrawingArea, LineSeries, PathElement, RED, WHITE,
 };
 use plotters::style::Color;
 use regex::Regex;
 use serde::Deserialize;
-use serde::de::DeserializeOwned;
 use serde_yaml::Value as YamlValue;
-use std::{env, fs, path::Path};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+pub mod advisories;
+pub mod airports;
+pub mod alerts;
+pub mod budget;
+pub mod clock;
+pub mod columns;
+pub mod compare;
+pub mod config;
+pub mod dates;
+pub mod day_property;
+pub mod destination_info;
+pub mod frontmatter;
+pub mod geo;
+pub mod geocoder;
+pub mod grid;
+pub mod health;
+pub mod history;
+pub mod holidays;
+pub mod hooks;
+pub mod http;
+pub mod i18n;
+pub mod io;
+pub mod locale;
+pub mod map;
+pub mod markers;
+pub mod metrics;
+pub mod migrate;
+pub mod outcome;
+pub mod outline;
+pub mod output;
+pub mod overlaps;
+pub mod overview;
+pub mod packing;
+pub mod plan;
+pub mod priority;
+pub mod provider;
+pub mod range_planner;
+pub mod recurrence;
+pub mod roadtrip;
+pub mod route;
+pub mod schema;
+pub mod secrets;
+pub mod separate;
+pub mod shortcode;
+pub mod sidecar;
+pub mod simulate;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod stale;
+pub mod stats;
+pub mod tags;
+pub mod telemetry;
+pub mod templates;
+pub mod terminal;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod tz;
+pub mod validate;
+pub mod variables;
+pub mod width;
+pub mod window;
+
+pub use geocoder::{Geocoder, Place};
+pub use outcome::{NoteOutcome, NoteResult, RunReport};
+
+/// Daily variables behind the temperature table.
+const TEMPERATURES: &[&str] = &["temperature_2m_max", "temperature_2m_min"];
 
 /// Metadata extracted from a note's YAML frontmatter.
-#[derive(Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct NoteMeta {
     pub city: String,
+    /// IATA/ICAO code from an `airport:` key.
+    pub airport: Option<String>,
+    /// Explicit `lat`/`lon` from the frontmatter; skips geocoding.
+    pub coords: Option<(f64, f64)>,
+    /// Explicit `timezone:` from the frontmatter.
+    pub timezone: Option<String>,
     pub arrival: NaiveDate,
     pub departure: NaiveDate,
+    /// Time of day from `arrival: 2025-08-20T22:30`, if given.
+    pub arrival_time: Option<NaiveTime>,
+    /// Time of day from `departure: 2025-08-25T09:15`, if given.
+    pub departure_time: Option<NaiveTime>,
     pub path: String,
+    /// Repeat schedule for regular trips; dates above are the first occurrence.
+    pub recurrence: Option<recurrence::Recurrence>,
+    /// Non-ISO date formats that were assumed while parsing, for reporting.
+    pub assumed_formats: Vec<String>,
+    /// `overlap-ok: true` exempts the trip from overlap warnings.
+    pub overlap_ok: bool,
+    /// `weather-pin: true` freezes the note's block as it is; updates skip
+    /// the note.
+    pub pinned: bool,
+    /// Per-note settings from `weather-<key>` frontmatter, keyed without the prefix.
+    pub overrides: BTreeMap<String, String>,
+    /// `trip-type:`, e.g. `roadtrip`.
+    pub trip_type: Option<String>,
+    /// Places along the way from a `route:` (or `waypoints:`) list.
+    pub waypoints: Vec<route::Waypoint>,
+    /// Where the trip sets off from, from `origin:`.
+    pub origin: Option<route::Waypoint>,
+    /// Destinations still being chosen between, from `candidates:`.
+    pub candidates: Vec<route::Waypoint>,
+    /// With `flexible: true`, the trip's length in days; `arrival` and
+    /// `departure` are then the window it may fall in.
+    pub flexible: Option<usize>,
+    /// `tags:` without the leading `#`.
+    pub tags: Vec<String>,
 }
 
-#[derive(Deserialize, Debug)]
-struct GeocodeResp {
-    results: Option<Vec<GeoItem>>,
-}
-#[derive(Deserialize, Debug)]
-struct GeoItem {
-    latitude: f64,
-    longitude: f64,
-    #[serde(default)]
-    timezone: Option<String>,
-}
-
+/// Raw `daily` arrays; Open‑Meteo reports missing values as `null`.
 #[derive(Deserialize, Debug)]
 pub struct DailyTemps {
     pub time: Vec<String>,
-    pub temperature_2m_max: Vec<f64>,
-    pub temperature_2m_min: Vec<f64>,
+    pub temperature_2m_max: Vec<Option<f64>>,
+    pub temperature_2m_min: Vec<Option<f64>>,
 }
 #[derive(Deserialize, Debug)]
 pub struct ForecastResp {
+    /// The model grid point the API answered for.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
     pub daily: Option<DailyTemps>,
 }
 
-/// Single day of temperatures (Celsius).
-#[derive(Clone, Debug, PartialEq)]
+impl ForecastResp {
+    /// Remember where the API answered a request for (`lat`, `lon`) from
+    /// (see [`geo::grid_point`]).
+    fn record_grid_point(&self, lat: f64, lon: f64) {
+        if let (Some(glat), Some(glon)) = (self.latitude, self.longitude) {
+            geo::record_grid_point((lat, lon), (glat, glon));
+        }
+    }
+
+    /// Whether any day has both a high and a low.
+    pub fn has_temperatures(&self) -> bool {
+        self.daily.as_ref().is_some_and(|d| {
+            d.temperature_2m_max
+                .iter()
+                .zip(&d.temperature_2m_min)
+                .any(|(max, min)| max.is_some() && min.is_some())
+        })
+    }
+}
+
+/// Single day of temperatures (Celsius), with the precipitation, wind and
+/// UV figures `weather-vars` asked for (see [`columns::Measure`]).
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct DayTemp {
     pub date: NaiveDate,
     pub tmax: f64,
     pub tmin: f64,
     pub tmax_f: f64,
     pub tmin_f: f64,
+    /// Precipitation sum (mm).
+    pub precipitation: Option<f64>,
+    /// Highest chance of precipitation (%).
+    pub precipitation_probability: Option<f64>,
+    /// Highest wind speed (km/h).
+    pub wind_speed: Option<f64>,
+    /// Highest UV index.
+    pub uv_index: Option<f64>,
 }
 
 /// Summary of the dataset for presentation.
@@ -68,116 +194,568 @@ pub struct Summary {
     pub note: String,
 }
 
+/// Annotation for a partial travel day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DayNote {
+    pub text: String,
+    /// Whether the day's weather is counted in the summary.
+    pub counted: bool,
+}
+
 /// 9/5 AKA celsius conversion rate
 const CONVERSION_RATE_CF: f64 = 9.0 / 5.0;
 
+/// Runs updating more notes than this ask first (see
+/// [`RunOptions::max_notes`]), in case the root is not the vault it was
+/// meant to be.
+pub const DEFAULT_MAX_NOTES: usize = 100;
+
+/// Arriving at or after this hour means the arrival day's weather hardly matters.
+const LATE_ARRIVAL_HOUR: u32 = 20;
+/// Leaving before this hour means the departure day's weather hardly matters.
+const EARLY_DEPARTURE_HOUR: u32 = 8;
+
 /// The height for images TODO: make configurable in the future
 const IMAGE_WIDTH: u32 = 640;
 const IMAGE_HEIGHT: u32 = 320;
 
-/// Gets the url for the geocode
-fn geocode_base() -> String {
-    env::var("OPEN_METEO_GEOCODE_BASE")
-        .unwrap_or_else(|_| "https://geocoding-api.open-meteo.com/v1".to_string())
-}
 /// gets the url for the forecast
-fn forecast_base() -> String {
+pub(crate) fn forecast_base() -> String {
     env::var("OPEN_METEO_FORECAST_BASE")
         .unwrap_or_else(|_| "https://api.open-meteo.com/v1".to_string())
 }
 
 /// Gets the url for the archive
-fn archive_base() -> String {
+pub(crate) fn archive_base() -> String {
     env::var("OPEN_METEO_ARCHIVE_BASE")
         .unwrap_or_else(|_| "https://archive-api.open-meteo.com/v1".to_string())
 }
 
 /// Helper function that takes in celsius and returns fahrenheit
-fn celcius_to_farenheit(temp_c: f64) -> f64 {
-    return temp_c * CONVERSION_RATE_CF + 32.0;
-}
-
-async fn get_json_with_retry<T: DeserializeOwned>(url: &str) -> Result<T> {
-    let mut delay_ms = 100u64;
-    let attempts = 3;
-    for attempt in 1..=attempts {
-        let resp = reqwest::get(url).await;
-        match resp {
-            Ok(r) => match r.error_for_status() {
-                Ok(ok) => {
-                    let parsed = ok
-                        .json::<T>()
-                        .await
-                        .with_context(|| format!("failed to parse JSON from {url}"))?;
-                    return Ok(parsed);
-                }
-                Err(e) => {
-                    if attempt == attempts {
-                        return Err(anyhow!(e)).with_context(|| format!("request failed: {url}"));
-                    }
-                }
-            },
-            Err(e) => {
-                if attempt == attempts {
-                    return Err(anyhow!(e)).with_context(|| format!("network error: {url}"));
-                }
+pub(crate) fn celcius_to_farenheit(temp_c: f64) -> f64 {
+    temp_c * CONVERSION_RATE_CF + 32.0
+}
+
+pub(crate) fn farenheit_to_celcius(temp_f: f64) -> f64 {
+    (temp_f - 32.0) / CONVERSION_RATE_CF
+}
+
+/// Knobs for a single run, usually set from CLI flags.
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    /// Upper bound for each individual HTTP request.
+    pub timeout: Option<std::time::Duration>,
+    /// Source of the current time and local zone; `None` is the system
+    /// clock (see [`clock`]).
+    pub clock: Option<std::sync::Arc<dyn clock::Clock>>,
+    /// Pretend it is this day when planning fetches and picking notes, for
+    /// reproducible runs and bug reports; `None` is the clock's date.
+    pub today: Option<NaiveDate>,
+    /// Pace API requests for shared networks and community-hosted
+    /// mirrors (see [`http::POLITE_INTERVAL`]).
+    pub polite: bool,
+    /// Fetch weather for the centre of a grid cell this many km across, so
+    /// nearby destinations share requests (see [`grid`]); `None` is off.
+    pub grid_radius: Option<f64>,
+    /// Upper bound for the whole run; notes not started by then are reported
+    /// as not attempted.
+    pub deadline: Option<std::time::Duration>,
+    /// Most notes a run updates without confirmation; `None` is
+    /// [`DEFAULT_MAX_NOTES`], `Some(0)` no limit.
+    pub max_notes: Option<usize>,
+    /// Update more than `max_notes` notes without asking (`--yes`).
+    pub yes: bool,
+    /// Asked with the note count and root before a run updates more than
+    /// `max_notes` notes; `None` refuses such runs.
+    pub confirm: Option<fn(usize, &str) -> bool>,
+    /// Backend used to resolve city names.
+    pub geocoder: Geocoder,
+    /// Language for geocoded place names (ISO 639-1, e.g. `de`).
+    pub language: Option<String>,
+    /// Record geocoded `lat`/`lon`/`timezone` in the note's frontmatter so
+    /// later runs skip geocoding.
+    pub write_coords: bool,
+    /// Output tier; `Quiet` prints errors only.
+    pub verbosity: telemetry::Verbosity,
+    /// Whether status lines are colored.
+    pub color: output::ColorChoice,
+    /// Unit for the summary lines; the table always shows both.
+    pub units: Units,
+    /// Languages of the summary line (see [`i18n`]).
+    pub summary_lang: i18n::Languages,
+    /// Extra Open‑Meteo daily variables shown as table columns (see
+    /// [`columns`]).
+    pub vars: Vec<String>,
+    /// Forecast source.
+    pub provider: provider::Provider,
+    /// Open‑Meteo weather model to request instead of the best match.
+    pub model: Option<String>,
+    /// Add a packing checklist to notes that do not have one yet.
+    pub packing: bool,
+    /// YAML file replacing the built-in packing rules.
+    pub packing_rules: Option<std::path::PathBuf>,
+    /// Add suggested clothing counts to the block summary.
+    pub outfit: bool,
+    /// YAML file replacing the built-in outfit rules.
+    pub outfit_rules: Option<std::path::PathBuf>,
+    /// Sun and hydration advisories, with their thresholds; `None` is off.
+    pub advisories: Option<advisories::Thresholds>,
+    /// Zone the header's clock difference is measured from; `None` is the
+    /// system's local zone.
+    pub home_timezone: Option<chrono_tz::Tz>,
+    /// Say in the block when the clocks change at the destination during
+    /// the trip.
+    pub dst_note: bool,
+    /// List public holidays at the destination below the table.
+    pub holidays: bool,
+    /// Add a destination facts footer (plugs, emergency number, tap water).
+    pub facts: bool,
+    /// Show where the forecast point is: an OpenStreetMap link, optionally
+    /// with an embedded static map image.
+    pub map: map::MapMode,
+    /// Show the resolved coordinates, elevation and timezone in the header.
+    pub show_location: bool,
+    /// Pad table cells so columns line up by display width in the raw file.
+    pub align_tables: bool,
+    /// Link note paths in terminal output and reports to Obsidian
+    /// (`obsidian://open?path=...`).
+    pub links: bool,
+    /// Also write the daily data to `<note>.weather.json` and link it.
+    pub sidecar: bool,
+    /// Also write the days as arrays into this frontmatter property (see
+    /// [`day_property`]).
+    pub day_property: Option<String>,
+    /// Put the block in its own `Weather - <city> <Mon YYYY>` note and only
+    /// embed it in the trip note.
+    pub separate_note: bool,
+    /// Keep the weather section inside this heading's section, e.g.
+    /// `## Logistics` (see [`upsert_weather_block_under`]).
+    pub under_heading: Option<String>,
+    /// What a note shows when its update fails.
+    pub on_error: OnError,
+    /// How often a note is fetched again (see [`stale::Refresh`]).
+    pub refresh: stale::Refresh,
+    /// Only process notes with one of these tags (or tags nested under
+    /// them); empty means every note.
+    pub tags: Vec<String>,
+    /// Only process trips under way or arriving within this many days.
+    pub only_upcoming: Option<u32>,
+    /// Skip trips that ended more than this many days ago; `None` means
+    /// [`priority::DEFAULT_RETENTION_DAYS`].
+    pub retention: Option<u32>,
+    /// Refresh past trips regardless of `retention`.
+    pub include_past: bool,
+    /// How hard writes try to reach the disk before replacing a file.
+    pub fsync: io::SyncPolicy,
+    /// Command whose output is used as a note's text (see [`hooks`]).
+    pub pre_read: Option<String>,
+    /// Command run after each note is written (see [`hooks`]).
+    pub post_write: Option<String>,
+    /// Days that should stand out (see [`alerts`]).
+    pub alert_if: Option<alerts::Rules>,
+    /// Command run for each note with alerts (see [`hooks`]).
+    pub on_alert: Option<String>,
+    /// Command run with each processed note's outcome (see [`hooks`]).
+    pub after_note: Option<String>,
+    /// Command run with the whole run's outcome (see [`hooks`]).
+    pub after_run: Option<String>,
+    /// Whether blocks carry their days as JSON (see [`sidecar`]).
+    pub embed_data: bool,
+    /// How the day table is written (see [`shortcode`]).
+    pub table_style: shortcode::TableStyle,
+    /// What makes days good for a flexible trip (see [`window`]).
+    pub window_score: window::Score,
+    /// Where each fetch is logged (see [`history`]).
+    pub history: history::HistoryMode,
+    /// Size at which a history log is rotated, in bytes; `None` is
+    /// [`history::DEFAULT_LIMIT`].
+    pub history_limit: Option<u64>,
+}
+
+impl RunOptions {
+    /// Make the process-wide settings (fsync policy, note hooks) take effect.
+    pub fn install(&self) {
+        io::set_sync_policy(self.fsync);
+        hooks::set(hooks::Hooks {
+            pre_read: self.pre_read.clone(),
+            post_write: self.post_write.clone(),
+            on_alert: self.on_alert.clone(),
+            after_note: self.after_note.clone(),
+            after_run: self.after_run.clone(),
+        });
+    }
+
+    /// The `max_notes` limit, if any.
+    pub fn max_notes(&self) -> Option<usize> {
+        match self.max_notes.unwrap_or(DEFAULT_MAX_NOTES) {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// Size at which history logs are rotated.
+    pub fn history_limit(&self) -> u64 {
+        self.history_limit.unwrap_or(history::DEFAULT_LIMIT)
+    }
+
+    /// The clock runs read the time from.
+    pub fn clock(&self) -> std::sync::Arc<dyn clock::Clock> {
+        self.clock
+            .clone()
+            .unwrap_or_else(|| std::sync::Arc::new(clock::SystemClock))
+    }
+
+    /// The current time, for fetch stamps and the age of stale data.
+    pub fn now(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        self.clock().now()
+    }
+
+    /// The day runs plan around: [`RunOptions::today`] if set, else the
+    /// clock's date.
+    pub fn today(&self) -> NaiveDate {
+        self.today.unwrap_or_else(|| self.clock().today())
+    }
+
+    /// The HTTP client for a run with these options.
+    pub fn client(&self) -> Result<http::Client> {
+        Ok(http::Client::new(self.timeout)?
+            .with_cache_dir(http::cache_dir())
+            .with_polite(self.polite)
+            .with_shared(self.grid_radius.is_some()))
+    }
+
+    /// These options with a note's `weather-<key>` frontmatter applied.
+    pub fn for_note(&self, meta: &NoteMeta) -> Result<RunOptions> {
+        let mut opts = self.clone();
+        for (key, value) in &meta.overrides {
+            if !config::NOTE_KEYS.contains(&key.as_str()) {
+                return Err(anyhow!(
+                    "'weather-{key}' cannot be set per note (allowed: {})",
+                    config::NOTE_KEYS.join(", ")
+                ));
             }
+            config::apply(&mut opts, key, value)
+                .with_context(|| format!("invalid 'weather-{key}'"))?;
         }
-        tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
-        delay_ms *= 2;
+        Ok(opts)
     }
-    Err(anyhow!("unreachable retry loop"))
 }
 
-pub async fn run(root: &str) -> Result<()> {
-    let mut notes = vec![];
-    for entry in walkdir::WalkDir::new(root)
+/// `on-error` setting: what a note shows when its update fails.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnError {
+    /// Leave the note as it was.
+    Keep,
+    /// Keep the earlier data and add a banner saying how old it is, when
+    /// the service could not be reached; otherwise like `Keep`.
+    #[default]
+    Stale,
+    /// Replace the block with a one-line warning and the reason.
+    Block,
+}
+
+impl OnError {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" => Ok(OnError::Keep),
+            "stale" => Ok(OnError::Stale),
+            "block" => Ok(OnError::Block),
+            other => Err(anyhow!(
+                "unknown on-error mode '{other}' (expected keep, stale or block)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`OnError::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            OnError::Keep => "keep",
+            OnError::Stale => "stale",
+            OnError::Block => "block",
+        }
+    }
+}
+
+/// Temperature unit used in summaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Units {
+    #[default]
+    Fahrenheit,
+    Celsius,
+}
+
+impl Units {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "f" | "fahrenheit" | "imperial" => Ok(Units::Fahrenheit),
+            "c" | "celsius" | "metric" => Ok(Units::Celsius),
+            other => Err(anyhow!(
+                "unknown units '{other}' (expected fahrenheit or celsius)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`Units::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Units::Fahrenheit => "fahrenheit",
+            Units::Celsius => "celsius",
+        }
+    }
+}
+
+/// Parse a human duration such as `500ms`, `10s`, `5m` or `1h` (bare numbers are seconds).
+pub fn parse_duration(s: &str) -> Result<std::time::Duration> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num
+        .parse()
+        .map_err(|_| anyhow!("invalid duration '{s}' (expected e.g. 10s, 5m)"))?;
+    let secs = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        "h" => value * 3600.0,
+        "d" => value * 86400.0,
+        "w" => value * 7.0 * 86400.0,
+        _ => return Err(anyhow!("unknown duration unit '{unit}' in '{s}'")),
+    };
+    std::time::Duration::try_from_secs_f64(secs).map_err(|_| anyhow!("duration '{s}' is too long"))
+}
+
+/// Markdown files under `root`, sorted so runs visit notes and report
+/// results in a stable order.
+pub fn note_paths(root: &str) -> Vec<std::path::PathBuf> {
+    walkdir::WalkDir::new(root)
+        .sort_by_file_name()
         .into_iter()
         .filter_map(|e| e.ok())
         .filter(|e| e.file_type().is_file())
-    {
-        if let Some(ext) = entry.path().extension() {
-            if ext == "md" {
-                match extract_meta(entry.path()) {
-                    Ok(meta) => notes.push(meta),
-                    Err(e) => eprintln!(
-                        "Failed to extract metadata from {}: {e}",
-                        entry.path().display()
-                    ),
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "md"))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Keep the notes a run on `today` refreshes (by tag, `only-upcoming` and
+/// retention), most urgent first. Returns how many were dropped as long
+/// past.
+pub fn select_notes(notes: &mut Vec<NoteMeta>, opts: &RunOptions, today: NaiveDate) -> usize {
+    notes.retain(|n| tags::selected(&n.tags, &opts.tags));
+    if let Some(days) = opts.only_upcoming {
+        notes.retain(|n| priority::is_upcoming(n, today, days));
+    }
+    let before = notes.len();
+    if !opts.include_past {
+        let days = opts.retention.unwrap_or(priority::DEFAULT_RETENTION_DAYS);
+        notes.retain(|n| !priority::is_long_past(n, today, days));
+    }
+    priority::sort_by_urgency(notes, today);
+    before - notes.len()
+}
+
+/// Update every selected note under `root`, printing a status line per
+/// note as it goes. Whether the run succeeded is up to the caller, see
+/// [`RunReport::into_result`].
+#[tracing::instrument(skip_all, fields(root = %root))]
+pub async fn run(root: &str, opts: &RunOptions) -> Result<RunReport> {
+    opts.install();
+    // `serve` runs again and again in one process.
+    alerts::clear();
+    tz::clear();
+    let client = opts.client()?;
+    let deadline = opts.deadline.map(|d| tokio::time::Instant::now() + d);
+    let printer = output::Printer::new(opts.verbosity, opts.color).with_links(opts.links);
+    let mut report = RunReport::default();
+    let mut record = |path: &str, tags: &[String], outcome: NoteOutcome| {
+        printer.status(outcome.status(), path, outcome.detail());
+        report.push(path, tags, outcome);
+    };
+    let mut notes = vec![];
+    for path in note_paths(root) {
+        match extract_meta(&path) {
+            Ok(meta) => {
+                if opts.verbosity.shows_progress() {
+                    for assumed in &meta.assumed_formats {
+                        println!("{}: {assumed}", meta.path);
+                    }
                 }
+                notes.push(meta)
             }
+            Err(e) => record(
+                &path.display().to_string(),
+                &[],
+                NoteOutcome::Skipped(e.to_string()),
+            ),
         }
     }
 
+    let today = opts.today();
+    let skipped = select_notes(&mut notes, opts, today);
+    if skipped > 0 && opts.verbosity.shows_progress() {
+        let days = opts.retention.unwrap_or(priority::DEFAULT_RETENTION_DAYS);
+        println!(
+            "Skipped {skipped} trip(s) that ended more than {days} days ago (--include-past to refresh them)."
+        );
+    }
     if notes.is_empty() {
-        println!("No packing notes with city/arrival/departure found.");
-        return Ok(());
+        if opts.verbosity.shows_progress() {
+            println!("No packing notes with city/arrival/departure found.");
+        }
+        hooks::after_run(Path::new(root), &report.to_json().to_string());
+        return Ok(report);
     }
 
-    let mut had_error = false;
-    for note in notes {
-        match process_note(&note).await {
-            Ok(_) => println!("Updated weather: {}", note.path),
+    let overlaps = match opts.verbosity.shows_progress() {
+        true => overlaps::find_overlaps(&notes, today),
+        false => Vec::new(),
+    };
+    for o in overlaps {
+        eprintln!(
+            "Warning: {} overlaps {} ({} → {})",
+            o.first, o.second, o.from, o.to
+        );
+    }
+
+    // A run given a single note updates it whatever its `refresh`.
+    let named = Path::new(root).is_file();
+    let due = notes
+        .iter()
+        .filter(|n| !n.pinned && (named || not_due(n, opts).is_none()))
+        .count();
+    // Shown even with `-q`, on stderr: it is how a run over the wrong
+    // folder is noticed, and stdout may be a report.
+    eprintln!("Updating {due} note(s) under {root}.");
+    if let Some(limit) = opts.max_notes()
+        && due > limit
+        && !opts.yes
+        && !opts.confirm.is_some_and(|ask| ask(due, root))
+    {
+        return Err(anyhow!(
+            "refusing to update {due} notes under {root}, more than max-notes ({limit}); \
+             check the folder, then pass --yes or raise max-notes"
+        ));
+    }
+    // Index of the first note the deadline left unattempted.
+    let mut stopped_at = None;
+    for (i, note) in notes.iter().enumerate() {
+        if deadline.is_some_and(|d| tokio::time::Instant::now() >= d) {
+            stopped_at = Some(i);
+            break;
+        }
+        if !named && let Some(why) = not_due(note, opts) {
+            record(&note.path, &note.tags, NoteOutcome::Skipped(why));
+            continue;
+        }
+        // `prepare_note` only fetches (a map image is kept in memory) and every
+        // file write in `update_note` comes after its last await point, so a
+        // deadline can only cancel a note before anything is written.
+        let calls_before = metrics::API_CALLS.get();
+        let updated = match deadline {
+            Some(d) => tokio::time::timeout_at(d, update_note(note, &client, opts))
+                .await
+                .ok(),
+            None => Some(update_note(note, &client, opts).await),
+        };
+        let city = if note.city.is_empty() {
+            &note.path
+        } else {
+            &note.city
+        };
+        budget::record_city(city, metrics::API_CALLS.get() - calls_before);
+        // Cancelled before anything was written, so the note is left alone
+        // (no `on-error` banner) and counts as not attempted.
+        let Some(updated) = updated else {
+            stopped_at = Some(i);
+            break;
+        };
+        let outcome = match updated {
+            Ok(outcome) => {
+                metrics::NOTES_PROCESSED.inc();
+                outcome
+            }
             Err(e) => {
-                eprintln!("Skipping {}: {e}", note.path);
-                had_error = true;
+                metrics::NOTES_FAILED.inc();
+                let error = format!("{e:#}");
+                let note_opts = opts.for_note(note).unwrap_or_else(|_| opts.clone());
+                let now = opts.now();
+                // Earlier data is only kept as "stale" when the service was
+                // out of reach; any other failure would fail again next run.
+                let written = match note_opts.on_error {
+                    OnError::Stale if http::is_unavailable(&e) => {
+                        write_stale_banner(note, &note_opts, now).map(|_| ())
+                    }
+                    OnError::Keep | OnError::Stale => Ok(()),
+                    OnError::Block => write_error_block(note, &note_opts, now.date_naive(), &error),
+                };
+                if let Err(err) = written {
+                    tracing::warn!(%err, path = %note.path, "could not mark the failed note");
+                }
+                NoteOutcome::Failed(error)
             }
-        }
+        };
+        let result = NoteResult {
+            path: note.path.clone(),
+            tags: note.tags.clone(),
+            outcome,
+        };
+        hooks::after_note(Path::new(&note.path), &result.to_json().to_string());
+        record(&result.path, &result.tags, result.outcome);
     }
-
-    if had_error {
+    if let Some(i) = stopped_at {
+        for rest in &notes[i..] {
+            let why = "not attempted (deadline reached)".to_string();
+            record(&rest.path, &rest.tags, NoteOutcome::Skipped(why));
+        }
         eprintln!(
-            "One or more notes could not be updated due to errors. Please check the log above."
+            "Run deadline reached; {} of {} notes not attempted.",
+            notes.len() - i,
+            notes.len()
         );
-        std::process::exit(1);
+        report.deadline_reached = true;
     }
 
-    Ok(())
+    let by_tag = report.by_tag();
+    if opts.verbosity.shows_progress() && by_tag.iter().any(|(tags, _)| !tags.is_empty()) {
+        print!("{}", tags::report(&by_tag));
+    }
+
+    if opts.verbosity.shows_progress() {
+        print!("{}", alerts::summary(&alerts::snapshot()));
+        for (path, warning) in tz::snapshot() {
+            eprintln!("Warning: {path}: {warning}");
+        }
+    }
+
+    let calls = budget::run_calls();
+    if !calls.is_empty() {
+        match budget::finish(opts.clock().today()) {
+            Ok(usage) if opts.verbosity.shows_progress() => {
+                println!("{}", budget::report(&calls));
+                for warning in usage.warnings() {
+                    eprintln!("Warning: {warning}");
+                }
+            }
+            Ok(_) => {}
+            Err(err) => tracing::warn!(%err, "could not save request usage"),
+        }
+        for (city, n) in budget::city_calls() {
+            tracing::debug!(city, requests = n, "requests per city");
+        }
+        if opts.verbosity.shows_progress() {
+            print!("{}", health::summary(&health::snapshot()));
+        }
+    }
+
+    hooks::after_run(Path::new(root), &report.to_json().to_string());
+    Ok(report)
 }
 
 /// Read the YAML frontmatter and extract required fields.
 pub fn extract_meta(path: &Path) -> Result<NoteMeta> {
-    let text = fs::read_to_string(path)?;
+    let text = hooks::read_note(path)?;
     let re = Regex::new(r"(?s)^---\s*(.*?)\s*---").unwrap();
     let caps = re
         .captures(&text)
@@ -185,220 +763,1596 @@ pub fn extract_meta(path: &Path) -> Result<NoteMeta> {
     let yaml_str = caps.get(1).unwrap().as_str();
 
     let yaml: YamlValue = serde_yaml::from_str(yaml_str)?;
-    let city = yaml
-        .get("city-place")
+    let airport = yaml
+        .get("airport")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("missing 'city-place'"))?
-        .trim()
-        .to_string();
-
-    let duration = yaml
-        .get("estimated-days")
+        .map(|s| s.trim().to_string());
+    let coord = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|k| yaml.get(*k))
+            .and_then(|v| v.as_f64().or_else(|| v.as_str()?.trim().parse().ok()))
+    };
+    let coords = match (coord(["lat", "latitude"]), coord(["lon", "longitude"])) {
+        (Some(lat), Some(lon)) => Some((lat, lon)),
+        (None, None) => None,
+        _ => return Err(anyhow!("'lat' and 'lon' must be given together")),
+    };
+    let timezone = yaml
+        .get("timezone")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("missing 'city-place'"))?
-        .trim()
-        .to_string();
+        .map(|s| s.trim().to_string());
+    let waypoints = |keys: &[&str]| -> Vec<route::Waypoint> {
+        keys.iter()
+            .find_map(|k| yaml.get(*k))
+            .and_then(|v| v.as_sequence())
+            .into_iter()
+            .flatten()
+            .filter_map(route::Waypoint::parse)
+            .collect()
+    };
+    let candidates = waypoints(&["candidates"]);
+    let city = ["city-place", "city_place", "city"]
+        .iter()
+        .find_map(|k| yaml.get(*k).and_then(|v| v.as_str()))
+        .map(|c| c.trim().to_string())
+        .or_else(|| airport.clone())
+        .or_else(|| coords.map(|_| String::new()))
+        .or_else(|| (!candidates.is_empty()).then(String::new))
+        .ok_or_else(|| anyhow!("missing 'city-place'"))?;
 
-    if duration.contains("-1") {
+    // `estimated-days: -1` marks a placeholder note that should be skipped.
+    let duration = yaml.get("estimated-days").map(|v| match v {
+        YamlValue::Number(n) => n.to_string(),
+        other => other.as_str().unwrap_or_default().trim().to_string(),
+    });
+    if duration.as_deref() == Some("-1") {
         return Err(anyhow!("duration is -1, skipping entry"));
     }
 
+    // A flexible trip is fetched for its whole window.
+    let flexible = match yaml.get("flexible").and_then(|v| v.as_bool()) {
+        Some(true) => Some(
+            duration
+                .as_deref()
+                .and_then(|d| d.parse::<usize>().ok())
+                .filter(|days| *days > 0)
+                .ok_or_else(|| anyhow!("flexible trips need 'estimated-days'"))?,
+        ),
+        _ => None,
+    };
+    let (arrival_key, departure_key) = match flexible {
+        Some(_) => ("window-start", "window-end"),
+        None => ("arrival", "departure"),
+    };
     let arrival_str = yaml
-        .get("arrival")
+        .get(arrival_key)
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("missing 'arrival' (YYYY-MM-DD)"))?;
+        .ok_or_else(|| anyhow!("missing '{arrival_key}' (YYYY-MM-DD)"))?;
     let departure_str = yaml
-        .get("departure")
+        .get(departure_key)
         .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow!("missing 'departure' (YYYY-MM-DD)"))?;
+        .ok_or_else(|| anyhow!("missing '{departure_key}' (YYYY-MM-DD)"))?;
 
-    let arrival =
-        NaiveDate::parse_from_str(arrival_str, "%Y-%m-%d").context("arrival must be YYYY-MM-DD")?;
-    let departure = NaiveDate::parse_from_str(departure_str, "%Y-%m-%d")
-        .context("departure must be YYYY-MM-DD")?;
+    let order = match yaml.get("date-order").and_then(|v| v.as_str()) {
+        Some(o) => dates::DateOrder::parse(o)?,
+        None => dates::DateOrder::default(),
+    };
+    let arrival = dates::parse_trip_date(arrival_str, order)
+        .with_context(|| format!("invalid '{arrival_key}'"))?;
+    let departure = dates::parse_trip_date(departure_str, order)
+        .with_context(|| format!("invalid '{departure_key}'"))?;
+    let assumed_formats = [
+        (arrival_key, arrival_str, &arrival),
+        (departure_key, departure_str, &departure),
+    ]
+    .into_iter()
+    .filter(|(_, _, parsed)| !parsed.is_iso())
+    .map(|(key, raw, parsed)| format!("{key} '{raw}' read as {}", parsed.format))
+    .collect();
+
+    let repeat = yaml
+        .get("recurrence")
+        .and_then(|v| v.as_str())
+        .map(recurrence::Recurrence::parse)
+        .transpose()
+        .context("invalid 'recurrence'")?;
 
     Ok(NoteMeta {
         city,
-        arrival,
-        departure,
+        airport,
+        coords,
+        timezone,
+        arrival: arrival.date,
+        departure: departure.date,
+        arrival_time: arrival.time,
+        departure_time: departure.time,
         path: path.to_string_lossy().to_string(),
+        recurrence: repeat,
+        assumed_formats,
+        overlap_ok: yaml
+            .get("overlap-ok")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        pinned: yaml
+            .get("weather-pin")
+            .is_some_and(|v| config::yaml_scalar(v).eq_ignore_ascii_case("true")),
+        overrides: yaml
+            .as_mapping()
+            .into_iter()
+            .flatten()
+            .filter_map(|(k, v)| {
+                let key = k.as_str()?.strip_prefix("weather-")?;
+                // A pin belongs to the note, not its settings.
+                (key != "pin").then(|| (key.to_string(), config::yaml_scalar(v)))
+            })
+            .chain(
+                // `provider:`/`model:`/`alert-if:`/`refresh:` are shorthands;
+                // `weather-` wins.
+                ["provider", "model", "alert-if", "refresh"]
+                    .into_iter()
+                    .filter_map(|key| Some((key.to_string(), config::yaml_scalar(yaml.get(key)?)))),
+            )
+            // `units: metric`, `locale: de-DE` and the like, weakest of all.
+            .chain(locale::hints(&yaml))
+            .fold(BTreeMap::new(), |mut map, (k, v)| {
+                map.entry(k).or_insert(v);
+                map
+            }),
+        trip_type: yaml
+            .get("trip-type")
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_ascii_lowercase()),
+        waypoints: waypoints(&["route", "waypoints"]),
+        origin: yaml.get("origin").and_then(route::Waypoint::parse),
+        candidates,
+        flexible,
+        tags: tags::parse_tags(&yaml),
     })
 }
 
+impl NoteMeta {
+    /// Whether `trip-type` marks a road trip.
+    pub fn is_roadtrip(&self) -> bool {
+        matches!(
+            self.trip_type.as_deref(),
+            Some("roadtrip" | "road-trip" | "road trip")
+        )
+    }
+
+    /// Whether the note compares `candidates:` rather than naming a
+    /// destination.
+    pub fn is_comparison(&self) -> bool {
+        !self.candidates.is_empty()
+            && self.city.is_empty()
+            && self.airport.is_none()
+            && self.coords.is_none()
+    }
+
+    /// The trip as it applies on `today`: recurring trips move to their next
+    /// occurrence that has not ended yet, others are returned unchanged.
+    pub fn occurrence_on(&self, today: NaiveDate) -> NoteMeta {
+        let mut meta = self.clone();
+        if let Some((arrival, departure)) = self
+            .recurrence
+            .as_ref()
+            .and_then(|r| r.next_occurrence(self.arrival, self.departure, today))
+        {
+            meta.arrival = arrival;
+            meta.departure = departure;
+        }
+        meta
+    }
+}
+
+/// Everything a note's block is built from, fetched but not yet written.
+#[derive(Clone, Debug)]
+pub struct PreparedNote {
+    /// The note as processed: the current occurrence of a recurring trip.
+    pub meta: NoteMeta,
+    /// Run options with the note's `weather-<key>` overrides applied.
+    pub opts: RunOptions,
+    pub place: Place,
+    /// Header line, e.g. `Forecast for Rome 2025-10-01 → 2025-10-05`.
+    pub label: String,
+    pub data: Vec<DayTemp>,
+    pub notes: BTreeMap<NaiveDate, DayNote>,
+    /// Lines after the summary (outfit, advisories, map, ...).
+    pub extra: Vec<String>,
+    /// Markdown sections below the table (route, holidays, facts, ...).
+    pub sections: Vec<String>,
+    /// Where the "today" divider goes in a trip under way.
+    pub divider: Option<NaiveDate>,
+    pub trip: Option<packing::Trip>,
+    /// The note's current text.
+    pub content: String,
+    /// With `separate-note`, the weather note's path and current text
+    /// (empty if it does not exist yet).
+    pub separate: Option<(PathBuf, String)>,
+    /// Forecast days crossing `alert-if`, also shown in [`Self::extra`].
+    pub alerts: Vec<alerts::Alert>,
+    /// The `vars` columns of the day table.
+    pub columns: Vec<columns::Column>,
+    /// A `map: image` download embedded in [`Self::sections`], saved by
+    /// [`Self::write`].
+    pub map_image: Option<(PathBuf, Vec<u8>)>,
+}
+
+impl PreparedNote {
+    /// The weather block as it is written into the note.
+    pub fn block(&self) -> String {
+        let opts = &self.opts;
+        let summary = block_summary_in(&self.data, &self.notes, opts.units, &opts.summary_lang);
+        let table = shortcode::render(
+            opts.table_style,
+            &self.data,
+            &self.notes,
+            opts.units,
+            self.divider,
+            &self.columns,
+        );
+        let mut block = assemble_block(&self.label, summary, &self.extra, &table);
+        for section in self.sections.iter().filter(|s| !s.is_empty()) {
+            let end_marker = block.rfind(markers::END).unwrap_or(block.len());
+            block.insert_str(end_marker, &format!("\n{section}"));
+        }
+        if opts.embed_data {
+            let end_marker = block.rfind(markers::END).unwrap_or(block.len());
+            block.insert_str(end_marker, &format!("{}\n", sidecar::comment(self)));
+        }
+        if opts.align_tables {
+            block = width::align_tables(&block);
+        }
+        stale::stamp(&block, opts.now())
+    }
+
+    /// The block for the trip note itself: the weather block, or with
+    /// `separate-note` an embed of the weather note.
+    pub fn trip_block(&self) -> String {
+        match self.separate {
+            Some(_) => separate::embed_block(&self.meta),
+            None => self.block(),
+        }
+    }
+
+    /// The note's text with the block, written-back coordinates and packing
+    /// checklist applied, as `process_note` would write it.
+    pub fn updated_content(&self) -> Result<String> {
+        let (meta, opts) = (&self.meta, &self.opts);
+        let mut content = self.content.clone();
+        upsert_weather_block_under(
+            &mut content,
+            &self.trip_block(),
+            opts.under_heading.as_deref(),
+        )?;
+        if opts.write_coords && meta.coords.is_none() && meta.airport.is_none() {
+            content = frontmatter::set_keys(&content, &coord_keys(&self.place, meta))?;
+        }
+        if let Some(key) = &opts.day_property {
+            content = day_property::apply(&content, key, &self.data, opts.units)?;
+        }
+        if opts.packing
+            && !packing::has_checklist(&content)
+            && let Some(trip) = &self.trip
+        {
+            let rules = packing::load_rules(opts.packing_rules.as_deref())?;
+            let items = packing::checklist(&rules, trip)?;
+            packing::insert_once(&mut content, &packing::render_checklist(&items));
+        }
+        let frontmatter = opts.write_coords || opts.day_property.is_some();
+        outline::check_untouched(&self.content, &content, frontmatter)?;
+        Ok(content)
+    }
+
+    /// Whether writing would change more than the fetch stamps.
+    pub fn changes_note(&self) -> Result<bool> {
+        let same = |old: &str, new: &str| stale::without_stamp(old) == stale::without_stamp(new);
+        if let Some((_, text)) = &self.separate {
+            let mut updated = text.clone();
+            upsert_weather_block(&mut updated, &self.block())?;
+            if !same(text, &updated) {
+                return Ok(true);
+            }
+        }
+        Ok(!same(&self.content, &self.updated_content()?))
+    }
+
+    /// Write the updated note, its map image, chart and, with `sidecar`, its
+    /// data file.
+    pub fn write(&self) -> Result<()> {
+        if let Some((path, bytes)) = &self.map_image {
+            map::save_image(path, bytes)?;
+        }
+        if let Some((path, text)) = &self.separate {
+            let mut text = text.clone();
+            upsert_weather_block(&mut text, &self.block())?;
+            hooks::write_note(path, &text)?;
+        }
+        hooks::write_note(Path::new(&self.meta.path), &self.updated_content()?)?;
+        if let Some(log) = self.opts.history.log_path(&self.meta.path) {
+            let entry = history::entry(self, self.opts.now().to_rfc3339());
+            if let Err(err) = history::append(&log, &entry, self.opts.history_limit()) {
+                tracing::warn!(%err, "could not log fetch history");
+            }
+        }
+        if self.opts.sidecar {
+            let data = sidecar::build(self, self.opts.now().to_rfc3339());
+            let path = sidecar::sidecar_path(&self.meta.path);
+            io::write(&path, serde_json::to_string_pretty(&data)? + "\n")
+                .with_context(|| format!("cannot write {}", path.display()))?;
+        }
+        save_chart(&self.data, &self.meta.path)
+    }
+}
+
+/// How a pinned note is reported.
+pub const PINNED: &str = "pinned (weather-pin: true)";
+
 /// Process a single note: geocode, fetch data, summarize, and update file.
-pub async fn process_note(meta: &NoteMeta) -> Result<()> {
-    let (lat, lon, tz) = geocode(&meta.city).await?;
-    let today = Local::now().date_naive();
-    let forecast_horizon = today + Duration::days(16);
+#[tracing::instrument(name = "note", skip_all, fields(path = %meta.path, city = %meta.city))]
+pub async fn process_note(
+    meta: &NoteMeta,
+    client: &http::Client,
+    opts: &RunOptions,
+) -> NoteOutcome {
+    update_note(meta, client, opts)
+        .await
+        .unwrap_or_else(|e| NoteOutcome::Failed(format!("{e:#}")))
+}
+
+/// [`process_note`] with the error kept, so `run` can tell a service that
+/// was down from any other failure.
+async fn update_note(
+    meta: &NoteMeta,
+    client: &http::Client,
+    opts: &RunOptions,
+) -> Result<NoteOutcome> {
+    if meta.pinned {
+        return Ok(NoteOutcome::Skipped(PINNED.to_string()));
+    }
+    let changed = match meta.is_comparison() {
+        true => compare::process(meta, client, opts).await?,
+        false => {
+            let prepared = prepare_note(meta, client, opts).await?;
+            let blocks = count_weather_blocks(&prepared.content);
+            if blocks > 1 {
+                tracing::warn!(blocks, "merging duplicate weather sections into one");
+            }
+            let changed = prepared.changes_note()?;
+            prepared.write()?;
+            if !prepared.alerts.is_empty() {
+                alerts::record(&meta.path, &prepared.alerts);
+                let lines: Vec<String> = prepared.alerts.iter().map(|a| a.to_string()).collect();
+                hooks::notify_alerts(Path::new(&meta.path), &lines.join("\n"));
+            }
+            changed
+        }
+    };
+    Ok(match changed {
+        true => NoteOutcome::Updated,
+        false => NoteOutcome::Unchanged,
+    })
+}
+
+/// Geocode, fetch and assemble everything for a note's block without
+/// writing anything.
+pub async fn prepare_note(
+    meta: &NoteMeta,
+    client: &http::Client,
+    opts: &RunOptions,
+) -> Result<PreparedNote> {
+    if meta.is_comparison() {
+        return Err(anyhow!(
+            "no destination chosen yet; the candidates are only compared in the note"
+        ));
+    }
+    let today = opts.today();
+    let meta = &meta.occurrence_on(today);
+    let opts = &opts.for_note(meta)?;
+    let language = opts.language.as_deref();
+    let place = resolve_location(client, meta, opts.geocoder, language).await?;
+    let (lat, lon, tz) = (place.latitude, place.longitude, place.timezone.clone());
+    // Weather comes from the grid point; everything else uses the place.
+    let (wlat, wlon) = match opts.grid_radius {
+        Some(km) => {
+            let point = grid::snap(lat, lon, km);
+            tracing::debug!(
+                place = %place.name,
+                lat = point.0,
+                lon = point.1,
+                off = %geo::format_distance(geo::distance_km((lat, lon), point)),
+                "approximating the destination by its grid point"
+            );
+            point
+        }
+        None => (lat, lon),
+    };
+    let forecast_horizon = range_planner::horizon(today);
 
     let start = meta.arrival.min(meta.departure);
     let end = meta.arrival.max(meta.departure);
 
-    let (data, label) = if start <= forecast_horizon {
-        let s = start.max(today);
-        let e = end.min(forecast_horizon);
-        let temps = fetch_daily(&lat, &lon, &s, &e, &tz).await?;
-        (temps, format!("Forecast {} → {}", s, e))
-    } else {
-        let last_year = start.year() - 1;
-        let s = NaiveDate::from_ymd_opt(last_year, start.month(), start.day())
-            .ok_or_else(|| anyhow!("bad start date"))?;
-        let e = NaiveDate::from_ymd_opt(last_year, end.month(), end.day())
-            .ok_or_else(|| anyhow!("bad end date"))?;
-        let temps = fetch_archive(&lat, &lon, &s, &e, &tz).await?;
-        (temps, format!("Historic (proxy) {} → {}", s, e))
-    };
-
-    let summary = summarize(&data);
-    let table = render_table(&data);
-    let block = format!(
-        "## Weather Forecast\n<!-- WEATHER:BEGIN -->\n**{}**  \n**Range**: {} / {}  \n\n{}\n\n{}\n<!-- WEATHER:END -->\n",
-        label, summary.max, summary.min, summary.note, table
+    let model = opts.model.as_deref();
+    let source = opts
+        .provider
+        .source(model, lat, lon, place.country_code.as_deref())?;
+    use range_planner::FetchKind;
+    let ranges = range_planner::plan_ranges(meta.arrival, meta.departure, today, forecast_horizon);
+    let range = |kind| ranges.iter().find(|r| r.kind == kind);
+    let (actuals, forecast) = (range(FetchKind::Actuals), range(FetchKind::Forecast));
+    let is_forecast = range(FetchKind::HistoricProxy).is_none();
+    let kind = match (actuals, forecast) {
+        // A trip under way shows observed days before today and forecast after.
+        (Some(_), Some(_)) => format!("Actuals + forecast{}", source.label()),
+        (Some(_), None) => "Actuals".to_string(),
+        (None, Some(_)) => format!("Forecast{}", source.label()),
+        (None, None) => "Historic (proxy)".to_string(),
+    };
+    let (s, e) = match (ranges.first(), ranges.last()) {
+        (Some(first), Some(last)) => (first.start, last.end),
+        _ => return Err(anyhow!("no dates to fetch for {start} → {end}")),
+    };
+    tracing::debug!(kind, start = %s, end = %e, "planned fetch range");
+    // Independent requests for the same note run concurrently.
+    let fetch = async {
+        if let Some(proxy) = range(FetchKind::HistoricProxy) {
+            return fetch_proxy(client, (wlat, wlon), (proxy.start, proxy.end), &tz).await;
+        }
+        let past = async {
+            match actuals {
+                Some(r) => fetch_actuals(client, wlat, wlon, r.start, r.end, &tz, today).await,
+                None => Ok(Vec::new()),
+            }
+        };
+        let ahead = async {
+            match forecast {
+                Some(r) => {
+                    source
+                        .fetch_forecast(client, wlat, wlon, r.start, r.end, &tz)
+                        .await
+                }
+                None => Ok(Vec::new()),
+            }
+        };
+        let (past, ahead) = tokio::join!(past, ahead);
+        let mut data = past?;
+        let from = forecast.map(|r| r.start);
+        data.extend(ahead?.into_iter().filter(|d| Some(d.date) >= from));
+        Ok((data, (s, e)))
+    };
+    let header_place = async {
+        match meta.coords {
+            Some((lat, lon)) if meta.city.is_empty() => {
+                match geocoder::reverse_geocode(client, lat, lon, language).await {
+                    Ok(name) => format!(
+                        " near {} ({})",
+                        width::isolate(&name),
+                        geo::format_coords(lat, lon, 2)
+                    ),
+                    Err(err) => {
+                        tracing::warn!(%err, "reverse geocoding failed");
+                        format!(" at ({})", geo::format_coords(lat, lon, 2))
+                    }
+                }
+            }
+            _ if place.name.is_empty() => String::new(),
+            _ => format!(" for {}", width::isolate(&place.name)),
+        }
+    };
+    let details = async {
+        if !opts.show_location {
+            return String::new();
+        }
+        let mut place = place.clone();
+        if place.elevation.is_none() {
+            place.elevation = geocoder::fetch_elevation(client, lat, lon)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::warn!(%err, "elevation unavailable");
+                    None
+                });
+        }
+        // Coordinate-only notes already show their coordinates.
+        let coords = !(meta.coords.is_some() && meta.city.is_empty());
+        geocoder::location_details(&place, coords)
+    };
+    let (data, place_label, details) = tokio::join!(fetch, header_place, details);
+    let (mut data, span) = data?;
+    // A proxy from further back than last year says which year it is.
+    let kind = match span.0 != s {
+        true => format!("Historic (proxy from {})", span.0.year()),
+        false => kind,
+    };
+    let (s, e) = span;
+    // Open-Meteo says which grid point it answered for once the data is in.
+    let source_off = geo::grid_point((wlat, wlon)).map(|point| geo::distance_km((lat, lon), point));
+    if let Some(km) = source_off {
+        tracing::debug!(off = %geo::format_distance(km), "provider grid point");
+    }
+    let details = match (details, source_off) {
+        (d, Some(km)) if opts.show_location && km >= 0.05 => {
+            let grid = format!("grid point {} away", geo::format_distance(km));
+            match d.is_empty() {
+                true => format!(" ({grid})"),
+                false => format!(" ({d} · {grid})"),
+            }
+        }
+        (d, _) if d.is_empty() => d,
+        (d, _) => format!(" ({d})"),
+    };
+    let zone = tz::parse_zone(&tz).ok();
+    let clock = zone
+        .and_then(|dest| {
+            let home = opts.home_timezone.or(opts.clock().zone());
+            tz::home_difference(home, dest, start, end)
+        })
+        .map(|d| format!(" ({d})"))
+        .unwrap_or_default();
+    let label = format!("{kind}{place_label}{details} {s} → {e}{clock}");
+    // Notes with coordinates use their own `timezone:` as is.
+    if let Some(given) = meta.timezone.as_deref()
+        && meta.coords.is_none()
+        && let Some(warning) = tz::mismatch(given, &tz, start, end)
+    {
+        tracing::warn!(%warning, "timezone mismatch");
+        tz::record(&meta.path, warning);
+    }
+    let changes = zone.map_or_else(Vec::new, |zone| tz::clock_changes(zone, start, end));
+    if let Some(dates) = tz::describe_changes(&changes) {
+        tz::record(
+            &meta.path,
+            format!("clocks change {dates} at {tz} during the trip"),
+        );
+    }
+
+    let content = hooks::read_note(Path::new(&meta.path))?;
+    let separate = match opts.separate_note {
+        true => {
+            let path = separate::note_path(meta);
+            let text = match path.exists() {
+                true => hooks::read_note(&path)?,
+                false => String::new(),
+            };
+            Some((path, text))
+        }
+        false => None,
+    };
+    let mut notes = day_notes(meta);
+    // Trend arrows compare against the table from the last run, wherever it is.
+    let previous = previous_temps(separate.as_ref().map_or(&content, |(_, text)| text));
+    for (date, trend) in trend_notes(&data, &previous, opts.units) {
+        add_day_note(&mut notes, date, trend, true);
+    }
+    let days = (end - start).num_days() + 1;
+    let trip = packing::Trip::new(days, &data, place.country_code.as_deref());
+    let mut extra = Vec::new();
+    if opts.dst_note {
+        extra.extend(tz::clock_change_line(&changes));
+    }
+    // The remaining requests only need the span fetched above and not each
+    // other, so they run concurrently as well.
+    let columns = async {
+        columns::fetch(client, &opts.vars, (wlat, wlon), (s, e), &tz, is_forecast)
+            .await
+            .unwrap_or_else(|err| {
+                tracing::warn!(%err, "no data for the extra table columns");
+                Vec::new()
+            })
+    };
+    let origin = async {
+        // Only the day of setting off matters at home.
+        let origin = meta.origin.as_ref()?;
+        let found = async {
+            let stop = route::resolve(
+                client,
+                std::slice::from_ref(origin),
+                opts.geocoder,
+                language,
+            )
+            .await?
+            .remove(0);
+            let days = route::fetch_days(
+                client,
+                stop.latitude,
+                stop.longitude,
+                s,
+                s,
+                &stop.timezone,
+                is_forecast,
+            )
+            .await?;
+            Ok::<_, anyhow::Error>((stop, days))
+        };
+        match found.await {
+            Ok((stop, days)) => Some(route::render_origin(&stop, &days, opts.units)),
+            Err(err) => {
+                tracing::warn!(%err, "no weather for the origin");
+                None
+            }
+        }
+    };
+    let route = async {
+        if !meta.is_roadtrip() && meta.waypoints.is_empty() {
+            return Ok(None);
+        }
+        // The destination itself is only needed for road trip hazards.
+        let mut stops: Vec<route::Stop> = Vec::new();
+        if meta.is_roadtrip() {
+            stops.push(route::Stop {
+                name: String::new(),
+                latitude: lat,
+                longitude: lon,
+                timezone: tz.clone(),
+            });
+        }
+        stops.extend(route::resolve(client, &meta.waypoints, opts.geocoder, language).await?);
+        let weather = route::fetch_route(client, &stops, s, e, is_forecast).await?;
+        Ok::<_, anyhow::Error>(Some(weather))
+    };
+    let exposure = async {
+        match opts.advisories.is_some() && is_forecast && e >= today {
+            true => advisories::fetch_exposure(client, wlat, wlon, s.max(today), e, &tz)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::warn!(%err, "no UV/humidity data for advisories");
+                    BTreeMap::new()
+                }),
+            false => BTreeMap::new(),
+        }
+    };
+    let alert_extras = async {
+        match &opts.alert_if {
+            Some(rules) if is_forecast && e >= today => {
+                alerts::fetch_extras(client, rules, (wlat, wlon), (s.max(today), e), &tz)
+                    .await
+                    .unwrap_or_else(|err| {
+                        tracing::warn!(%err, "no extra data for alerts");
+                        BTreeMap::new()
+                    })
+            }
+            _ => BTreeMap::new(),
+        }
+    };
+    let rain = async {
+        match meta.flexible.is_some() && opts.window_score.dry {
+            true => window::fetch_rain(client, (wlat, wlon), (s, e), &tz, is_forecast)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::warn!(%err, "no precipitation data for the best window");
+                    BTreeMap::new()
+                }),
+            false => BTreeMap::new(),
+        }
+    };
+    let public_holidays = async {
+        let country = place.country_code.as_deref().filter(|_| opts.holidays)?;
+        match holidays::fetch_holidays(client, country, start, end).await {
+            Ok(found) => Some(holidays::render_holidays(&found)),
+            Err(err) => {
+                tracing::warn!(%err, "public holidays unavailable");
+                None
+            }
+        }
+    };
+    let map_image = async {
+        if opts.map != map::MapMode::Image {
+            return None;
+        }
+        let image = map::image_path(&meta.path, lat, lon);
+        match map::fetch_image(client, lat, lon, &image).await {
+            Ok(bytes) => Some((image, bytes)),
+            Err(err) => {
+                tracing::warn!(%err, "static map unavailable");
+                None
+            }
+        }
+    };
+    let (columns, origin, route, exposure, alert_extras, rain, public_holidays, map_image) = tokio::join!(
+        columns,
+        origin,
+        route,
+        exposure,
+        alert_extras,
+        rain,
+        public_holidays,
+        map_image
     );
 
-    let mut content = fs::read_to_string(&meta.path)?;
-    upsert_weather_block(&mut content, &block)?;
-    fs::write(&meta.path, content)?;
-    save_chart(&data, &meta.path)?;
-    Ok(())
+    let columns = columns::merge(&mut data, columns);
+    if opts.outfit
+        && let Some(trip) = &trip
+    {
+        let rules = packing::load_outfit_rules(opts.outfit_rules.as_deref())?;
+        extra.extend(packing::outfit_line(&packing::outfit(&rules, trip)?));
+    }
+    // Extra sections rendered below the table.
+    let mut sections: Vec<String> = Vec::new();
+    sections.extend(origin);
+    if let Some(weather) = route? {
+        let travel_days = if is_forecast { [start, end] } else { [s, e] };
+        sections.push(route::render_route(&weather, &travel_days, opts.units));
+        let flagged = if meta.is_roadtrip() {
+            roadtrip::route_notes(&weather)
+        } else {
+            BTreeMap::new()
+        };
+        if !flagged.is_empty() {
+            extra.push(roadtrip::WINTER_TIRES.to_string());
+        }
+        for (date, texts) in flagged {
+            add_day_note(&mut notes, date, texts.join("; "), true);
+        }
+    }
+    if let Some(thresholds) = &opts.advisories {
+        extra.extend(advisories::advisories(
+            &data, &exposure, thresholds, opts.units,
+        ));
+    }
+    let mut alerts = Vec::new();
+    if let Some(rules) = &opts.alert_if
+        && is_forecast
+        && e >= today
+    {
+        let ahead: Vec<DayTemp> = data.iter().filter(|d| d.date >= today).cloned().collect();
+        alerts = alerts::evaluate(rules, &ahead, &alert_extras);
+        extra.splice(0..0, alerts::banner(&alerts));
+    }
+    if let Some(days) = meta.flexible {
+        let best = window::best(&data, &rain, days, &opts.window_score);
+        let shift = if is_forecast {
+            chrono::Duration::zero()
+        } else {
+            start - s
+        };
+        extra.push(window::line(
+            best.as_ref(),
+            days,
+            data.len(),
+            shift,
+            opts.units,
+        ));
+        if let Some(best) = best {
+            for date in best.start.iter_days().take_while(|d| *d <= best.end) {
+                add_day_note(&mut notes, date, "best window".to_string(), true);
+            }
+        }
+    }
+    sections.extend(public_holidays);
+    if opts.map != map::MapMode::Off {
+        extra.push(map::map_line(lat, lon));
+    }
+    let map_image = map_image.map(|(image, bytes)| {
+        sections.push(map::embed(&image));
+        bytes.map(|bytes| (image, bytes))
+    });
+    if opts.sidecar {
+        extra.push(sidecar::link_line(&meta.path));
+    }
+    if opts.facts
+        && let Some(info) = place
+            .country_code
+            .as_deref()
+            .and_then(destination_info::lookup)
+    {
+        sections.push(destination_info::render_footer(&info));
+    }
+    Ok(PreparedNote {
+        meta: meta.clone(),
+        opts: opts.clone(),
+        place,
+        label,
+        data,
+        notes,
+        extra,
+        sections,
+        divider: actuals.map(|_| today),
+        trip,
+        content,
+        separate,
+        alerts,
+        columns,
+        map_image: map_image.flatten(),
+    })
+}
+
+/// Frontmatter pairs recording where `place` resolved to.
+fn coord_keys(place: &Place, meta: &NoteMeta) -> Vec<(&'static str, String)> {
+    let mut keys = vec![
+        ("lat", format!("{:.4}", place.latitude)),
+        ("lon", format!("{:.4}", place.longitude)),
+    ];
+    if meta.timezone.is_none() && place.timezone != "auto" {
+        keys.push(("timezone", place.timezone.clone()));
+    }
+    keys
+}
+
+/// Summary of the days that count: those whose annotation is not `counted`
+/// are left out unless that would leave nothing to summarize.
+pub fn block_summary(
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    units: Units,
+) -> Summary {
+    block_summary_in(data, notes, units, &i18n::Languages::default())
+}
+
+/// [`block_summary`] with the summary line in `langs`.
+pub fn block_summary_in(
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    units: Units,
+    langs: &i18n::Languages,
+) -> Summary {
+    let counted: Vec<DayTemp> = data
+        .iter()
+        .filter(|d| notes.get(&d.date).is_none_or(|n| n.counted))
+        .cloned()
+        .collect();
+    summarize_with(
+        if counted.is_empty() { data } else { &counted },
+        units,
+        langs,
+    )
+}
+
+/// Render the full weather section (heading, markers, summary and table).
+///
+/// Days whose annotation is not `counted` are left out of the summary unless
+/// that would leave nothing to summarize. With `today`, a divider row
+/// separates observed days from forecast ones. `extra` lines (outfit counts,
+/// advisories) follow the summary.
+pub fn render_block(
+    label: &str,
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    units: Units,
+    today: Option<NaiveDate>,
+    extra: &[String],
+) -> String {
+    let style = shortcode::TableStyle::Markdown;
+    render_block_styled(label, data, notes, units, today, extra, style)
+}
+
+/// [`render_block`] with the day table written in `style`.
+pub fn render_block_styled(
+    label: &str,
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    units: Units,
+    today: Option<NaiveDate>,
+    extra: &[String],
+    style: shortcode::TableStyle,
+) -> String {
+    let summary = block_summary(data, notes, units);
+    let table = shortcode::render(style, data, notes, units, today, &[]);
+    assemble_block(label, summary, extra, &table)
+}
+
+/// The weather section around a rendered `summary` and day `table`.
+fn assemble_block(label: &str, summary: Summary, extra: &[String], table: &str) -> String {
+    let mut note = summary.note;
+    for line in extra {
+        note.push_str("  \n");
+        note.push_str(line);
+    }
+    format!(
+        "## Weather Forecast\n{}\n**{}**  \n**Range**: {} / {}  \n\n{}\n\n{}\n{}\n",
+        markers::BEGIN,
+        label,
+        summary.max,
+        summary.min,
+        note,
+        table,
+        markers::END
+    )
+}
+
+/// The block written in place of the weather when a note's update failed.
+///
+/// It uses the usual markers, so the next successful run replaces it.
+pub fn render_error_block(date: NaiveDate, error: &str) -> String {
+    let reason = error.split_whitespace().collect::<Vec<_>>().join(" ");
+    let (begin, end) = (markers::BEGIN, markers::END);
+    format!("## Weather Forecast\n{begin}\n> ⚠️ weather update failed on {date}: {reason}\n{end}\n")
+}
+
+/// The note holding a trip's weather block: the trip note, or with
+/// `separate-note` its weather note.
+fn block_note_path(meta: &NoteMeta, opts: &RunOptions, today: NaiveDate) -> PathBuf {
+    let meta = meta.occurrence_on(today);
+    match opts.separate_note {
+        true => separate::note_path(&meta),
+        false => PathBuf::from(&meta.path),
+    }
+}
+
+/// Why `refresh` leaves the note alone this run, or `None` if it is due.
+/// Notes whose settings are invalid are due, so processing reports why.
+fn not_due(meta: &NoteMeta, opts: &RunOptions) -> Option<String> {
+    // Processing reports pinned notes as pinned.
+    if meta.pinned {
+        return None;
+    }
+    let opts = opts.for_note(meta).ok()?;
+    if opts.refresh == stale::Refresh::Always {
+        return None;
+    }
+    let path = block_note_path(meta, &opts, opts.today());
+    let fetched = match path.exists() {
+        true => hooks::read_note(&path)
+            .ok()
+            .and_then(|text| stale::fetched_at(&text)),
+        false => None,
+    };
+    opts.refresh.not_due(fetched, opts.now())
+}
+
+/// Replace a note's block with [`render_error_block`].
+pub fn write_error_block(
+    meta: &NoteMeta,
+    opts: &RunOptions,
+    date: NaiveDate,
+    error: &str,
+) -> Result<()> {
+    let path = block_note_path(meta, opts, date);
+    let mut content = match path.exists() {
+        true => hooks::read_note(&path)?,
+        false => String::new(),
+    };
+    // A separate weather note holds nothing but the block.
+    let parent = opts
+        .under_heading
+        .as_deref()
+        .filter(|_| !opts.separate_note);
+    upsert_weather_block_under(&mut content, &render_error_block(date, error), parent)?;
+    hooks::write_note(&path, &content)
+}
+
+/// Keep a note's earlier data and mark how old it is. Returns whether
+/// there was stamped data to keep.
+pub fn write_stale_banner(
+    meta: &NoteMeta,
+    opts: &RunOptions,
+    now: chrono::DateTime<chrono::FixedOffset>,
+) -> Result<bool> {
+    let path = block_note_path(meta, opts, now.date_naive());
+    if !path.exists() {
+        return Ok(false);
+    }
+    match stale::mark_stale(&hooks::read_note(&path)?, now) {
+        Some(content) => hooks::write_note(&path, &content).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Render the weather block for a saved API payload, without network or notes.
+///
+/// The label defaults to `Forecast <first day> → <last day>`.
+pub fn render_fixture(json: &[u8], label: Option<&str>) -> Result<String> {
+    let resp: ForecastResp = http::decode_json(json)?;
+    let data = parse_daily(resp)?;
+    let label = match (label, data.first(), data.last()) {
+        (Some(l), _, _) => l.to_string(),
+        (None, Some(first), Some(last)) => format!("Forecast {} → {}", first.date, last.date),
+        (None, _, _) => "Forecast".to_string(),
+    };
+    Ok(render_block(
+        &label,
+        &data,
+        &BTreeMap::new(),
+        Units::default(),
+        None,
+        &[],
+    ))
+}
+
+/// How many weather sections `content` holds; more than one is left over
+/// from older releases that appended a new section instead of replacing.
+pub fn count_weather_blocks(content: &str) -> usize {
+    outline::weather_sections(content).len()
+}
+
+/// Remove `ranges` (in order, not overlapping) from `content`, each with
+/// the blank lines before it.
+fn remove_blocks(content: &mut String, ranges: &[std::ops::Range<usize>]) {
+    // From the back, so earlier ranges stay valid.
+    for range in ranges.iter().rev() {
+        let text = content[..range.start].trim_end_matches('\n');
+        // The line before keeps its own line break.
+        let start = (text.len() + 1).min(range.start);
+        content.replace_range(start..range.end, "");
+    }
 }
 
 /// Insert or replace the weather block under the designated heading.
+///
+/// Blocks of any format version are replaced, except one written by a newer
+/// weather-helper (see [`markers`]). When a note holds several sections,
+/// the first is replaced and the others are removed.
 pub fn upsert_weather_block(content: &mut String, new_block: &str) -> Result<()> {
-    let block_re =
-        Regex::new("(?s)##\\s*Weather Forecast\\s*\n<!-- WEATHER:BEGIN -->.*?<!-- WEATHER:END -->")
-            .unwrap();
+    upsert_weather_block_under(content, new_block, None)
+}
 
-    if block_re.is_match(content) {
-        *content = block_re.replace(content, new_block).to_string();
+/// [`upsert_weather_block`], keeping the section inside the `parent`
+/// heading's section (e.g. `## Logistics`) when the note has that heading.
+///
+/// There the section goes one level below the parent, at the end of the
+/// parent's section; a block elsewhere in the note is moved. An existing
+/// block keeps its heading level. Notes without the parent heading are
+/// handled as by [`upsert_weather_block`].
+///
+/// Code blocks and the frontmatter are never changed; an edit that would
+/// (say, appending below a code fence left open) is refused.
+pub fn upsert_weather_block_under(
+    content: &mut String,
+    new_block: &str,
+    parent: Option<&str>,
+) -> Result<()> {
+    let mut updated = content.clone();
+    place_weather_block(&mut updated, new_block, parent)?;
+    outline::check_untouched(content, &updated, false)?;
+    *content = updated;
+    Ok(())
+}
+
+fn place_weather_block(content: &mut String, new_block: &str, parent: Option<&str>) -> Result<()> {
+    // Sections end with their line break, so replacing one with a block
+    // that ends in one does not add a blank line on every run.
+    let sections = outline::weather_sections(content);
+    let blocks: Vec<_> = sections.iter().map(|s| s.range.clone()).collect();
+    for block in &blocks {
+        markers::check_supported(&content[block.clone()])?;
+    }
+    let within_parent = |content: &str, at: usize| {
+        let Some(spec) = parent else {
+            return true;
+        };
+        let headings = outline::headings(content);
+        match outline::find(&headings, spec) {
+            Some(p) => (p.line.end..=outline::section_end(content, &headings, p)).contains(&at),
+            None => true,
+        }
+    };
+    if let Some(first) = sections.first()
+        && within_parent(content, first.range.start)
+    {
+        remove_blocks(content, &blocks[1..]);
+        let block = outline::relevel(new_block, first.level);
+        content.replace_range(first.range.clone(), &block);
         return Ok(());
     }
+    // Whatever is left is in the wrong place.
+    remove_blocks(content, &blocks);
 
-    let heading_re = Regex::new(r"(?m)^##\s*Weather Forecast\s*$").unwrap();
-    if heading_re.is_match(content) {
-        *content = heading_re
-            .replace(
-                content,
-                "## Weather Forecast\n<!-- WEATHER:BEGIN -->\n<!-- WEATHER:END -->",
-            )
-            .to_string();
-        let empty_block = Regex::new(
-            "(?s)##\\s*Weather Forecast\\s*\n<!-- WEATHER:BEGIN -->\n<!-- WEATHER:END -->",
-        )
-        .unwrap();
-        *content = empty_block.replace(content, new_block).to_string();
-        Ok(())
+    if content.trim().is_empty() {
+        *content = new_block.to_string();
+        return Ok(());
+    }
+    let headings = outline::headings(content);
+    let is_weather = |h: &&outline::Heading| h.title.eq_ignore_ascii_case(outline::WEATHER_TITLE);
+    if let Some(p) = parent.and_then(|spec| outline::find(&headings, spec)) {
+        let end = outline::section_end(content, &headings, p);
+        let section = p.line.end..end;
+        if let Some(h) = headings
+            .iter()
+            .filter(|h| section.contains(&h.line.start))
+            .find(is_weather)
+        {
+            let block = outline::relevel(new_block, h.level);
+            content.replace_range(h.line.clone(), &block);
+            return Ok(());
+        }
+        // Separated by a blank line from what comes before and after.
+        let before = &content[..end];
+        let gap = 2 - before[before.trim_end_matches('\n').len()..].len().min(2);
+        let mut block = "\n".repeat(gap) + &outline::relevel(new_block, p.level + 1);
+        if end < content.len() {
+            block.push('\n');
+        }
+        content.insert_str(end, &block);
+        return Ok(());
+    }
+    if let Some(h) = headings.iter().find(is_weather) {
+        let block = outline::relevel(new_block, h.level);
+        content.replace_range(h.line.clone(), &block);
     } else {
         content.push_str("\n\n");
         content.push_str(new_block);
-        Ok(())
     }
+    Ok(())
 }
 
 /// Compute min/max strings and a human-friendly note for a set of `DayTemp`s.
 pub fn summarize(data: &[DayTemp]) -> Summary {
+    summarize_in(data, Units::Fahrenheit)
+}
+
+/// [`summarize`] with the figures in `units`.
+pub fn summarize_in(data: &[DayTemp], units: Units) -> Summary {
+    summarize_with(data, units, &i18n::Languages::default())
+}
+
+/// [`summarize_in`] with the summary line in `langs` (see [`i18n`]).
+pub fn summarize_with(data: &[DayTemp], units: Units, langs: &i18n::Languages) -> Summary {
     if data.is_empty() {
         return Summary {
             max: "n/a".into(),
             min: "n/a".into(),
-            note: "_No data returned_".into(),
+            note: langs.summary(None),
         };
     }
-    let max = data.iter().fold(f64::MIN, |m, d| m.max(d.tmax_f));
-    let min = data.iter().fold(f64::MAX, |m, d| m.min(d.tmin_f));
-    let note = format!(
-        "_{} days • High range {:.0}° → {:.0}° • Low range {:.0}° → {:.0}°_",
-        data.len(),
-        data.iter().map(|d| d.tmax_f).fold(f64::MAX, f64::min),
-        data.iter().map(|d| d.tmax_f).fold(f64::MIN, f64::max),
-        data.iter().map(|d| d.tmin_f).fold(f64::MAX, f64::min),
-        data.iter().map(|d| d.tmin_f).fold(f64::MIN, f64::max),
+    let celsius = units == Units::Celsius;
+    let high = |d: &DayTemp| if celsius { d.tmax } else { d.tmax_f };
+    let low = |d: &DayTemp| if celsius { d.tmin } else { d.tmin_f };
+    let symbol = if celsius { "°C" } else { "°F" };
+    let max = data.iter().map(high).fold(f64::MIN, f64::max);
+    let min = data.iter().map(low).fold(f64::MAX, f64::min);
+    let highs = (
+        fmt_temp(data.iter().map(high).fold(f64::MAX, f64::min)),
+        fmt_temp(max),
     );
+    let lows = (
+        fmt_temp(min),
+        fmt_temp(data.iter().map(low).fold(f64::MIN, f64::max)),
+    );
+    let measures: Vec<(columns::Measure, String)> = columns::measures(data)
+        .into_iter()
+        .filter_map(|m| Some((m, m.format(m.over(data)?))))
+        .collect();
+    let note = langs.summary(Some(i18n::Figures {
+        days: data.len(),
+        highs: (&highs.0, &highs.1),
+        lows: (&lows.0, &lows.1),
+        measures: &measures,
+    }));
     Summary {
-        max: format!("{:.0}°F", max),
-        min: format!("{:.0}°F", min),
+        max: format!("{}{symbol}", fmt_temp(max)),
+        min: format!("{}{symbol}", fmt_temp(min)),
         note,
     }
 }
 
+/// Format a temperature as a whole number.
+///
+/// Rounds half away from zero and never prints `-0`, so the same data always
+/// renders to the same bytes.
+pub fn fmt_temp(v: f64) -> String {
+    let r = v.round();
+    if r == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{r:.0}")
+    }
+}
+
+/// Annotate arrival/departure days from the optional times of day.
+///
+/// Late arrivals and early departures are excluded from the summary; other
+/// partial days are only labelled as half days.
+pub fn day_notes(meta: &NoteMeta) -> BTreeMap<NaiveDate, DayNote> {
+    let mut notes: BTreeMap<NaiveDate, DayNote> = BTreeMap::new();
+    let mut add = |date, text, counted| add_day_note(&mut notes, date, text, counted);
+    if let Some(t) = meta.arrival_time {
+        let hm = t.format("%H:%M");
+        if t.hour() >= LATE_ARRIVAL_HOUR {
+            add(
+                meta.arrival,
+                format!("arrive {hm}, late (not counted)"),
+                false,
+            );
+        } else if t.hour() >= 12 {
+            add(meta.arrival, format!("arrive {hm} (half day)"), true);
+        }
+    }
+    if let Some(t) = meta.departure_time {
+        let hm = t.format("%H:%M");
+        if t.hour() < EARLY_DEPARTURE_HOUR {
+            add(
+                meta.departure,
+                format!("depart {hm}, early (not counted)"),
+                false,
+            );
+        } else if t.hour() < 12 {
+            add(meta.departure, format!("depart {hm} (half day)"), true);
+        }
+    }
+    notes
+}
+
+/// Add an annotation for `date`, joining it to any existing one with `; `.
+pub fn add_day_note(
+    notes: &mut BTreeMap<NaiveDate, DayNote>,
+    date: NaiveDate,
+    text: String,
+    counted: bool,
+) {
+    notes
+        .entry(date)
+        .and_modify(|n| {
+            n.text = format!("{}; {text}", n.text);
+            n.counted &= counted;
+        })
+        .or_insert(DayNote { text, counted });
+}
+
+/// Daily values from the table of a previously rendered block, as shown
+/// (rounded). Used to tell how the forecast moved since the last run.
+pub fn previous_temps(content: &str) -> BTreeMap<NaiveDate, DayTemp> {
+    let (begin, end) = (markers::BEGIN_PATTERN, markers::END);
+    let block_re = Regex::new(&format!("(?s){begin}.*?{end}")).unwrap();
+    let row_re = Regex::new(
+        r"(?m)^\| +(\d{4}-\d{2}-\d{2}) +\| +(-?\d+) +\| +(-?\d+) +\| +(-?\d+) +\| +(-?\d+) +\|",
+    )
+    .unwrap();
+    let code = outline::protected_ranges(content);
+    let Some(block) = block_re
+        .find_iter(content)
+        .find(|m| !outline::is_protected(&code, m.start()))
+    else {
+        return BTreeMap::new();
+    };
+    if let Some(embedded) = sidecar::embedded(block.as_str()) {
+        return embedded.days.iter().map(|d| (d.date, d.into())).collect();
+    }
+    row_re
+        .captures_iter(block.as_str())
+        .filter_map(|c| {
+            let num = |i: usize| c[i].parse::<f64>().ok();
+            let date = NaiveDate::parse_from_str(&c[1], "%Y-%m-%d").ok()?;
+            Some((
+                date,
+                DayTemp {
+                    date,
+                    tmax_f: num(2)?,
+                    tmin_f: num(3)?,
+                    tmax: num(4)?,
+                    tmin: num(5)?,
+                    ..Default::default()
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Trend markers such as `high ↑2°, low ↓1°` for days whose rounded high or
+/// low in `units` changed since `previous`.
+pub fn trend_notes(
+    data: &[DayTemp],
+    previous: &BTreeMap<NaiveDate, DayTemp>,
+    units: Units,
+) -> BTreeMap<NaiveDate, String> {
+    let shown = |d: &DayTemp| match units {
+        Units::Celsius => (d.tmax.round(), d.tmin.round()),
+        Units::Fahrenheit => (d.tmax_f.round(), d.tmin_f.round()),
+    };
+    let arrow = |name: &str, delta: f64| match delta {
+        d if d > 0.0 => Some(format!("{name} ↑{d:.0}°")),
+        d if d < 0.0 => Some(format!("{name} ↓{:.0}°", -d)),
+        _ => None,
+    };
+    data.iter()
+        .filter_map(|d| {
+            let (old_max, old_min) = shown(previous.get(&d.date)?);
+            let (new_max, new_min) = shown(d);
+            let parts: Vec<String> = [
+                arrow("high", new_max - old_max),
+                arrow("low", new_min - old_min),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            (!parts.is_empty()).then(|| (d.date, parts.join(", ")))
+        })
+        .collect()
+}
+
 /// Render a Markdown table of daily highs and lows.
 pub fn render_table(data: &[DayTemp]) -> String {
+    render_table_with_notes(data, &BTreeMap::new())
+}
+
+/// Render the table with an extra Notes column when any day is annotated.
+pub fn render_table_with_notes(data: &[DayTemp], notes: &BTreeMap<NaiveDate, DayNote>) -> String {
+    render_table_split(data, notes, None)
+}
+
+/// Render the table, inserting a "today" divider row before the first day on
+/// or after `today` when earlier days precede it.
+pub fn render_table_split(
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    today: Option<NaiveDate>,
+) -> String {
+    render_table_columns(data, notes, today, &[])
+}
+
+/// [`render_table_split`] with a column per measure the days have (rain,
+/// wind, UV) and per other `vars` variable after the others.
+pub fn render_table_columns(
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    today: Option<NaiveDate>,
+    columns: &[columns::Column],
+) -> String {
     if data.is_empty() {
         return "_(no rows)_".into();
     }
-    let mut s = String::from(
-        "| Date | High (°F) | Low (°F) | High (°C) | Low (°C) |\n|---|---:|---:|---:|---:|\n",
-    );
-    for d in data {
+    let with_notes = data.iter().any(|d| notes.contains_key(&d.date));
+    let mut s = String::from("| Date | High (°F) | Low (°F) | High (°C) | Low (°C) |");
+    let mut rule = String::from("|---|---:|---:|---:|---:|");
+    if with_notes {
+        s.push_str(" Notes |");
+        rule.push_str("---|");
+    }
+    let measures = columns::measures(data);
+    let headers = measures
+        .iter()
+        .map(|m| m.header())
+        .chain(columns.iter().map(columns::Column::header));
+    for header in headers {
+        s.push_str(&format!(" {header} |"));
+        rule.push_str("---:|");
+    }
+    s.push_str(&format!("\n{rule}\n"));
+    let blanks = " |".repeat(4 + usize::from(with_notes) + measures.len() + columns.len());
+    let split_at = today.and_then(|t| data.iter().position(|d| d.date >= t));
+    for (i, d) in data.iter().enumerate() {
+        if i > 0 && split_at == Some(i) {
+            s.push_str(&format!("| **— today —** |{blanks}\n"));
+        }
         s.push_str(&format!(
-            "| {} | {:.0} | {:.0} | {:.0} | {:.0} |\n",
-            d.date, d.tmax_f, d.tmin_f, d.tmax, d.tmin
+            "| {} | {} | {} | {} | {} |",
+            d.date,
+            fmt_temp(d.tmax_f),
+            fmt_temp(d.tmin_f),
+            fmt_temp(d.tmax),
+            fmt_temp(d.tmin)
         ));
+        if with_notes {
+            let note = notes.get(&d.date).map(|n| n.text.as_str()).unwrap_or("");
+            s.push_str(&format!(" {note} |"));
+        }
+        for measure in &measures {
+            let value = measure.of(d).map_or(String::new(), columns::number);
+            s.push_str(&format!(" {value} |"));
+        }
+        for column in columns {
+            s.push_str(&format!(" {} |", column.cell(d.date)));
+        }
+        s.push('\n');
     }
     s
 }
 
-/// Geocode a city to `(latitude, longitude, timezone)` using Open‑Meteo.
-pub async fn geocode(city: &str) -> Result<(f64, f64, String)> {
-    let url = format!(
-        "{}/search?name={}&country=IT&count=1",
-        geocode_base(),
-        urlencoding::encode(city)
-    );
-    let geo: GeocodeResp = get_json_with_retry(&url).await?;
-    let item = geo
-        .results
-        .and_then(|mut v| v.pop())
-        .ok_or_else(|| anyhow!("geocoding failed for city: {}", city))?;
-    let tz = item.timezone.unwrap_or_else(|| "Europe/Rome".to_string());
-    Ok((item.latitude, item.longitude, tz))
+/// Resolve a note to a `Place`.
+///
+/// Explicit coordinates are used as-is, airport codes (an `airport:` key, or a
+/// `city:` that looks like one) come from the bundled dataset, and everything
+/// else is geocoded with `geocoder`.
+pub async fn resolve_location(
+    client: &http::Client,
+    meta: &NoteMeta,
+    geocoder: Geocoder,
+    language: Option<&str>,
+) -> Result<Place> {
+    let code = meta
+        .airport
+        .as_deref()
+        .or_else(|| airports::looks_like_code(&meta.city).then_some(meta.city.as_str()));
+    if let Some((lat, lon)) = meta.coords {
+        return Ok(Place {
+            name: meta.city.clone(),
+            latitude: lat,
+            longitude: lon,
+            timezone: meta.timezone.clone().unwrap_or_else(|| "auto".to_string()),
+            country_code: None,
+            elevation: None,
+        });
+    }
+    if let Some(code) = code {
+        if let Some(a) = airports::lookup(code) {
+            return Ok(Place {
+                name: a.name.to_string(),
+                latitude: a.latitude,
+                longitude: a.longitude,
+                timezone: a.timezone.to_string(),
+                country_code: None,
+                elevation: None,
+            });
+        }
+        if meta.airport.is_some() {
+            return Err(anyhow!("unknown airport code '{code}'"));
+        }
+    }
+    geocoder.search(client, &meta.city, language).await
 }
 
 /// Fetch forecast daily highs/lows for a date range using Open‑Meteo forecast API.
+/// `model` picks a specific weather model (e.g. `ecmwf_ifs025`) instead of
+/// Open‑Meteo's best match.
 pub async fn fetch_daily(
+    client: &http::Client,
     lat: &f64,
     lon: &f64,
     start: &NaiveDate,
     end: &NaiveDate,
     tz: &str,
+    model: Option<&str>,
 ) -> Result<Vec<DayTemp>> {
     let url = format!(
-        "{}/forecast?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min&start_date={}&end_date={}&timezone={}",
+        "{}/forecast?latitude={}&longitude={}&daily={}&start_date={}&end_date={}&timezone={}{}",
         forecast_base(),
         lat,
         lon,
+        variables::daily_param(TEMPERATURES, variables::Endpoint::Forecast),
         start,
         end,
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz }),
+        model
+            .map(|m| format!("&models={}", urlencoding::encode(m)))
+            .unwrap_or_default()
+    );
+    let data: ForecastResp = client.get_json(&url).await?;
+    data.record_grid_point(*lat, *lon);
+    parse_daily(data)
+}
+
+/// Observed highs/lows for days already past, as of `today`.
+///
+/// Trips within the forecast API's `past_days` window come from there (see
+/// [`range_planner`]). Older ones come from ERA5, which lags real time by
+/// about five days, so days the archive does not have yet are filled from
+/// the forecast API.
+pub async fn fetch_actuals(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &str,
+    today: NaiveDate,
+) -> Result<Vec<DayTemp>> {
+    if let range_planner::ActualsSource::PastDays(n) = range_planner::actuals_source(start, today) {
+        match fetch_past_days(client, &lat, &lon, n, tz).await {
+            Ok(recent) => {
+                let days: Vec<DayTemp> = recent
+                    .into_iter()
+                    .filter(|d| start <= d.date && d.date <= end)
+                    .collect();
+                if days.len() as i64 == (end - start).num_days() + 1 {
+                    return Ok(days);
+                }
+                tracing::warn!(
+                    have = days.len(),
+                    "past_days incomplete; trying the archive"
+                );
+            }
+            Err(err) => tracing::warn!(%err, "past_days failed; trying the archive"),
+        }
+    }
+    let mut days = match fetch_archive(client, &lat, &lon, &start, &end, tz).await {
+        Ok(days) => days,
+        Err(err) => {
+            tracing::warn!(%err, "archive has no actuals yet");
+            Vec::new()
+        }
+    };
+    let have: std::collections::BTreeSet<NaiveDate> = days.iter().map(|d| d.date).collect();
+    if let Some(gap) = start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .find(|d| !have.contains(d))
+    {
+        let recent = fetch_daily(client, &lat, &lon, &gap, &end, tz, None).await?;
+        days.extend(recent.into_iter().filter(|d| !have.contains(&d.date)));
+        days.sort_by_key(|d| d.date);
+    }
+    Ok(days)
+}
+
+/// The last `past_days` days (and today) from the forecast API.
+pub async fn fetch_past_days(
+    client: &http::Client,
+    lat: &f64,
+    lon: &f64,
+    past_days: u32,
+    tz: &str,
+) -> Result<Vec<DayTemp>> {
+    let url = format!(
+        "{}/forecast?latitude={}&longitude={}&daily={}&past_days={}&forecast_days=1&timezone={}",
+        forecast_base(),
+        lat,
+        lon,
+        variables::daily_param(TEMPERATURES, variables::Endpoint::Forecast),
+        past_days,
         urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
     );
-    let data: ForecastResp = get_json_with_retry(&url).await?;
+    let data: ForecastResp = client.get_json(&url).await?;
+    data.record_grid_point(*lat, *lon);
     parse_daily(data)
 }
 
 /// Fetch historical proxy using ERA5 archive (same calendar span last year).
 pub async fn fetch_archive(
+    client: &http::Client,
     lat: &f64,
     lon: &f64,
     start: &NaiveDate,
     end: &NaiveDate,
     tz: &str,
 ) -> Result<Vec<DayTemp>> {
+    parse_daily(archive_response(client, lat, lon, start, end, tz).await?)
+}
+
+/// [`fetch_archive`] for a proxy span, walking back a year at a time (up to
+/// [`range_planner::PROXY_YEARS`] years) while the archive fails or has no
+/// temperatures for it. Returns the data and the span it is for; when no
+/// year has data, what the archive first said, or its first error if every
+/// request failed.
+pub async fn fetch_proxy(
+    client: &http::Client,
+    (lat, lon): (f64, f64),
+    span: (NaiveDate, NaiveDate),
+    tz: &str,
+) -> Result<(Vec<DayTemp>, (NaiveDate, NaiveDate))> {
+    let (mut first, mut error) = (None, None);
+    for years in 0..range_planner::PROXY_YEARS {
+        let (start, end) = (
+            range_planner::years_earlier(span.0, years),
+            range_planner::years_earlier(span.1, years),
+        );
+        let resp = match archive_response(client, &lat, &lon, &start, &end, tz).await {
+            Ok(resp) => resp,
+            Err(err) => {
+                tracing::warn!(%start, %end, %err, "archive request for the proxy failed; trying a year earlier");
+                error.get_or_insert(err);
+                continue;
+            }
+        };
+        if resp.has_temperatures() {
+            return Ok((parse_daily(resp)?, (start, end)));
+        }
+        tracing::debug!(%start, %end, "no archive data for the proxy; trying a year earlier");
+        first.get_or_insert(resp);
+    }
+    match (first, error) {
+        (Some(resp), _) => Ok((parse_daily(resp)?, span)),
+        (None, Some(err)) => Err(err),
+        (None, None) => Ok((Vec::new(), span)),
+    }
+}
+
+async fn archive_response(
+    client: &http::Client,
+    lat: &f64,
+    lon: &f64,
+    start: &NaiveDate,
+    end: &NaiveDate,
+    tz: &str,
+) -> Result<ForecastResp> {
     let url = format!(
-        "{}/era5?latitude={}&longitude={}&daily=temperature_2m_max,temperature_2m_min&start_date={}&end_date={}&timezone={}",
+        "{}/era5?latitude={}&longitude={}&daily={}&start_date={}&end_date={}&timezone={}",
         archive_base(),
         lat,
         lon,
+        variables::daily_param(TEMPERATURES, variables::Endpoint::Archive),
         start,
         end,
         urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
     );
-    let data: ForecastResp = get_json_with_retry(&url).await?;
-    parse_daily(data)
+    let data: ForecastResp = client.get_json(&url).await?;
+    data.record_grid_point(*lat, *lon);
+    Ok(data)
 }
 
 /// Convert Open‑Meteo `daily` arrays into a vector of `DayTemp`.
+///
+/// Days with a `null` high or low are skipped; it is only an error when every
+/// day in a non-empty series is missing.
 pub fn parse_daily(api: ForecastResp) -> Result<Vec<DayTemp>> {
-    let d = api.daily.ok_or_else(|| anyhow!("no daily data"))?;
+    let d = api
+        .daily
+        .ok_or_else(|| anyhow!("response has no 'daily' section"))?;
     let n_time = d.time.len();
     let n_max = d.temperature_2m_max.len();
     let n_min = d.temperature_2m_min.len();
@@ -416,8 +2370,10 @@ pub fn parse_daily(api: ForecastResp) -> Result<Vec<DayTemp>> {
     let mut out = Vec::with_capacity(n_time);
     for i in 0..n_time {
         let date = NaiveDate::parse_from_str(&d.time[i], "%Y-%m-%d")?;
-        let tmax = d.temperature_2m_max[i];
-        let tmin = d.temperature_2m_min[i];
+        let (Some(tmax), Some(tmin)) = (d.temperature_2m_max[i], d.temperature_2m_min[i]) else {
+            tracing::debug!(%date, "skipping day with missing temperatures");
+            continue;
+        };
         let tmax_f: f64 = celcius_to_farenheit(tmax);
         let tmin_f: f64 = celcius_to_farenheit(tmin);
         out.push(DayTemp {
@@ -426,8 +2382,16 @@ pub fn parse_daily(api: ForecastResp) -> Result<Vec<DayTemp>> {
             tmin,
             tmax_f,
             tmin_f,
+            ..Default::default()
         });
     }
+    if out.is_empty() {
+        return Err(anyhow!(
+            "all {n_time} days in the daily series are missing temperatures"
+        ));
+    }
+    out.sort_by_key(|d| d.date);
+    out.dedup_by_key(|d| d.date);
     Ok(out)
 }
 
@@ -444,8 +2408,17 @@ pub fn save_chart(data: &[DayTemp], md_path: &str) -> Result<()> {
         let parent = p.parent().unwrap_or_else(|| Path::new(""));
         parent.join(format!("{stem}-forecast.png"))
     };
+    let tmp = io::temp_path(&out_path);
+    let drawn = draw_chart(data, &tmp).and_then(|_| Ok(io::persist(&tmp, &out_path)?));
+    if drawn.is_err() {
+        let _ = io::remove_file(&tmp);
+    }
+    drawn
+}
+
+fn draw_chart(data: &[DayTemp], out_path: &Path) -> Result<()> {
     // Make an image with configured dimensions
-    let root = BitMapBackend::new(&out_path, (IMAGE_WIDTH, IMAGE_HEIGHT)).into_drawing_area();
+    let root = BitMapBackend::new(out_path, (IMAGE_WIDTH, IMAGE_HEIGHT)).into_drawing_area();
     root.fill(&WHITE)?;
     let dates: Vec<_> = data.iter().map(|d| d.date).collect();
     let highs: Vec<_> = data.iter().map(|d| d.tmax_f).collect();
@@ -476,19 +2449,20 @@ pub fn save_chart(data: &[DayTemp], md_path: &str) -> Result<()> {
             &RED,
         ))?
         .label("High")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &RED));
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], RED));
     chart
         .draw_series(LineSeries::new(
             dates.iter().zip(lows.iter()).map(|(d, t)| (*d, *t)),
             &BLUE,
         ))?
         .label("Low")
-        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], &BLUE));
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 10, y)], BLUE));
     chart
         .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
         .label_font(("sans-serif", 14))
         .draw()?;
+    root.present()?;
     Ok(())
 }
@@ -0,0 +1,88 @@
+//! Units and language from a note's own properties.
+//!
+//! Vaults often already say how a note should read: `units: metric`, or
+//! `locale: de-DE` for a German-language trip. Such properties become the
+//! note's `units` and `language` settings, as if written as
+//! `weather-units`/`weather-language`, which still win when both are given.
+//!
+//! A locale's region picks the units (°F for the US and the few other
+//! places that use it, °C elsewhere) unless a units property says
+//! otherwise, and its language subtag is used for geocoding. Values that do
+//! not read as units or a locale are left alone: the property may mean
+//! something else in that vault.
+
+use crate::Units;
+use serde_yaml::Value;
+
+/// Property names read as the note's units, most specific first.
+pub const UNIT_KEYS: &[&str] = &[
+    "temperature-unit",
+    "unit-system",
+    "measurement-system",
+    "units",
+    "unit",
+];
+
+/// Property names read as the note's locale.
+pub const LOCALE_KEYS: &[&str] = &["locale", "lang", "language"];
+
+/// Regions that measure temperature in °F.
+const FAHRENHEIT_REGIONS: &[&str] = &["US", "LR", "MM", "BS", "KY", "PW", "FM", "MH"];
+
+/// `metric`, `°F`, `SI` and the like.
+fn units(value: &str) -> Option<Units> {
+    match value
+        .trim()
+        .trim_start_matches('°')
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "si" => Some(Units::Celsius),
+        "us" => Some(Units::Fahrenheit),
+        other => Units::parse(other).ok(),
+    }
+}
+
+/// `de-DE` → `("de", Some("DE"))`; `None` for anything that is not a
+/// BCP 47-style language tag.
+pub fn parse_locale(value: &str) -> Option<(String, Option<String>)> {
+    let mut parts = value.trim().split(['-', '_']);
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let region = parts
+        .find(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_alphabetic()))
+        .map(|p| p.to_ascii_uppercase());
+    Some((language.to_ascii_lowercase(), region))
+}
+
+/// The settings a note's properties imply, as `(key, value)` overrides.
+pub fn hints(yaml: &Value) -> Vec<(String, String)> {
+    let text = |keys: &[&str]| -> Vec<String> {
+        keys.iter()
+            .filter_map(|k| yaml.get(*k)?.as_str())
+            .map(str::to_string)
+            .collect()
+    };
+    let locale = text(LOCALE_KEYS).iter().find_map(|v| parse_locale(v));
+    let from_region = locale
+        .as_ref()
+        .and_then(|(_, region)| region.as_deref())
+        .map(|region| match FAHRENHEIT_REGIONS.contains(&region) {
+            true => Units::Fahrenheit,
+            false => Units::Celsius,
+        });
+    let mut out = Vec::new();
+    if let Some(units) = text(UNIT_KEYS)
+        .iter()
+        .find_map(|v| units(v))
+        .or(from_region)
+    {
+        out.push(("units".to_string(), units.name().to_string()));
+    }
+    if let Some((language, _)) = locale {
+        out.push(("language".to_string(), language));
+    }
+    out
+}
@@ -1,13 +1,983 @@
 //! weather-helper binary
 //!
-//! Thin CLI wrapper around the library. Parses the root argument and invokes
-//! `weather_helper::run`.
+//! Thin CLI wrapper around the library. Parses the root argument and flags and
+//! invokes `weather_helper::run`, or handles the `new` subcommand. The
+//! verbosity flags `-q`, `-v` and `-vv` apply to every subcommand, and
+//! `completions <shell>` prints a bash/zsh/fish completion script.
 
-use anyhow::Result;
-use std::env;
+use anyhow::{Context, Result, anyhow};
+use chrono::{Datelike, NaiveDate};
+use clap::builder::{PossibleValue, TypedValueParser};
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use weather_helper::config::{self, Config, Origin};
+use weather_helper::dates::{DateOrder, parse_trip_date};
+use weather_helper::history::HistoryMode;
+use weather_helper::map::MapMode;
+use weather_helper::telemetry::Verbosity;
+use weather_helper::{
+    RunOptions, history, io, metrics, migrate, output, overview, plan, schema, simulate, stats,
+    templates, terminal, validate, width,
+};
+
+/// Update Markdown packing notes with weather forecasts for their trips.
+#[derive(Parser, Debug)]
+#[command(name = "weather-helper", version)]
+struct Cli {
+    /// Print errors only.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print request URLs and timing (-v), or everything traced (-vv).
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+    /// Config file to use instead of ~/.config/weather-helper/config.yaml.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Override a setting, e.g. --set geocoder=photon (repeatable).
+    #[arg(
+        long = "set",
+        global = true,
+        value_name = "KEY=VALUE",
+        value_parser = SetParser
+    )]
+    set: Vec<(String, String)>,
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+/// Options for the default command, which updates every note under a root.
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Folder to scan for trip notes.
+    #[arg(default_value = ".")]
+    root: String,
+    /// Give up on a single request after this long (e.g. 10s, 500ms).
+    #[arg(long)]
+    timeout: Option<String>,
+    /// Stop starting new notes after this long (e.g. 5m).
+    #[arg(long)]
+    deadline: Option<String>,
+    /// Ask before updating more than this many notes (default 100; off for
+    /// no limit), in case the folder is not the vault.
+    #[arg(long, value_name = "N")]
+    max_notes: Option<String>,
+    /// Update more than max-notes notes without asking.
+    #[arg(long, short = 'y')]
+    yes: bool,
+    /// Plan as if today were this date (YYYY-MM-DD), e.g. to reproduce a
+    /// bug report; defaults to the system clock.
+    #[arg(long, value_name = "DATE")]
+    today: Option<String>,
+    /// Pace API requests (one per second plus a random delay) for shared
+    /// networks and community-hosted mirrors.
+    #[arg(long)]
+    polite: bool,
+    /// Fetch weather for shared grid points this many km apart, so nearby
+    /// destinations reuse one request (e.g. 5km).
+    #[arg(long, value_name = "KM")]
+    grid_radius: Option<String>,
+    /// Geocoding backend.
+    #[arg(long, value_parser = ["open-meteo", "nominatim", "photon"])]
+    geocoder: Option<String>,
+    /// Language for place names (ISO 639-1, e.g. de).
+    #[arg(long)]
+    language: Option<String>,
+    /// Record geocoded lat/lon/timezone in each note's frontmatter.
+    #[arg(long)]
+    write_coords: bool,
+    /// When to color status lines.
+    #[arg(long, value_parser = ["auto", "always", "never"])]
+    color: Option<String>,
+    /// Forecast source; auto uses NWS for US locations.
+    #[arg(long, value_parser = ["open-meteo", "nws", "met-norway", "visual-crossing", "auto"])]
+    provider: Option<String>,
+    /// Open-Meteo weather model, e.g. ecmwf_ifs025 (implies open-meteo).
+    #[arg(long)]
+    model: Option<String>,
+    /// Unit for summary lines.
+    #[arg(long, value_parser = ["fahrenheit", "celsius"])]
+    units: Option<String>,
+    /// Languages of the summary line, e.g. "en, it" for one line in each.
+    #[arg(long, value_name = "LANGS")]
+    summary_lang: Option<String>,
+    /// Open-Meteo daily variables to add as table columns, e.g.
+    /// "precipitation_sum, wind_speed_10m_max".
+    #[arg(long, value_name = "NAMES")]
+    vars: Option<String>,
+    /// Add a packing checklist to notes that lack one.
+    #[arg(long)]
+    packing: bool,
+    /// YAML file of packing rules replacing the built-in ones.
+    #[arg(long, value_name = "PATH")]
+    packing_rules: Option<String>,
+    /// Suggest clothing counts in the block summary.
+    #[arg(long)]
+    outfit: bool,
+    /// YAML file of outfit rules replacing the built-in ones.
+    #[arg(long, value_name = "PATH")]
+    outfit_rules: Option<String>,
+    /// Sun and hydration advisories: on, off, or thresholds such as
+    /// "uv=7, hot=32, heat-index=35".
+    #[arg(long, value_name = "on|off|THRESHOLDS")]
+    advisories: Option<String>,
+    /// Home timezone for the header's clock difference (IANA name; defaults
+    /// to the system zone).
+    #[arg(long, value_name = "ZONE")]
+    home_timezone: Option<String>,
+    /// Say in the block when the clocks change at the destination during
+    /// the trip.
+    #[arg(long)]
+    dst_note: bool,
+    /// List public holidays at the destination.
+    #[arg(long)]
+    holidays: bool,
+    /// Add a destination facts footer (plugs, emergency number, tap water).
+    #[arg(long)]
+    facts: bool,
+    /// Show where the forecast point is: an OpenStreetMap link, or a link
+    /// plus a static map image saved to attachments/.
+    #[arg(long, value_parser = ["off", "link", "image"])]
+    map: Option<String>,
+    /// Show the resolved coordinates, elevation and timezone in the header.
+    #[arg(long)]
+    show_location: bool,
+    /// Pad table cells so columns line up in the raw Markdown, by display
+    /// width (CJK and other wide characters count double).
+    #[arg(long)]
+    align_tables: bool,
+    /// Write the day table as Hugo or Zola shortcodes, or an HTML partial,
+    /// for static sites.
+    #[arg(long, value_parser = ["markdown", "hugo", "zola", "html"])]
+    table_style: Option<String>,
+    /// Also write each note's daily data to `<note>.weather.json`.
+    #[arg(long)]
+    sidecar: bool,
+    /// Also embed each note's daily data in its block, as JSON in an HTML
+    /// comment.
+    #[arg(long)]
+    embed_data: bool,
+    /// Also write each note's days (dates, highs, lows) into this
+    /// frontmatter property, for Obsidian Bases and Dataview.
+    #[arg(long, value_name = "NAME")]
+    day_property: Option<String>,
+    /// Put each block in its own "Weather - <city> <Mon YYYY>" note and
+    /// embed it in the trip note.
+    #[arg(long)]
+    separate_note: bool,
+    /// Keep the weather section inside this heading's section, e.g.
+    /// "## Logistics" (one level below it, at the end of that section).
+    #[arg(long, value_name = "HEADING")]
+    under_heading: Option<String>,
+    /// When a note's update fails: keep it as it was, keep its data with a
+    /// banner saying how old it is, or replace its block with a warning.
+    #[arg(long, value_parser = ["keep", "stale", "block"])]
+    on_error: Option<String>,
+    /// How often a note is fetched again; notes can set their own with
+    /// `refresh:`. Manual notes only update in runs given the note itself.
+    #[arg(long, value_parser = ["always", "hourly", "daily", "weekly", "manual"])]
+    refresh: Option<String>,
+    /// Only process notes tagged with this tag (repeatable; nested tags
+    /// such as work/conference match `work`).
+    #[arg(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+    /// Only process trips under way or arriving within this long (e.g. 30d).
+    #[arg(long)]
+    only_upcoming: Option<String>,
+    /// Skip trips that ended more than this long ago (default 30d).
+    #[arg(long)]
+    retention: Option<String>,
+    /// Refresh past trips too, however long ago they ended.
+    #[arg(long)]
+    include_past: bool,
+    /// Link note paths in the output to Obsidian (obsidian://open).
+    #[arg(long)]
+    links: bool,
+    /// Sync written files to disk: off, file (before the rename) or full
+    /// (also the directory).
+    #[arg(long, value_parser = ["off", "file", "full"])]
+    fsync: Option<String>,
+    /// Read notes through this shell command; its stdout is the note's text.
+    /// The note's path is `$1`.
+    #[arg(long)]
+    pre_read: Option<String>,
+    /// Run this shell command after writing each note (path in `$1`).
+    #[arg(long)]
+    post_write: Option<String>,
+    /// Log every fetch to a JSONL history: one global log, or one beside
+    /// each note.
+    #[arg(long, value_parser = ["off", "global", "per-note"])]
+    history: Option<String>,
+    /// Rotate a history log once it reaches this size (e.g. 5MB).
+    #[arg(long, value_name = "SIZE")]
+    history_limit: Option<String>,
+    /// Flag forecast days crossing any of these rules, e.g.
+    /// "tmax_f > 95, rain_prob > 60".
+    #[arg(long, value_name = "RULES")]
+    alert_if: Option<String>,
+    /// What makes days good when picking a flexible trip's dates, e.g.
+    /// "dry, mild=24".
+    #[arg(long, value_name = "TERMS")]
+    window_score: Option<String>,
+    /// Run this shell command for each note with alerts (path in `$1`,
+    /// alerts in `WEATHER_HELPER_ALERTS`).
+    #[arg(long)]
+    on_alert: Option<String>,
+    /// Run this shell command after each note, with its outcome as JSON on
+    /// stdin and its path in `$1`.
+    #[arg(long)]
+    after_note: Option<String>,
+    /// Run this shell command at the end of the run, with every note's
+    /// outcome as JSON on stdin and the vault root in `$1`.
+    #[arg(long)]
+    after_run: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create a trip note from a template.
+    New(NewArgs),
+    /// Render the block for a saved API payload (maintainer tool).
+    #[command(hide = true)]
+    Render {
+        /// Recorded forecast/archive JSON response.
+        #[arg(long)]
+        fixture: PathBuf,
+        /// Header line to use instead of the date range.
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Check a recorded API payload and report which field or index fails.
+    ValidateFixture {
+        /// Saved Open-Meteo forecast or archive JSON response.
+        file: PathBuf,
+    },
+    /// Time a run over a synthetic vault against a mock provider.
+    #[cfg(feature = "simulator")]
+    Bench {
+        #[arg(long, default_value_t = 1000)]
+        notes: usize,
+        #[arg(long)]
+        dir: Option<PathBuf>,
+        #[arg(long, default_value_t = 1)]
+        seed: u64,
+    },
+    /// Preview one note's weather as a terminal table, without changing it.
+    Show {
+        /// The trip note.
+        note: PathBuf,
+    },
+    /// Browse trips interactively: refresh, review the diff, then apply.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+    },
+    /// Fetch and record the blocks a run would write, without writing.
+    Plan {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// Write the plan here instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Write exactly the blocks recorded by `plan`.
+    Apply {
+        /// Plan file from `weather-helper plan`.
+        #[arg(long)]
+        plan: PathBuf,
+        /// Folder the plan's note paths are relative to.
+        #[arg(default_value = ".")]
+        root: String,
+    },
+    /// Print every trip under way or to come with its weather, without
+    /// writing to the vault.
+    Overview {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// Print JSON instead of text.
+        #[arg(long)]
+        json: bool,
+        /// Write the overview here instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Keep running: update the vault every interval and serve Prometheus
+    /// metrics on /metrics.
+    Serve {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// Time between runs, e.g. 30m or 6h.
+        #[arg(long, default_value = "1h")]
+        every: String,
+        /// Address for the /metrics endpoint.
+        #[arg(long, default_value = "127.0.0.1:9184")]
+        metrics_addr: String,
+    },
+    /// Show what a run on another day would fetch for each note, without
+    /// network calls.
+    Simulate {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// The day to simulate (YYYY-MM-DD).
+        #[arg(long, value_name = "DATE")]
+        on: String,
+    },
+    /// Rename frontmatter keys or rewrite trip dates to ISO across a vault.
+    MigrateFrontmatter {
+        /// Folder to scan for notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// Key to rename.
+        #[arg(long, requires = "to")]
+        from: Option<String>,
+        /// New name for the `--from` key.
+        #[arg(long, requires = "from")]
+        to: Option<String>,
+        /// Rewrite `arrival` and `departure` to YYYY-MM-DD.
+        #[arg(long)]
+        iso_dates: bool,
+        /// How to read ambiguous NN/NN/YYYY dates (dmy or mdy) in notes
+        /// without a `date-order` key.
+        #[arg(long, value_name = "ORDER", requires = "iso_dates")]
+        date_order: Option<String>,
+        /// List the edits without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Save each changed note's original as `<note>.md.bak` first.
+        #[arg(long)]
+        backup: bool,
+    },
+    /// Summarize a year of past trips from archive actuals.
+    Stats {
+        /// Folder to scan for trip notes.
+        #[arg(default_value = ".")]
+        root: String,
+        /// Calendar year; defaults to the current one.
+        #[arg(long)]
+        year: Option<i32>,
+        /// Write the report note here instead of printing it.
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+    },
+    /// Print the frontmatter keys trip notes may use, for vault-side
+    /// validation and autocomplete.
+    Schema {
+        /// JSON Schema, or Obsidian's `.obsidian/types.json`.
+        #[arg(long, default_value = "json-schema", value_parser = ["json-schema", "obsidian"])]
+        format: String,
+        /// Write the schema here instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Inspect the effective configuration.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Read the fetch log kept with `history`.
+    #[command(subcommand)]
+    History(HistoryCommand),
+    /// Print a shell completion script.
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Print every setting's effective value.
+    Show {
+        /// Also print which layer each value came from.
+        #[arg(long)]
+        origin: bool,
+        /// Include the `weather-<key>` overrides of this note.
+        #[arg(long, value_name = "NOTE")]
+        note: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum HistoryCommand {
+    /// List logged fetches, oldest first.
+    Show {
+        /// Only fetches for this note (a path, or its end such as Rome.md).
+        note: Option<String>,
+        /// Only this day's values, to see how its forecast moved.
+        #[arg(long, value_name = "YYYY-MM-DD")]
+        date: Option<NaiveDate>,
+    },
+}
+
+#[derive(Args, Debug)]
+struct NewArgs {
+    /// Template name (a `<name>.md` file in the templates directory).
+    #[arg(long, required_unless_present = "list")]
+    template: Option<String>,
+    /// Output path; defaults to "<city> <arrival>.md".
+    #[arg(long)]
+    out: Option<PathBuf>,
+    /// Directory holding templates.
+    #[arg(long)]
+    templates_dir: Option<PathBuf>,
+    /// List available templates and exit.
+    #[arg(long)]
+    list: bool,
+    #[arg(long)]
+    city: Option<String>,
+    #[arg(long)]
+    arrival: Option<String>,
+    #[arg(long)]
+    departure: Option<String>,
+    /// Extra template variable (repeatable).
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_var)]
+    vars: Vec<(String, String)>,
+}
+
+/// Parses `--set key=value`, offering `key=` completions.
+#[derive(Clone, Debug)]
+struct SetParser;
+
+impl TypedValueParser for SetParser {
+    type Value = (String, String);
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &std::ffi::OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        let invalid = |msg: String| {
+            let mut err = clap::Error::new(clap::error::ErrorKind::InvalidValue).with_cmd(cmd);
+            if let Some(arg) = arg {
+                err.insert(
+                    clap::error::ContextKind::InvalidArg,
+                    clap::error::ContextValue::String(arg.to_string()),
+                );
+            }
+            err.insert(
+                clap::error::ContextKind::InvalidValue,
+                clap::error::ContextValue::String(msg),
+            );
+            err
+        };
+        let text = value.to_string_lossy();
+        let (key, val) = parse_var(&text).map_err(|e| invalid(e.to_string()))?;
+        if !config::KEYS.contains(&key.as_str()) {
+            return Err(invalid(format!(
+                "{text} (unknown key '{key}'; expected one of {})",
+                config::KEYS.join(", ")
+            )));
+        }
+        Ok((key, val))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            config::KEYS
+                .iter()
+                .map(|k| PossibleValue::new(format!("{k}="))),
+        ))
+    }
+}
+
+fn parse_var(kv: &str) -> Result<(String, String)> {
+    let (k, v) = kv
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected key=value, got '{kv}'"))?;
+    Ok((k.trim().to_string(), v.trim().to_string()))
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let root = env::args().nth(1).unwrap_or_else(|| ".".to_string());
-    weather_helper::run(&root).await
+    let mut cli = Cli::parse();
+    let verbosity = Verbosity::from_flags(cli.quiet, cli.verbose);
+    let _telemetry = weather_helper::telemetry::init(verbosity)?;
+    match cli.command.take() {
+        Some(Command::New(args)) => new_note(args, today(&cli)?, verbosity),
+        Some(Command::Render { fixture, label }) => render(&fixture, label.as_deref()),
+        Some(Command::ValidateFixture { file }) => {
+            let json = io::read(&file)
+                .with_context(|| format!("cannot read fixture {}", file.display()))?;
+            let report = validate::validate_fixture(&json)
+                .with_context(|| format!("{} is not a usable payload", file.display()))?;
+            print!("{report}");
+            Ok(())
+        }
+        #[cfg(feature = "simulator")]
+        Some(Command::Bench { notes, dir, seed }) => {
+            bench(notes, dir, seed, today(&cli)?, verbosity).await
+        }
+        Some(Command::Completions { shell }) => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "weather-helper",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+        Some(Command::Config(ConfigCommand::Show { origin, note })) => {
+            let mut config = load_config(&cli)?;
+            if let Some(note) = note {
+                let meta = weather_helper::extract_meta(&note)?;
+                config.apply_note(&meta.overrides, &meta.path)?;
+            }
+            print!("{}", config.show(origin));
+            Ok(())
+        }
+        Some(Command::Show { note }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let mut opts = load_config(&cli)?.run_options(&base)?;
+            opts.install();
+            // A preview never downloads map images into the vault.
+            if opts.map == MapMode::Image {
+                opts.map = MapMode::Link;
+            }
+            let meta = weather_helper::extract_meta(&note)
+                .with_context(|| format!("cannot read trip from {}", note.display()))?;
+            let client = opts.client()?;
+            let prepared = weather_helper::prepare_note(&meta, &client, &opts).await?;
+            let color = opts.color.enabled(std::io::stdout().is_terminal());
+            print!("{}", terminal::render(&prepared, color));
+            Ok(())
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Tui { root }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            weather_helper::tui::run(&root, &opts).await
+        }
+        Some(Command::Plan { root, out }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            let (plan, failures) = plan::plan(&root, &opts).await?;
+            for failure in &failures {
+                eprintln!("Warning: not planned: {failure}");
+            }
+            let json = serde_json::to_string_pretty(&plan)? + "\n";
+            match out {
+                Some(path) => io::write(&path, json)
+                    .with_context(|| format!("cannot write {}", path.display()))?,
+                None => print!("{json}"),
+            }
+            if verbosity.shows_progress() {
+                eprintln!("{} note(s) would change.", plan.changes.len());
+            }
+            Ok(())
+        }
+        Some(Command::Apply { plan: file, root }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            opts.install();
+            let text = io::read_to_string(&file)
+                .with_context(|| format!("cannot read plan {}", file.display()))?;
+            let plan = plan::parse(&text)
+                .with_context(|| format!("{} is not a usable plan", file.display()))?;
+            let printer = output::Printer::new(verbosity, opts.color).with_links(opts.links);
+            let mut refused = 0;
+            for change in &plan.changes {
+                match plan::apply_change(Path::new(&root), change) {
+                    Ok(plan::Applied::Written) => {
+                        printer.status(output::Status::Updated, &change.note, None)
+                    }
+                    Ok(plan::Applied::Conflict) => {
+                        refused += 1;
+                        let why = "block changed since the plan was made";
+                        printer.status(output::Status::Failed, &change.note, Some(why));
+                    }
+                    Err(e) => {
+                        refused += 1;
+                        printer.status(
+                            output::Status::Failed,
+                            &change.note,
+                            Some(&format!("{e:#}")),
+                        );
+                    }
+                }
+            }
+            if refused > 0 {
+                return Err(anyhow!("{refused} planned change(s) were not applied"));
+            }
+            Ok(())
+        }
+        Some(Command::Overview { root, json, out }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            let overview = overview::overview(&root, &opts).await?;
+            let text = match json {
+                true => serde_json::to_string_pretty(&overview)? + "\n",
+                false => overview::report(&overview),
+            };
+            match out {
+                Some(path) => io::write(&path, text)
+                    .with_context(|| format!("cannot write {}", path.display()))?,
+                None => print!("{text}"),
+            }
+            Ok(())
+        }
+        Some(Command::Serve {
+            root,
+            every,
+            metrics_addr,
+        }) => {
+            let every = weather_helper::parse_duration(&every).context("invalid --every")?;
+            let base = RunOptions {
+                verbosity,
+                yes: cli.run.yes,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            let listener = metrics::bind(&metrics_addr).await?;
+            if verbosity.shows_progress() {
+                eprintln!("Serving metrics on http://{metrics_addr}/metrics");
+            }
+            metrics::serve_runs(&root, &opts, every, listener).await
+        }
+        Some(Command::Simulate { root, on }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let mut config = load_config(&cli)?;
+            config
+                .set("today", &on, Origin::Flag)
+                .context("invalid --on")?;
+            let opts = config.run_options(&base)?;
+            print!("{}", simulate::report(&simulate::simulate(&root, &opts)));
+            Ok(())
+        }
+        Some(Command::MigrateFrontmatter {
+            root,
+            from,
+            to,
+            iso_dates,
+            date_order,
+            dry_run,
+            backup,
+        }) => {
+            let mut migrations = Vec::new();
+            if let (Some(from), Some(to)) = (from, to) {
+                migrations.push(migrate::Migration::RenameKey { from, to });
+            }
+            if iso_dates {
+                let order = date_order
+                    .as_deref()
+                    .map(DateOrder::parse)
+                    .transpose()?
+                    .unwrap_or_default();
+                migrations.push(migrate::Migration::IsoDates {
+                    keys: migrate::DATE_KEYS.iter().map(|k| k.to_string()).collect(),
+                    order,
+                });
+            }
+            if migrations.is_empty() {
+                return Err(anyhow!(
+                    "nothing to migrate: pass --from/--to or --iso-dates"
+                ));
+            }
+            let color = load_config(&cli)?
+                .run_options(&RunOptions::default())?
+                .color;
+            let printer = output::Printer::new(verbosity, color);
+            let (mut changed, mut failed) = (0, 0);
+            for note in migrate::migrate_vault(&root, &migrations, dry_run, backup) {
+                let path = note.path.display().to_string();
+                match note.result {
+                    Ok(changes) => {
+                        changed += 1;
+                        printer.status(output::Status::Updated, &path, Some(&changes.join("; ")));
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        printer.status(output::Status::Failed, &path, Some(&format!("{e:#}")));
+                    }
+                }
+            }
+            match dry_run {
+                true => eprintln!("{changed} note(s) would change (dry run)."),
+                false => eprintln!("{changed} note(s) migrated."),
+            }
+            if failed > 0 {
+                return Err(anyhow!("{failed} note(s) were not migrated"));
+            }
+            Ok(())
+        }
+        Some(Command::History(HistoryCommand::Show { note, date })) => {
+            let opts = load_config(&cli)?.run_options(&RunOptions::default())?;
+            let log = match (&note, opts.history) {
+                (Some(note), HistoryMode::PerNote) => history::note_path(note),
+                _ => history::global_path(),
+            };
+            let entries = history::read(&log);
+            let entries = match &note {
+                Some(note) => history::for_note(&entries, note),
+                None => entries.iter().collect(),
+            };
+            print!("{}", history::show(&entries, date, opts.units));
+            Ok(())
+        }
+        Some(Command::Schema { format, out }) => {
+            let text = schema::export(&load_config(&cli)?, schema::SchemaFormat::parse(&format)?);
+            match out {
+                Some(path) => io::write(&path, text)
+                    .with_context(|| format!("cannot write {}", path.display())),
+                None => {
+                    print!("{text}");
+                    Ok(())
+                }
+            }
+        }
+        Some(Command::Stats { root, year, out }) => {
+            let base = RunOptions {
+                verbosity,
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            opts.install();
+            let today = opts.today();
+            let year = year.unwrap_or_else(|| today.year());
+            let trips = stats::collect(&root, year, today, &opts).await?;
+            let mut report = stats::report(year, &trips, opts.units, opts.links);
+            if opts.align_tables {
+                report = width::align_tables(&report);
+            }
+            match out {
+                Some(path) => io::write(&path, report)
+                    .with_context(|| format!("cannot write {}", path.display())),
+                None => {
+                    print!("{report}");
+                    Ok(())
+                }
+            }
+        }
+        None => {
+            let base = RunOptions {
+                verbosity,
+                yes: cli.run.yes,
+                confirm: Some(confirm_large_run),
+                ..RunOptions::default()
+            };
+            let opts = load_config(&cli)?.run_options(&base)?;
+            weather_helper::run(&cli.run.root, &opts)
+                .await?
+                .into_result()
+        }
+    }
+}
+
+/// Ask on the terminal before a run updates more than `max-notes` notes;
+/// without a terminal to ask on, the answer is no.
+fn confirm_large_run(count: usize, root: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    eprint!("Update {count} notes under {root}? [y/N] ");
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer).is_ok()
+        && matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+/// Resolve defaults, config file and environment, then the command-line
+/// flags and `--set` overrides in order.
+fn load_config(cli: &Cli) -> Result<Config> {
+    let mut config = Config::load(cli.config.as_deref())?;
+    let run = &cli.run;
+    let polite = run.polite.then(|| "true".to_string());
+    let write_coords = run.write_coords.then(|| "true".to_string());
+    let packing = run.packing.then(|| "true".to_string());
+    let outfit = run.outfit.then(|| "true".to_string());
+    let dst_note = run.dst_note.then(|| "true".to_string());
+    let holidays = run.holidays.then(|| "true".to_string());
+    let facts = run.facts.then(|| "true".to_string());
+    let show_location = run.show_location.then(|| "true".to_string());
+    let align_tables = run.align_tables.then(|| "true".to_string());
+    let links = run.links.then(|| "true".to_string());
+    let sidecar = run.sidecar.then(|| "true".to_string());
+    let embed_data = run.embed_data.then(|| "true".to_string());
+    let separate_note = run.separate_note.then(|| "true".to_string());
+    let tags = (!run.tags.is_empty()).then(|| run.tags.join(","));
+    let include_past = run.include_past.then(|| "true".to_string());
+    for (key, value) in [
+        ("timeout", &run.timeout),
+        ("deadline", &run.deadline),
+        ("max-notes", &run.max_notes),
+        ("today", &run.today),
+        ("polite", &polite),
+        ("grid-radius", &run.grid_radius),
+        ("geocoder", &run.geocoder),
+        ("language", &run.language),
+        ("write-coords", &write_coords),
+        ("color", &run.color),
+        ("units", &run.units),
+        ("summary-lang", &run.summary_lang),
+        ("vars", &run.vars),
+        ("provider", &run.provider),
+        ("model", &run.model),
+        ("packing", &packing),
+        ("packing-rules", &run.packing_rules),
+        ("outfit", &outfit),
+        ("outfit-rules", &run.outfit_rules),
+        ("advisories", &run.advisories),
+        ("home-timezone", &run.home_timezone),
+        ("dst-note", &dst_note),
+        ("holidays", &holidays),
+        ("facts", &facts),
+        ("map", &run.map),
+        ("show-location", &show_location),
+        ("align-tables", &align_tables),
+        ("table-style", &run.table_style),
+        ("sidecar", &sidecar),
+        ("embed-data", &embed_data),
+        ("day-property", &run.day_property),
+        ("separate-note", &separate_note),
+        ("under-heading", &run.under_heading),
+        ("on-error", &run.on_error),
+        ("refresh", &run.refresh),
+        ("tags", &tags),
+        ("only-upcoming", &run.only_upcoming),
+        ("retention", &run.retention),
+        ("include-past", &include_past),
+        ("links", &links),
+        ("fsync", &run.fsync),
+        ("pre-read", &run.pre_read),
+        ("post-write", &run.post_write),
+        ("alert-if", &run.alert_if),
+        ("window-score", &run.window_score),
+        ("on-alert", &run.on_alert),
+        ("after-note", &run.after_note),
+        ("after-run", &run.after_run),
+        ("history", &run.history),
+        ("history-limit", &run.history_limit),
+    ] {
+        if let Some(value) = value {
+            config
+                .set(key, value, Origin::Flag)
+                .with_context(|| format!("invalid --{key}"))?;
+        }
+    }
+    for (key, value) in &cli.set {
+        config.set(key, value, Origin::Flag)?;
+    }
+    Ok(config)
+}
+
+/// The day commands outside a run treat as today: `today` from the config
+/// or `--set`, else the clock's date.
+fn today(cli: &Cli) -> Result<NaiveDate> {
+    Ok(load_config(cli)?
+        .run_options(&RunOptions::default())?
+        .today())
+}
+
+/// Print the block rendered from a saved API response.
+fn render(fixture: &PathBuf, label: Option<&str>) -> Result<()> {
+    let json =
+        io::read(fixture).with_context(|| format!("cannot read fixture {}", fixture.display()))?;
+    print!("{}", weather_helper::render_fixture(&json, label)?);
+    Ok(())
+}
+
+/// Create a note from a template with the built-in `today`/`days` variables
+/// plus `--city`, `--arrival`, `--departure` and `--var` values.
+fn new_note(args: NewArgs, today: NaiveDate, verbosity: Verbosity) -> Result<()> {
+    let dir = args
+        .templates_dir
+        .unwrap_or_else(templates::default_templates_dir);
+    if args.list {
+        for name in templates::list_templates(&dir)? {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+    let mut vars = BTreeMap::new();
+    vars.insert("today".to_string(), today.format("%Y-%m-%d").to_string());
+    for (key, value) in [
+        ("city", args.city),
+        ("arrival", args.arrival),
+        ("departure", args.departure),
+    ] {
+        if let Some(value) = value {
+            vars.insert(key.to_string(), value);
+        }
+    }
+    vars.extend(args.vars);
+
+    let template = args
+        .template
+        .ok_or_else(|| anyhow!("new requires --template <name>"))?;
+    if let (Some(a), Some(d)) = (vars.get("arrival"), vars.get("departure")) {
+        let a = parse_trip_date(a, DateOrder::default())?.date;
+        let d = parse_trip_date(d, DateOrder::default())?.date;
+        vars.insert("days".to_string(), ((d - a).num_days() + 1).to_string());
+    }
+    let out = args.out.unwrap_or_else(|| {
+        let city = vars.get("city").map(String::as_str).unwrap_or(&template);
+        match vars.get("arrival") {
+            Some(arrival) => PathBuf::from(format!("{city} {arrival}.md")),
+            None => PathBuf::from(format!("{city}.md")),
+        }
+    });
+    templates::new_note(&dir, &template, &vars, &out)?;
+    if verbosity.shows_progress() {
+        println!("Created {}", out.display());
+    }
+    Ok(())
+}
+
+/// Generate a synthetic vault and time a full run against the in-process
+/// mock provider.
+#[cfg(feature = "simulator")]
+async fn bench(
+    count: usize,
+    dir: Option<PathBuf>,
+    seed: u64,
+    today: NaiveDate,
+    verbosity: Verbosity,
+) -> Result<()> {
+    use weather_helper::simulator::{MockProvider, bench_options, generate_vault};
+    let dir = dir.unwrap_or_else(|| std::env::temp_dir().join(format!("weather-bench-{count}")));
+    let started = std::time::Instant::now();
+    generate_vault(&dir, count, today, seed)?;
+    println!("Generated {count} notes in {:?}", started.elapsed());
+
+    let mock = MockProvider::start().await?;
+    // SAFETY: set once before the run starts; nothing reads the
+    // environment concurrently at this point.
+    unsafe { mock.install_env() };
+    // Keep mock traffic out of the real daily request totals.
+    // SAFETY: as above.
+    unsafe { std::env::set_var("WEATHER_HELPER_USAGE_FILE", dir.join("usage.json")) };
+    let started = std::time::Instant::now();
+    let opts = RunOptions {
+        today: Some(today),
+        ..bench_options(verbosity)
+    };
+    let result = weather_helper::run(&dir.to_string_lossy(), &opts)
+        .await
+        .and_then(weather_helper::RunReport::into_result);
+    println!("Processed {count} notes in {:?}", started.elapsed());
+    result
 }
@@ -0,0 +1,103 @@
+//! Where the forecast point is.
+//!
+//! With `map: link` the block's summary gets an OpenStreetMap link to the
+//! resolved coordinates, so an ambiguous name that geocoded to the wrong
+//! "Springfield" is easy to spot. `map: image` also downloads a small static
+//! map into an `attachments/` folder next to the note and embeds it below
+//! the table. Images are named after the coordinates and only downloaded
+//! once per location.
+
+use crate::http;
+use crate::io;
+use anyhow::{Context, Result, anyhow};
+use std::path::{Path, PathBuf};
+
+/// `map` setting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MapMode {
+    #[default]
+    Off,
+    Link,
+    Image,
+}
+
+impl MapMode {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "none" | "" => Ok(MapMode::Off),
+            "link" | "on" | "true" => Ok(MapMode::Link),
+            "image" => Ok(MapMode::Image),
+            other => Err(anyhow!(
+                "unknown map mode '{other}' (expected off, link or image)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`MapMode::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            MapMode::Off => "off",
+            MapMode::Link => "link",
+            MapMode::Image => "image",
+        }
+    }
+}
+
+/// Static map service; overridable for tests.
+pub fn static_map_base() -> String {
+    std::env::var("STATIC_MAP_BASE")
+        .unwrap_or_else(|_| "https://staticmap.openstreetmap.de/staticmap.php".to_string())
+}
+
+/// OpenStreetMap page centered on the point, with a marker.
+pub fn osm_link(lat: f64, lon: f64) -> String {
+    format!("https://www.openstreetmap.org/?mlat={lat:.4}&mlon={lon:.4}#map=12/{lat:.4}/{lon:.4}")
+}
+
+/// Summary line, e.g. `**Map**: [41.9028, 12.4964](https://www.openstreetmap.org/...)`.
+pub fn map_line(lat: f64, lon: f64) -> String {
+    format!("**Map**: [{lat:.4}, {lon:.4}]({})", osm_link(lat, lon))
+}
+
+/// Where the image for a note at `md_path` goes: `attachments/<stem>-map-<lat>_<lon>.png`
+/// next to the note.
+pub fn image_path(md_path: &str, lat: f64, lon: f64) -> PathBuf {
+    let p = Path::new(md_path);
+    let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+    let parent = p.parent().unwrap_or_else(|| Path::new(""));
+    parent
+        .join("attachments")
+        .join(format!("{stem}-map-{lat:.3}_{lon:.3}.png"))
+}
+
+/// Markdown embedding the image, relative to the note.
+pub fn embed(image: &Path) -> String {
+    let name = image.file_name().unwrap_or_default().to_string_lossy();
+    format!("![Map](attachments/{})\n", name.replace(' ', "%20"))
+}
+
+/// Download the static map for `path`, or `None` if it is already there.
+/// Nothing is written; see [`save_image`].
+pub async fn fetch_image(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    path: &Path,
+) -> Result<Option<Vec<u8>>> {
+    if path.exists() {
+        return Ok(None);
+    }
+    let url = format!(
+        "{}?center={lat:.4},{lon:.4}&zoom=11&size=480x240&markers={lat:.4},{lon:.4},red-pushpin",
+        static_map_base()
+    );
+    Ok(Some(client.get_bytes(&url).await?))
+}
+
+/// Write a downloaded map to `path`, creating its folder.
+pub fn save_image(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        io::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    }
+    io::write(path, bytes).with_context(|| format!("cannot write {}", path.display()))
+}
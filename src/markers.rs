@@ -0,0 +1,78 @@
+//! The markers around a weather block and the block format's version.
+//!
+//! Blocks open with `<!-- WEATHER:BEGIN v2 -->`. Blocks written before the
+//! format was versioned have a bare `<!-- WEATHER:BEGIN -->` and count as
+//! v1. Notes are read whatever the version; blocks that are kept rather
+//! than re-rendered (the stale banner after a failed update) go through
+//! [`migrate`], one step per version, so a vault does not drift into mixed
+//! formats. A block from a newer weather-helper is never overwritten.
+
+use crate::outline;
+use anyhow::{Result, anyhow};
+use regex::Regex;
+
+/// Format version of the blocks this build writes.
+pub const VERSION: u32 = 2;
+/// Begin marker for [`VERSION`].
+pub const BEGIN: &str = "<!-- WEATHER:BEGIN v2 -->";
+pub const END: &str = "<!-- WEATHER:END -->";
+/// Regex source matching a begin marker of any version.
+pub const BEGIN_PATTERN: &str = r"<!-- WEATHER:BEGIN(?: v\d+)? -->";
+
+/// `MIGRATIONS[i]` upgrades a block from version `i + 1` to `i + 2`.
+const MIGRATIONS: &[fn(&str) -> String] = &[v1_to_v2];
+
+/// v2 only adds the version to the begin marker.
+fn v1_to_v2(block: &str) -> String {
+    block.replacen("<!-- WEATHER:BEGIN -->", "<!-- WEATHER:BEGIN v2 -->", 1)
+}
+
+/// Versions of the blocks in `text`, in order.
+pub fn versions(text: &str) -> Vec<u32> {
+    let re = Regex::new(r"<!-- WEATHER:BEGIN(?: v(\d+))? -->").unwrap();
+    re.captures_iter(text)
+        .map(|c| c.get(1).and_then(|v| v.as_str().parse().ok()).unwrap_or(1))
+        .collect()
+}
+
+/// Version of a block, from its begin marker; `None` if it has none.
+pub fn version(block: &str) -> Option<u32> {
+    versions(block).first().copied()
+}
+
+/// Refuse notes holding a block newer than this build understands.
+pub fn check_supported(content: &str) -> Result<()> {
+    match versions(content).into_iter().max() {
+        Some(v) if v > VERSION => Err(anyhow!(
+            "weather block is format v{v}, newer than this weather-helper (v{VERSION}); upgrade to update it"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Upgrade one block (begin marker onwards) to [`VERSION`].
+pub fn migrate_block(block: &str) -> Result<String> {
+    let Some(from) = version(block) else {
+        return Ok(block.to_string());
+    };
+    check_supported(block)?;
+    let steps = MIGRATIONS.iter().skip(from.saturating_sub(1) as usize);
+    Ok(steps.fold(block.to_string(), |block, step| step(&block)))
+}
+
+/// `content` with every weather block upgraded to [`VERSION`]. Examples
+/// in code blocks or the frontmatter are left as written.
+pub fn migrate(content: &str) -> Result<String> {
+    let re = Regex::new(&format!("(?s){BEGIN_PATTERN}.*?{END}")).unwrap();
+    let protected = outline::protected_ranges(content);
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    let blocks = re.find_iter(content);
+    for m in blocks.filter(|m| !outline::is_protected(&protected, m.start())) {
+        out.push_str(&content[last..m.start()]);
+        out.push_str(&migrate_block(m.as_str())?);
+        last = m.end();
+    }
+    out.push_str(&content[last..]);
+    Ok(out)
+}
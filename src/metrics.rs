@@ -0,0 +1,232 @@
+//! Process-wide counters in Prometheus text exposition format.
+//!
+//! Counters are plain atomics so they can be bumped from anywhere in the
+//! library without threading a registry around. `render` produces the text a
+//! `/metrics` endpoint returns and `serve` exposes it over HTTP for
+//! long-running deployments; `weather-helper serve` runs [`serve_runs`],
+//! which updates the vault on an interval with the endpoint alongside.
+
+use crate::{RunOptions, RunReport, budget};
+use anyhow::{Context, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Monotonic counter.
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    pub const fn new() -> Self {
+        Counter(AtomicU64::new(0))
+    }
+
+    pub fn inc(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Upper bounds (seconds) of the request latency buckets.
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Cumulative latency histogram with fixed buckets.
+pub struct Histogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    pub const fn new() -> Self {
+        Histogram {
+            buckets: [const { AtomicU64::new(0) }; LATENCY_BUCKETS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub static NOTES_PROCESSED: Counter = Counter::new();
+pub static NOTES_FAILED: Counter = Counter::new();
+pub static API_CALLS: Counter = Counter::new();
+pub static API_FAILURES: Counter = Counter::new();
+pub static API_RETRIES: Counter = Counter::new();
+pub static CACHE_HITS: Counter = Counter::new();
+pub static REQUEST_LATENCY: Histogram = Histogram::new();
+
+/// Render every metric in Prometheus text format.
+pub fn render() -> String {
+    let mut out = String::new();
+    let counters: [(&str, &str, &Counter); 6] = [
+        (
+            "weather_helper_notes_processed_total",
+            "Notes successfully updated.",
+            &NOTES_PROCESSED,
+        ),
+        (
+            "weather_helper_notes_failed_total",
+            "Notes that failed to update.",
+            &NOTES_FAILED,
+        ),
+        (
+            "weather_helper_api_calls_total",
+            "HTTP requests sent to weather and geocoding APIs.",
+            &API_CALLS,
+        ),
+        (
+            "weather_helper_api_failures_total",
+            "HTTP requests that ended in an error.",
+            &API_FAILURES,
+        ),
+        (
+            "weather_helper_api_retries_total",
+            "HTTP requests retried after a failure.",
+            &API_RETRIES,
+        ),
+        (
+            "weather_helper_cache_hits_total",
+            "Responses served from cache instead of the network.",
+            &CACHE_HITS,
+        ),
+    ];
+    for (name, help, counter) in counters {
+        out.push_str(&format!(
+            "# HELP {name} {help}\n# TYPE {name} counter\n{name} {}\n",
+            counter.get()
+        ));
+    }
+
+    let labeled = [
+        (
+            "weather_helper_api_calls_by_service_total",
+            "HTTP requests this run, per service.",
+            "counter",
+            "service",
+            budget::run_calls(),
+        ),
+        (
+            "weather_helper_api_calls_by_city_total",
+            "HTTP requests this run, per note city.",
+            "counter",
+            "city",
+            budget::city_calls(),
+        ),
+        (
+            "weather_helper_api_calls_today",
+            "HTTP requests today across runs, per service.",
+            "gauge",
+            "service",
+            budget::today_calls(),
+        ),
+    ];
+    for (name, help, kind, label, values) in labeled {
+        out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+        for (key, n) in values {
+            let key = key.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("{name}{{{label}=\"{key}\"}} {n}\n"));
+        }
+    }
+
+    let name = "weather_helper_request_duration_seconds";
+    out.push_str(&format!(
+        "# HELP {name} Latency of HTTP requests.\n# TYPE {name} histogram\n"
+    ));
+    for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&REQUEST_LATENCY.buckets) {
+        out.push_str(&format!(
+            "{name}_bucket{{le=\"{bound}\"}} {}\n",
+            bucket.load(Ordering::Relaxed)
+        ));
+    }
+    let count = REQUEST_LATENCY.count.load(Ordering::Relaxed);
+    let sum = REQUEST_LATENCY.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+    out.push_str(&format!("{name}_bucket{{le=\"+Inf\"}} {count}\n"));
+    out.push_str(&format!("{name}_sum {sum}\n{name}_count {count}\n"));
+    out
+}
+
+/// Listen for metrics requests on `addr`.
+pub async fn bind(addr: &str) -> Result<tokio::net::TcpListener> {
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .with_context(|| format!("failed to bind metrics listener on {addr}"))
+}
+
+/// Serve `GET /metrics` on `addr` until the task is dropped.
+pub async fn serve(addr: &str) -> Result<()> {
+    serve_on(bind(addr).await?).await
+}
+
+/// Update `root` every `every`, serving `GET /metrics` on `listener` in
+/// the meantime. A failed run is reported and the next one goes ahead as
+/// planned; only a failing listener ends the loop.
+pub async fn serve_runs(
+    root: &str,
+    opts: &RunOptions,
+    every: Duration,
+    listener: tokio::net::TcpListener,
+) -> Result<()> {
+    let runs = async {
+        loop {
+            let result = crate::run(root, opts)
+                .await
+                .and_then(RunReport::into_result);
+            if let Err(e) = result {
+                eprintln!("Error: {e:#}");
+            }
+            tokio::time::sleep(every).await;
+        }
+    };
+    tokio::select! {
+        result = serve_on(listener) => result,
+        () = runs => Ok(()),
+    }
+}
+
+/// Serve `GET /metrics` on `listener` until the task is dropped.
+pub async fn serve_on(listener: tokio::net::TcpListener) -> Result<()> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let n = stream.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let (status, body) = if request.starts_with("GET /metrics ") {
+                ("200 OK", render())
+            } else {
+                ("404 Not Found", "not found\n".to_string())
+            };
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
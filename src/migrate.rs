@@ -0,0 +1,175 @@
+//! Bulk rewrites of frontmatter across a vault.
+//!
+//! Vaults that predate the tool often name keys differently (`destination`
+//! where the tool reads `city`) or write dates as `20/08/2025`.
+//! `weather-helper migrate-frontmatter` renames keys and rewrites dates to
+//! ISO in every note at once. Edits are line-based, like
+//! [`crate::frontmatter::set_keys`], so comments, key order and the rest of
+//! the note stay as written.
+
+use crate::dates::{self, DateOrder};
+use crate::{hooks, io, note_paths};
+use anyhow::{Context, Result, anyhow};
+use std::path::{Path, PathBuf};
+
+/// One rewrite applied to every note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Migration {
+    /// Rename top-level key `from` to `to`, keeping its value as written.
+    RenameKey { from: String, to: String },
+    /// Rewrite the dates of `keys` to `YYYY-MM-DD`. Ambiguous dates are
+    /// read in `order` unless the note sets its own `date-order`.
+    IsoDates { keys: Vec<String>, order: DateOrder },
+}
+
+/// The keys trip dates are read from.
+pub const DATE_KEYS: &[&str] = &["arrival", "departure"];
+
+/// What migrating one note did.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Migrated {
+    pub content: String,
+    /// One entry per edit, e.g. `destination → city` or
+    /// `arrival 20/08/2025 → 2025-08-20`.
+    pub changes: Vec<String>,
+}
+
+/// The frontmatter's line indices, excluding the `---` fences.
+fn frontmatter_lines(lines: &[&str]) -> Option<std::ops::Range<usize>> {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return None;
+    }
+    let close = lines.iter().skip(1).position(|l| l.trim_end() == "---")? + 1;
+    Some(1..close)
+}
+
+/// The value of a top-level `key: value` line, if `line` sets `key`.
+fn value_of<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.strip_prefix(key)?.trim_start().strip_prefix(':')
+}
+
+/// `value` without a trailing comment or surrounding quotes.
+fn bare(value: &str) -> &str {
+    let value = value.split(" #").next().unwrap_or(value).trim();
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value)
+}
+
+/// Apply `migrations` in order to one note. Notes without frontmatter come
+/// back unchanged; a rename onto a key the note already has is an error, as
+/// is a date that cannot be read.
+pub fn migrate_content(content: &str, migrations: &[Migration]) -> Result<Migrated> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut changes = Vec::new();
+    let borrowed: Vec<&str> = lines.iter().map(String::as_str).collect();
+    let Some(front) = frontmatter_lines(&borrowed) else {
+        return Ok(Migrated {
+            content: content.to_string(),
+            changes,
+        });
+    };
+    let find = |lines: &[String], key: &str| {
+        lines[front.clone()]
+            .iter()
+            .position(|l| value_of(l, key).is_some())
+            .map(|i| i + front.start)
+    };
+    for migration in migrations {
+        match migration {
+            Migration::RenameKey { from, to } => {
+                let Some(i) = find(&lines, from) else {
+                    continue;
+                };
+                if find(&lines, to).is_some() {
+                    return Err(anyhow!("has both '{from}' and '{to}'"));
+                }
+                let value = value_of(&lines[i], from).unwrap_or_default();
+                lines[i] = format!("{to}:{value}");
+                changes.push(format!("{from} → {to}"));
+            }
+            Migration::IsoDates { keys, order } => {
+                let order = match find(&lines, "date-order") {
+                    Some(i) => DateOrder::parse(bare(value_of(&lines[i], "date-order").unwrap()))?,
+                    None => *order,
+                };
+                for key in keys {
+                    let Some(i) = find(&lines, key) else {
+                        continue;
+                    };
+                    let raw = bare(value_of(&lines[i], key).unwrap()).to_string();
+                    let parsed = dates::parse_trip_date(&raw, order)
+                        .with_context(|| format!("invalid '{key}'"))?;
+                    // Datetimes are ISO already; only date-only styles change.
+                    if parsed.is_iso() || parsed.time.is_some() {
+                        continue;
+                    }
+                    lines[i] = format!("{key}: {}", parsed.date);
+                    changes.push(format!("{key} {raw} → {}", parsed.date));
+                }
+            }
+        }
+    }
+    let mut out = lines.join("\n");
+    if content.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(Migrated {
+        content: out,
+        changes,
+    })
+}
+
+/// Where the backup of `note` goes: `Rome.md` → `Rome.md.bak`.
+pub fn backup_path(note: &Path) -> PathBuf {
+    let mut name = note.as_os_str().to_owned();
+    name.push(".bak");
+    PathBuf::from(name)
+}
+
+/// Outcome for one note of a vault-wide migration.
+#[derive(Debug)]
+pub struct NoteMigration {
+    pub path: PathBuf,
+    /// The edits made (or, in a dry run, that would be), or why the note
+    /// was left alone.
+    pub result: Result<Vec<String>>,
+}
+
+/// Migrate every note under `root` that `migrations` change. With
+/// `dry_run` nothing is written; with `backup` each note's original is
+/// first saved next to it (see [`backup_path`]), and a note whose backup
+/// already exists is left alone so an earlier original is never lost.
+/// Notes are read and written through the `pre-read` and `post-write`
+/// [`hooks`], like any other run; the backup is the file as it was on disk.
+pub fn migrate_vault(
+    root: &str,
+    migrations: &[Migration],
+    dry_run: bool,
+    backup: bool,
+) -> Vec<NoteMigration> {
+    let mut out = Vec::new();
+    for path in note_paths(root) {
+        let result = (|| {
+            let content = hooks::read_note(&path).context("cannot read note")?;
+            let migrated = migrate_content(&content, migrations)?;
+            if migrated.changes.is_empty() || dry_run {
+                return Ok(migrated.changes);
+            }
+            if backup {
+                let bak = backup_path(&path);
+                let stored = io::read(&path).context("cannot read note")?;
+                io::write_new(&bak, stored)
+                    .with_context(|| format!("cannot write backup {}", bak.display()))?;
+            }
+            hooks::write_note(&path, &migrated.content).context("cannot write note")?;
+            Ok(migrated.changes)
+        })();
+        if !matches!(&result, Ok(changes) if changes.is_empty()) {
+            out.push(NoteMigration { path, result });
+        }
+    }
+    out
+}
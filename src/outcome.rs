@@ -0,0 +1,138 @@
+//! What a run did to each note.
+//!
+//! `process_note` returns a [`NoteOutcome`] and `run` collects one
+//! [`NoteResult`] per note into a [`RunReport`]. Status lines, the per-tag
+//! summary and the exit status are all derived from that report rather
+//! than from what was printed along the way.
+
+use crate::output::Status;
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+
+/// The result of processing one note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NoteOutcome {
+    /// The note was rewritten with different weather.
+    Updated,
+    /// The note was refreshed but only its fetch stamp moved.
+    Unchanged,
+    /// The note was not processed, and why.
+    Skipped(String),
+    /// Processing failed; the error with its causes, as shown to the user.
+    Failed(String),
+}
+
+impl NoteOutcome {
+    /// Status column for the note's line.
+    pub fn status(&self) -> Status {
+        match self {
+            NoteOutcome::Updated => Status::Updated,
+            NoteOutcome::Unchanged => Status::Unchanged,
+            NoteOutcome::Skipped(_) => Status::Skipped,
+            NoteOutcome::Failed(_) => Status::Failed,
+        }
+    }
+
+    /// Text after the path, if any.
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            NoteOutcome::Skipped(why) | NoteOutcome::Failed(why) => Some(why),
+            NoteOutcome::Updated | NoteOutcome::Unchanged => None,
+        }
+    }
+
+    /// Lowercase name, as written in JSON reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoteOutcome::Updated => "updated",
+            NoteOutcome::Unchanged => "unchanged",
+            NoteOutcome::Skipped(_) => "skipped",
+            NoteOutcome::Failed(_) => "failed",
+        }
+    }
+
+    /// Whether the note was fetched and written, changed or not.
+    pub fn is_success(&self) -> bool {
+        matches!(self, NoteOutcome::Updated | NoteOutcome::Unchanged)
+    }
+}
+
+/// One note's outcome with what reports group it by.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NoteResult {
+    pub path: String,
+    pub tags: Vec<String>,
+    pub outcome: NoteOutcome,
+}
+
+/// Every note a run looked at, in the order it visited them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RunReport {
+    pub notes: Vec<NoteResult>,
+    /// Whether the run stopped early at its deadline.
+    pub deadline_reached: bool,
+}
+
+impl NoteResult {
+    /// `{"path", "tags", "outcome", "detail"}`, as hooks receive it.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "path": self.path,
+            "tags": self.tags,
+            "outcome": self.outcome.name(),
+            "detail": self.outcome.detail(),
+        })
+    }
+}
+
+impl RunReport {
+    pub fn push(&mut self, path: &str, tags: &[String], outcome: NoteOutcome) {
+        self.notes.push(NoteResult {
+            path: path.to_string(),
+            tags: tags.to_vec(),
+            outcome,
+        });
+    }
+
+    /// How many notes ended with an outcome like `outcome` (details ignored).
+    pub fn count(&self, outcome: &NoteOutcome) -> usize {
+        let kind = std::mem::discriminant(outcome);
+        self.notes
+            .iter()
+            .filter(|n| std::mem::discriminant(&n.outcome) == kind)
+            .count()
+    }
+
+    /// Attempted notes as `(tags, succeeded)`, for [`crate::tags::report`].
+    pub fn by_tag(&self) -> Vec<(Vec<String>, bool)> {
+        self.notes
+            .iter()
+            .filter(|n| !matches!(n.outcome, NoteOutcome::Skipped(_)))
+            .map(|n| (n.tags.clone(), n.outcome.is_success()))
+            .collect()
+    }
+
+    /// Every note's [`NoteResult::to_json`] with counts per outcome.
+    pub fn to_json(&self) -> Value {
+        let count = |outcome| self.count(&outcome);
+        json!({
+            "notes": self.notes.iter().map(NoteResult::to_json).collect::<Vec<_>>(),
+            "updated": count(NoteOutcome::Updated),
+            "unchanged": count(NoteOutcome::Unchanged),
+            "skipped": count(NoteOutcome::Skipped(String::new())),
+            "failed": count(NoteOutcome::Failed(String::new())),
+            "deadline_reached": self.deadline_reached,
+        })
+    }
+
+    /// `Err` when any note failed or the deadline cut the run short, so
+    /// the process exits non-zero.
+    pub fn into_result(self) -> Result<()> {
+        if self.deadline_reached || self.count(&NoteOutcome::Failed(String::new())) > 0 {
+            return Err(anyhow!(
+                "One or more notes could not be updated due to errors. Please check the log above."
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! A note's Markdown structure, for finding and placing the weather section.
+//!
+//! Notes are parsed with pulldown-cmark rather than matched with regexes, so
+//! only real headings and real HTML comments count: a weather block pasted
+//! into a code fence as an example, an inline code span mentioning a marker,
+//! or a `# comment` in the YAML frontmatter is never mistaken for the
+//! section. A heading's section runs until the next heading of the same or a
+//! higher level.
+
+use crate::markers;
+use anyhow::{Result, anyhow};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::ops::Range;
+
+/// Title of the heading above every weather block.
+pub const WEATHER_TITLE: &str = "Weather Forecast";
+
+/// One heading.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heading {
+    /// 1 for `#`, 2 for `##`, ...
+    pub level: usize,
+    pub title: String,
+    /// The heading's bytes, final newline included.
+    pub line: Range<usize>,
+}
+
+/// A weather section: its heading through the end marker's line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Section {
+    /// Level of the section's heading.
+    pub level: usize,
+    pub range: Range<usize>,
+}
+
+fn parser(content: &str) -> impl Iterator<Item = (Event<'_>, Range<usize>)> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    Parser::new_ext(content, options).into_offset_iter()
+}
+
+/// `range` extended over the line break that ends it, if it stops short.
+fn with_newline(content: &str, range: Range<usize>) -> Range<usize> {
+    let done = content[..range.end].ends_with('\n');
+    match !done && content[range.end..].starts_with('\n') {
+        true => range.start..range.end + 1,
+        false => range,
+    }
+}
+
+/// The headings of `content`, in order.
+pub fn headings(content: &str) -> Vec<Heading> {
+    let mut out = Vec::new();
+    let mut open: Option<Heading> = None;
+    for (event, range) in parser(content) {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                open = Some(Heading {
+                    level: level as usize,
+                    title: String::new(),
+                    line: with_newline(content, range),
+                })
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(h) = open.as_mut() {
+                    h.title.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => out.extend(open.take()),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The YAML frontmatter, with its final line break.
+pub fn frontmatter(content: &str) -> Option<Range<usize>> {
+    parser(content).find_map(|(event, range)| match event {
+        Event::Start(Tag::MetadataBlock(_)) => Some(with_newline(content, range)),
+        _ => None,
+    })
+}
+
+/// Code blocks, fenced or indented, each with its final line break.
+pub fn code_blocks(content: &str) -> Vec<Range<usize>> {
+    parser(content)
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) => Some(with_newline(content, range)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Byte ranges of `content` that are not prose: the YAML frontmatter and
+/// code blocks. Nothing in them is read as a weather block or rewritten.
+pub fn protected_ranges(content: &str) -> Vec<Range<usize>> {
+    frontmatter(content)
+        .into_iter()
+        .chain(code_blocks(content))
+        .collect()
+}
+
+/// Whether byte `at` lies in one of `ranges`.
+pub fn is_protected(ranges: &[Range<usize>], at: usize) -> bool {
+    ranges.iter().any(|r| r.contains(&at))
+}
+
+/// Refuse an edit from `before` to `after` that changed a code block, or
+/// the frontmatter unless `frontmatter` allows it (`write-coords`).
+pub fn check_untouched(before: &str, after: &str, frontmatter: bool) -> Result<()> {
+    let texts = |content: &str, ranges: Vec<Range<usize>>| -> Vec<String> {
+        ranges
+            .into_iter()
+            .map(|r| content[r].trim_end_matches('\n').to_string())
+            .collect()
+    };
+    if texts(before, code_blocks(before)) != texts(after, code_blocks(after)) {
+        return Err(anyhow!(
+            "refusing to write: the update would change a code block (is a code fence left open?)"
+        ));
+    }
+    let front = |content: &str| texts(content, self::frontmatter(content).into_iter().collect());
+    if !frontmatter && front(before) != front(after) {
+        return Err(anyhow!(
+            "refusing to write: the update would change the frontmatter"
+        ));
+    }
+    Ok(())
+}
+
+/// The weather sections of `content`, in order: a `Weather Forecast`
+/// heading directly followed by a begin marker (any version), through the
+/// next end marker. Markers inside code are text, not comments, and are
+/// ignored.
+pub fn weather_sections(content: &str) -> Vec<Section> {
+    let begin = Regex::new(&format!("^{}", markers::BEGIN_PATTERN)).unwrap();
+    let headings = headings(content);
+    let mut out = Vec::new();
+    let mut open: Option<(usize, usize)> = None;
+    for (event, range) in parser(content) {
+        let (Event::Html(html) | Event::InlineHtml(html)) = event else {
+            continue;
+        };
+        let html = html.trim();
+        if begin.is_match(html) {
+            open = headings
+                .iter()
+                .rfind(|h| h.line.end <= range.start)
+                .filter(|h| h.title.trim().eq_ignore_ascii_case(WEATHER_TITLE))
+                .filter(|h| content[h.line.end..range.start].trim().is_empty())
+                .map(|h| (h.level, h.line.start));
+        } else if html.starts_with(markers::END)
+            && let Some((level, start)) = open.take()
+        {
+            out.push(Section {
+                level,
+                range: start..with_newline(content, range).end,
+            });
+        }
+    }
+    out
+}
+
+/// Split a heading spec such as `## Logistics` into its level (if given)
+/// and title.
+fn parse_spec(spec: &str) -> (Option<usize>, &str) {
+    let spec = spec.trim();
+    let level = spec.chars().take_while(|c| *c == '#').count();
+    let title = spec[level..].trim();
+    ((level > 0).then_some(level), title)
+}
+
+/// The first heading matching `spec`: a title (compared ignoring case),
+/// optionally prefixed with `#`s to require a level.
+pub fn find<'a>(headings: &'a [Heading], spec: &str) -> Option<&'a Heading> {
+    let (level, title) = parse_spec(spec);
+    headings
+        .iter()
+        .find(|h| level.is_none_or(|l| h.level == l) && h.title.eq_ignore_ascii_case(title))
+}
+
+/// Where the section under `heading` ends: the start of the next heading
+/// at its level or above, or the end of the note.
+pub fn section_end(content: &str, headings: &[Heading], heading: &Heading) -> usize {
+    headings
+        .iter()
+        .filter(|h| h.line.start > heading.line.start && h.level <= heading.level)
+        .map(|h| h.line.start)
+        .next()
+        .unwrap_or(content.len())
+}
+
+/// `block` with its first heading set to `level`.
+pub fn relevel(block: &str, level: usize) -> String {
+    let hashes = block.chars().take_while(|c| *c == '#').count();
+    if hashes == 0 {
+        return block.to_string();
+    }
+    format!("{}{}", "#".repeat(level.clamp(1, 6)), &block[hashes..])
+}
@@ -0,0 +1,178 @@
+//! Per-note status lines for the CLI.
+//!
+//! Each processed note gets one line with a fixed-width status column:
+//! green `updated` and grey `current` (re-fetched, same numbers) on stdout,
+//! yellow `skipped` and red `failed` on stderr.
+//! Color is used only on terminals, never when `NO_COLOR` is set, and can be
+//! forced either way with `--color always|never`. With `links` on, the path
+//! is also an OSC 8 hyperlink to the note in Obsidian on terminals that
+//! support them.
+
+use crate::telemetry::Verbosity;
+use anyhow::{Result, anyhow};
+use std::io::IsTerminal;
+use std::path::Path;
+
+/// `--color` setting.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            other => Err(anyhow!(
+                "unknown color mode '{other}' (expected auto, always or never)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`ColorChoice::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        }
+    }
+
+    /// Whether to color a stream that is (`is_tty`) or is not a terminal.
+    pub fn enabled(self, is_tty: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                is_tty && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+        }
+    }
+}
+
+/// Outcome shown in the status column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Updated,
+    /// Refreshed, but the weather shown did not change.
+    Unchanged,
+    Skipped,
+    Failed,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Updated => "updated",
+            Status::Unchanged => "current",
+            Status::Skipped => "skipped",
+            Status::Failed => "failed",
+        }
+    }
+
+    /// ANSI SGR color code.
+    fn color(self) -> &'static str {
+        match self {
+            Status::Updated => "32",
+            Status::Unchanged => "90",
+            Status::Skipped => "33",
+            Status::Failed => "31",
+        }
+    }
+}
+
+/// Width of the status column, wide enough for every label.
+const STATUS_WIDTH: usize = 8;
+
+/// Format one status line: `<status>  <path>[: <detail>]`.
+pub fn status_line(status: Status, path: &str, detail: Option<&str>, color: bool) -> String {
+    let label = format!("{:<STATUS_WIDTH$}", status.label());
+    let label = if color {
+        format!("\x1b[{}m{label}\x1b[0m", status.color())
+    } else {
+        label
+    };
+    match detail {
+        Some(detail) => format!("{label} {path}: {detail}"),
+        None => format!("{label} {path}"),
+    }
+}
+
+/// `obsidian://open?path=...` deep link to a note. Relative paths are made
+/// absolute when the file exists, since Obsidian resolves them against no
+/// particular directory.
+pub fn obsidian_uri(path: &str) -> String {
+    let absolute = Path::new(path)
+        .canonicalize()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| path.to_string());
+    format!("obsidian://open?path={}", urlencoding::encode(&absolute))
+}
+
+/// Wrap `text` in an OSC 8 terminal hyperlink to `url`.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Prints status lines honoring verbosity, color and link settings.
+#[derive(Clone, Copy, Debug)]
+pub struct Printer {
+    verbosity: Verbosity,
+    color_out: bool,
+    color_err: bool,
+    links_out: bool,
+    links_err: bool,
+}
+
+impl Printer {
+    pub fn new(verbosity: Verbosity, color: ColorChoice) -> Self {
+        Printer {
+            verbosity,
+            color_out: color.enabled(std::io::stdout().is_terminal()),
+            color_err: color.enabled(std::io::stderr().is_terminal()),
+            links_out: false,
+            links_err: false,
+        }
+    }
+
+    /// Link note paths to Obsidian on streams that are terminals.
+    pub fn with_links(mut self, links: bool) -> Self {
+        self.links_out = links && std::io::stdout().is_terminal();
+        self.links_err = links && std::io::stderr().is_terminal();
+        self
+    }
+
+    fn line(&self, status: Status, path: &str, detail: Option<&str>, stderr: bool) -> String {
+        let (color, link) = if stderr {
+            (self.color_err, self.links_err)
+        } else {
+            (self.color_out, self.links_out)
+        };
+        if link {
+            let path = hyperlink(path, &obsidian_uri(path));
+            status_line(status, &path, detail, color)
+        } else {
+            status_line(status, path, detail, color)
+        }
+    }
+
+    /// Print a note's outcome. Failures always print; the rest only when
+    /// progress output is enabled.
+    pub fn status(&self, status: Status, path: &str, detail: Option<&str>) {
+        match status {
+            Status::Updated | Status::Unchanged if self.verbosity.shows_progress() => {
+                println!("{}", self.line(status, path, detail, false))
+            }
+            Status::Skipped if self.verbosity.shows_progress() => {
+                eprintln!("{}", self.line(status, path, detail, true))
+            }
+            Status::Failed => eprintln!("{}", self.line(status, path, detail, true)),
+            _ => {}
+        }
+    }
+}
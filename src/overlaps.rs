@@ -0,0 +1,46 @@
+//! Detection of trips whose dates collide.
+//!
+//! Someone can only be in one place at a time, so two upcoming trips sharing a
+//! night usually means a typo in the dates. Nested side trips can opt out with
+//! `overlap-ok: true` in either note.
+
+use crate::NoteMeta;
+use chrono::NaiveDate;
+
+/// Two notes whose trips share at least one night.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overlap {
+    pub first: String,
+    pub second: String,
+    /// Shared span; the first day of one trip may equal the last day of the
+    /// other without counting as an overlap.
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Pairs of trips ending on or after `today` that overlap, in note order.
+/// Recurring trips are compared by their next occurrence.
+pub fn find_overlaps(notes: &[NoteMeta], today: NaiveDate) -> Vec<Overlap> {
+    let trips: Vec<NoteMeta> = notes
+        .iter()
+        .filter(|n| !n.overlap_ok)
+        .map(|n| n.occurrence_on(today))
+        .filter(|n| n.arrival.max(n.departure) >= today)
+        .collect();
+    let mut found = Vec::new();
+    for (i, a) in trips.iter().enumerate() {
+        for b in &trips[i + 1..] {
+            let from = a.arrival.min(a.departure).max(b.arrival.min(b.departure));
+            let to = a.arrival.max(a.departure).min(b.arrival.max(b.departure));
+            if from < to {
+                found.push(Overlap {
+                    first: a.path.clone(),
+                    second: b.path.clone(),
+                    from,
+                    to,
+                });
+            }
+        }
+    }
+    found
+}
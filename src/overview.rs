@@ -0,0 +1,134 @@
+//! A read-only overview of the vault's coming trips.
+//!
+//! `weather-helper overview` fetches the weather for every trip under way
+//! or still to come and prints one entry per trip: where and when, the
+//! summary line and any `alert-if` days, soonest first. It is the content a
+//! run would put in the notes, for users who never want the tool writing to
+//! their vault: no note, sidecar, map image or history log is touched.
+//! `--json` prints the same entries for scripts and dashboards.
+
+use crate::map::MapMode;
+use crate::{RunOptions, block_summary_in, extract_meta, note_paths, prepare_note, priority};
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::path::Path;
+
+/// One trip in the overview.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TripOverview {
+    /// Path relative to the scanned folder.
+    pub note: String,
+    /// The place as geocoded, or the note's `city` if fetching failed.
+    pub place: String,
+    /// The trip as it applies today (recurring trips move ahead).
+    pub arrival: NaiveDate,
+    pub departure: NaiveDate,
+    pub under_way: bool,
+    /// Summary line per `summary-lang`, without Markdown.
+    pub summary: Vec<String>,
+    /// Forecast days crossing `alert-if`.
+    pub alerts: Vec<String>,
+    /// Why the trip's weather could not be fetched.
+    pub error: Option<String>,
+}
+
+/// Every trip under way or to come under a folder.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Overview {
+    pub on: NaiveDate,
+    /// Trips under way first, then by arrival.
+    pub trips: Vec<TripOverview>,
+}
+
+/// Fetch the overview for `root` as of `opts.today()`. A trip whose weather
+/// cannot be fetched is listed with its error rather than failing the rest.
+pub async fn overview(root: &str, opts: &RunOptions) -> Result<Overview> {
+    opts.install();
+    let mut opts = opts.clone();
+    // An overview never writes, so no map image is downloaded.
+    if opts.map == MapMode::Image {
+        opts.map = MapMode::Link;
+    }
+    let client = opts.client()?;
+    let on = opts.today();
+    let mut metas: Vec<_> = note_paths(root)
+        .iter()
+        .filter_map(|path| extract_meta(path).ok())
+        .collect();
+    crate::select_notes(&mut metas, &opts, on);
+    metas.retain(|meta| priority::urgency(meta, on).0 < 2);
+    let mut trips = Vec::new();
+    for meta in &metas {
+        let current = meta.occurrence_on(on);
+        let path = Path::new(&meta.path);
+        let mut trip = TripOverview {
+            note: path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned(),
+            place: meta.city.clone(),
+            arrival: current.arrival,
+            departure: current.departure,
+            under_way: priority::urgency(meta, on).0 == 0,
+            summary: Vec::new(),
+            alerts: Vec::new(),
+            error: None,
+        };
+        match prepare_note(meta, &client, &opts).await {
+            Ok(prepared) => {
+                let note_opts = &prepared.opts;
+                let summary = block_summary_in(
+                    &prepared.data,
+                    &prepared.notes,
+                    note_opts.units,
+                    &note_opts.summary_lang,
+                );
+                trip.place = prepared.place.name.clone();
+                trip.summary = summary
+                    .note
+                    .split("  \n")
+                    .map(|line| line.trim_matches('_').to_string())
+                    .collect();
+                trip.alerts = prepared.alerts.iter().map(|a| a.to_string()).collect();
+            }
+            Err(e) => trip.error = Some(format!("{e:#}")),
+        }
+        trips.push(trip);
+    }
+    Ok(Overview { on, trips })
+}
+
+/// The report `overview` prints: a heading, then one entry per trip with
+/// its summary lines and alerts indented below.
+pub fn report(overview: &Overview) -> String {
+    if overview.trips.is_empty() {
+        return format!("No trips under way or to come as of {}.\n", overview.on);
+    }
+    let mut out = format!(
+        "{} trip(s) under way or to come as of {}:\n",
+        overview.trips.len(),
+        overview.on
+    );
+    for trip in &overview.trips {
+        let when = match trip.under_way {
+            true => ", under way",
+            false => "",
+        };
+        out.push_str(&format!(
+            "- {} ({} → {}{when}) in {}\n",
+            trip.place, trip.arrival, trip.departure, trip.note
+        ));
+        if let Some(error) = &trip.error {
+            out.push_str(&format!("  failed: {error}\n"));
+        }
+        for line in &trip.summary {
+            out.push_str(&format!("  {line}\n"));
+        }
+        for alert in &trip.alerts {
+            out.push_str(&format!("  alert: {alert}\n"));
+        }
+    }
+    out
+}
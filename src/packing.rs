@@ -0,0 +1,459 @@
+//! Packing checklist generated from the fetched weather.
+//!
+//! Rules map conditions over the trip's weather to checklist items. The
+//! built-in set can be replaced by a YAML list in the file named by the
+//! `packing-rules` setting:
+//!
+//! ```yaml
+//! - item: sunscreen
+//!   when: high >= 25
+//! - item: power adapter (Type {plug})
+//!   when: plug not A B
+//! ```
+//!
+//! A `when` is a comma-separated list of clauses that must all hold:
+//! `high`/`low` compare the trip's warmest high and coldest low (°C, or °F
+//! with an `F` suffix as in `high >= 80F`), `days` its length,
+//! `country in IT FR` the destination's ISO code, and `plug not A B` holds
+//! when the destination uses none of those plug types. `{plug}` and
+//! `{country}` in an item are filled in.
+//!
+//! The checklist is inserted once, under its own markers, and never
+//! rewritten so ticked boxes survive later runs.
+//!
+//! The same rules drive the outfit estimate in the block summary ("7
+//! t-shirts, 2 warm layers"); there each rule also has a `count` formula
+//! over `days`, `nights`, `high` and `low` using `+ - * /`, parentheses and
+//! `min`, `max`, `ceil`, `floor`, `round`. `{n}` in the item is the count
+//! and `{s}` is `s` unless it is 1.
+
+use crate::io;
+use crate::{DayTemp, destination_info, farenheit_to_celcius, markers, outline};
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+use std::path::Path;
+
+const BEGIN: &str = "<!-- PACKING:BEGIN -->";
+const END: &str = "<!-- PACKING:END -->";
+
+/// What rules are evaluated against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trip {
+    /// Length of the trip in days, travel days included.
+    pub days: i64,
+    /// Warmest daily high, °C.
+    pub high: f64,
+    /// Coldest daily low, °C.
+    pub low: f64,
+    /// ISO 3166-1 alpha-2 code of the destination, when known.
+    pub country: Option<String>,
+}
+
+impl Trip {
+    /// Summarize fetched days; `None` when there is no data.
+    pub fn new(days: i64, data: &[DayTemp], country: Option<&str>) -> Option<Trip> {
+        let high = data.iter().map(|d| d.tmax).reduce(f64::max)?;
+        let low = data.iter().map(|d| d.tmin).reduce(f64::min)?;
+        Some(Trip {
+            days,
+            high,
+            low,
+            country: country.map(str::to_ascii_uppercase),
+        })
+    }
+
+    /// Plug types used at the destination, e.g. `["C", "F"]`; empty when
+    /// the country is unknown, so adapter rules do not fire.
+    pub fn plugs(&self) -> Vec<&'static str> {
+        self.country
+            .as_deref()
+            .map(destination_info::plug_types)
+            .unwrap_or_default()
+    }
+}
+
+/// One checklist entry and when it applies.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct Rule {
+    pub item: String,
+    #[serde(default)]
+    pub when: String,
+    /// How many to pack; only used by outfit rules.
+    #[serde(default)]
+    pub count: Option<String>,
+}
+
+/// Rules used when no `packing-rules` file is configured. The adapter rule
+/// assumes a home country with US plugs (types A/B).
+pub fn default_rules() -> Vec<Rule> {
+    let rule = |item: &str, when: &str| Rule {
+        item: item.to_string(),
+        when: when.to_string(),
+        count: None,
+    };
+    vec![
+        rule("sunscreen", "high >= 25"),
+        rule("sun hat", "high >= 30"),
+        rule("warm jacket", "low <= 10"),
+        rule("warm hat and gloves", "low <= 3"),
+        rule("power adapter (Type {plug})", "plug not A B"),
+    ]
+}
+
+/// Checklist rules from `path`, or the built-in ones.
+pub fn load_rules(path: Option<&Path>) -> Result<Vec<Rule>> {
+    path.map_or_else(|| Ok(default_rules()), read_rules)
+}
+
+/// Outfit rules from `path`, or the built-in ones.
+pub fn load_outfit_rules(path: Option<&Path>) -> Result<Vec<Rule>> {
+    path.map_or_else(|| Ok(default_outfit_rules()), read_rules)
+}
+
+fn read_rules(path: &Path) -> Result<Vec<Rule>> {
+    let text = io::read_to_string(path)
+        .with_context(|| format!("cannot read packing rules {}", path.display()))?;
+    let rules: Vec<Rule> = serde_yaml::from_str(&text)
+        .with_context(|| format!("invalid packing rules {}", path.display()))?;
+    // Surface typos now rather than as silently missing items.
+    let probe = Trip {
+        days: 1,
+        high: 0.0,
+        low: 0.0,
+        country: None,
+    };
+    for rule in &rules {
+        matches(&rule.when, &probe).with_context(|| format!("in rule for '{}'", rule.item))?;
+        if let Some(count) = &rule.count {
+            eval_count(count, &probe).with_context(|| format!("in rule for '{}'", rule.item))?;
+        }
+    }
+    Ok(rules)
+}
+
+/// Outfit rules used when no `outfit-rules` file is configured: a week's
+/// worth of clothes at most, assuming laundry after that.
+pub fn default_outfit_rules() -> Vec<Rule> {
+    let rule = |item: &str, when: &str, count: &str| Rule {
+        item: item.to_string(),
+        when: when.to_string(),
+        count: Some(count.to_string()),
+    };
+    vec![
+        rule("{n} t-shirt{s}", "high >= 18", "min(days, 7)"),
+        rule("{n} long-sleeve top{s}", "high < 18", "min(days, 7)"),
+        rule(
+            "{n} pair{s} of shorts",
+            "high >= 25",
+            "ceil(min(days, 7) / 2)",
+        ),
+        rule(
+            "{n} pair{s} of trousers",
+            "high < 25",
+            "ceil(min(days, 7) / 3)",
+        ),
+        rule("{n} warm layer{s}", "low <= 12", "ceil(min(days, 7) / 4)"),
+        rule("{n} pair{s} of socks", "", "min(days + 1, 8)"),
+    ]
+}
+
+/// `(count, item)` for outfit rules that hold, skipping counts below one.
+pub fn outfit(rules: &[Rule], trip: &Trip) -> Result<Vec<(u32, String)>> {
+    let mut counts = Vec::new();
+    for rule in rules {
+        let context = || format!("in rule for '{}'", rule.item);
+        let Some(formula) = &rule.count else {
+            continue;
+        };
+        if !matches(&rule.when, trip).with_context(context)? {
+            continue;
+        }
+        let n = eval_count(formula, trip).with_context(context)?.ceil();
+        if n < 1.0 {
+            continue;
+        }
+        let n = n as u32;
+        let item = rule
+            .item
+            .replace("{n}", &n.to_string())
+            .replace("{s}", if n == 1 { "" } else { "s" });
+        counts.push((n, item));
+    }
+    Ok(counts)
+}
+
+/// The outfit estimate as a summary line, e.g. `_Pack: 7 t-shirts, 1 warm layer_`.
+pub fn outfit_line(counts: &[(u32, String)]) -> Option<String> {
+    if counts.is_empty() {
+        return None;
+    }
+    let items: Vec<&str> = counts.iter().map(|(_, item)| item.as_str()).collect();
+    Some(format!("_Pack: {}_", items.join(", ")))
+}
+
+/// Evaluate a count formula for `trip`.
+pub fn eval_count(formula: &str, trip: &Trip) -> Result<f64> {
+    let tokens = tokenize(formula)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        trip,
+    };
+    let value = parser.sum()?;
+    match parser.tokens.get(parser.pos) {
+        None => Ok(value),
+        Some(t) => Err(anyhow!("unexpected '{t}' in '{formula}'")),
+    }
+}
+
+fn tokenize(formula: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = formula.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek()
+                && (c.is_ascii_alphanumeric() || c == '.' || c == '_')
+            {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(word);
+        } else if "+-*/(),".contains(c) {
+            tokens.push(c.to_string());
+            chars.next();
+        } else {
+            return Err(anyhow!("unexpected '{c}' in '{formula}'"));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent evaluator for count formulas.
+struct Parser<'a> {
+    tokens: &'a [String],
+    pos: usize,
+    trip: &'a Trip,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&str> {
+        let t = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(t)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let hit = self.tokens.get(self.pos).is_some_and(|t| t == token);
+        self.pos += hit as usize;
+        hit
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(anyhow!("expected '{token}'"))
+        }
+    }
+
+    fn sum(&mut self) -> Result<f64> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat("+") {
+                value += self.product()?;
+            } else if self.eat("-") {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64> {
+        let mut value = self.atom()?;
+        loop {
+            if self.eat("*") {
+                value *= self.atom()?;
+            } else if self.eat("/") {
+                let divisor = self.atom()?;
+                if divisor == 0.0 {
+                    return Err(anyhow!("division by zero"));
+                }
+                value /= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64> {
+        let token = self
+            .next()
+            .ok_or_else(|| anyhow!("formula ends too early"))?
+            .to_string();
+        match token.as_str() {
+            "-" => Ok(-self.atom()?),
+            "(" => {
+                let value = self.sum()?;
+                self.expect(")")?;
+                Ok(value)
+            }
+            "days" => Ok(self.trip.days as f64),
+            "nights" => Ok((self.trip.days - 1).max(0) as f64),
+            "high" => Ok(self.trip.high),
+            "low" => Ok(self.trip.low),
+            "min" | "max" => {
+                self.expect("(")?;
+                let a = self.sum()?;
+                self.expect(",")?;
+                let b = self.sum()?;
+                self.expect(")")?;
+                Ok(if token == "min" { a.min(b) } else { a.max(b) })
+            }
+            "ceil" | "floor" | "round" => {
+                self.expect("(")?;
+                let a = self.sum()?;
+                self.expect(")")?;
+                Ok(match token.as_str() {
+                    "ceil" => a.ceil(),
+                    "floor" => a.floor(),
+                    _ => a.round(),
+                })
+            }
+            number => number
+                .parse()
+                .map_err(|_| anyhow!("unknown name '{number}'")),
+        }
+    }
+}
+
+/// Whether every clause of `when` holds for `trip`.
+pub fn matches(when: &str, trip: &Trip) -> Result<bool> {
+    let mut all = true;
+    for clause in when.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        all &= clause_holds(clause, trip)?;
+    }
+    Ok(all)
+}
+
+fn clause_holds(clause: &str, trip: &Trip) -> Result<bool> {
+    let words: Vec<&str> = clause.split_whitespace().collect();
+    match words.as_slice() {
+        ["country", "in", codes @ ..] if !codes.is_empty() => Ok(trip
+            .country
+            .as_deref()
+            .is_some_and(|c| codes.iter().any(|code| code.eq_ignore_ascii_case(c)))),
+        ["plug", "not", types @ ..] if !types.is_empty() => {
+            let plugs = trip.plugs();
+            Ok(!plugs.is_empty()
+                && !plugs
+                    .iter()
+                    .any(|p| types.iter().any(|t| t.eq_ignore_ascii_case(p))))
+        }
+        [metric, op, value] => {
+            let actual = match *metric {
+                "high" => trip.high,
+                "low" => trip.low,
+                "days" => trip.days as f64,
+                other => return Err(anyhow!("unknown quantity '{other}' in '{clause}'")),
+            };
+            let expected = parse_value(value, *metric != "days")
+                .ok_or_else(|| anyhow!("bad number '{value}' in '{clause}'"))?;
+            compare(actual, op, expected)
+                .ok_or_else(|| anyhow!("bad operator '{op}' in '{clause}'"))
+        }
+        _ => Err(anyhow!("cannot read condition '{clause}'")),
+    }
+}
+
+/// A number, with an optional `C`/`F` suffix for temperatures.
+pub(crate) fn parse_value(value: &str, temperature: bool) -> Option<f64> {
+    let lower = value.to_ascii_lowercase();
+    if temperature && let Some(f) = lower.strip_suffix('f') {
+        return f
+            .trim_end_matches('°')
+            .parse()
+            .ok()
+            .map(farenheit_to_celcius);
+    }
+    let plain = if temperature {
+        lower.trim_end_matches('c').trim_end_matches('°')
+    } else {
+        &lower
+    };
+    plain.parse().ok()
+}
+
+fn compare(actual: f64, op: &str, expected: f64) -> Option<bool> {
+    Some(match op {
+        ">" => actual > expected,
+        ">=" => actual >= expected,
+        "<" => actual < expected,
+        "<=" => actual <= expected,
+        "=" | "==" => (actual - expected).abs() < 1e-9,
+        "!=" => (actual - expected).abs() >= 1e-9,
+        _ => return None,
+    })
+}
+
+/// Items whose rules hold, in rule order, without duplicates.
+pub fn checklist(rules: &[Rule], trip: &Trip) -> Result<Vec<String>> {
+    let mut items: Vec<String> = Vec::new();
+    for rule in rules {
+        if matches(&rule.when, trip).with_context(|| format!("in rule for '{}'", rule.item))? {
+            let item = rule
+                .item
+                .replace("{plug}", &trip.plugs().join("/"))
+                .replace("{country}", trip.country.as_deref().unwrap_or(""));
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// The checklist section, heading and markers included.
+pub fn render_checklist(items: &[String]) -> String {
+    let mut s = format!("## Packing List\n{BEGIN}\n");
+    for item in items {
+        s.push_str(&format!("- [ ] {item}\n"));
+    }
+    s.push_str(END);
+    s.push('\n');
+    s
+}
+
+/// Whether the note already has a checklist section.
+pub fn has_checklist(content: &str) -> bool {
+    content.contains(BEGIN)
+}
+
+/// Insert `block` after the weather block, or at the end of the note. Does
+/// nothing when a checklist already exists; returns whether it inserted.
+pub fn insert_once(content: &mut String, block: &str) -> bool {
+    if has_checklist(content) {
+        return false;
+    }
+    let protected = outline::protected_ranges(content);
+    let end = content
+        .match_indices(markers::END)
+        .map(|(at, _)| at)
+        .find(|at| !outline::is_protected(&protected, *at));
+    match end {
+        Some(at) => {
+            let after = content[at..]
+                .find('\n')
+                .map_or(content.len(), |nl| at + nl + 1);
+            content.insert_str(after, &format!("\n{block}"));
+        }
+        None => {
+            content.push_str("\n\n");
+            content.push_str(block);
+        }
+    }
+    true
+}
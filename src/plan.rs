@@ -0,0 +1,146 @@
+//! Two-step runs for reviewed or GitOps workflows.
+//!
+//! `plan` fetches everything and records, per note, the hash of the block
+//! that is there now and the block that would replace it, without writing.
+//! `apply --plan` later writes exactly those blocks, on this machine or
+//! another, and refuses any note whose block changed since the plan was made.
+
+use crate::map::MapMode;
+use crate::{
+    RunOptions, extract_meta, hooks, note_paths, outline, prepare_note, stale, upsert_weather_block,
+};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+pub const PLAN_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Plan {
+    pub version: u32,
+    /// When the plan was made (RFC 3339).
+    pub created: String,
+    pub changes: Vec<Change>,
+}
+
+/// One note whose block would change.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    /// Path relative to the planned root, with `/` separators.
+    pub note: String,
+    /// SHA-256 of the block in the note when planned; `None` if it had none.
+    pub old_block_hash: Option<String>,
+    pub new_block: String,
+}
+
+/// The weather block currently in a note, heading included.
+pub fn current_block(content: &str) -> Option<&str> {
+    let section = outline::weather_sections(content).into_iter().next()?;
+    Some(content[section.range].trim_end_matches('\n'))
+}
+
+pub fn block_hash(block: &str) -> String {
+    format!("{:x}", Sha256::digest(block.as_bytes()))
+}
+
+/// A note's path relative to `root`, as recorded in a plan.
+fn relative(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Plan a run over `root`. Notes that fail to fetch are left out and
+/// returned as messages; notes already up to date are left out silently.
+///
+/// A plan never writes, so `map: image` is planned as a link.
+pub async fn plan(root: &str, opts: &RunOptions) -> Result<(Plan, Vec<String>)> {
+    opts.install();
+    let mut opts = opts.clone();
+    if opts.map == MapMode::Image {
+        opts.map = MapMode::Link;
+    }
+    let client = opts.client()?;
+    let mut changes = Vec::new();
+    let mut failures = Vec::new();
+    for path in note_paths(root) {
+        let Ok(meta) = extract_meta(&path) else {
+            continue;
+        };
+        if meta.pinned {
+            continue;
+        }
+        let prepared = match prepare_note(&meta, &client, &opts).await {
+            Ok(p) => p,
+            Err(e) => {
+                failures.push(format!("{}: {e:#}", path.display()));
+                continue;
+            }
+        };
+        // With `separate-note` the block goes to the weather note and the
+        // trip note only gets the embed.
+        let mut targets = vec![(path, prepared.content.clone(), prepared.trip_block())];
+        if let Some((weather, text)) = &prepared.separate {
+            targets.push((weather.clone(), text.clone(), prepared.block()));
+        }
+        for (path, content, new_block) in targets {
+            let old = current_block(&content);
+            // A re-fetch of the same numbers only moves the fetch stamp.
+            if old.map(stale::without_stamp) == Some(stale::without_stamp(new_block.trim_end())) {
+                continue;
+            }
+            changes.push(Change {
+                note: relative(Path::new(root), &path),
+                old_block_hash: old.map(block_hash),
+                new_block,
+            });
+        }
+    }
+    let plan = Plan {
+        version: PLAN_VERSION,
+        created: opts.now().to_rfc3339(),
+        changes,
+    };
+    Ok((plan, failures))
+}
+
+/// Outcome of applying one change.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Applied {
+    Written,
+    /// The note's block is no longer the one the plan was made against.
+    Conflict,
+}
+
+/// Apply one planned change to the note under `root`.
+pub fn apply_change(root: &Path, change: &Change) -> Result<Applied> {
+    if change.note.split('/').any(|part| part == "..") || Path::new(&change.note).is_absolute() {
+        return Err(anyhow!("refusing path outside the root: {}", change.note));
+    }
+    let path = root.join(&change.note);
+    // A separate weather note that did not exist when planned is created.
+    let mut content = match change.old_block_hash.is_none() && !path.exists() {
+        true => String::new(),
+        false => hooks::read_note(&path)?,
+    };
+    if current_block(&content).map(block_hash) != change.old_block_hash {
+        return Ok(Applied::Conflict);
+    }
+    upsert_weather_block(&mut content, &change.new_block)?;
+    hooks::write_note(&path, &content)?;
+    Ok(Applied::Written)
+}
+
+pub fn parse(json: &str) -> Result<Plan> {
+    let plan: Plan = serde_json::from_str(json)?;
+    if plan.version != PLAN_VERSION {
+        return Err(anyhow!(
+            "plan version {} is not supported (expected {PLAN_VERSION})",
+            plan.version
+        ));
+    }
+    Ok(plan)
+}
@@ -0,0 +1,48 @@
+//! Which notes to refresh first.
+//!
+//! Runs visit trips under way first, then upcoming trips by arrival date,
+//! then past trips from the most recent back, so a run that is interrupted,
+//! hits its deadline or runs out of API budget has already refreshed the
+//! forecasts that matter soonest.
+//!
+//! Trips that ended more than `retention` days ago (30 by default) are not
+//! refreshed at all unless `include-past` is set: their archive data no
+//! longer changes, and mature vaults are mostly old trips.
+
+use crate::NoteMeta;
+use chrono::NaiveDate;
+
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// Sort key: lower is more urgent.
+pub fn urgency(meta: &NoteMeta, today: NaiveDate) -> (u8, i64) {
+    let meta = meta.occurrence_on(today);
+    let start = meta.arrival.min(meta.departure);
+    let end = meta.arrival.max(meta.departure);
+    if start <= today && today <= end {
+        (0, 0)
+    } else if start > today {
+        (1, (start - today).num_days())
+    } else {
+        (2, (today - end).num_days())
+    }
+}
+
+/// Most urgent first; ties keep their path order.
+pub fn sort_by_urgency(notes: &mut [NoteMeta], today: NaiveDate) {
+    notes.sort_by_key(|n| urgency(n, today));
+}
+
+/// Whether the trip ended more than `days` before `today`.
+pub fn is_long_past(meta: &NoteMeta, today: NaiveDate, days: u32) -> bool {
+    matches!(urgency(meta, today), (2, since) if since > i64::from(days))
+}
+
+/// Whether the trip is under way or arrives within `days` of `today`.
+pub fn is_upcoming(meta: &NoteMeta, today: NaiveDate, days: u32) -> bool {
+    match urgency(meta, today) {
+        (0, _) => true,
+        (1, until) => until <= i64::from(days),
+        _ => false,
+    }
+}
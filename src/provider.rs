@@ -0,0 +1,446 @@
+//! Forecast providers.
+//!
+//! Open‑Meteo is the default. The US National Weather Service (weather.gov)
+//! needs no key and is what many US users trust, so it can be chosen with
+//! `provider: nws`, or picked automatically for US coordinates with
+//! `provider: auto`. MET Norway's Locationforecast (the data behind Yr) is a
+//! keyless global alternative with good Nordic coverage. Visual Crossing is
+//! a commercial service and needs an API key (see [`crate::secrets`]).
+//! Historic proxies
+//! always come from the Open‑Meteo archive.
+
+use crate::{DayTemp, celcius_to_farenheit, farenheit_to_celcius, fetch_daily, http, secrets};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+
+/// Which service supplies forecasts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Provider {
+    #[default]
+    OpenMeteo,
+    Nws,
+    /// MET Norway Locationforecast (Yr).
+    MetNo,
+    /// Visual Crossing Timeline API; needs an API key.
+    VisualCrossing,
+    /// NWS inside the United States, Open‑Meteo elsewhere.
+    Auto,
+}
+
+impl Provider {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open-meteo" | "openmeteo" => Ok(Provider::OpenMeteo),
+            "nws" | "weather.gov" => Ok(Provider::Nws),
+            "met-norway" | "met-no" | "metno" | "yr" => Ok(Provider::MetNo),
+            "visual-crossing" | "visualcrossing" => Ok(Provider::VisualCrossing),
+            "auto" => Ok(Provider::Auto),
+            other => Err(anyhow!(
+                "unknown provider '{other}' (expected open-meteo, nws, met-norway, visual-crossing or auto)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`Provider::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Provider::OpenMeteo => "open-meteo",
+            Provider::Nws => "nws",
+            Provider::MetNo => "met-norway",
+            Provider::VisualCrossing => "visual-crossing",
+            Provider::Auto => "auto",
+        }
+    }
+
+    /// The concrete provider for a location.
+    pub fn resolve(self, lat: f64, lon: f64, country_code: Option<&str>) -> Provider {
+        match self {
+            Provider::Auto if country_code.map_or(in_us(lat, lon), |c| c == "US") => Provider::Nws,
+            Provider::Auto => Provider::OpenMeteo,
+            other => other,
+        }
+    }
+
+    /// Like [`Provider::resolve`], for a note that may pin an Open‑Meteo
+    /// `model`. A model implies Open‑Meteo; asking for one from any other
+    /// provider is an error.
+    pub fn source<'a>(
+        self,
+        model: Option<&'a str>,
+        lat: f64,
+        lon: f64,
+        country_code: Option<&str>,
+    ) -> Result<Source<'a>> {
+        let provider = match (self, model) {
+            (_, None) => self.resolve(lat, lon, country_code),
+            (Provider::Auto | Provider::OpenMeteo, Some(_)) => Provider::OpenMeteo,
+            (other, Some(model)) => {
+                return Err(anyhow!(
+                    "model '{model}' is an Open-Meteo model but the provider is {}",
+                    other.name()
+                ));
+            }
+        };
+        Ok(Source { provider, model })
+    }
+
+    /// Short attribution for the block header; empty for the default.
+    pub fn label(self) -> &'static str {
+        match self {
+            Provider::Nws => " (NWS)",
+            Provider::MetNo => " (MET Norway)",
+            Provider::VisualCrossing => " (Visual Crossing)",
+            _ => "",
+        }
+    }
+
+    /// Daily highs/lows for `start..=end`. `self` must already be resolved.
+    pub async fn fetch_forecast(
+        self,
+        client: &http::Client,
+        lat: f64,
+        lon: f64,
+        start: NaiveDate,
+        end: NaiveDate,
+        tz: &str,
+    ) -> Result<Vec<DayTemp>> {
+        let source = Source {
+            provider: self,
+            model: None,
+        };
+        source
+            .fetch_forecast(client, lat, lon, start, end, tz)
+            .await
+    }
+}
+
+/// A resolved provider plus the Open‑Meteo model a note asked for, if any.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Source<'a> {
+    pub provider: Provider,
+    pub model: Option<&'a str>,
+}
+
+impl Source<'_> {
+    /// Header attribution: the model when one is pinned, else the provider's.
+    pub fn label(&self) -> String {
+        match self.model {
+            Some(model) => format!(" ({model})"),
+            None => self.provider.label().to_string(),
+        }
+    }
+
+    /// Daily highs/lows for `start..=end`.
+    pub async fn fetch_forecast(
+        &self,
+        client: &http::Client,
+        lat: f64,
+        lon: f64,
+        start: NaiveDate,
+        end: NaiveDate,
+        tz: &str,
+    ) -> Result<Vec<DayTemp>> {
+        match self.provider {
+            Provider::Nws => nws_forecast(client, lat, lon, start, end).await,
+            Provider::MetNo => metno_forecast(client, lat, lon, start, end, tz).await,
+            Provider::VisualCrossing => {
+                visual_crossing_forecast(client, lat, lon, start, end).await
+            }
+            _ => fetch_daily(client, &lat, &lon, &start, &end, tz, self.model).await,
+        }
+    }
+}
+
+/// Rough bounding boxes for the contiguous US, Alaska and Hawaii.
+fn in_us(lat: f64, lon: f64) -> bool {
+    let within = |(lat0, lat1): (f64, f64), (lon0, lon1): (f64, f64)| {
+        (lat0..=lat1).contains(&lat) && (lon0..=lon1).contains(&lon)
+    };
+    within((24.5, 49.5), (-125.0, -66.9))
+        || within((51.0, 71.5), (-170.0, -129.9))
+        || within((18.9, 22.3), (-160.3, -154.8))
+}
+
+/// Gets the url for weather.gov
+fn nws_base() -> String {
+    env::var("NWS_BASE").unwrap_or_else(|_| "https://api.weather.gov".to_string())
+}
+
+#[derive(Deserialize, Debug)]
+struct NwsPoints {
+    properties: NwsPointProps,
+}
+#[derive(Deserialize, Debug)]
+struct NwsPointProps {
+    forecast: Option<String>,
+}
+
+/// A gridpoint forecast: alternating day/night periods.
+#[derive(Deserialize, Debug)]
+pub struct NwsForecast {
+    pub properties: NwsForecastProps,
+}
+#[derive(Deserialize, Debug)]
+pub struct NwsForecastProps {
+    pub periods: Vec<NwsPeriod>,
+}
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct NwsPeriod {
+    pub start_time: DateTime<FixedOffset>,
+    pub is_daytime: bool,
+    pub temperature: f64,
+    #[serde(default = "fahrenheit_unit")]
+    pub temperature_unit: String,
+}
+
+fn fahrenheit_unit() -> String {
+    "F".to_string()
+}
+
+/// Points lookup, then the gridpoint forecast it links to.
+async fn nws_forecast(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<DayTemp>> {
+    // weather.gov redirects requests with more than four decimals.
+    let url = format!("{}/points/{lat:.4},{lon:.4}", nws_base());
+    let points: NwsPoints = client.get_json_revalidated(&url).await?;
+    let forecast_url = points
+        .properties
+        .forecast
+        .ok_or_else(|| anyhow!("weather.gov has no forecast for ({lat:.4}, {lon:.4})"))?;
+    let forecast: NwsForecast = client.get_json_revalidated(&forecast_url).await?;
+    Ok(nws_days(forecast)
+        .into_iter()
+        .filter(|d| (start..=end).contains(&d.date))
+        .collect())
+}
+
+/// Pair each local date's daytime high with the low of the night that
+/// follows it. Dates missing either half (e.g. a leading "Tonight") are
+/// dropped.
+pub fn nws_days(forecast: NwsForecast) -> Vec<DayTemp> {
+    let mut days: BTreeMap<NaiveDate, (Option<f64>, Option<f64>)> = BTreeMap::new();
+    for period in forecast.properties.periods {
+        let celsius = if period.temperature_unit.eq_ignore_ascii_case("C") {
+            period.temperature
+        } else {
+            farenheit_to_celcius(period.temperature)
+        };
+        let entry = days.entry(period.start_time.date_naive()).or_default();
+        if period.is_daytime {
+            entry.0 = Some(celsius);
+        } else {
+            entry.1 = Some(celsius);
+        }
+    }
+    days.into_iter()
+        .filter_map(|(date, (high, low))| {
+            let (tmax, tmin) = (high?, low?);
+            Some(DayTemp {
+                date,
+                tmax,
+                tmin,
+                tmax_f: celcius_to_farenheit(tmax),
+                tmin_f: celcius_to_farenheit(tmin),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// Gets the url for api.met.no
+fn metno_base() -> String {
+    env::var("METNO_BASE").unwrap_or_else(|_| "https://api.met.no/weatherapi".to_string())
+}
+
+/// A Locationforecast `compact` response: hourly, then 6-hourly steps.
+#[derive(Deserialize, Debug)]
+pub struct MetNoForecast {
+    pub properties: MetNoProps,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoProps {
+    pub timeseries: Vec<MetNoStep>,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoStep {
+    pub time: DateTime<Utc>,
+    pub data: MetNoData,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoData {
+    pub instant: MetNoInstant,
+    pub next_6_hours: Option<MetNoPeriod>,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoInstant {
+    pub details: MetNoInstantDetails,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoInstantDetails {
+    pub air_temperature: Option<f64>,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoPeriod {
+    pub details: MetNoPeriodDetails,
+}
+#[derive(Deserialize, Debug)]
+pub struct MetNoPeriodDetails {
+    pub air_temperature_max: Option<f64>,
+    pub air_temperature_min: Option<f64>,
+}
+
+/// Locationforecast for the coordinates. MET Norway requires an identifying
+/// User-Agent (set on every client) and asks clients to respect `Expires`
+/// and revalidate with `If-Modified-Since`, which the client's revalidating
+/// GET does.
+async fn metno_forecast(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &str,
+) -> Result<Vec<DayTemp>> {
+    // The terms ask for at most four decimals so responses can be cached.
+    let url = format!(
+        "{}/locationforecast/2.0/compact?lat={lat:.4}&lon={lon:.4}",
+        metno_base()
+    );
+    let forecast: MetNoForecast = client.get_json_revalidated(&url).await?;
+    Ok(metno_days(forecast, tz, lon)
+        .into_iter()
+        .filter(|d| (start..=end).contains(&d.date))
+        .collect())
+}
+
+/// Fold the UTC timeseries into daily highs/lows by local date. Instant
+/// temperatures and 6-hour extremes both count toward the day a step starts
+/// on. `tz` is an IANA name; when it is `auto` or unknown the offset is
+/// estimated from the longitude.
+pub fn metno_days(forecast: MetNoForecast, tz: &str, lon: f64) -> Vec<DayTemp> {
+    let zone: Option<Tz> = tz.parse().ok();
+    let fallback = FixedOffset::east_opt(((lon / 15.0).round() as i32) * 3600)
+        .unwrap_or(FixedOffset::east_opt(0).unwrap());
+    let mut days: BTreeMap<NaiveDate, (f64, f64)> = BTreeMap::new();
+    for step in forecast.properties.timeseries {
+        let date = match zone {
+            Some(zone) => step.time.with_timezone(&zone).date_naive(),
+            None => step.time.with_timezone(&fallback).date_naive(),
+        };
+        let period = step.data.next_6_hours.map(|p| p.details);
+        let values = [
+            step.data.instant.details.air_temperature,
+            period.as_ref().and_then(|d| d.air_temperature_max),
+            period.as_ref().and_then(|d| d.air_temperature_min),
+        ];
+        for v in values.into_iter().flatten() {
+            let entry = days.entry(date).or_insert((v, v));
+            entry.0 = entry.0.max(v);
+            entry.1 = entry.1.min(v);
+        }
+    }
+    days.into_iter()
+        .map(|(date, (tmax, tmin))| DayTemp {
+            date,
+            tmax,
+            tmin,
+            tmax_f: celcius_to_farenheit(tmax),
+            tmin_f: celcius_to_farenheit(tmin),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Gets the url for the Visual Crossing web services
+fn visual_crossing_base() -> String {
+    env::var("VISUAL_CROSSING_BASE").unwrap_or_else(|_| {
+        "https://weather.visualcrossing.com/VisualCrossingWebServices/rest/services".to_string()
+    })
+}
+
+/// A Timeline API response trimmed to daily extremes.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VisualCrossingTimeline {
+    pub days: Vec<VisualCrossingDay>,
+    /// Records billed for the request; the free tier allows 1000 a day.
+    pub query_cost: Option<u64>,
+}
+#[derive(Deserialize, Debug)]
+pub struct VisualCrossingDay {
+    pub datetime: NaiveDate,
+    pub tempmax: Option<f64>,
+    pub tempmin: Option<f64>,
+}
+
+/// Timeline request for the coordinates. The key goes in the `key` query
+/// parameter, which `http::redact_url` masks in logs and errors.
+async fn visual_crossing_forecast(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<DayTemp>> {
+    let name = Provider::VisualCrossing.name();
+    let (key, source) = secrets::api_key(name)?.ok_or_else(|| {
+        anyhow!(
+            "provider {name} needs an API key: set {}",
+            secrets::env_var(name)
+        )
+    })?;
+    tracing::debug!(%source, "using {name} API key");
+    // "us" is the service default; ask for it explicitly and convert, so the
+    // mapping does not depend on account settings.
+    let unit_group = "us";
+    let url = format!(
+        "{}/timeline/{lat:.4},{lon:.4}/{start}/{end}?unitGroup={unit_group}&include=days&elements=datetime,tempmax,tempmin&contentType=json&key={}",
+        visual_crossing_base(),
+        urlencoding::encode(key.expose())
+    );
+    let timeline: VisualCrossingTimeline = client.get_json(&url).await?;
+    if let Some(cost) = timeline.query_cost {
+        tracing::debug!(cost, "{name} query cost");
+    }
+    visual_crossing_days(timeline, unit_group)
+}
+
+/// Daily highs/lows from a timeline in the given `unitGroup` (`us`, `uk`,
+/// `metric` or `base`, which is Kelvin). Days missing either value are
+/// dropped.
+pub fn visual_crossing_days(
+    timeline: VisualCrossingTimeline,
+    unit_group: &str,
+) -> Result<Vec<DayTemp>> {
+    let to_celsius: fn(f64) -> f64 = match unit_group {
+        "us" => farenheit_to_celcius,
+        "uk" | "metric" => |c| c,
+        "base" => |k| k - 273.15,
+        other => return Err(anyhow!("unknown Visual Crossing unitGroup '{other}'")),
+    };
+    Ok(timeline
+        .days
+        .into_iter()
+        .filter_map(|d| {
+            let (tmax, tmin) = (to_celsius(d.tempmax?), to_celsius(d.tempmin?));
+            Some(DayTemp {
+                date: d.datetime,
+                tmax,
+                tmin,
+                tmax_f: celcius_to_farenheit(tmax),
+                tmin_f: celcius_to_farenheit(tmin),
+                ..Default::default()
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,116 @@
+//! Which dates a note's table covers and where each comes from.
+//!
+//! [`plan_ranges`] splits a trip into observed days, forecast days and, for
+//! trips beyond the forecast horizon, last year's span as a proxy. It is
+//! pure so the clamping and shifting can be tested without the network.
+//!
+//! The forecast API keeps about three months of past days (`past_days`, up
+//! to 92) from its own model runs, and those are updated within hours. ERA5
+//! lags real time by about five days and is only worth asking for trips
+//! older than that window. Recent trips therefore come from `past_days`
+//! first and fall back to the archive only when that fails.
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// The most past days the forecast API serves.
+pub const PAST_DAYS_MAX: i64 = 92;
+
+/// How many years back a historical proxy may come from when the archive
+/// has nothing for last year's span (new stations, data gaps).
+pub const PROXY_YEARS: i32 = 5;
+
+/// How far ahead the forecast reaches, in days after today.
+pub const FORECAST_DAYS: i64 = 16;
+
+/// The last forecast day, as of `today`.
+pub fn horizon(today: NaiveDate) -> NaiveDate {
+    today + Duration::days(FORECAST_DAYS)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActualsSource {
+    /// The forecast API with `past_days=N`, reaching back to the trip start.
+    PastDays(u32),
+    /// The ERA5 archive, its missing last days filled from the forecast API.
+    Archive,
+}
+
+/// Where to get observed days from `start` up to yesterday, given `today`.
+pub fn actuals_source(start: NaiveDate, today: NaiveDate) -> ActualsSource {
+    let back = (today - start).num_days();
+    if back <= PAST_DAYS_MAX {
+        ActualsSource::PastDays(back.max(0) as u32)
+    } else {
+        ActualsSource::Archive
+    }
+}
+
+/// Where a [`PlannedFetch`] gets its days from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FetchKind {
+    /// Observed days, see [`actuals_source`].
+    Actuals,
+    /// The provider's forecast.
+    Forecast,
+    /// The same calendar span a year earlier, from ERA5.
+    HistoricProxy,
+}
+
+/// One request's date span, both ends included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlannedFetch {
+    pub kind: FetchKind,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// The fetches that cover a trip, in date order.
+///
+/// Arrival and departure may be given in either order. Days before `today`
+/// are actuals and days from `today` to `horizon` (the last forecast day)
+/// are forecast; days past the horizon are dropped. A trip that starts
+/// after the horizon has no forecast at all and gets the same span a year
+/// earlier instead, with 29 February read as the 28th.
+pub fn plan_ranges(
+    arrival: NaiveDate,
+    departure: NaiveDate,
+    today: NaiveDate,
+    horizon: NaiveDate,
+) -> Vec<PlannedFetch> {
+    let start = arrival.min(departure);
+    let end = arrival.max(departure);
+    let fetch = |kind, start, end| PlannedFetch { kind, start, end };
+    if start > horizon {
+        return vec![fetch(
+            FetchKind::HistoricProxy,
+            year_earlier(start),
+            year_earlier(end),
+        )];
+    }
+    let mut plan = Vec::new();
+    if start < today {
+        let yesterday = today.pred_opt().unwrap_or(today);
+        plan.push(fetch(FetchKind::Actuals, start, end.min(yesterday)));
+    }
+    if end >= today && today <= horizon {
+        plan.push(fetch(
+            FetchKind::Forecast,
+            start.max(today),
+            end.min(horizon),
+        ));
+    }
+    plan
+}
+
+/// The same day a year earlier; 29 February becomes the 28th.
+fn year_earlier(date: NaiveDate) -> NaiveDate {
+    years_earlier(date, 1)
+}
+
+/// The same day `years` years earlier; 29 February becomes the 28th when
+/// that year has none.
+pub fn years_earlier(date: NaiveDate, years: i32) -> NaiveDate {
+    date.with_year(date.year() - years)
+        .or_else(|| date.pred_opt().and_then(|d| d.with_year(d.year() - years)))
+        .unwrap_or(date)
+}
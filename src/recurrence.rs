@@ -0,0 +1,176 @@
+//! Recurring trips.
+//!
+//! A note with `recurrence:` describes a regular trip; its `arrival` and
+//! `departure` are the first occurrence and every run works on the next
+//! occurrence that has not ended yet. Supported values are `weekly`,
+//! `biweekly`, `monthly`, `yearly`, `every N days|weeks|months|years`, and
+//! five-field cron expressions whose day-of-month, month and day-of-week
+//! fields select the arrival day (minute and hour are ignored).
+
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+/// Longest span searched for the next cron match.
+const CRON_SEARCH_DAYS: u64 = 366 * 5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+/// A parsed `recurrence:` value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Recurrence {
+    Every { n: u32, unit: Unit },
+    Cron(CronSpec),
+}
+
+/// Day-level subset of a cron expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronSpec {
+    days_of_month: Option<Vec<u32>>,
+    months: Option<Vec<u32>>,
+    days_of_week: Option<Vec<u32>>,
+}
+
+impl Recurrence {
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let every = |n, unit| Ok(Recurrence::Every { n, unit });
+        match s.as_str() {
+            "daily" => return every(1, Unit::Days),
+            "weekly" => return every(1, Unit::Weeks),
+            "biweekly" | "fortnightly" => return every(2, Unit::Weeks),
+            "monthly" => return every(1, Unit::Months),
+            "quarterly" => return every(3, Unit::Months),
+            "yearly" | "annually" => return every(1, Unit::Years),
+            _ => {}
+        }
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        if let ["every", n, unit] = parts.as_slice() {
+            let n: u32 = n
+                .parse()
+                .map_err(|_| anyhow!("invalid recurrence count in '{s}'"))?;
+            if n == 0 {
+                return Err(anyhow!("recurrence interval must be positive"));
+            }
+            let unit = match unit.trim_end_matches('s') {
+                "day" => Unit::Days,
+                "week" => Unit::Weeks,
+                "month" => Unit::Months,
+                "year" => Unit::Years,
+                other => return Err(anyhow!("unknown recurrence unit '{other}'")),
+            };
+            return every(n, unit);
+        }
+        if parts.len() == 5 {
+            return Ok(Recurrence::Cron(CronSpec {
+                days_of_month: cron_field(parts[2], 1, 31)?,
+                months: cron_field(parts[3], 1, 12)?,
+                days_of_week: cron_field(parts[4], 0, 7)?
+                    .map(|v| v.into_iter().map(|d| d % 7).collect()),
+            }));
+        }
+        Err(anyhow!(
+            "unsupported recurrence '{s}' (try weekly, monthly, 'every 2 weeks' or a cron expression)"
+        ))
+    }
+
+    /// The first occurrence of the `arrival..=departure` trip that ends on or
+    /// after `today`.
+    pub fn next_occurrence(
+        &self,
+        arrival: NaiveDate,
+        departure: NaiveDate,
+        today: NaiveDate,
+    ) -> Option<(NaiveDate, NaiveDate)> {
+        let length = Days::new((departure - arrival).num_days().max(0) as u64);
+        if departure >= today {
+            return Some((arrival, departure));
+        }
+        match self {
+            Recurrence::Every { n, unit } => {
+                // A `None` (overflow, or past the last date) ends the search.
+                let shift = |k: u32| -> Option<NaiveDate> {
+                    let steps = k.checked_mul(*n)?;
+                    match unit {
+                        Unit::Days => arrival.checked_add_days(Days::new(u64::from(steps))),
+                        Unit::Weeks => {
+                            arrival.checked_add_days(Days::new(u64::from(steps.checked_mul(7)?)))
+                        }
+                        Unit::Months => arrival.checked_add_months(Months::new(steps)),
+                        Unit::Years => {
+                            arrival.checked_add_months(Months::new(steps.checked_mul(12)?))
+                        }
+                    }
+                };
+                (1..=10_000)
+                    .map_while(|k| {
+                        let start = shift(k)?;
+                        Some((start, start.checked_add_days(length)?))
+                    })
+                    .find(|(_, end)| *end >= today)
+            }
+            Recurrence::Cron(spec) => {
+                let first = today.checked_sub_days(length)?.max(arrival);
+                (0..CRON_SEARCH_DAYS).find_map(|i| {
+                    let start = first.checked_add_days(Days::new(i))?;
+                    spec.matches(start)
+                        .then(|| start.checked_add_days(length).map(|end| (start, end)))
+                        .flatten()
+                })
+            }
+        }
+    }
+}
+
+impl CronSpec {
+    fn matches(&self, date: NaiveDate) -> bool {
+        let contains =
+            |field: &Option<Vec<u32>>, v: u32| field.as_ref().is_none_or(|f| f.contains(&v));
+        if !contains(&self.months, date.month()) {
+            return false;
+        }
+        let dom = contains(&self.days_of_month, date.day());
+        let dow = contains(&self.days_of_week, date.weekday().num_days_from_sunday());
+        // Standard cron: when both day fields are restricted, either may match.
+        match (&self.days_of_month, &self.days_of_week) {
+            (Some(_), Some(_)) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+/// Parse one cron field; `None` means `*` (any value).
+fn cron_field(field: &str, min: u32, max: u32) -> Result<Option<Vec<u32>>> {
+    if field == "*" {
+        return Ok(None);
+    }
+    let bad = || anyhow!("invalid cron field '{field}'");
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((r, s)) => (r, s.parse::<u32>().map_err(|_| bad())?),
+            None => (part, 1),
+        };
+        if step == 0 {
+            return Err(bad());
+        }
+        let (lo, hi) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (a.parse().map_err(|_| bad())?, b.parse().map_err(|_| bad())?)
+        } else {
+            let v: u32 = range.parse().map_err(|_| bad())?;
+            (v, v)
+        };
+        if lo < min || hi > max || lo > hi {
+            return Err(bad());
+        }
+        values.extend((lo..=hi).step_by(step as usize));
+    }
+    Ok(Some(values))
+}
@@ -0,0 +1,76 @@
+//! Driving conditions for road trips.
+//!
+//! Notes with `trip-type: roadtrip` get days with snow, freezing rain or
+//! sub-zero lows flagged in the table, for the destination and for any
+//! `route:` waypoints listed in the frontmatter, plus a reminder to check
+//! winter tire rules when anything is flagged. The data comes from
+//! [`crate::route::fetch_route`].
+
+use crate::route::Stop;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Summary line added when any day is flagged.
+pub const WINTER_TIRES: &str =
+    "**Driving**: snow or ice possible — check winter tire and chain requirements along the route";
+
+/// Road-relevant weather for one day at one place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoadDay {
+    pub date: NaiveDate,
+    /// WMO weather interpretation code, as reported by Open‑Meteo.
+    pub weather_code: Option<u8>,
+    /// Daily high, °C.
+    pub tmax: Option<f64>,
+    /// Daily low, °C.
+    pub tmin: Option<f64>,
+}
+
+/// What a WMO code means for driving, if anything.
+pub fn hazard(code: u8) -> Option<&'static str> {
+    match code {
+        56 | 57 | 66 | 67 => Some("freezing rain"),
+        71 | 73 | 75 | 77 | 85 | 86 => Some("snow"),
+        _ => None,
+    }
+}
+
+/// Hazards per day, e.g. `["snow", "low -3°C"]`; days without any are left out.
+pub fn hazards(days: &[RoadDay]) -> BTreeMap<NaiveDate, Vec<String>> {
+    days.iter()
+        .filter_map(|d| {
+            let mut found: Vec<String> = d
+                .weather_code
+                .and_then(hazard)
+                .map(str::to_string)
+                .into_iter()
+                .collect();
+            if let Some(low) = d.tmin
+                && low < 0.0
+            {
+                found.push(format!("low {}°C", crate::fmt_temp(low)));
+            }
+            (!found.is_empty()).then_some((d.date, found))
+        })
+        .collect()
+}
+
+/// Table annotations for every flagged day across the route, e.g.
+/// `roads: snow; roads at Brenner: low -4°C`.
+pub fn route_notes(route: &[(Stop, Vec<RoadDay>)]) -> BTreeMap<NaiveDate, Vec<String>> {
+    let mut notes: BTreeMap<NaiveDate, Vec<String>> = BTreeMap::new();
+    for (stop, days) in route {
+        for (date, found) in hazards(days) {
+            let at = if stop.name.is_empty() {
+                String::new()
+            } else {
+                format!(" at {}", stop.name)
+            };
+            notes
+                .entry(date)
+                .or_default()
+                .push(format!("roads{at}: {}", found.join(", ")));
+        }
+    }
+    notes
+}
@@ -0,0 +1,304 @@
+//! Weather along the way.
+//!
+//! A `route:` (or `waypoints:`) list in the frontmatter names intermediate
+//! places or gives coordinates:
+//!
+//! ```yaml
+//! route:
+//!   - Innsbruck
+//!   - 47.00, 11.51
+//!   - { name: Brenner Pass, lat: 47.00, lon: 11.51 }
+//! ```
+//!
+//! Each distinct stop is fetched once for the whole trip range, all stops
+//! concurrently, and the result feeds both the per-waypoint table for the
+//! travel days and the road trip hazard flags.
+
+use crate::geo;
+use crate::roadtrip::RoadDay;
+use crate::variables::{self, Endpoint};
+use crate::{Geocoder, Units, celcius_to_farenheit, fmt_temp, http};
+use anyhow::{Context, Result, anyhow};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use serde_yaml::Value as YamlValue;
+use tokio::task::JoinSet;
+
+/// One entry of a `route:` list.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Waypoint {
+    /// A place name to geocode.
+    Named(String),
+    /// Explicit coordinates, shown as `label`.
+    Coords { lat: f64, lon: f64, label: String },
+}
+
+impl Waypoint {
+    /// Read a list entry: a name, `"lat, lon"`, or a mapping with
+    /// `lat`/`lon` and an optional `name`.
+    pub fn parse(value: &YamlValue) -> Option<Waypoint> {
+        let number = |v: &YamlValue| v.as_f64().or_else(|| v.as_str()?.trim().parse().ok());
+        match value {
+            YamlValue::String(s) => {
+                let s = s.trim();
+                if s.is_empty() {
+                    return None;
+                }
+                if let Some((lat, lon)) = geo::parse_coords(s) {
+                    return Some(Waypoint::Coords {
+                        lat,
+                        lon,
+                        label: geo::format_coords(lat, lon, 2),
+                    });
+                }
+                Some(Waypoint::Named(s.to_string()))
+            }
+            YamlValue::Mapping(_) => {
+                let lat = number(value.get("lat").or_else(|| value.get("latitude"))?)?;
+                let lon = number(value.get("lon").or_else(|| value.get("longitude"))?)?;
+                let label = value
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_else(|| geo::format_coords(lat, lon, 2));
+                Some(Waypoint::Coords { lat, lon, label })
+            }
+            _ => None,
+        }
+    }
+
+    /// How the waypoint is shown in notes and errors.
+    pub fn label(&self) -> &str {
+        match self {
+            Waypoint::Named(name) => name,
+            Waypoint::Coords { label, .. } => label,
+        }
+    }
+}
+
+/// A resolved place along the route; an empty name is the destination.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stop {
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+}
+
+/// Geocode named waypoints; coordinates are used as given.
+pub async fn resolve(
+    client: &http::Client,
+    waypoints: &[Waypoint],
+    geocoder: Geocoder,
+    language: Option<&str>,
+) -> Result<Vec<Stop>> {
+    let mut stops = Vec::with_capacity(waypoints.len());
+    for waypoint in waypoints {
+        let stop = match waypoint {
+            Waypoint::Named(name) => {
+                let place = geocoder
+                    .search(client, name, language)
+                    .await
+                    .with_context(|| format!("waypoint '{name}'"))?;
+                Stop {
+                    name: name.clone(),
+                    latitude: place.latitude,
+                    longitude: place.longitude,
+                    timezone: place.timezone,
+                }
+            }
+            Waypoint::Coords { lat, lon, label } => Stop {
+                name: label.clone(),
+                latitude: *lat,
+                longitude: *lon,
+                timezone: "auto".to_string(),
+            },
+        };
+        stops.push(stop);
+    }
+    Ok(stops)
+}
+
+/// Daily weather for every stop over `start..=end`, in `stops` order.
+///
+/// Stops within about 10 m of each other share one request, and the
+/// requests run concurrently.
+pub async fn fetch_route(
+    client: &http::Client,
+    stops: &[Stop],
+    start: NaiveDate,
+    end: NaiveDate,
+    forecast: bool,
+) -> Result<Vec<(Stop, Vec<RoadDay>)>> {
+    let key = |s: &Stop| format!("{:.4},{:.4}", s.latitude, s.longitude);
+    let mut unique: Vec<&Stop> = Vec::new();
+    for stop in stops {
+        if !unique.iter().any(|u| key(u) == key(stop)) {
+            unique.push(stop);
+        }
+    }
+    let mut tasks = JoinSet::new();
+    for (i, stop) in unique.iter().enumerate() {
+        let (client, stop) = (client.clone(), (*stop).clone());
+        tasks.spawn(async move {
+            let days = fetch_days(
+                &client,
+                stop.latitude,
+                stop.longitude,
+                start,
+                end,
+                &stop.timezone,
+                forecast,
+            )
+            .await
+            .with_context(|| {
+                let name = if stop.name.is_empty() {
+                    "destination"
+                } else {
+                    &stop.name
+                };
+                format!("route weather for {name}")
+            });
+            (i, days)
+        });
+    }
+    let mut fetched: Vec<Option<Vec<RoadDay>>> = vec![None; unique.len()];
+    while let Some(joined) = tasks.join_next().await {
+        let (i, days) = joined.map_err(|e| anyhow!("route fetch task failed: {e}"))?;
+        fetched[i] = Some(days?);
+    }
+    Ok(stops
+        .iter()
+        .map(|stop| {
+            let i = unique.iter().position(|u| key(u) == key(stop)).unwrap();
+            (stop.clone(), fetched[i].clone().unwrap_or_default())
+        })
+        .collect())
+}
+
+#[derive(Deserialize, Debug)]
+struct DailyResp {
+    daily: Option<Daily>,
+}
+#[derive(Deserialize, Debug)]
+struct Daily {
+    time: Vec<NaiveDate>,
+    #[serde(default)]
+    weather_code: Vec<Option<u8>>,
+    #[serde(default)]
+    temperature_2m_max: Vec<Option<f64>>,
+    #[serde(default)]
+    temperature_2m_min: Vec<Option<f64>>,
+}
+
+/// Weather codes, highs and lows from the Open‑Meteo forecast, or from the
+/// archive when `forecast` is false.
+pub async fn fetch_days(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &str,
+    forecast: bool,
+) -> Result<Vec<RoadDay>> {
+    let (endpoint, source) = if forecast {
+        (
+            format!("{}/forecast", crate::forecast_base()),
+            Endpoint::Forecast,
+        )
+    } else {
+        (format!("{}/era5", crate::archive_base()), Endpoint::Archive)
+    };
+    let daily = variables::daily_param(
+        &["weather_code", "temperature_2m_max", "temperature_2m_min"],
+        source,
+    );
+    let url = format!(
+        "{endpoint}?latitude={lat}&longitude={lon}&daily={daily}&start_date={start}&end_date={end}&timezone={}",
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let resp: DailyResp = client.get_json(&url).await?;
+    let Some(daily) = resp.daily else {
+        return Ok(Vec::new());
+    };
+    let at = |v: &Vec<Option<f64>>, i: usize| v.get(i).copied().flatten();
+    Ok(daily
+        .time
+        .iter()
+        .enumerate()
+        .map(|(i, date)| RoadDay {
+            date: *date,
+            weather_code: daily.weather_code.get(i).copied().flatten(),
+            tmax: at(&daily.temperature_2m_max, i),
+            tmin: at(&daily.temperature_2m_min, i),
+        })
+        .collect())
+}
+
+/// Short description of a WMO weather code.
+pub fn describe(code: u8) -> &'static str {
+    match code {
+        0 => "clear",
+        1 | 2 => "partly cloudy",
+        3 => "overcast",
+        45 | 48 => "fog",
+        51..=55 => "drizzle",
+        56 | 57 => "freezing drizzle",
+        61..=65 => "rain",
+        66 | 67 => "freezing rain",
+        71..=77 => "snow",
+        80..=82 => "showers",
+        85 | 86 => "snow showers",
+        95..=99 => "thunderstorm",
+        _ => "",
+    }
+}
+
+/// One `| place | date | high | low | conditions |` row.
+fn row(stop: &Stop, day: &RoadDay, units: Units) -> String {
+    let show = |c: Option<f64>| match (c, units) {
+        (None, _) => "–".to_string(),
+        (Some(c), Units::Celsius) => format!("{}°C", fmt_temp(c)),
+        (Some(c), Units::Fahrenheit) => format!("{}°F", fmt_temp(celcius_to_farenheit(c))),
+    };
+    format!(
+        "| {} | {} | {} | {} | {} |\n",
+        crate::width::isolate(&stop.name),
+        day.date,
+        show(day.tmax),
+        show(day.tmin),
+        day.weather_code.map(describe).unwrap_or("")
+    )
+}
+
+/// Compact table of each waypoint's weather on the travel days.
+pub fn render_route(
+    route: &[(Stop, Vec<RoadDay>)],
+    travel_days: &[NaiveDate],
+    units: Units,
+) -> String {
+    let mut rows = String::new();
+    for (stop, days) in route.iter().filter(|(stop, _)| !stop.name.is_empty()) {
+        for day in days.iter().filter(|d| travel_days.contains(&d.date)) {
+            rows.push_str(&row(stop, day, units));
+        }
+    }
+    if rows.is_empty() {
+        return String::new();
+    }
+    format!(
+        "**Along the route**\n\n| Waypoint | Date | High | Low | Conditions |\n|---|---|---:|---:|---|\n{rows}"
+    )
+}
+
+/// The one-row table for the `origin:` on the day of setting off.
+pub fn render_origin(origin: &Stop, days: &[RoadDay], units: Units) -> String {
+    let Some(day) = days.first() else {
+        return String::new();
+    };
+    format!(
+        "**Leaving from**\n\n| Origin | Date | High | Low | Conditions |\n|---|---|---:|---:|---|\n{}",
+        row(origin, day, units)
+    )
+}
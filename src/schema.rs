@@ -0,0 +1,313 @@
+//! A machine-readable description of the frontmatter the tool reads.
+//!
+//! `weather-helper schema` prints it as a JSON Schema (for linters and
+//! editor autocomplete) or as an Obsidian `types.json` (property types for
+//! the vault's Properties view). Besides the trip keys it lists every
+//! `weather-<key>` override a note may set, with the current configuration's
+//! value as the default, so the schema matches the config it was made with.
+
+use crate::config::{self, Config};
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+
+/// Output format of `weather-helper schema`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaFormat {
+    /// JSON Schema (draft 2020-12).
+    #[default]
+    JsonSchema,
+    /// `.obsidian/types.json`.
+    Obsidian,
+}
+
+impl SchemaFormat {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json-schema" | "jsonschema" | "json" => Ok(SchemaFormat::JsonSchema),
+            "obsidian" | "types" => Ok(SchemaFormat::Obsidian),
+            other => Err(anyhow!(
+                "unknown schema format '{other}' (expected json-schema or obsidian)"
+            )),
+        }
+    }
+}
+
+/// What a key's value looks like.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    Number,
+    Boolean,
+    /// A trip date; any format [`crate::dates::parse_trip_date`] reads.
+    Date,
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+    /// A list of strings (or, for `route`, coordinate mappings).
+    List,
+    /// Obsidian tags.
+    Tags,
+}
+
+/// One frontmatter key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Property {
+    pub key: String,
+    pub kind: Kind,
+    pub description: String,
+    /// The effective value when the note leaves the key out.
+    pub default: Option<String>,
+}
+
+/// Trip keys, in the order the README documents them.
+const TRIP_KEYS: &[(&str, Kind, &str)] = &[
+    (
+        "city",
+        Kind::Text,
+        "Destination; geocoded, or an IATA/ICAO airport code",
+    ),
+    ("city-place", Kind::Text, "Same as city"),
+    ("city_place", Kind::Text, "Same as city"),
+    (
+        "airport",
+        Kind::Text,
+        "IATA or ICAO code resolved from the bundled airport list",
+    ),
+    (
+        "arrival",
+        Kind::Date,
+        "First day of the trip (YYYY-MM-DD, optionally with a time)",
+    ),
+    (
+        "departure",
+        Kind::Date,
+        "Last day of the trip (YYYY-MM-DD, optionally with a time)",
+    ),
+    (
+        "flexible",
+        Kind::Boolean,
+        "Dates not booked yet: recommend the best estimated-days within the window",
+    ),
+    (
+        "window-start",
+        Kind::Date,
+        "With flexible, the first day the trip may start",
+    ),
+    (
+        "window-end",
+        Kind::Date,
+        "With flexible, the last day the trip may end",
+    ),
+    (
+        "date-order",
+        Kind::Choice(&["dmy", "mdy"]),
+        "How to read NN/NN/YYYY dates",
+    ),
+    (
+        "estimated-days",
+        Kind::Number,
+        "Trip length; -1 skips the note",
+    ),
+    (
+        "lat",
+        Kind::Number,
+        "Latitude; skips geocoding together with lon",
+    ),
+    ("latitude", Kind::Number, "Same as lat"),
+    (
+        "lon",
+        Kind::Number,
+        "Longitude; skips geocoding together with lat",
+    ),
+    ("longitude", Kind::Number, "Same as lon"),
+    (
+        "timezone",
+        Kind::Text,
+        "IANA zone of the destination, e.g. Europe/Rome",
+    ),
+    (
+        "recurrence",
+        Kind::Text,
+        "weekly, biweekly, monthly, yearly, every N weeks or a cron expression",
+    ),
+    (
+        "overlap-ok",
+        Kind::Boolean,
+        "Do not warn when this trip overlaps another",
+    ),
+    (
+        "weather-pin",
+        Kind::Boolean,
+        "Freeze the weather block as it is; updates skip the note",
+    ),
+    (
+        "trip-type",
+        Kind::Choice(&["roadtrip"]),
+        "roadtrip adds weather for each stop of route",
+    ),
+    (
+        "route",
+        Kind::List,
+        "Road trip stops: place names, \"lat, lon\" or {lat, lon, name}",
+    ),
+    ("waypoints", Kind::List, "Same as route"),
+    (
+        "origin",
+        Kind::Text,
+        "Home city, shown with its weather on the day of setting off",
+    ),
+    (
+        "candidates",
+        Kind::List,
+        "Destinations to compare side by side until city is set",
+    ),
+    ("tags", Kind::Tags, "Obsidian tags, matched by --tag"),
+    (
+        "provider",
+        Kind::Choice(PROVIDERS),
+        "Shorthand for weather-provider",
+    ),
+    ("model", Kind::Text, "Shorthand for weather-model"),
+    (
+        "units",
+        Kind::Choice(&["metric", "imperial", "celsius", "fahrenheit"]),
+        "Shorthand for weather-units",
+    ),
+    (
+        "locale",
+        Kind::Text,
+        "Language tag such as de-DE; sets the note's language and, by region, its units",
+    ),
+    (
+        "alert-if",
+        Kind::Text,
+        "Shorthand for weather-alert-if, e.g. tmax_f > 95, rain_prob > 60",
+    ),
+    (
+        "refresh",
+        Kind::Choice(REFRESH),
+        "Shorthand for weather-refresh",
+    ),
+];
+
+const REFRESH: &[&str] = &["always", "hourly", "daily", "weekly", "manual"];
+
+const PROVIDERS: &[&str] = &["open-meteo", "nws", "met-norway", "visual-crossing", "auto"];
+
+/// How a `weather-<key>` override's value looks.
+fn override_kind(key: &str) -> Kind {
+    match key {
+        "geocoder" => Kind::Choice(&["open-meteo", "nominatim", "photon"]),
+        "units" => Kind::Choice(&["celsius", "fahrenheit"]),
+        "provider" => Kind::Choice(PROVIDERS),
+        "map" => Kind::Choice(&["off", "link", "image"]),
+        "on-error" => Kind::Choice(&["keep", "stale", "block"]),
+        "refresh" => Kind::Choice(REFRESH),
+        "table-style" => Kind::Choice(&["markdown", "hugo", "zola", "html"]),
+        "language" | "model" | "under-heading" | "advisories" | "day-property" | "alert-if"
+        | "window-score" | "summary-lang" => Kind::Text,
+        "vars" => Kind::List,
+        _ => Kind::Boolean,
+    }
+}
+
+/// Every key a note may use under `config`: the trip keys, then the
+/// `weather-<key>` overrides with their current values as defaults.
+pub fn properties(config: &Config) -> Vec<Property> {
+    let trip = TRIP_KEYS.iter().map(|(key, kind, description)| Property {
+        key: key.to_string(),
+        kind: *kind,
+        description: description.to_string(),
+        default: None,
+    });
+    let overrides = config::NOTE_KEYS.iter().map(|key| Property {
+        key: format!("weather-{key}"),
+        kind: override_kind(key),
+        description: format!("This note's `{key}` setting"),
+        default: config
+            .get(key)
+            .map(|(value, _)| value.to_string())
+            .filter(|v| !v.is_empty()),
+    });
+    trip.chain(overrides).collect()
+}
+
+fn json_type(property: &Property) -> Value {
+    let mut out = match property.kind {
+        Kind::Text | Kind::Date => json!({ "type": "string" }),
+        Kind::Number => json!({ "type": "number" }),
+        Kind::Boolean => json!({ "type": "boolean" }),
+        Kind::Choice(values) => json!({ "type": "string", "enum": values }),
+        Kind::List => json!({ "type": "array", "items": { "type": ["string", "object"] } }),
+        Kind::Tags => json!({ "type": ["array", "string"], "items": { "type": "string" } }),
+    };
+    out["description"] = property.description.clone().into();
+    if let Some(default) = &property.default {
+        out["default"] = match property.kind {
+            Kind::Boolean => default
+                .parse::<bool>()
+                .map_or(default.clone().into(), Value::Bool),
+            _ => default.clone().into(),
+        };
+    }
+    out
+}
+
+/// A JSON Schema for a trip note's frontmatter. Unknown keys are allowed:
+/// notes carry plenty the tool does not read. A note needs dates (or, when
+/// flexible, a window) and a place.
+pub fn json_schema(properties: &[Property]) -> String {
+    let props: Map<String, Value> = properties
+        .iter()
+        .map(|p| (p.key.clone(), json_type(p)))
+        .collect();
+    let place = ["city", "city-place", "city_place", "airport", "candidates"]
+        .iter()
+        .map(|key| json!({ "required": [key] }))
+        .chain([
+            json!({ "required": ["lat", "lon"] }),
+            json!({ "required": ["latitude", "longitude"] }),
+        ])
+        .collect::<Vec<_>>();
+    let schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "weather-helper trip note",
+        "type": "object",
+        "properties": props,
+        "allOf": [
+            { "anyOf": [
+                { "required": ["arrival", "departure"] },
+                { "required": ["flexible", "window-start", "window-end", "estimated-days"] },
+            ] },
+            { "anyOf": place },
+        ],
+    });
+    serde_json::to_string_pretty(&schema).unwrap_or_default() + "\n"
+}
+
+/// An Obsidian `types.json` assigning each key a property type.
+pub fn obsidian_types(properties: &[Property]) -> String {
+    let types: Map<String, Value> = properties
+        .iter()
+        .map(|p| {
+            let kind = match p.kind {
+                Kind::Text | Kind::Choice(_) => "text",
+                Kind::Number => "number",
+                Kind::Boolean => "checkbox",
+                Kind::Date => "date",
+                Kind::List => "multitext",
+                Kind::Tags => "tags",
+            };
+            (p.key.clone(), kind.into())
+        })
+        .collect();
+    serde_json::to_string_pretty(&json!({ "types": types })).unwrap_or_default() + "\n"
+}
+
+/// The schema for `config` in `format`.
+pub fn export(config: &Config, format: SchemaFormat) -> String {
+    let properties = properties(config);
+    match format {
+        SchemaFormat::JsonSchema => json_schema(&properties),
+        SchemaFormat::Obsidian => obsidian_types(&properties),
+    }
+}
@@ -0,0 +1,131 @@
+//! Provider API keys.
+//!
+//! A key for provider `<name>` is looked up, in order, in the
+//! `WEATHER_HELPER_<NAME>_API_KEY` environment variable, in the file named by
+//! `WEATHER_HELPER_<NAME>_API_KEY_FILE` (for Docker/systemd secrets), and,
+//! with the `keychain` feature, in the OS keychain under service
+//! `weather-helper` and user `<name>`. Keys are wrapped in [`Secret`], whose
+//! `Debug`/`Display` never reveal the value, and request URLs are masked by
+//! `http::redact_url` before they reach logs or error messages.
+
+use crate::io;
+use anyhow::{Context, Result};
+use std::{env, fmt};
+
+/// Keychain service name.
+#[cfg(feature = "keychain")]
+const KEYCHAIN_SERVICE: &str = "weather-helper";
+
+/// A credential that prints as `***`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    /// The raw value, for building a request. Never log the result.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(***)")
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+/// Where a key was found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeySource {
+    Env(String),
+    File(String),
+    Keychain,
+}
+
+impl fmt::Display for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Env(var) => write!(f, "env {var}"),
+            KeySource::File(var) => write!(f, "file from {var}"),
+            KeySource::Keychain => write!(f, "OS keychain"),
+        }
+    }
+}
+
+/// Environment variable holding the key for `provider`.
+pub fn env_var(provider: &str) -> String {
+    format!(
+        "WEATHER_HELPER_{}_API_KEY",
+        provider.to_ascii_uppercase().replace('-', "_")
+    )
+}
+
+/// The API key for `provider`, if one is configured anywhere.
+pub fn api_key(provider: &str) -> Result<Option<(Secret, KeySource)>> {
+    let var = env_var(provider);
+    if let Ok(value) = env::var(&var)
+        && !value.trim().is_empty()
+    {
+        return Ok(Some((Secret::new(value.trim()), KeySource::Env(var))));
+    }
+    let file_var = format!("{var}_FILE");
+    if let Some(path) = env::var_os(&file_var) {
+        // The path may be shown; the contents never are.
+        let value = io::read_to_string(&path)
+            .with_context(|| format!("cannot read {file_var} ({})", path.to_string_lossy()))?;
+        return Ok(Some((Secret::new(value.trim()), KeySource::File(file_var))));
+    }
+    keychain_key(provider)
+}
+
+#[cfg(feature = "keychain")]
+fn keychain_key(provider: &str) -> Result<Option<(Secret, KeySource)>> {
+    let entry =
+        keyring::Entry::new(KEYCHAIN_SERVICE, provider).context("cannot open the OS keychain")?;
+    match entry.get_password() {
+        Ok(value) => Ok(Some((Secret::new(value), KeySource::Keychain))),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(anyhow::anyhow!(e)).context("cannot read the OS keychain"),
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+fn keychain_key(_provider: &str) -> Result<Option<(Secret, KeySource)>> {
+    Ok(None)
+}
+
+/// Providers with a key set through the environment, with the key's source;
+/// keychain entries cannot be listed.
+pub fn configured() -> Vec<(String, KeySource)> {
+    let mut found: Vec<(String, KeySource)> = env::vars_os()
+        .filter_map(|(name, _)| {
+            let name = name.into_string().ok()?;
+            let rest = name.strip_prefix("WEATHER_HELPER_")?;
+            let (provider, source) = match rest.strip_suffix("_API_KEY_FILE") {
+                Some(p) => (p, KeySource::File(name.clone())),
+                None => (rest.strip_suffix("_API_KEY")?, KeySource::Env(name.clone())),
+            };
+            Some((provider.to_ascii_lowercase().replace('_', "-"), source))
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found.dedup_by(|a, b| a.0 == b.0);
+    found
+}
+
+/// Replace every occurrence of the given secrets in `text` with `***`.
+pub fn redact(text: &str, secrets: &[&Secret]) -> String {
+    secrets
+        .iter()
+        .filter(|s| !s.0.is_empty())
+        .fold(text.to_string(), |acc, s| acc.replace(&s.0, "***"))
+}
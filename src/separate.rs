@@ -0,0 +1,45 @@
+//! Weather in its own note.
+//!
+//! With `separate-note: true` the block goes into `Weather - Rome Aug 2025.md`
+//! next to the trip note, and the trip note's block holds only an
+//! `![[Weather - Rome Aug 2025]]` embed, so Obsidian still shows the
+//! forecast inline while the trip note's own text stays short.
+
+use crate::{NoteMeta, markers};
+use std::path::{Path, PathBuf};
+
+/// Characters Obsidian does not allow in note names.
+const FORBIDDEN: &[char] = &[
+    '\\', '/', ':', '*', '?', '"', '<', '>', '|', '#', '^', '[', ']',
+];
+
+/// `Weather - <city> <Mon YYYY>`, after the trip's arrival month.
+pub fn note_name(meta: &NoteMeta) -> String {
+    let place = if meta.city.is_empty() {
+        Path::new(&meta.path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    } else {
+        meta.city.clone()
+    };
+    let start = meta.arrival.min(meta.departure);
+    let name = format!("Weather - {place} {}", start.format("%b %Y"));
+    name.chars().filter(|c| !FORBIDDEN.contains(c)).collect()
+}
+
+/// The weather note's path, in the trip note's folder.
+pub fn note_path(meta: &NoteMeta) -> PathBuf {
+    Path::new(&meta.path).with_file_name(format!("{}.md", note_name(meta)))
+}
+
+/// The trip note's block: just the embed.
+pub fn embed_block(meta: &NoteMeta) -> String {
+    format!(
+        "## Weather Forecast\n{}\n![[{}]]\n{}\n",
+        markers::BEGIN,
+        note_name(meta),
+        markers::END
+    )
+}
@@ -0,0 +1,171 @@
+//! The day table as static-site shortcodes or HTML.
+//!
+//! Travel blogs built from the vault with Hugo or Zola render a Markdown
+//! table as a plain grid. With `table-style: hugo` (or `zola`) each day is
+//! written as a shortcode call instead, so the site's own `weather` and
+//! `weather-day` templates decide how it looks; `table-style: html` writes
+//! an HTML partial with classes and `data-` attributes for a stylesheet.
+//! Every day carries highs and lows in both units, its rain, wind and UV
+//! figures when `weather-vars` asked for them (`rain-mm="2.5"`) and its
+//! annotation; the visible text of the HTML partial uses `units`. Other
+//! `weather-vars` columns are only written to Markdown tables.
+//!
+//! The header, range and summary lines above the table are unchanged.
+//! Trend arrows compare against the previous Markdown table, so other
+//! styles only get them with `embed-data`.
+
+use crate::columns::{Column, Measure, number};
+use crate::{DayNote, DayTemp, Units, fmt_temp};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// How the day table is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableStyle {
+    /// A Markdown table.
+    #[default]
+    Markdown,
+    /// `{{< weather >}}` with one `{{< weather-day … >}}` per day.
+    Hugo,
+    /// `{% weather() %}` with one `{{ weather_day(…) }}` per day.
+    Zola,
+    /// A `<div class="weather-widget">` partial.
+    Html,
+}
+
+impl TableStyle {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" | "table" | "" => Ok(TableStyle::Markdown),
+            "hugo" => Ok(TableStyle::Hugo),
+            "zola" => Ok(TableStyle::Zola),
+            "html" => Ok(TableStyle::Html),
+            other => Err(anyhow!(
+                "unknown table style '{other}' (expected markdown, hugo, zola or html)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`TableStyle::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            TableStyle::Markdown => "markdown",
+            TableStyle::Hugo => "hugo",
+            TableStyle::Zola => "zola",
+            TableStyle::Html => "html",
+        }
+    }
+}
+
+/// `text` safe inside a double-quoted attribute or shortcode argument.
+fn attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The day's measures as `{name}="{value}"` pairs, `name` being
+/// [`Measure::attribute`] passed through `key`.
+fn measures(d: &DayTemp, key: impl Fn(&str) -> String) -> Vec<(String, String)> {
+    Measure::ALL
+        .into_iter()
+        .filter_map(|m| Some((key(m.attribute()), number(m.of(d)?))))
+        .collect()
+}
+
+/// The day table in `style`. Markdown goes through
+/// [`crate::render_table_columns`], the only style with `columns`.
+pub fn render(
+    style: TableStyle,
+    data: &[DayTemp],
+    notes: &BTreeMap<NaiveDate, DayNote>,
+    units: Units,
+    today: Option<NaiveDate>,
+    columns: &[Column],
+) -> String {
+    let note = |d: &DayTemp| attribute(notes.get(&d.date).map_or("", |n| n.text.as_str()));
+    let mut s = String::new();
+    match style {
+        TableStyle::Markdown => return crate::render_table_columns(data, notes, today, columns),
+        TableStyle::Hugo => {
+            s.push_str("{{< weather >}}\n");
+            for d in data {
+                let extra: String = measures(d, str::to_string)
+                    .iter()
+                    .map(|(name, value)| format!(" {name}=\"{value}\""))
+                    .collect();
+                s.push_str(&format!(
+                    "{{{{< weather-day date=\"{}\" high-c=\"{}\" low-c=\"{}\" high-f=\"{}\" low-f=\"{}\"{extra} note=\"{}\" >}}}}\n",
+                    d.date,
+                    fmt_temp(d.tmax),
+                    fmt_temp(d.tmin),
+                    fmt_temp(d.tmax_f),
+                    fmt_temp(d.tmin_f),
+                    note(d)
+                ));
+            }
+            s.push_str("{{< /weather >}}");
+        }
+        TableStyle::Zola => {
+            s.push_str("{% weather() %}\n");
+            for d in data {
+                let extra: String = measures(d, |name| name.replace('-', "_"))
+                    .iter()
+                    .map(|(name, value)| format!(", {name}={value}"))
+                    .collect();
+                s.push_str(&format!(
+                    "{{{{ weather_day(date=\"{}\", high_c={}, low_c={}, high_f={}, low_f={}{extra}, note=\"{}\") }}}}\n",
+                    d.date,
+                    fmt_temp(d.tmax),
+                    fmt_temp(d.tmin),
+                    fmt_temp(d.tmax_f),
+                    fmt_temp(d.tmin_f),
+                    note(d)
+                ));
+            }
+            s.push_str("{% end %}");
+        }
+        TableStyle::Html => {
+            let shown = |d: &DayTemp| match units {
+                Units::Celsius => (d.tmax, d.tmin, "°C"),
+                Units::Fahrenheit => (d.tmax_f, d.tmin_f, "°F"),
+            };
+            s.push_str("<div class=\"weather-widget\">\n");
+            for d in data {
+                let (high, low, unit) = shown(d);
+                let observed = today.is_some_and(|t| d.date < t);
+                let data_attributes: String = measures(d, |name| format!("data-{name}"))
+                    .iter()
+                    .map(|(name, value)| format!(" {name}=\"{value}\""))
+                    .collect();
+                let spans: String = Measure::ALL
+                    .into_iter()
+                    .filter_map(|m| {
+                        let value = m.format(m.of(d)?);
+                        Some(format!(" <span class=\"{}\">{value}</span>", m.short()))
+                    })
+                    .collect();
+                s.push_str(&format!(
+                    "<div class=\"weather-day{}\" data-date=\"{}\" data-high-c=\"{}\" data-low-c=\"{}\" data-high-f=\"{}\" data-low-f=\"{}\"{data_attributes}><span class=\"date\">{}</span> <span class=\"high\">{}{unit}</span> <span class=\"low\">{}{unit}</span>{spans}",
+                    if observed { " observed" } else { "" },
+                    d.date,
+                    fmt_temp(d.tmax),
+                    fmt_temp(d.tmin),
+                    fmt_temp(d.tmax_f),
+                    fmt_temp(d.tmin_f),
+                    d.date,
+                    fmt_temp(high),
+                    fmt_temp(low),
+                ));
+                if notes.contains_key(&d.date) {
+                    s.push_str(&format!(" <span class=\"note\">{}</span>", note(d)));
+                }
+                s.push_str("</div>\n");
+            }
+            s.push_str("</div>");
+        }
+    }
+    s
+}
@@ -0,0 +1,141 @@
+//! Structured data next to the note.
+//!
+//! With `sidecar: true` the daily figures behind a note's table are also
+//! written to `<note>.weather.json` beside it, and the block links to the
+//! file, so Dataview JS, chart plugins and scripts can read the numbers
+//! without parsing the Markdown table.
+//!
+//! `embed-data: true` puts the same days inside the block instead, as one
+//! compact `<!-- WEATHER:DATA {...} -->` comment that Markdown renderers
+//! hide, for scripts that only see the note. It has no fetch time (the
+//! block's stamp has that), so a re-run with the same numbers leaves it
+//! unchanged. Trend arrows read it in preference to the table.
+
+use crate::{DayTemp, PreparedNote};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const DATA_PREFIX: &str = "<!-- WEATHER:DATA ";
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Sidecar {
+    pub city: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+    /// The block's header line, e.g. `Forecast for Rome 2025-10-01 → 2025-10-05`.
+    pub label: String,
+    /// Units used in the note's summary; the days carry both.
+    pub units: &'static str,
+    /// When the data was fetched (RFC 3339).
+    pub generated: String,
+    pub days: Vec<SidecarDay>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SidecarDay {
+    pub date: chrono::NaiveDate,
+    pub tmax_c: f64,
+    pub tmin_c: f64,
+    pub tmax_f: f64,
+    pub tmin_f: f64,
+    /// `weather-vars` measures, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precipitation_mm: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precipitation_probability: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wind_speed_kmh: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uv_index: Option<f64>,
+    /// The day's annotation in the table (arrival time, trend, hazards).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// What `embed-data` writes into the block.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Embedded {
+    /// Units used in the note's summary; the days carry both.
+    pub units: String,
+    pub days: Vec<SidecarDay>,
+}
+
+/// `Trips/Rome.md` → `Trips/Rome.weather.json`.
+pub fn sidecar_path(md_path: &str) -> PathBuf {
+    let p = Path::new(md_path);
+    let stem = p.file_stem().unwrap_or_default().to_string_lossy();
+    p.with_file_name(format!("{stem}.weather.json"))
+}
+
+/// The block's link to the sidecar file, relative to the note.
+pub fn link_line(md_path: &str) -> String {
+    let path = sidecar_path(md_path);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    format!("**Data**: [{name}]({})", urlencoding::encode(&name))
+}
+
+fn days(note: &PreparedNote) -> Vec<SidecarDay> {
+    note.data
+        .iter()
+        .map(|d| SidecarDay {
+            date: d.date,
+            tmax_c: d.tmax,
+            tmin_c: d.tmin,
+            tmax_f: d.tmax_f,
+            tmin_f: d.tmin_f,
+            precipitation_mm: d.precipitation,
+            precipitation_probability: d.precipitation_probability,
+            wind_speed_kmh: d.wind_speed,
+            uv_index: d.uv_index,
+            note: note.notes.get(&d.date).map(|n| n.text.clone()),
+        })
+        .collect()
+}
+
+pub fn build(note: &PreparedNote, generated: String) -> Sidecar {
+    Sidecar {
+        city: note.meta.city.clone(),
+        latitude: note.place.latitude,
+        longitude: note.place.longitude,
+        timezone: note.place.timezone.clone(),
+        label: note.label.clone(),
+        units: note.opts.units.name(),
+        generated,
+        days: days(note),
+    }
+}
+
+/// The `<!-- WEATHER:DATA {...} -->` line for `note`'s block.
+pub fn comment(note: &PreparedNote) -> String {
+    let data = Embedded {
+        units: note.opts.units.name().to_string(),
+        days: days(note),
+    };
+    let json = serde_json::to_string(&data).unwrap_or_default();
+    // `-->` can only occur inside a JSON string, where `>` may be escaped.
+    format!("{DATA_PREFIX}{} -->", json.replace("-->", "--\\u003e"))
+}
+
+/// The days embedded in `block`, if it has a readable data comment.
+pub fn embedded(block: &str) -> Option<Embedded> {
+    let re = Regex::new(&format!(r"(?m)^{}(.*) -->$", regex::escape(DATA_PREFIX))).unwrap();
+    serde_json::from_str(&re.captures(block)?[1]).ok()
+}
+
+impl From<&SidecarDay> for DayTemp {
+    fn from(d: &SidecarDay) -> Self {
+        DayTemp {
+            date: d.date,
+            tmax: d.tmax_c,
+            tmin: d.tmin_c,
+            tmax_f: d.tmax_f,
+            tmin_f: d.tmin_f,
+            precipitation: d.precipitation_mm,
+            precipitation_probability: d.precipitation_probability,
+            wind_speed: d.wind_speed_kmh,
+            uv_index: d.uv_index,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! What a run on another day would fetch.
+//!
+//! `weather-helper simulate --on 2025-08-10` picks and orders the notes a
+//! run on that day would refresh and lists, per note, which spans would
+//! come from observations, the forecast or last year's archive (see
+//! [`range_planner`]). Nothing is fetched and no note is touched.
+
+use crate::range_planner::{self, ActualsSource, FetchKind, PlannedFetch};
+use crate::{NoteMeta, RunOptions, extract_meta, note_paths, select_notes};
+use chrono::NaiveDate;
+use std::path::Path;
+
+/// One note as a run on the simulated day would handle it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulatedNote {
+    /// Path relative to the scanned folder.
+    pub note: String,
+    /// The trip as it applies that day (recurring trips move ahead).
+    pub arrival: NaiveDate,
+    pub departure: NaiveDate,
+    pub fetches: Vec<PlannedFetch>,
+}
+
+/// Everything a run on `opts.today()` would fetch under `root`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Simulation {
+    pub on: NaiveDate,
+    /// Selected notes, most urgent first.
+    pub notes: Vec<SimulatedNote>,
+    /// Trips left out for having ended before the retention window.
+    pub long_past: usize,
+}
+
+/// Plan every selected note under `root` as of `opts.today()`.
+pub fn simulate(root: &str, opts: &RunOptions) -> Simulation {
+    let on = opts.today();
+    let mut metas: Vec<NoteMeta> = note_paths(root)
+        .iter()
+        .filter_map(|path| extract_meta(path).ok())
+        .collect();
+    let long_past = select_notes(&mut metas, opts, on);
+    let notes = metas
+        .iter()
+        .map(|meta| {
+            let meta = meta.occurrence_on(on);
+            let path = Path::new(&meta.path);
+            SimulatedNote {
+                note: path
+                    .strip_prefix(root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .into_owned(),
+                arrival: meta.arrival,
+                departure: meta.departure,
+                fetches: range_planner::plan_ranges(
+                    meta.arrival,
+                    meta.departure,
+                    on,
+                    range_planner::horizon(on),
+                ),
+            }
+        })
+        .collect();
+    Simulation {
+        on,
+        notes,
+        long_past,
+    }
+}
+
+/// `actuals 2025-08-08 → 2025-08-09 (forecast API, past_days=2)`.
+pub fn describe(fetch: &PlannedFetch, on: NaiveDate) -> String {
+    let span = format!("{} → {}", fetch.start, fetch.end);
+    match fetch.kind {
+        FetchKind::Actuals => match range_planner::actuals_source(fetch.start, on) {
+            ActualsSource::PastDays(n) => format!("actuals {span} (forecast API, past_days={n})"),
+            ActualsSource::Archive => format!("actuals {span} (ERA5 archive)"),
+        },
+        FetchKind::Forecast => format!("forecast {span}"),
+        FetchKind::HistoricProxy => format!("last year {span} (ERA5 archive, as a proxy)"),
+    }
+}
+
+/// The report `simulate` prints: one line per note, then the long-past
+/// count.
+pub fn report(sim: &Simulation) -> String {
+    let mut out = format!(
+        "On {}, a run would refresh {} note(s):\n",
+        sim.on,
+        sim.notes.len()
+    );
+    for note in &sim.notes {
+        let fetches: Vec<String> = note.fetches.iter().map(|f| describe(f, sim.on)).collect();
+        let fetches = match fetches.is_empty() {
+            true => "nothing to fetch".to_string(),
+            false => fetches.join("; "),
+        };
+        out.push_str(&format!(
+            "- {} ({} → {}): {fetches}\n",
+            note.note, note.arrival, note.departure
+        ));
+    }
+    if sim.long_past > 0 {
+        out.push_str(&format!(
+            "{} trip(s) would be skipped as long past.\n",
+            sim.long_past
+        ));
+    }
+    out
+}
@@ -0,0 +1,65 @@
+//! Synthetic vaults and a mock weather provider for benchmarking.
+//!
+//! `weather-helper bench --notes 10000` (built with `--features simulator`)
+//! fabricates a vault of trip notes, points every API base URL at an
+//! in-process mock, and times a full run, so regressions in scanning,
+//! fetching and writing can be measured without touching real services.
+
+use crate::RunOptions;
+use crate::io;
+use crate::telemetry::Verbosity;
+use anyhow::{Context, Result};
+use chrono::{Days, NaiveDate};
+use std::path::Path;
+
+/// Cities used for generated notes.
+const CITIES: &[&str] = &[
+    "Rome", "Milan", "Venice", "Florence", "Naples", "Turin", "Bologna", "Genoa", "Palermo",
+    "Bari", "Verona", "Trieste",
+];
+
+/// Small deterministic generator so vaults are reproducible for a seed.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % bound
+    }
+}
+
+/// Write `count` trip notes into `dir`, with arrivals spread over the 400
+/// days after `start`. Notes are marked `overlap-ok` so the run output stays
+/// about processing rather than overlap warnings.
+pub fn generate_vault(dir: &Path, count: usize, start: NaiveDate, seed: u64) -> Result<()> {
+    io::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    let mut rng = Lcg(seed);
+    for i in 0..count {
+        let city = CITIES[rng.next(CITIES.len() as u64) as usize];
+        let arrival = start + Days::new(rng.next(400));
+        let departure = arrival + Days::new(1 + rng.next(10));
+        let note = format!(
+            "---\ncity: {city}\narrival: {arrival}\ndeparture: {departure}\noverlap-ok: true\n---\n\n# Trip {i} to {city}\n\n- passport\n"
+        );
+        let path = dir.join(format!("trip-{i:05}.md"));
+        io::write(&path, note).with_context(|| format!("cannot write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Options for a benchmark run. Generated vaults are meant to be large, so
+/// the `max-notes` guard is waived.
+pub fn bench_options(verbosity: Verbosity) -> RunOptions {
+    RunOptions {
+        verbosity,
+        yes: true,
+        ..RunOptions::default()
+    }
+}
+
+/// The mock provider runs use: [`crate::test_util::MockServer`] with its
+/// built-in responses.
+pub use crate::test_util::MockServer as MockProvider;
@@ -0,0 +1,164 @@
+//! How old a note's weather is.
+//!
+//! Every written block carries a `<!-- WEATHER:FETCHED <time> -->` stamp.
+//! When a later update fails but the note still holds data from an earlier
+//! run, the old table is kept and a banner says how old it is
+//! ("⏳ data from 3 days ago"); the next successful run replaces both.
+//!
+//! The same stamp decides whether a note is due at all: with `refresh:
+//! daily` (or `hourly`, `weekly`) a run leaves a note alone until its data
+//! is that old, and `refresh: manual` only updates it in runs given the note
+//! itself. Notes with no stamped data yet are always due, except manual ones.
+
+use crate::{markers, outline};
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+
+const STAMP_PREFIX: &str = "<!-- WEATHER:FETCHED ";
+const BANNER_PREFIX: &str = "⏳ _data from ";
+
+/// `block` with a fetch stamp after its begin marker.
+pub fn stamp(block: &str, fetched: DateTime<FixedOffset>) -> String {
+    let marker = Regex::new(&format!("{}\n", markers::BEGIN_PATTERN)).unwrap();
+    let line = format!("{STAMP_PREFIX}{} -->\n", fetched.to_rfc3339());
+    match marker.find(block) {
+        Some(m) => format!("{}{line}{}", &block[..m.end()], &block[m.end()..]),
+        None => block.to_string(),
+    }
+}
+
+/// When the note's block was last fetched, if it is stamped. Stamps in
+/// code blocks are examples, not the note's.
+pub fn fetched_at(content: &str) -> Option<DateTime<FixedOffset>> {
+    let re = Regex::new(r"<!-- WEATHER:FETCHED (\S+) -->").unwrap();
+    let protected = outline::protected_ranges(content);
+    let caps = re
+        .captures_iter(content)
+        .find(|c| !outline::is_protected(&protected, c.get(0).unwrap().start()))?;
+    DateTime::parse_from_rfc3339(&caps[1]).ok()
+}
+
+/// `text` without fetch stamps, for telling real changes from a re-fetch
+/// of the same numbers.
+pub fn without_stamp(text: &str) -> String {
+    text.split_inclusive('\n')
+        .filter(|line| !line.starts_with(STAMP_PREFIX))
+        .collect()
+}
+
+/// "2 hours ago", "yesterday", "3 days ago".
+pub fn age(fetched: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> String {
+    let elapsed = now - fetched;
+    let days = (now.date_naive() - fetched.with_timezone(&now.timezone()).date_naive()).num_days();
+    match days {
+        d if d >= 2 => format!("{d} days ago"),
+        1 => "yesterday".to_string(),
+        _ if elapsed.num_hours() >= 2 => format!("{} hours ago", elapsed.num_hours()),
+        _ if elapsed.num_hours() == 1 => "1 hour ago".to_string(),
+        _ => "less than an hour ago".to_string(),
+    }
+}
+
+/// The banner line for data fetched at `fetched`.
+pub fn banner(fetched: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> String {
+    format!(
+        "{BANNER_PREFIX}{} (last updated {}); the latest update failed_",
+        age(fetched, now),
+        fetched.date_naive()
+    )
+}
+
+/// Add or refresh the age banner under the stamp of a note's block, and
+/// bring the kept block up to the current format. `None` when the note has
+/// no stamped data to keep, or its block is from a newer version.
+pub fn mark_stale(content: &str, now: DateTime<FixedOffset>) -> Option<String> {
+    let fetched = fetched_at(content)?;
+    let content = &markers::migrate(content).ok()?;
+    let protected = outline::protected_ranges(content);
+    let mut out = String::with_capacity(content.len() + 80);
+    let mut at = 0;
+    for line in content.split_inclusive('\n') {
+        let prose = !outline::is_protected(&protected, at);
+        at += line.len();
+        if prose && line.starts_with(BANNER_PREFIX) {
+            continue;
+        }
+        out.push_str(line);
+        if prose && line.starts_with(STAMP_PREFIX) {
+            out.push_str(&banner(fetched, now));
+            out.push_str("  \n");
+        }
+    }
+    Some(out)
+}
+
+/// `refresh` setting: how often a note's weather is fetched again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Refresh {
+    /// Every run.
+    #[default]
+    Always,
+    /// Once the data is an hour old.
+    Hourly,
+    /// Once the data is from an earlier day.
+    Daily,
+    /// Once the data is a week old.
+    Weekly,
+    /// Only in runs given the note itself.
+    Manual,
+}
+
+impl Refresh {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "always" | "" => Ok(Refresh::Always),
+            "hourly" => Ok(Refresh::Hourly),
+            "daily" => Ok(Refresh::Daily),
+            "weekly" => Ok(Refresh::Weekly),
+            "manual" | "never" => Ok(Refresh::Manual),
+            other => Err(anyhow!(
+                "unknown refresh '{other}' (expected always, hourly, daily, weekly or manual)"
+            )),
+        }
+    }
+
+    /// Canonical name, as accepted by [`Refresh::parse`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Refresh::Always => "always",
+            Refresh::Hourly => "hourly",
+            Refresh::Daily => "daily",
+            Refresh::Weekly => "weekly",
+            Refresh::Manual => "manual",
+        }
+    }
+
+    /// Why a note whose block was fetched at `fetched` is left alone at
+    /// `now`, or `None` if it is due.
+    pub fn not_due(
+        self,
+        fetched: Option<DateTime<FixedOffset>>,
+        now: DateTime<FixedOffset>,
+    ) -> Option<String> {
+        if self == Refresh::Manual {
+            return Some("refresh: manual (run on the note itself to update it)".to_string());
+        }
+        let fetched = fetched?;
+        let fresh = match self {
+            Refresh::Always | Refresh::Manual => false,
+            Refresh::Hourly => now - fetched < chrono::Duration::hours(1),
+            Refresh::Daily => {
+                fetched.with_timezone(&now.timezone()).date_naive() >= now.date_naive()
+            }
+            Refresh::Weekly => now - fetched < chrono::Duration::days(7),
+        };
+        fresh.then(|| {
+            format!(
+                "not due (refresh: {}, last updated {})",
+                self.name(),
+                age(fetched, now)
+            )
+        })
+    }
+}
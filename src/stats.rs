@@ -0,0 +1,253 @@
+//! Yearly travel-weather statistics.
+//!
+//! `weather-helper stats` scans the vault for trips that took place in a
+//! year, fetches what the weather actually was from the ERA5 archive, and
+//! writes a report note: days traveled, the hottest day and coldest night
+//! experienced, and the rainiest trip.
+
+use crate::variables::{self, Endpoint};
+use crate::width::isolate;
+use crate::{
+    NoteMeta, RunOptions, Units, celcius_to_farenheit, extract_meta, fmt_temp, http, output,
+    resolve_location,
+};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+/// Observed weather for one day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatDay {
+    pub date: NaiveDate,
+    /// Daily high, °C.
+    pub tmax: f64,
+    /// Daily low, °C.
+    pub tmin: f64,
+    /// Precipitation, mm.
+    pub precip: Option<f64>,
+}
+
+/// A past trip with its observed weather.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TripActuals {
+    pub path: String,
+    pub place: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: Vec<StatDay>,
+}
+
+impl TripActuals {
+    /// Total precipitation, or `None` when the archive reported none.
+    pub fn rain(&self) -> Option<f64> {
+        self.days
+            .iter()
+            .filter_map(|d| d.precip)
+            .reduce(|a, b| a + b)
+    }
+}
+
+/// The part of a trip inside `year` that has already happened by `today`.
+pub fn clip_to_year(
+    meta: &NoteMeta,
+    year: i32,
+    today: NaiveDate,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let first = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    let last = NaiveDate::from_ymd_opt(year, 12, 31)?;
+    let start = meta.arrival.min(meta.departure).max(first);
+    let end = meta
+        .arrival
+        .max(meta.departure)
+        .min(last)
+        .min(today - Duration::days(1));
+    (start <= end).then_some((start, end))
+}
+
+/// Trips in `year` under `root`, with archive actuals. Notes that fail are
+/// reported and left out.
+pub async fn collect(
+    root: &str,
+    year: i32,
+    today: NaiveDate,
+    opts: &RunOptions,
+) -> Result<Vec<TripActuals>> {
+    let client = http::Client::new(opts.timeout)?.with_polite(opts.polite);
+    let printer = output::Printer::new(opts.verbosity, opts.color).with_links(opts.links);
+    let mut trips = Vec::new();
+    for path in crate::note_paths(root) {
+        let Ok(meta) = extract_meta(&path) else {
+            continue;
+        };
+        if !crate::tags::selected(&meta.tags, &opts.tags) {
+            continue;
+        }
+        let Some((start, end)) = clip_to_year(&meta, year, today) else {
+            continue;
+        };
+        let language = opts.language.as_deref();
+        let fetched = async {
+            let place = resolve_location(&client, &meta, opts.geocoder, language).await?;
+            let days = fetch_days(
+                &client,
+                place.latitude,
+                place.longitude,
+                start,
+                end,
+                &place.timezone,
+            )
+            .await?;
+            let name = if meta.city.is_empty() {
+                place.name
+            } else {
+                meta.city.clone()
+            };
+            anyhow::Ok((name, days))
+        };
+        match fetched.await {
+            Ok((place, days)) => trips.push(TripActuals {
+                path: meta.path.clone(),
+                place,
+                start,
+                end,
+                days,
+            }),
+            Err(e) => printer.status(output::Status::Failed, &meta.path, Some(&format!("{e:#}"))),
+        }
+    }
+    Ok(trips)
+}
+
+#[derive(Deserialize, Debug)]
+struct StatsResp {
+    daily: Option<StatsDaily>,
+}
+#[derive(Deserialize, Debug)]
+struct StatsDaily {
+    time: Vec<NaiveDate>,
+    temperature_2m_max: Vec<Option<f64>>,
+    temperature_2m_min: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_sum: Vec<Option<f64>>,
+}
+
+/// Highs, lows and precipitation from the ERA5 archive. Days the archive
+/// does not have yet (about the last five) are left out.
+pub async fn fetch_days(
+    client: &http::Client,
+    lat: f64,
+    lon: f64,
+    start: NaiveDate,
+    end: NaiveDate,
+    tz: &str,
+) -> Result<Vec<StatDay>> {
+    let url = format!(
+        "{}/era5?latitude={lat}&longitude={lon}&daily={}&start_date={start}&end_date={end}&timezone={}",
+        crate::archive_base(),
+        variables::daily_param(
+            &[
+                "temperature_2m_max",
+                "temperature_2m_min",
+                "precipitation_sum"
+            ],
+            Endpoint::Archive
+        ),
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let resp: StatsResp = client.get_json(&url).await?;
+    let Some(daily) = resp.daily else {
+        return Ok(Vec::new());
+    };
+    Ok(daily
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(i, date)| {
+            Some(StatDay {
+                date: *date,
+                tmax: (*daily.temperature_2m_max.get(i)?)?,
+                tmin: (*daily.temperature_2m_min.get(i)?)?,
+                precip: daily.precipitation_sum.get(i).copied().flatten(),
+            })
+        })
+        .collect())
+}
+
+/// The report note for `year`. With `links`, each trip in the table links to
+/// its note through an `obsidian://` URI.
+pub fn report(year: i32, trips: &[TripActuals], units: Units, links: bool) -> String {
+    let temp = |c: f64| match units {
+        Units::Celsius => format!("{}°C", fmt_temp(c)),
+        Units::Fahrenheit => format!("{}°F", fmt_temp(celcius_to_farenheit(c))),
+    };
+    let mut s = format!("# Travel weather {year}\n\n");
+    if trips.is_empty() {
+        s.push_str("_No past trips found for this year._\n");
+        return s;
+    }
+    let traveled: BTreeSet<NaiveDate> = trips
+        .iter()
+        .flat_map(|t| t.start.iter_days().take_while(move |d| *d <= t.end))
+        .collect();
+    s.push_str(&format!("- Trips: {}\n", trips.len()));
+    s.push_str(&format!("- Days traveled: {}\n", traveled.len()));
+    let days = || {
+        trips
+            .iter()
+            .flat_map(|t| t.days.iter().map(move |d| (t, d)))
+    };
+    if let Some((t, d)) = days().max_by(|a, b| a.1.tmax.total_cmp(&b.1.tmax)) {
+        s.push_str(&format!(
+            "- Hottest day: {} on {} in {}\n",
+            temp(d.tmax),
+            d.date,
+            isolate(&t.place)
+        ));
+    }
+    if let Some((t, d)) = days().min_by(|a, b| a.1.tmin.total_cmp(&b.1.tmin)) {
+        s.push_str(&format!(
+            "- Coldest night: {} on {} in {}\n",
+            temp(d.tmin),
+            d.date,
+            isolate(&t.place)
+        ));
+    }
+    match trips
+        .iter()
+        .filter_map(|t| Some((t, t.rain()?)))
+        .filter(|(_, mm)| *mm > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+    {
+        Some((t, mm)) => s.push_str(&format!(
+            "- Rainiest trip: {} ({} → {}), {mm:.0} mm\n",
+            isolate(&t.place),
+            t.start,
+            t.end
+        )),
+        None => s.push_str("- Rainiest trip: no rain recorded\n"),
+    }
+    s.push_str("\n| Trip | Dates | Days | High | Low | Rain |\n|---|---|---:|---:|---:|---:|\n");
+    for t in trips {
+        let place = if links {
+            format!("[{}]({})", isolate(&t.place), output::obsidian_uri(&t.path))
+        } else {
+            isolate(&t.place)
+        };
+        let high = t.days.iter().map(|d| d.tmax).reduce(f64::max);
+        let low = t.days.iter().map(|d| d.tmin).reduce(f64::min);
+        s.push_str(&format!(
+            "| {} | {} → {} | {} | {} | {} | {} |\n",
+            place,
+            t.start,
+            t.end,
+            (t.end - t.start).num_days() + 1,
+            high.map(temp).unwrap_or_else(|| "–".into()),
+            low.map(temp).unwrap_or_else(|| "–".into()),
+            t.rain()
+                .map(|mm| format!("{mm:.0} mm"))
+                .unwrap_or_else(|| "–".into()),
+        ));
+    }
+    s
+}
@@ -0,0 +1,82 @@
+//! Note tags, for processing or summarizing groups of trips separately.
+//!
+//! Tags come from the note's `tags:` (or `tag:`) frontmatter, as a list or
+//! a comma/space separated string, with or without Obsidian's leading `#`.
+//! Matching ignores case, and a tag filter also matches nested tags:
+//! `--tag work` selects `work/conference`.
+
+use serde_yaml::Value as YamlValue;
+use std::collections::BTreeMap;
+
+/// Tags from a note's frontmatter, `#` removed.
+pub fn parse_tags(yaml: &YamlValue) -> Vec<String> {
+    let Some(value) = ["tags", "tag"].iter().find_map(|k| yaml.get(*k)) else {
+        return Vec::new();
+    };
+    let raw: Vec<String> = match value {
+        YamlValue::Sequence(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        YamlValue::String(s) => s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    };
+    raw.iter()
+        .map(|t| t.trim().trim_start_matches('#').to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Whether `tag` is `filter` or nested under it.
+pub fn tag_matches(tag: &str, filter: &str) -> bool {
+    let filter = filter.trim().trim_start_matches('#');
+    let (tag, filter) = (tag.to_lowercase(), filter.to_lowercase());
+    tag == filter || tag.starts_with(&format!("{filter}/"))
+}
+
+/// Whether a note with `tags` passes the filter; an empty filter passes all.
+pub fn selected(tags: &[String], filter: &[String]) -> bool {
+    filter.is_empty()
+        || filter
+            .iter()
+            .any(|f| tags.iter().any(|t| tag_matches(t, f)))
+}
+
+/// Run results per tag, e.g. `work-travel: 2 updated, 1 failed`. A note
+/// counts once under each of its tags; untagged notes are listed last.
+pub fn report(outcomes: &[(Vec<String>, bool)]) -> String {
+    fn count((updated, failed): &mut (usize, usize), ok: bool) {
+        match ok {
+            true => *updated += 1,
+            false => *failed += 1,
+        }
+    }
+    let mut groups: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut untagged = (0, 0);
+    for (tags, ok) in outcomes {
+        let mut seen: Vec<String> = tags.iter().map(|t| t.to_lowercase()).collect();
+        seen.sort();
+        seen.dedup();
+        if seen.is_empty() {
+            count(&mut untagged, *ok);
+        }
+        for tag in seen {
+            count(groups.entry(tag).or_default(), *ok);
+        }
+    }
+    let line = |name: &str, (updated, failed): (usize, usize)| match failed {
+        0 => format!("  {name}: {updated} updated\n"),
+        _ => format!("  {name}: {updated} updated, {failed} failed\n"),
+    };
+    let mut out = String::from("By tag:\n");
+    for (tag, counts) in groups {
+        out.push_str(&line(&tag, counts));
+    }
+    if untagged != (0, 0) {
+        out.push_str(&line("(untagged)", untagged));
+    }
+    out
+}
@@ -0,0 +1,118 @@
+//! Tracing setup.
+//!
+//! Spans are emitted per run, per note and per API call. Log output follows
+//! the `-q`/`-v`/`-vv` verbosity tier (errors only by default) unless
+//! `RUST_LOG` is set, which always wins. With the `otel` feature
+//! enabled and `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are also exported over
+//! OTLP/HTTP so slow geocodes and retry storms show up in an existing tracing
+//! stack.
+
+use anyhow::Result;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, fmt};
+
+/// Output tier selected with `-q`, `-v` or `-vv`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// `-q`: errors only.
+    Quiet,
+    /// One line per updated note plus warnings.
+    #[default]
+    Normal,
+    /// `-v`: also per-request URLs (keys redacted), decisions and timing.
+    Verbose,
+    /// `-vv`: everything the crate traces.
+    Trace,
+}
+
+impl Verbosity {
+    /// Combine a `-q` flag with the number of `-v` flags.
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+
+    /// Whether progress lines (updated notes, warnings) should be printed.
+    pub fn shows_progress(self) -> bool {
+        self >= Verbosity::Normal
+    }
+
+    /// Tracing filter used when `RUST_LOG` is not set.
+    pub fn default_filter(self) -> &'static str {
+        match self {
+            Verbosity::Quiet | Verbosity::Normal => "error",
+            Verbosity::Verbose => "warn,weather_helper=debug",
+            Verbosity::Trace => "warn,weather_helper=trace",
+        }
+    }
+}
+
+/// Keeps the exporter alive; flushes pending spans when dropped.
+pub struct TelemetryGuard {
+    #[cfg(feature = "otel")]
+    provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        #[cfg(feature = "otel")]
+        if let Some(provider) = self.provider.take() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Install the global tracing subscriber. Call once from `main`.
+pub fn init(verbosity: Verbosity) -> Result<TelemetryGuard> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(verbosity.default_filter()));
+    let fmt_layer = fmt::layer().with_writer(std::io::stderr);
+
+    #[cfg(feature = "otel")]
+    {
+        let provider = otlp_provider()?;
+        let otel_layer = provider.as_ref().map(|p| {
+            use opentelemetry::trace::TracerProvider as _;
+            tracing_opentelemetry::layer().with_tracer(p.tracer("weather-helper"))
+        });
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt_layer)
+            .with(otel_layer)
+            .try_init()?;
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otel"))]
+    {
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt_layer)
+            .try_init()?;
+        Ok(TelemetryGuard {})
+    }
+}
+
+/// Build an OTLP tracer provider when an endpoint is configured.
+#[cfg(feature = "otel")]
+fn otlp_provider() -> Result<Option<opentelemetry_sdk::trace::SdkTracerProvider>> {
+    if std::env::var_os("OTEL_EXPORTER_OTLP_ENDPOINT").is_none() {
+        return Ok(None);
+    }
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()?;
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_service_name("weather-helper")
+        .build();
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource)
+        .build();
+    Ok(Some(provider))
+}
@@ -0,0 +1,79 @@
+//! Trip note templates.
+//!
+//! `weather-helper new --template <name>` reads `<name>.md` from the templates
+//! directory, substitutes `{{placeholder}}` variables, and writes a new note
+//! ready for the next run. Templates usually pre-place the weather markers.
+
+use crate::io;
+use anyhow::{Context, Result, anyhow};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Where templates are looked up: `WEATHER_HELPER_TEMPLATES`, then
+/// `$XDG_CONFIG_HOME/weather-helper/templates`, then
+/// `~/.config/weather-helper/templates`.
+pub fn default_templates_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("WEATHER_HELPER_TEMPLATES") {
+        return PathBuf::from(dir);
+    }
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|h| Path::new(&h).join(".config")))
+        .unwrap_or_else(|| PathBuf::from("."));
+    config.join("weather-helper").join("templates")
+}
+
+/// Names of the `*.md` templates in `dir`, sorted.
+pub fn list_templates(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = io::read_dir(dir)
+        .with_context(|| format!("cannot read templates directory {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Replace every `{{name}}` in `template` with its value from `vars`.
+///
+/// Unknown placeholders are an error so typos do not end up in notes.
+pub fn render_template(template: &str, vars: &BTreeMap<String, String>) -> Result<String> {
+    let re = Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)\s*\}\}").unwrap();
+    let mut missing: Vec<String> = re
+        .captures_iter(template)
+        .map(|c| c[1].to_string())
+        .filter(|name| !vars.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        missing.sort();
+        missing.dedup();
+        return Err(anyhow!(
+            "template uses undefined placeholders: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(re
+        .replace_all(template, |c: &regex::Captures| vars[&c[1]].clone())
+        .to_string())
+}
+
+/// Render template `name` from `dir` into `out`, refusing to overwrite.
+pub fn new_note(dir: &Path, name: &str, vars: &BTreeMap<String, String>, out: &Path) -> Result<()> {
+    let path = dir.join(format!("{name}.md"));
+    let template = io::read_to_string(&path).with_context(|| {
+        let known = list_templates(dir).unwrap_or_default().join(", ");
+        format!(
+            "template '{name}' not found in {} (available: {known})",
+            dir.display()
+        )
+    })?;
+    let note = render_template(&template, vars)?;
+    if out.exists() {
+        return Err(anyhow!("{} already exists", out.display()));
+    }
+    io::write_new(out, note).with_context(|| format!("failed to write {}", out.display()))
+}
@@ -0,0 +1,147 @@
+//! Terminal rendering for `weather-helper show`.
+//!
+//! The same data as the note's block, laid out for a terminal instead of
+//! Markdown: plain aligned columns sized by display width, emphasis markup
+//! dropped, and the header in bold when color is enabled.
+
+use crate::width::{display_width, pad};
+use crate::{PreparedNote, block_summary_in, fmt_temp};
+
+/// A plain table: columns padded to their widest cell, numbers (`right`)
+/// aligned right, and a rule under the header.
+pub fn table(header: &[&str], rows: &[Vec<String>], right: &[bool]) -> String {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|r| r.get(i))
+                .map(|c| display_width(c))
+                .chain([display_width(header[i])])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: Vec<String>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| pad(c, widths[i], !right.get(i).copied().unwrap_or(false)))
+            .collect();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+    let mut out = line(header.iter().map(|h| h.to_string()).collect());
+    out.push_str(&line(widths.iter().map(|w| "─".repeat(*w)).collect()));
+    for row in rows {
+        out.push_str(&line(row.clone()));
+    }
+    out
+}
+
+/// Markdown emphasis removed: `**bold**` and `_italic_` lines.
+pub fn plain(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.replace("**", "");
+            let trimmed = line.trim_end();
+            match trimmed.strip_prefix('_').and_then(|l| l.strip_suffix('_')) {
+                Some(inner) => inner.to_string(),
+                None => trimmed.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A Markdown section with its tables re-laid out as plain columns.
+pub fn section(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let flush = |rows: &mut Vec<Vec<String>>, out: &mut String| {
+        if rows.is_empty() {
+            return;
+        }
+        let header: Vec<String> = rows.remove(0);
+        let header: Vec<&str> = header.iter().map(String::as_str).collect();
+        let right: Vec<bool> = vec![false; header.len()];
+        out.push_str(&table(&header, rows, &right));
+        rows.clear();
+    };
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if let Some(inner) = trimmed.strip_prefix('|') {
+            let inner = inner.strip_suffix('|').unwrap_or(inner);
+            let cells: Vec<String> = inner.split('|').map(|c| plain(c.trim())).collect();
+            let rule = cells
+                .iter()
+                .all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':')));
+            if !rule {
+                rows.push(cells);
+            }
+            continue;
+        }
+        flush(&mut rows, &mut out);
+        out.push_str(&plain(line));
+        out.push('\n');
+    }
+    flush(&mut rows, &mut out);
+    out
+}
+
+/// The whole preview for a prepared note.
+pub fn render(note: &PreparedNote, color: bool) -> String {
+    let bold = |s: &str| {
+        if color {
+            format!("\x1b[1m{s}\x1b[0m")
+        } else {
+            s.to_string()
+        }
+    };
+    let opts = &note.opts;
+    let summary = block_summary_in(&note.data, &note.notes, opts.units, &opts.summary_lang);
+    let mut out = format!("{}\n", bold(&note.label));
+    out.push_str(&format!("Range: {} / {}\n", summary.max, summary.min));
+    out.push_str(&plain(&summary.note));
+    out.push('\n');
+    for line in &note.extra {
+        out.push_str(&plain(line));
+        out.push('\n');
+    }
+    out.push('\n');
+    let with_notes = note.data.iter().any(|d| note.notes.contains_key(&d.date));
+    let mut header = vec!["Date", "High °F", "Low °F", "High °C", "Low °C"];
+    if with_notes {
+        header.push("Notes");
+    }
+    let mut rows = Vec::new();
+    for (i, d) in note.data.iter().enumerate() {
+        if i > 0
+            && note
+                .divider
+                .is_some_and(|t| d.date >= t && note.data[i - 1].date < t)
+        {
+            rows.push(vec!["— today —".to_string()]);
+        }
+        let mut row = vec![
+            d.date.to_string(),
+            fmt_temp(d.tmax_f),
+            fmt_temp(d.tmin_f),
+            fmt_temp(d.tmax),
+            fmt_temp(d.tmin),
+        ];
+        if with_notes {
+            row.push(
+                note.notes
+                    .get(&d.date)
+                    .map(|n| n.text.clone())
+                    .unwrap_or_default(),
+            );
+        }
+        rows.push(row);
+    }
+    let right = [false, true, true, true, true, false];
+    out.push_str(&table(&header, &rows, &right));
+    for s in note.sections.iter().filter(|s| !s.trim().is_empty()) {
+        out.push('\n');
+        out.push_str(&section(s));
+    }
+    out
+}
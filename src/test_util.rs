@@ -0,0 +1,258 @@
+//! End-to-end test support (only with `--features test-util`).
+//!
+//! [`MockServer`] is an embedded HTTP server standing in for the Open‑Meteo
+//! geocoding, forecast and archive endpoints. Out of the box it answers
+//! every city search with a place near Rome and every date range with a
+//! smooth seasonal temperature curve; [`MockServer::on`] and
+//! [`MockServer::on_fn`] replace the answer for one endpoint with a canned
+//! or computed response, e.g. to make the archive fail. Every request is
+//! recorded for assertions.
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use weather_helper::test_util::{MockResponse, MockServer};
+//! let mock = MockServer::start().await?;
+//! mock.on("/era5", MockResponse::error(400, "end_date out of range"));
+//! // SAFETY: no other thread reads the environment yet.
+//! unsafe { mock.install_env() };
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use reqwest::StatusCode;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Environment variables holding the base URLs [`MockServer`] serves.
+pub const BASE_VARS: &[&str] = &[
+    "OPEN_METEO_FORECAST_BASE",
+    "OPEN_METEO_ARCHIVE_BASE",
+    "OPEN_METEO_GEOCODE_BASE",
+];
+
+/// A response the mock sends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    /// How long to wait before answering.
+    pub delay: Option<Duration>,
+}
+
+impl MockResponse {
+    /// `200 OK` with a JSON body.
+    pub fn json(body: impl Into<String>) -> Self {
+        MockResponse::status(200, body)
+    }
+
+    /// Any status with a JSON body.
+    pub fn status(status: u16, body: impl Into<String>) -> Self {
+        MockResponse {
+            status,
+            body: body.into(),
+            delay: None,
+        }
+    }
+
+    /// An Open‑Meteo style `{"error": true, "reason": ...}` failure.
+    pub fn error(status: u16, reason: &str) -> Self {
+        let reason = serde_json::Value::String(reason.to_string());
+        MockResponse::status(status, format!(r#"{{"error":true,"reason":{reason}}}"#))
+    }
+
+    /// The same response, sent only after `delay`.
+    pub fn delayed(self, delay: Duration) -> Self {
+        MockResponse {
+            delay: Some(delay),
+            ..self
+        }
+    }
+}
+
+/// A request the mock received.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MockRequest {
+    /// Path without the query, e.g. `/v1/forecast`.
+    pub path: String,
+    /// Raw query string, without the `?`.
+    pub query: String,
+}
+
+impl MockRequest {
+    fn parse(target: &str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        MockRequest {
+            path: path.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    /// A decoded query parameter.
+    pub fn param(&self, name: &str) -> Option<String> {
+        self.query
+            .split('&')
+            .find_map(|kv| kv.strip_prefix(name)?.strip_prefix('='))
+            .map(|v| {
+                urlencoding::decode(v)
+                    .map(|s| s.into_owned())
+                    .unwrap_or_default()
+            })
+    }
+
+    /// A query parameter parsed as a date.
+    pub fn date(&self, name: &str) -> Option<NaiveDate> {
+        self.param(name)?.parse().ok()
+    }
+}
+
+type Handler = Arc<dyn Fn(&MockRequest) -> MockResponse + Send + Sync>;
+
+/// Handlers by path suffix; later registrations win.
+#[derive(Default)]
+struct Routes(Vec<(String, Handler)>);
+
+impl Routes {
+    fn respond(&self, request: &MockRequest) -> MockResponse {
+        self.0
+            .iter()
+            .rev()
+            .find(|(suffix, _)| request.path.ends_with(suffix.as_str()))
+            .map_or_else(|| canned(request), |(_, handler)| handler(request))
+    }
+}
+
+/// A running mock of the geocoding, forecast and archive endpoints.
+pub struct MockServer {
+    pub addr: SocketAddr,
+    routes: Arc<Mutex<Routes>>,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Bind to an ephemeral local port and serve requests in the background.
+    pub async fn start() -> Result<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .context("failed to bind mock server")?;
+        let addr = listener.local_addr()?;
+        let routes = Arc::new(Mutex::new(Routes::default()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let (served, seen) = (routes.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let (routes, seen) = (served.clone(), seen.clone());
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    let n = stream.read(&mut buf).await.unwrap_or(0);
+                    let head = String::from_utf8_lossy(&buf[..n]);
+                    let target = head.split_whitespace().nth(1).unwrap_or("/");
+                    let request = MockRequest::parse(target);
+                    let response = routes.lock().unwrap().respond(&request);
+                    seen.lock().unwrap().push(request);
+                    if let Some(delay) = response.delay {
+                        tokio::time::sleep(delay).await;
+                    }
+                    let reason = StatusCode::from_u16(response.status)
+                        .ok()
+                        .and_then(|s| s.canonical_reason())
+                        .unwrap_or("");
+                    let out = format!(
+                        "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.status,
+                        response.body.len(),
+                        response.body
+                    );
+                    let _ = stream.write_all(out.as_bytes()).await;
+                });
+            }
+        });
+        Ok(MockServer {
+            addr,
+            routes,
+            requests,
+        })
+    }
+
+    /// Base URL to use for every provider.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer requests whose path ends with `suffix` (`/search`,
+    /// `/forecast`, `/era5`) with `response`.
+    pub fn on(&self, suffix: &str, response: MockResponse) -> &Self {
+        self.on_fn(suffix, move |_| response.clone())
+    }
+
+    /// Answer requests whose path ends with `suffix` with whatever
+    /// `handler` returns for them.
+    pub fn on_fn(
+        &self,
+        suffix: &str,
+        handler: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static,
+    ) -> &Self {
+        let mut routes = self.routes.lock().unwrap();
+        routes.0.push((suffix.to_string(), Arc::new(handler)));
+        self
+    }
+
+    /// Go back to the built-in responses.
+    pub fn reset(&self) {
+        self.routes.lock().unwrap().0.clear();
+    }
+
+    /// Every request received so far, in order.
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Point [`BASE_VARS`] at this server.
+    ///
+    /// # Safety
+    ///
+    /// Sets process environment variables; no other thread may read or
+    /// write the environment at the same time (see [`std::env::set_var`]).
+    pub unsafe fn install_env(&self) {
+        for var in BASE_VARS {
+            // SAFETY: guaranteed by the caller.
+            unsafe { std::env::set_var(var, self.base_url()) };
+        }
+    }
+}
+
+/// The built-in response for `request`.
+pub fn canned(request: &MockRequest) -> MockResponse {
+    if request.path.ends_with("/search") {
+        let name = serde_json::Value::String(request.param("name").unwrap_or_default());
+        return MockResponse::json(format!(
+            r#"{{"results":[{{"name":{name},"latitude":42.0,"longitude":12.5,"timezone":"Europe/Rome","country_code":"IT"}}]}}"#
+        ));
+    }
+    if (request.path.ends_with("/forecast") || request.path.ends_with("/era5"))
+        && let (Some(start), Some(end)) = (request.date("start_date"), request.date("end_date"))
+    {
+        return MockResponse::json(daily_body(start, end));
+    }
+    MockResponse::error(404, "unknown mock endpoint")
+}
+
+/// A `daily` payload from `start` to `end` with a smooth seasonal
+/// temperature curve.
+pub fn daily_body(start: NaiveDate, end: NaiveDate) -> String {
+    let (mut time, mut tmax, mut tmin) = (String::new(), String::new(), String::new());
+    for (i, day) in start.iter_days().take_while(|d| *d <= end).enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let season = (f64::from(day.ordinal()) / 365.0 * std::f64::consts::TAU).cos();
+        let _ = write!(time, r#"{sep}"{day}""#);
+        let _ = write!(tmax, "{sep}{:.1}", 22.0 - 10.0 * season);
+        let _ = write!(tmin, "{sep}{:.1}", 12.0 - 8.0 * season);
+    }
+    format!(
+        r#"{{"daily":{{"time":[{time}],"temperature_2m_max":[{tmax}],"temperature_2m_min":[{tmin}]}}}}"#
+    )
+}
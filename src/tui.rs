@@ -0,0 +1,299 @@
+//! Interactive dashboard (`weather-helper tui`, only with `--features tui`).
+//!
+//! Lists the vault's trips with their dates and fetch status. Selected notes
+//! are refreshed through the same pipeline as a normal run, but nothing is
+//! written until the change has been looked at as a diff and applied.
+
+use anyhow::Result;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::width::pad;
+use crate::{NoteMeta, PreparedNote, RunOptions, extract_meta, http, note_paths, prepare_note};
+
+/// Where a trip is in the refresh → review → apply cycle.
+#[derive(Debug)]
+pub enum Status {
+    Idle,
+    Fetching,
+    /// Fetched; the note would change as shown by the diff.
+    Ready(Box<PreparedNote>, Vec<DiffLine>),
+    /// Fetched; the note is already up to date.
+    Unchanged,
+    Applied,
+    /// `weather-pin: true`; never refreshed.
+    Pinned,
+    Failed(String),
+}
+
+impl Status {
+    fn label(&self) -> (&'static str, Color) {
+        match self {
+            Status::Idle => ("", Color::Reset),
+            Status::Fetching => ("fetching…", Color::Yellow),
+            Status::Ready(..) => ("changed", Color::Cyan),
+            Status::Unchanged => ("up to date", Color::DarkGray),
+            Status::Applied => ("applied", Color::Green),
+            Status::Pinned => ("pinned", Color::DarkGray),
+            Status::Failed(_) => ("failed", Color::Red),
+        }
+    }
+}
+
+/// One line of a note diff.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff of `old` → `new`: the changed middle after trimming common
+/// leading and trailing lines, with `context` unchanged lines around it.
+pub fn line_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old.len() && prefix == new.len() {
+        return Vec::new();
+    }
+    let same = |l: &&str| DiffLine::Same(l.to_string());
+    let mut out: Vec<DiffLine> = old[prefix.saturating_sub(context)..prefix]
+        .iter()
+        .map(same)
+        .collect();
+    out.extend(
+        old[prefix..old.len() - suffix]
+            .iter()
+            .map(|l| DiffLine::Removed(l.to_string())),
+    );
+    out.extend(
+        new[prefix..new.len() - suffix]
+            .iter()
+            .map(|l| DiffLine::Added(l.to_string())),
+    );
+    let tail = old.len() - suffix;
+    out.extend(old[tail..(tail + context).min(old.len())].iter().map(same));
+    out
+}
+
+pub struct Entry {
+    pub meta: NoteMeta,
+    pub selected: bool,
+    pub status: Status,
+}
+
+pub struct App {
+    pub entries: Vec<Entry>,
+    state: ListState,
+    /// Showing the diff of the entry under the cursor, scrolled this far.
+    diff: Option<u16>,
+    message: String,
+}
+
+impl App {
+    /// Every trip note under `root`; notes that are not trips are left out.
+    pub fn scan(root: &str) -> Self {
+        let entries: Vec<Entry> = note_paths(root)
+            .iter()
+            .filter_map(|p| extract_meta(p).ok())
+            .map(|meta| Entry {
+                meta,
+                selected: false,
+                status: Status::Idle,
+            })
+            .collect();
+        let message = format!("{} trip(s) in {root}", entries.len());
+        Self {
+            entries,
+            state: ListState::default().with_selected(Some(0)),
+            diff: None,
+            message,
+        }
+    }
+
+    fn cursor(&self) -> usize {
+        self.state.selected().unwrap_or(0)
+    }
+
+    /// Indices to act on: the selected entries, or the one under the cursor.
+    fn targets(&self) -> Vec<usize> {
+        let selected: Vec<usize> = (0..self.entries.len())
+            .filter(|&i| self.entries[i].selected)
+            .collect();
+        match selected.is_empty() && !self.entries.is_empty() {
+            true => vec![self.cursor()],
+            false => selected,
+        }
+    }
+
+    fn apply(&mut self) {
+        let (mut applied, mut failed) = (0, 0);
+        for i in self.targets() {
+            let entry = &mut self.entries[i];
+            if let Status::Ready(prepared, _) = &entry.status {
+                entry.status = match prepared.write() {
+                    Ok(()) => {
+                        applied += 1;
+                        Status::Applied
+                    }
+                    Err(e) => {
+                        failed += 1;
+                        Status::Failed(format!("{e:#}"))
+                    }
+                };
+                entry.selected = false;
+            }
+        }
+        self.message = format!("applied {applied}, failed {failed}");
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(frame.area());
+        let help = "↑↓ move · space select · r refresh · d diff · a apply · q quit";
+        frame.render_widget(
+            Paragraph::new(vec![Line::from(self.message.as_str()), Line::from(help)]),
+            footer,
+        );
+        if let Some(scroll) = self.diff {
+            let entry = &self.entries[self.cursor()];
+            let lines: Vec<Line> = match &entry.status {
+                Status::Ready(_, diff) => diff.iter().map(diff_line).collect(),
+                Status::Failed(e) => vec![Line::from(e.as_str())],
+                _ => vec![Line::from("Nothing to show; refresh the note first.")],
+            };
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .title(entry.meta.path.as_str());
+            frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), main);
+            return;
+        }
+        let items: Vec<ListItem> = self.entries.iter().map(entry_line).collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Trips"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, main, &mut self.state);
+    }
+}
+
+fn entry_line(entry: &Entry) -> ListItem<'static> {
+    let meta = &entry.meta;
+    let (status, color) = entry.status.label();
+    let mark = if entry.selected { "[x]" } else { "[ ]" };
+    let place = if meta.city.is_empty() {
+        meta.path.clone()
+    } else {
+        meta.city.clone()
+    };
+    ListItem::new(Line::from(vec![
+        Span::raw(format!(
+            "{mark} {} → {}  ",
+            meta.arrival.min(meta.departure),
+            meta.arrival.max(meta.departure)
+        )),
+        Span::raw(format!("{} ", pad(&place, 24, true))),
+        Span::styled(status, Style::default().fg(color)),
+    ]))
+}
+
+fn diff_line(line: &DiffLine) -> Line<'static> {
+    match line {
+        DiffLine::Same(l) => Line::from(format!("  {l}")),
+        DiffLine::Removed(l) => Line::styled(format!("- {l}"), Style::default().fg(Color::Red)),
+        DiffLine::Added(l) => Line::styled(format!("+ {l}"), Style::default().fg(Color::Green)),
+    }
+}
+
+/// Run the dashboard until the user quits.
+pub async fn run(root: &str, opts: &RunOptions) -> Result<()> {
+    opts.install();
+    let client = opts.client()?;
+    let mut app = App::scan(root);
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, &client, opts).await;
+    ratatui::restore();
+    Ok(result?)
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    client: &http::Client,
+    opts: &RunOptions,
+) -> std::io::Result<()> {
+    loop {
+        terminal.draw(|f| app.draw(f))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if let Some(scroll) = app.diff {
+            app.diff = match key.code {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('d') => None,
+                KeyCode::Down | KeyCode::Char('j') => Some(scroll.saturating_add(1)),
+                KeyCode::Up | KeyCode::Char('k') => Some(scroll.saturating_sub(1)),
+                KeyCode::PageDown => Some(scroll.saturating_add(20)),
+                KeyCode::PageUp => Some(scroll.saturating_sub(20)),
+                _ => Some(scroll),
+            };
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Down | KeyCode::Char('j') => app.state.select_next(),
+            KeyCode::Up | KeyCode::Char('k') => app.state.select_previous(),
+            KeyCode::Char(' ') => {
+                let i = app.cursor();
+                if let Some(entry) = app.entries.get_mut(i) {
+                    entry.selected = !entry.selected;
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Enter if !app.entries.is_empty() => app.diff = Some(0),
+            KeyCode::Char('a') => app.apply(),
+            KeyCode::Char('r') => {
+                let targets = app.targets();
+                for &i in &targets {
+                    app.entries[i].status = Status::Fetching;
+                }
+                for (n, &i) in targets.iter().enumerate() {
+                    app.message = format!("fetching {} of {}…", n + 1, targets.len());
+                    terminal.draw(|f| app.draw(f))?;
+                    app.entries[i].status = refresh(&app.entries[i].meta, client, opts).await;
+                }
+                app.message = format!("refreshed {} note(s)", targets.len());
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn refresh(meta: &NoteMeta, client: &http::Client, opts: &RunOptions) -> Status {
+    if meta.pinned {
+        return Status::Pinned;
+    }
+    let prepared = match prepare_note(meta, client, opts).await {
+        Ok(prepared) => prepared,
+        Err(e) => return Status::Failed(format!("{e:#}")),
+    };
+    match prepared.updated_content() {
+        Ok(updated) if updated == prepared.content => Status::Unchanged,
+        Ok(updated) => {
+            let diff = line_diff(&prepared.content, &updated, 2);
+            Status::Ready(Box::new(prepared), diff)
+        }
+        Err(e) => Status::Failed(format!("{e:#}")),
+    }
+}
@@ -0,0 +1,179 @@
+//! Timezone helpers.
+//!
+//! The block header notes how far the destination's clock is from home
+//! ("+6h ahead of home"), computed per day so a DST change during the trip
+//! is reported rather than averaged away.
+//!
+//! Notes are also checked for two things that make the times in them
+//! misleading: a `timezone:` that disagrees with the zone found for the
+//! place, and clocks changing at the destination during the trip. Both are
+//! reported at the end of the run; with `dst-note` the block also says when
+//! the clocks change.
+
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use std::sync::Mutex;
+
+/// Parse an IANA zone name such as `Europe/Rome`.
+pub fn parse_zone(name: &str) -> Result<Tz> {
+    name.trim()
+        .parse()
+        .map_err(|_| anyhow!("unknown timezone '{name}' (expected an IANA name like Europe/Rome)"))
+}
+
+/// UTC offset in seconds at local noon on `date`, in `zone` or, for `None`,
+/// the system's local zone.
+pub fn offset_on(zone: Option<Tz>, date: NaiveDate) -> Option<i32> {
+    let noon = date.and_time(NaiveTime::from_hms_opt(12, 0, 0)?);
+    match zone {
+        Some(zone) => zone
+            .from_local_datetime(&noon)
+            .earliest()
+            .map(|dt| dt.offset().fix().local_minus_utc()),
+        None => Local
+            .from_local_datetime(&noon)
+            .earliest()
+            .map(|dt| dt.offset().local_minus_utc()),
+    }
+}
+
+/// `+6h`, `-3h`, `+5h30m`.
+pub fn fmt_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.unsigned_abs() / 60;
+    match minutes % 60 {
+        0 => format!("{sign}{}h", minutes / 60),
+        m => format!("{sign}{}h{m:02}m", minutes / 60),
+    }
+}
+
+/// How the destination's clock relates to home during `start..=end`, e.g.
+/// `+6h ahead of home` or `3h behind home, -2h from 2025-11-02`. `None`
+/// when the clocks agree throughout or an offset cannot be determined.
+pub fn home_difference(
+    home: Option<Tz>,
+    destination: Tz,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Option<String> {
+    let diff = |date| Some(offset_on(Some(destination), date)? - offset_on(home, date)?);
+    let first = diff(start)?;
+    let describe = |d: i32| match d {
+        d if d > 0 => format!("{} ahead of home", fmt_offset(d)),
+        d => format!("{} behind home", fmt_offset(-d).trim_start_matches('+')),
+    };
+    let mut text = if first == 0 {
+        String::new()
+    } else {
+        describe(first)
+    };
+    let mut last = first;
+    for date in start.iter_days().skip(1).take_while(|d| *d <= end) {
+        let d = diff(date)?;
+        if d != last {
+            let change = if d == 0 {
+                format!("same as home from {date}")
+            } else {
+                format!("{} from {date}", fmt_offset(d))
+            };
+            if text.is_empty() {
+                text = format!("same as home, {change}");
+            } else {
+                text = format!("{text}, {change}");
+            }
+            last = d;
+        }
+    }
+    (!text.is_empty()).then_some(text)
+}
+
+/// Days in `start..=end` when the clocks at `zone` change, with the change
+/// in seconds (positive when they go forward).
+pub fn clock_changes(zone: Tz, start: NaiveDate, end: NaiveDate) -> Vec<(NaiveDate, i32)> {
+    let mut changes = Vec::new();
+    let Some(mut last) = offset_on(Some(zone), start) else {
+        return changes;
+    };
+    for date in start.iter_days().skip(1).take_while(|d| *d <= end) {
+        let Some(offset) = offset_on(Some(zone), date) else {
+            continue;
+        };
+        if offset != last {
+            changes.push((date, offset - last));
+            last = offset;
+        }
+    }
+    changes
+}
+
+/// `Mar 30 (forward 1h)` or `Mar 30 (forward 1h) and Oct 26 (back 1h)`;
+/// `None` for no changes.
+pub fn describe_changes(changes: &[(NaiveDate, i32)]) -> Option<String> {
+    let parts: Vec<String> = changes
+        .iter()
+        .map(|(date, change)| {
+            let direction = if *change > 0 { "forward" } else { "back" };
+            let amount = fmt_offset(change.abs());
+            format!(
+                "{} ({direction} {})",
+                date.format("%b %-d"),
+                amount.trim_start_matches('+')
+            )
+        })
+        .collect();
+    match parts.as_slice() {
+        [] => None,
+        [one] => Some(one.clone()),
+        [rest @ .., last] => Some(format!("{} and {last}", rest.join(", "))),
+    }
+}
+
+/// The block line for `dst-note`: "🕑 _Clocks change Mar 30 (forward 1h)
+/// during your trip_".
+pub fn clock_change_line(changes: &[(NaiveDate, i32)]) -> Option<String> {
+    describe_changes(changes).map(|dates| format!("🕑 _Clocks change {dates} during your trip_"))
+}
+
+/// Why a note's `timezone:` disagrees with the zone found for its place, or
+/// `None` if the two keep the same time throughout the trip (aliases such
+/// as `Europe/Vatican` for `Europe/Rome` agree).
+pub fn mismatch(given: &str, found: &str, start: NaiveDate, end: NaiveDate) -> Option<String> {
+    let given_zone = match parse_zone(given) {
+        Ok(zone) => zone,
+        Err(_) => {
+            return Some(format!(
+                "timezone '{given}' is not a known zone; using {found}"
+            ));
+        }
+    };
+    let found_zone = parse_zone(found).ok()?;
+    let diff = |date| Some(offset_on(Some(given_zone), date)? - offset_on(Some(found_zone), date)?);
+    let first = start
+        .iter_days()
+        .take_while(|d| *d <= end)
+        .find_map(|date| diff(date).filter(|d| *d != 0))?;
+    Some(format!(
+        "timezone {given} differs from {found} found for the place ({} during the trip); using {found}",
+        fmt_offset(first)
+    ))
+}
+
+/// Timezone warnings by note, for the end-of-run report.
+static WARNINGS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Remember `warning` about `note` for the end-of-run report.
+pub fn record(note: &str, warning: String) {
+    WARNINGS.lock().unwrap().push((note.to_string(), warning));
+}
+
+/// Every timezone warning so far in this run, in the order they were
+/// recorded.
+pub fn snapshot() -> Vec<(String, String)> {
+    WARNINGS.lock().unwrap().clone()
+}
+
+/// Forget earlier runs' warnings, for a process that runs more than once.
+pub fn clear() {
+    WARNINGS.lock().unwrap().clear();
+}
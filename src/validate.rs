@@ -0,0 +1,72 @@
+//! Checking recorded API payloads.
+//!
+//! `weather-helper validate-fixture <file>` runs a saved Open‑Meteo response
+//! through the same decoding, [`crate::parse_daily`] and rendering as a real
+//! run and names the exact field or array index that breaks, so a bug report
+//! can carry a payload that reproduces the problem.
+
+use crate::{ForecastResp, Units, http, parse_daily, render_block, render_table_with_notes};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Validate `json` and describe what a run would make of it, e.g.
+/// `ok: 5 days, 2025-10-01 → 2025-10-05` followed by any warnings. Errors
+/// name the failing path, e.g. `daily.temperature_2m_max[3]: invalid type`.
+pub fn validate_fixture(json: &[u8]) -> Result<String> {
+    // API error payloads and non-JSON bodies get the same message a run
+    // would print.
+    http::decode_json::<serde_json::Value>(json)?;
+    let de = &mut serde_json::Deserializer::from_slice(json);
+    let resp: ForecastResp = serde_path_to_error::deserialize(de).map_err(|err| {
+        let path = err.path().to_string();
+        anyhow!("{path}: {}", err.inner())
+    })?;
+    let mut warnings = Vec::new();
+    if let Some(daily) = &resp.daily {
+        for (i, time) in daily.time.iter().enumerate() {
+            if NaiveDate::parse_from_str(time, "%Y-%m-%d").is_err() {
+                return Err(anyhow!(
+                    "daily.time[{i}]: '{time}' is not a YYYY-MM-DD date"
+                ));
+            }
+        }
+        for (name, values) in [
+            ("temperature_2m_max", &daily.temperature_2m_max),
+            ("temperature_2m_min", &daily.temperature_2m_min),
+        ] {
+            if values.len() != daily.time.len() {
+                return Err(anyhow!(
+                    "daily.{name}: {} values for {} days in daily.time",
+                    values.len(),
+                    daily.time.len()
+                ));
+            }
+            for (i, _) in values.iter().enumerate().filter(|(_, v)| v.is_none()) {
+                warnings.push(format!(
+                    "daily.{name}[{i}] is null; {} is skipped",
+                    daily.time[i]
+                ));
+            }
+        }
+    }
+    let data = parse_daily(resp)?;
+    for units in [Units::Celsius, Units::Fahrenheit] {
+        render_block("Fixture", &data, &BTreeMap::new(), units, None, &[]);
+    }
+    render_table_with_notes(&data, &BTreeMap::new());
+    let mut report = match (data.first(), data.last()) {
+        (Some(first), Some(last)) => format!(
+            "ok: {} day{}, {} → {}\n",
+            data.len(),
+            if data.len() == 1 { "" } else { "s" },
+            first.date,
+            last.date
+        ),
+        _ => "ok: no days (the block would be empty)\n".to_string(),
+    };
+    for warning in warnings {
+        report.push_str(&format!("warning: {warning}\n"));
+    }
+    Ok(report)
+}
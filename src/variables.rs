@@ -0,0 +1,120 @@
+//! Daily variable names per Open‑Meteo endpoint.
+//!
+//! The forecast and ERA5 archive APIs do not offer the same daily
+//! variables, and older names (`weathercode`, `windspeed_10m_max`) are only
+//! accepted as aliases. Code asks for the variables it wants; [`translate`]
+//! turns that into what each endpoint understands and lists the ones it
+//! cannot provide. Those are left out of the request and reported, and
+//! [`restore_names`] fills them with nulls in the response, so a missing
+//! variable reads as "no data" instead of failing the whole request.
+
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Forecast,
+    Archive,
+}
+
+impl Endpoint {
+    pub fn name(self) -> &'static str {
+        match self {
+            Endpoint::Forecast => "forecast",
+            Endpoint::Archive => "archive",
+        }
+    }
+}
+
+/// Older spellings and the current name both endpoints use.
+const ALIASES: &[(&str, &str)] = &[
+    ("weathercode", "weather_code"),
+    ("windspeed_10m_max", "wind_speed_10m_max"),
+    ("windgusts_10m_max", "wind_gusts_10m_max"),
+    ("winddirection_10m_dominant", "wind_direction_10m_dominant"),
+];
+
+/// Daily variables the forecast API has and the ERA5 archive does not.
+const FORECAST_ONLY: &[&str] = &[
+    "uv_index_max",
+    "uv_index_clear_sky_max",
+    "precipitation_probability_max",
+    "precipitation_probability_mean",
+    "precipitation_probability_min",
+    "relative_humidity_2m_mean",
+    "relative_humidity_2m_max",
+    "relative_humidity_2m_min",
+    "showers_sum",
+];
+
+/// A requested variable set as one endpoint understands it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Translation {
+    /// `(endpoint name, requested name)` for each variable sent.
+    pub names: Vec<(String, String)>,
+    /// Requested variables the endpoint does not provide.
+    pub unsupported: Vec<String>,
+}
+
+impl Translation {
+    /// The `daily=` query value.
+    pub fn query(&self) -> String {
+        self.names
+            .iter()
+            .map(|(sent, _)| sent.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// What to send `endpoint` for `requested`. Unknown names are passed
+/// through unchanged.
+pub fn translate(requested: &[&str], endpoint: Endpoint) -> Translation {
+    let mut t = Translation::default();
+    for &name in requested {
+        let current = ALIASES
+            .iter()
+            .find(|(old, _)| *old == name)
+            .map_or(name, |(_, new)| new);
+        if endpoint == Endpoint::Archive && FORECAST_ONLY.contains(&current) {
+            t.unsupported.push(name.to_string());
+        } else {
+            t.names.push((current.to_string(), name.to_string()));
+        }
+    }
+    t
+}
+
+/// The `daily=` value for `requested`, logging what `endpoint` cannot provide.
+pub fn daily_param(requested: &[&str], endpoint: Endpoint) -> String {
+    let t = translate(requested, endpoint);
+    if !t.unsupported.is_empty() {
+        tracing::warn!(
+            source = endpoint.name(),
+            variables = %t.unsupported.join(","),
+            "daily variables not available from this source; left empty"
+        );
+    }
+    t.query()
+}
+
+/// Rename a response's `daily` arrays back to the requested names and add
+/// all-null arrays for unsupported variables.
+pub fn restore_names(json: &mut Value, t: &Translation) {
+    let Some(daily) = json.get_mut("daily").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for (sent, requested) in &t.names {
+        if sent != requested
+            && let Some(values) = daily.remove(sent)
+        {
+            daily.insert(requested.clone(), values);
+        }
+    }
+    let days = daily
+        .get("time")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len);
+    for name in &t.unsupported {
+        daily.insert(name.clone(), Value::Array(vec![Value::Null; days]));
+    }
+}
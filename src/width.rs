@@ -0,0 +1,145 @@
+//! Display width and bidirectional text.
+//!
+//! Place names arrive in any script. Padding by `str::len` misaligns CJK
+//! (two columns per character) and anything with combining marks, and a
+//! right-to-left name such as "תל אביב" can drag neighbouring dates and table
+//! pipes into its run when shown. These helpers measure text by terminal
+//! columns and isolate RTL names so the surrounding layout stays put.
+
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `s` occupies in a monospace font.
+pub fn display_width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// `s` padded with spaces to `width` columns, on the right (`left_align`)
+/// or on the left.
+pub fn pad(s: &str, width: usize, left_align: bool) -> String {
+    let fill = " ".repeat(width.saturating_sub(display_width(s)));
+    if left_align {
+        format!("{s}{fill}")
+    } else {
+        format!("{fill}{s}")
+    }
+}
+
+/// Whether `c` is written right to left (Hebrew, Arabic, Syriac, Thaana,
+/// N'Ko and their presentation forms).
+pub fn is_rtl(c: char) -> bool {
+    matches!(c,
+        '\u{0590}'..='\u{08FF}'
+        | '\u{FB1D}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFF}'
+        | '\u{10800}'..='\u{10FFF}'
+        | '\u{1E800}'..='\u{1EFFF}')
+}
+
+/// Invisible bidi controls (marks, embeddings, overrides and isolates) that
+/// should not reach a geocoder.
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{200E}' | '\u{200F}' | '\u{061C}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Combining marks that decorate the previous letter: Latin diacritics,
+/// Hebrew points and cantillation, Arabic harakat.
+pub fn is_combining_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0300}'..='\u{036F}'
+        | '\u{0591}'..='\u{05BD}'
+        | '\u{05BF}'
+        | '\u{05C1}' | '\u{05C2}'
+        | '\u{05C4}' | '\u{05C5}'
+        | '\u{05C7}'
+        | '\u{0610}'..='\u{061A}'
+        | '\u{064B}'..='\u{065F}'
+        | '\u{0670}'
+        | '\u{06D6}'..='\u{06ED}')
+}
+
+/// Wrap `s` in a first-strong isolate (U+2068 … U+2069) when it contains
+/// right-to-left text, so it reorders only within itself.
+pub fn isolate(s: &str) -> String {
+    if s.chars().any(is_rtl) {
+        format!("\u{2068}{s}\u{2069}")
+    } else {
+        s.to_string()
+    }
+}
+
+/// Pad every Markdown table in `text` so its columns line up by display
+/// width in the raw file. Alignment rows keep their colons; other lines are
+/// left alone.
+pub fn align_tables(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut table: Vec<&str> = Vec::new();
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with('|') {
+            table.push(line);
+            continue;
+        }
+        out.push_str(&align_table(&table));
+        table.clear();
+        out.push_str(line);
+    }
+    out.push_str(&align_table(&table));
+    out
+}
+
+fn align_table(lines: &[&str]) -> String {
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| {
+            let line = line.trim_end_matches('\n').trim();
+            let inner = line.strip_prefix('|').unwrap_or(line);
+            let inner = inner.strip_suffix('|').unwrap_or(inner);
+            inner.split('|').map(|c| c.trim().to_string()).collect()
+        })
+        .collect();
+    let is_rule = |row: &Vec<String>| {
+        row.iter()
+            .all(|c| !c.is_empty() && c.chars().all(|ch| matches!(ch, '-' | ':')))
+    };
+    let Some(rule) = rows.iter().find(|r| is_rule(r)) else {
+        return lines.concat();
+    };
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    // (leading colon, trailing colon) per column.
+    let colons: Vec<(bool, bool)> = (0..columns)
+        .map(|i| {
+            rule.get(i)
+                .map_or((false, false), |c| (c.starts_with(':'), c.ends_with(':')))
+        })
+        .collect();
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| {
+            rows.iter()
+                .filter(|r| !is_rule(r))
+                .filter_map(|r| r.get(i))
+                .map(|c| display_width(c))
+                .max()
+                .unwrap_or(0)
+                .max(3)
+        })
+        .collect();
+    let mut out = String::new();
+    for row in &rows {
+        out.push('|');
+        for (i, width) in widths.iter().enumerate() {
+            let cell = row.get(i).map(String::as_str).unwrap_or("");
+            let (left, right) = colons[i];
+            if is_rule(row) {
+                let dashes = "-".repeat(width - usize::from(left) - usize::from(right));
+                let (left, right) = (if left { ":" } else { "" }, if right { ":" } else { "" });
+                out.push_str(&format!(" {left}{dashes}{right} |"));
+            } else {
+                out.push_str(&format!(" {} |", pad(cell, *width, !right || left)));
+            }
+        }
+        out.push('\n');
+    }
+    if !lines.last().is_some_and(|l| l.ends_with('\n')) {
+        out.pop();
+    }
+    out
+}
@@ -0,0 +1,230 @@
+//! Finding the best days in a flexible date range.
+//!
+//! A note that has not booked dates yet can set `flexible: true` with a
+//! `window-start`/`window-end` range and the trip's length as
+//! `estimated-days`. The whole window is fetched and shown as usual, and the
+//! block recommends the `estimated-days` run of consecutive days that scores
+//! best under `window-score`:
+//!
+//! - `dry` adds each day's precipitation in millimetres (capped at 10, so
+//!   one downpour does not outweigh everything else);
+//! - `mild` adds how far each day's high is from a comfortable 22°C
+//!   (`mild=25` or `mild=77F` picks another target).
+//!
+//! The lowest total wins; ties go to the earliest start.
+
+use crate::variables::{self, Endpoint};
+use crate::{DayTemp, Units, celcius_to_farenheit, fmt_temp, http};
+use anyhow::{Result, anyhow};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Rain beyond this many millimetres a day counts no more.
+const RAIN_CAP_MM: f64 = 10.0;
+
+/// What makes a day good. Temperatures are °C.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Score {
+    pub dry: bool,
+    /// Ideal daily high, if highs count.
+    pub mild: Option<f64>,
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score {
+            dry: true,
+            mild: Some(22.0),
+        }
+    }
+}
+
+impl Score {
+    /// Parse `dry, mild` or `mild=77F`; terms left out do not count.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut score = Score {
+            dry: false,
+            mild: None,
+        };
+        for term in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let (key, value) = match term.split_once('=') {
+                Some((key, value)) => (key.trim(), Some(value.trim())),
+                None => (term, None),
+            };
+            match (key, value) {
+                ("dry", None) => score.dry = true,
+                ("mild", None) => score.mild = Score::default().mild,
+                ("mild", Some(value)) => {
+                    score.mild = Some(
+                        crate::packing::parse_value(value, true)
+                            .ok_or_else(|| anyhow!("bad temperature '{value}' for mild"))?,
+                    )
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "unknown window score '{term}' (expected dry, mild or mild=<temperature>)"
+                    ));
+                }
+            }
+        }
+        if !score.dry && score.mild.is_none() {
+            return Err(anyhow!("window-score needs dry, mild or both"));
+        }
+        Ok(score)
+    }
+
+    /// Canonical form, as accepted by [`Score::parse`].
+    pub fn name(&self) -> String {
+        let mut terms = Vec::new();
+        if self.dry {
+            terms.push("dry".to_string());
+        }
+        if let Some(target) = self.mild {
+            terms.push(format!("mild={target}"));
+        }
+        terms.join(", ")
+    }
+
+    /// A day's penalty; lower is better.
+    fn penalty(&self, day: &DayTemp, rain_mm: Option<f64>) -> f64 {
+        let wet = match self.dry {
+            true => rain_mm.unwrap_or(0.0).clamp(0.0, RAIN_CAP_MM),
+            false => 0.0,
+        };
+        let off = self.mild.map_or(0.0, |target| (day.tmax - target).abs());
+        wet + off
+    }
+}
+
+/// The recommended days.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Window {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Total precipitation over the window, where known.
+    pub rain_mm: Option<f64>,
+    /// Lowest and highest daily high, °C.
+    pub highs: (f64, f64),
+}
+
+/// The best run of `days` consecutive days of `data` under `score`, or
+/// `None` if `data` has fewer days than that.
+pub fn best(
+    data: &[DayTemp],
+    rain: &BTreeMap<NaiveDate, f64>,
+    days: usize,
+    score: &Score,
+) -> Option<Window> {
+    if days == 0 || data.len() < days {
+        return None;
+    }
+    let penalties: Vec<f64> = data
+        .iter()
+        .map(|d| score.penalty(d, rain.get(&d.date).copied()))
+        .collect();
+    let start = (0..=data.len() - days)
+        .map(|i| (i, penalties[i..i + days].iter().sum::<f64>()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?
+        .0;
+    let chosen = &data[start..start + days];
+    let known: Vec<f64> = chosen
+        .iter()
+        .filter_map(|d| rain.get(&d.date).copied())
+        .collect();
+    let highs = chosen.iter().map(|d| d.tmax);
+    Some(Window {
+        start: chosen[0].date,
+        end: chosen[days - 1].date,
+        rain_mm: (!known.is_empty()).then(|| known.iter().sum()),
+        highs: (
+            highs.clone().fold(f64::INFINITY, f64::min),
+            highs.fold(f64::NEG_INFINITY, f64::max),
+        ),
+    })
+}
+
+/// The recommendation line under the summary. `shift` moves the dates of a
+/// historic proxy back to the trip's year.
+pub fn line(
+    window: Option<&Window>,
+    days: usize,
+    available: usize,
+    shift: chrono::Duration,
+    units: Units,
+) -> String {
+    let Some(w) = window else {
+        return format!(
+            "**Best window**: only {available} of the {days} days needed have weather data yet"
+        );
+    };
+    let show = |c: f64| match units {
+        Units::Celsius => fmt_temp(c),
+        Units::Fahrenheit => fmt_temp(celcius_to_farenheit(c)),
+    };
+    let unit = match units {
+        Units::Celsius => "°C",
+        Units::Fahrenheit => "°F",
+    };
+    let highs = match (show(w.highs.0), show(w.highs.1)) {
+        (lo, hi) if lo == hi => format!("highs {lo}{unit}"),
+        (lo, hi) => format!("highs {lo}–{hi}{unit}"),
+    };
+    let rain = w
+        .rain_mm
+        .map(|mm| format!("{mm:.1} mm rain, "))
+        .unwrap_or_default();
+    let proxy = match shift.is_zero() {
+        true => "",
+        false => ", going by last year",
+    };
+    format!(
+        "**Best window**: {} → {} ({rain}{highs}{proxy})",
+        w.start + shift,
+        w.end + shift
+    )
+}
+
+#[derive(Deserialize, Debug)]
+struct RainResp {
+    daily: Option<RainDaily>,
+}
+#[derive(Deserialize, Debug)]
+struct RainDaily {
+    time: Vec<NaiveDate>,
+    #[serde(default)]
+    precipitation_sum: Vec<Option<f64>>,
+}
+
+/// Daily precipitation for `start..=end` from the Open‑Meteo forecast, or
+/// from the archive when `forecast` is false.
+pub async fn fetch_rain(
+    client: &http::Client,
+    (lat, lon): (f64, f64),
+    (start, end): (NaiveDate, NaiveDate),
+    tz: &str,
+    forecast: bool,
+) -> Result<BTreeMap<NaiveDate, f64>> {
+    let (endpoint, source) = match forecast {
+        true => (
+            format!("{}/forecast", crate::forecast_base()),
+            Endpoint::Forecast,
+        ),
+        false => (format!("{}/era5", crate::archive_base()), Endpoint::Archive),
+    };
+    let url = format!(
+        "{endpoint}?latitude={lat}&longitude={lon}&daily={}&start_date={start}&end_date={end}&timezone={}",
+        variables::daily_param(&["precipitation_sum"], source),
+        urlencoding::encode(if tz.is_empty() { "Europe/Rome" } else { tz })
+    );
+    let resp: RainResp = client.get_json(&url).await?;
+    let Some(daily) = resp.daily else {
+        return Ok(BTreeMap::new());
+    };
+    Ok(daily
+        .time
+        .iter()
+        .zip(daily.precipitation_sum)
+        .filter_map(|(date, mm)| Some((*date, mm?)))
+        .collect())
+}
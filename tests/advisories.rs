@@ -0,0 +1,69 @@
+use std::collections::BTreeMap;
+use weather_helper::advisories::{Exposure, Thresholds, advisories, heat_index};
+use weather_helper::{DayTemp, Units};
+
+fn day(date: &str, tmax: f64) -> DayTemp {
+    DayTemp {
+        date: date.parse().unwrap(),
+        tmax,
+        tmin: tmax - 10.0,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: (tmax - 10.0) * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hot_humid_sunny_trip_gets_both_advisories() {
+    let data = [day("2025-08-20", 31.0), day("2025-08-21", 29.0)];
+    let mut exposure = BTreeMap::new();
+    exposure.insert(
+        data[0].date,
+        Exposure {
+            uv_index: Some(8.6),
+            humidity: Some(60.0),
+        },
+    );
+    let lines = advisories(&data, &exposure, &Thresholds::default(), Units::Celsius);
+    assert_eq!(lines.len(), 2, "{lines:?}");
+    assert!(lines[0].contains("UV index up to 9"));
+    assert!(
+        lines[1].contains("highs up to 31°C (feels like 35°C)"),
+        "{}",
+        lines[1]
+    );
+    assert!(lines[1].contains("carry 2 L of water"));
+}
+
+#[test]
+fn mild_trip_gets_none_and_thresholds_parse() {
+    let data = [day("2025-04-20", 21.0)];
+    let lines = advisories(
+        &data,
+        &BTreeMap::new(),
+        &Thresholds::default(),
+        Units::Fahrenheit,
+    );
+    assert!(lines.is_empty());
+
+    let t = Thresholds::parse("uv=3, hot=68F").unwrap();
+    assert_eq!(t.uv, 3.0);
+    assert!((t.hot - 20.0).abs() < 1e-9);
+    assert_eq!(t.heat_index, Thresholds::default().heat_index);
+    let lines = advisories(&data, &BTreeMap::new(), &t, Units::Fahrenheit);
+    assert_eq!(
+        lines,
+        ["**Advisory**: highs up to 70°F — carry 2 L of water per person per day"]
+    );
+    assert!(Thresholds::parse("wind=3").is_err());
+}
+
+#[test]
+fn heat_index_matches_nws_table() {
+    // NWS chart: 90°F at 60% RH feels like 100°F.
+    let hi = heat_index((90.0 - 32.0) / 1.8, 60.0) * 1.8 + 32.0;
+    assert!((hi - 100.0).abs() < 1.0, "{hi}");
+    // Below 80°F the simple formula stays close to the air temperature.
+    let mild = heat_index(20.0, 50.0);
+    assert!((mild - 20.0).abs() < 1.0, "{mild}");
+}
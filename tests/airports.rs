@@ -0,0 +1,29 @@
+//! Bundled airport dataset tests
+
+use weather_helper::airports::{looks_like_code, lookup};
+
+#[test]
+fn resolves_iata_and_icao() {
+    let fco = lookup("FCO").unwrap();
+    assert_eq!(fco.icao, "LIRF");
+    assert_eq!(fco.timezone, "Europe/Rome");
+    assert_eq!(lookup("lirf").unwrap().iata, "FCO");
+    assert!(lookup("ZZZ").is_none());
+}
+
+#[test]
+fn code_shape_detection() {
+    assert!(looks_like_code("FCO"));
+    assert!(looks_like_code("LIRF"));
+    assert!(!looks_like_code("Rome"));
+    assert!(!looks_like_code("Bra"));
+}
+
+#[test]
+fn dataset_rows_all_parse() {
+    let csv = include_str!("../data/airports.csv");
+    for line in csv.lines().skip(1) {
+        let iata = line.split(',').next().unwrap();
+        assert!(lookup(iata).is_some(), "row failed to parse: {line}");
+    }
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use weather_helper::DayTemp;
+use weather_helper::alerts::{Extras, Rules, Variable, banner, evaluate, summary};
+
+fn day(d: u32, tmax: f64) -> DayTemp {
+    DayTemp {
+        date: NaiveDate::from_ymd_opt(2025, 8, d).unwrap(),
+        tmax,
+        tmin: tmax - 10.0,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: (tmax - 10.0) * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rules_parse_and_print_canonically() {
+    let rules = Rules::parse(" TMAX_F>95 ,rain_prob >= 60.5, tmin<0").unwrap();
+    assert_eq!(rules.0.len(), 3);
+    assert_eq!(rules.name(), "tmax_f > 95, rain_prob >= 60.5, tmin < 0");
+    assert_eq!(Rules::parse(&rules.name()).unwrap(), rules);
+    assert!(Rules::parse("wind > 50").is_err());
+    assert!(Rules::parse("tmax hot").is_err());
+    assert!(Rules::parse("tmax > warm").is_err());
+}
+
+#[test]
+fn any_rule_flags_a_day() {
+    let rules = Rules::parse("tmax_f > 95, rain_prob > 60").unwrap();
+    let data = [day(20, 30.0), day(21, 36.2), day(22, 25.0)];
+    let mut extras = Extras::new();
+    extras.insert(data[2].date, BTreeMap::from([(Variable::RainProb, 70.0)]));
+    let alerts = evaluate(&rules, &data, &extras);
+    assert_eq!(alerts.len(), 2);
+    assert_eq!(
+        banner(&alerts).unwrap(),
+        "**Alert**: 2025-08-21 tmax_f 97.2 > 95; 2025-08-22 rain_prob 70 > 60"
+    );
+    assert_eq!(banner(&[]), None);
+
+    let report = summary(&[("Trips/Rome.md".to_string(), alerts)]);
+    assert_eq!(
+        report,
+        "Alerts:\n  Trips/Rome.md: 2025-08-21 tmax_f 97.2 > 95; 2025-08-22 rain_prob 70 > 60\n"
+    );
+    assert_eq!(summary(&[]), "");
+}
@@ -0,0 +1,224 @@
+//! API payload decoding diagnostics (no network)
+
+use weather_helper::ForecastResp;
+use weather_helper::http::decode_json;
+
+#[test]
+fn decode_reports_api_reason() {
+    let body = br#"{"error": true, "reason": "end_date out of range"}"#;
+    let err = decode_json::<ForecastResp>(body).unwrap_err();
+    assert_eq!(err.to_string(), "API said: 'end_date out of range'");
+}
+
+#[test]
+fn decode_reports_renamed_fields() {
+    let body =
+        br#"{"daily": {"time": ["2025-01-01"], "temp_max": [1.0], "temperature_2m_min": [0.0]}}"#;
+    let err = decode_json::<ForecastResp>(body).unwrap_err().to_string();
+    assert!(err.contains("temperature_2m_max"), "{err}");
+    assert!(err.contains("top-level keys: daily"), "{err}");
+}
+
+#[test]
+fn decode_rejects_non_json() {
+    let err = decode_json::<ForecastResp>(b"<html>502</html>")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not valid JSON"), "{err}");
+}
+
+#[test]
+fn only_transient_statuses_are_retried() {
+    use reqwest::StatusCode;
+    use weather_helper::http::is_retryable;
+    assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+    assert!(is_retryable(StatusCode::BAD_GATEWAY));
+    assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+    assert!(!is_retryable(StatusCode::BAD_REQUEST));
+    assert!(!is_retryable(StatusCode::NOT_FOUND));
+}
+
+#[tokio::test]
+async fn oversized_bodies_are_rejected() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut sock, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = sock.read(&mut buf).await;
+        let body = format!("[{}0]", "0,".repeat(1000));
+        let head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+            body.len()
+        );
+        let _ = sock.write_all(head.as_bytes()).await;
+        let _ = sock.write_all(body.as_bytes()).await;
+    });
+    let client = weather_helper::http::Client::new(None)
+        .unwrap()
+        .with_max_body(1024);
+    let err = client
+        .get_json::<Vec<u32>>(&format!("http://{addr}/"))
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("byte limit"), "{err:#}");
+}
+
+/// Everything logged while it is the default subscriber.
+#[derive(Clone, Default)]
+struct Logs(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn failed_requests_never_show_credentials() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    let logs = Logs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+    let client = weather_helper::http::Client::new(None).unwrap();
+
+    // Nothing listens on the discard port.
+    let refused = client
+        .get_json::<serde_json::Value>("http://127.0.0.1:9/x?key=SUPERSECRET")
+        .await
+        .unwrap_err();
+    // A body cut short after its headers.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut sock, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = sock.read(&mut buf).await;
+        let head =
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 100\r\n\r\n[1,";
+        let _ = sock.write_all(head.as_bytes()).await;
+    });
+    let truncated = client
+        .get_json::<serde_json::Value>(&format!("http://{addr}/x?key=SUPERSECRET"))
+        .await
+        .unwrap_err();
+
+    let logged = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(logged.contains("network error"), "{logged}");
+    for text in [format!("{refused:#}"), format!("{truncated:#}"), logged] {
+        assert!(!text.contains("SUPERSECRET"), "{text}");
+    }
+}
+
+#[test]
+fn logged_urls_hide_credentials() {
+    use weather_helper::http::redact_url;
+    assert_eq!(
+        redact_url("https://api.example/v1?q=Rome&key=s3cret&unitGroup=metric"),
+        "https://api.example/v1?q=Rome&key=***&unitGroup=metric"
+    );
+    assert_eq!(
+        redact_url("https://api.example/v1?APIKEY=x"),
+        "https://api.example/v1?APIKEY=***"
+    );
+    assert_eq!(
+        redact_url("https://api.example/v1"),
+        "https://api.example/v1"
+    );
+}
+
+#[tokio::test]
+async fn validators_persist_and_304_reuses_the_body() {
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 2048];
+            let n = sock.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+            let revalidating = request.contains("if-none-match: \"v1\"");
+            seen.lock().unwrap().push(request);
+            let response = if revalidating {
+                "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\n\r\n".to_string()
+            } else {
+                let body = "[1,2,3]";
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\netag: \"v1\"\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            };
+            let _ = sock.write_all(response.as_bytes()).await;
+        }
+    });
+    let dir = std::env::temp_dir().join(format!("wh-http-cache-{}", std::process::id()));
+    let url = format!("http://{addr}/forecast");
+    // Two clients stand in for two runs sharing the cache directory.
+    for _ in 0..2 {
+        let client = weather_helper::http::Client::new(None)
+            .unwrap()
+            .with_cache_dir(dir.clone());
+        let body: Vec<u32> = client.get_json_revalidated(&url).await.unwrap();
+        assert_eq!(body, vec![1, 2, 3]);
+    }
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(!requests[0].contains("if-none-match"));
+    assert!(requests[1].contains("if-none-match: \"v1\""));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn polite_clients_space_requests_and_identify_themselves() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use weather_helper::http::{Client, POLITE_INTERVAL, USER_AGENT};
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::<(Instant, String)>::new()));
+    let seen = requests.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 2048];
+            let n = sock.read(&mut buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+            seen.lock().unwrap().push((Instant::now(), request));
+            let _ = sock
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\n[]")
+                .await;
+        }
+    });
+    let client = Client::new(None).unwrap().with_polite(true);
+    assert!(client.is_polite());
+    let url = format!("http://{addr}/");
+    // Clones share the pace, as the notes of one run do.
+    let other = client.clone();
+    let (a, b) = tokio::join!(
+        client.get_json::<Vec<u32>>(&url),
+        other.get_json::<Vec<u32>>(&url)
+    );
+    a.unwrap();
+    b.unwrap();
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].0 - requests[0].0 >= POLITE_INTERVAL);
+    let agent = format!("user-agent: {}", USER_AGENT.to_ascii_lowercase());
+    assert!(requests.iter().all(|(_, r)| r.contains(&agent)));
+}
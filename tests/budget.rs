@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use weather_helper::budget::{self, DailyUsage};
+use weather_helper::metrics;
+
+#[test]
+fn requests_are_attributed_to_services() {
+    assert_eq!(
+        budget::service("https://api.open-meteo.com/v1/forecast?latitude=1"),
+        "open-meteo"
+    );
+    assert_eq!(
+        budget::service("https://archive-api.open-meteo.com/v1/era5"),
+        "open-meteo"
+    );
+    assert_eq!(budget::service("https://api.weather.gov/points/1,2"), "nws");
+    assert_eq!(
+        budget::service("https://api.met.no/weatherapi/locationforecast/2.0/compact"),
+        "met-norway"
+    );
+    assert_eq!(budget::service("http://127.0.0.1:8080/x"), "127.0.0.1:8080");
+}
+
+#[test]
+fn daily_usage_accumulates_and_resets() {
+    let path = std::env::temp_dir().join(format!("wh-usage-{}.json", std::process::id()));
+    let today = "2026-03-01".parse().unwrap();
+    let mut usage = DailyUsage::load(&path, today);
+    assert!(usage.calls.is_empty());
+    usage.add(&BTreeMap::from([("open-meteo".to_string(), 7_900)]));
+    usage.save(&path).unwrap();
+
+    let mut again = DailyUsage::load(&path, today);
+    assert!(again.warnings().is_empty());
+    again.add(&BTreeMap::from([("open-meteo".to_string(), 100)]));
+    assert_eq!(
+        again.warnings(),
+        vec!["open-meteo: 8000 of 10000 free daily requests used today"]
+    );
+
+    let tomorrow = DailyUsage::load(&path, "2026-03-02".parse().unwrap());
+    assert!(tomorrow.calls.is_empty());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn run_report_and_metrics_show_the_counters() {
+    budget::record("https://geocoding-api.open-meteo.com/v1/search?name=Rome");
+    budget::record_city("Rome \"centro\"", 2);
+    let report = budget::report(&BTreeMap::from([
+        ("nominatim".to_string(), 2),
+        ("open-meteo".to_string(), 12),
+    ]));
+    assert_eq!(report, "API requests: 14 (nominatim 2, open-meteo 12)");
+    let text = metrics::render();
+    assert!(text.contains("weather_helper_api_calls_by_service_total{service=\"open-meteo\"} 1"));
+    assert!(
+        text.contains("weather_helper_api_calls_by_city_total{city=\"Rome \\\"centro\\\"\"} 2")
+    );
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, NaiveTime, Utc};
+use std::sync::Arc;
+use weather_helper::RunOptions;
+use weather_helper::clock::{Clock, FixedClock};
+use weather_helper::stale::{banner, stamp};
+use weather_helper::tz::parse_zone;
+
+fn utc(s: &str) -> DateTime<Utc> {
+    s.parse().unwrap()
+}
+
+#[test]
+fn today_is_the_date_in_the_clock_zone() {
+    // 22:30 UTC is already the next day in Rome.
+    let rome = parse_zone("Europe/Rome").unwrap();
+    let clock = FixedClock::new(utc("2025-08-01T22:30:00Z"), Some(rome));
+    assert_eq!(clock.today(), "2025-08-02".parse().unwrap());
+    assert_eq!(clock.now().to_rfc3339(), "2025-08-02T00:30:00+02:00");
+
+    let opts = RunOptions {
+        clock: Some(Arc::new(clock)),
+        ..RunOptions::default()
+    };
+    assert_eq!(opts.today(), "2025-08-02".parse().unwrap());
+    let fixed = RunOptions {
+        today: Some("2025-09-01".parse().unwrap()),
+        ..opts.clone()
+    };
+    assert_eq!(fixed.today(), "2025-09-01".parse().unwrap());
+    assert_eq!(fixed.now(), opts.now());
+}
+
+#[test]
+fn local_times_follow_dst_transitions() {
+    let rome = parse_zone("Europe/Rome").unwrap();
+    let at = |date: &str, hm: (u32, u32)| {
+        let time = NaiveTime::from_hms_opt(hm.0, hm.1, 0).unwrap();
+        FixedClock::at_local(date.parse().unwrap(), time, rome)
+    };
+    // Clocks jump from 02:00 to 03:00 on 2025-03-30 ...
+    assert!(at("2025-03-30", (2, 30)).is_none());
+    let after = at("2025-03-30", (3, 30)).unwrap();
+    assert_eq!(after.at, utc("2025-03-30T01:30:00Z"));
+    // ... and 02:30 happens twice on 2025-10-26; the first one counts.
+    let ambiguous = at("2025-10-26", (2, 30)).unwrap();
+    assert_eq!(ambiguous.now().to_rfc3339(), "2025-10-26T02:30:00+02:00");
+
+    // A day before the change back is 25 hours earlier by the clock.
+    let before = at("2025-10-25", (12, 0)).unwrap();
+    let later = at("2025-10-26", (12, 0)).unwrap();
+    assert_eq!((later.at - before.at).num_hours(), 25);
+}
+
+#[test]
+fn stamps_and_ages_use_the_clock() {
+    let rome = parse_zone("Europe/Rome").unwrap();
+    let fetched = FixedClock::new(utc("2025-10-25T20:00:00Z"), Some(rome)).now();
+    let now = FixedClock::new(utc("2025-10-27T08:00:00Z"), Some(rome)).now();
+    let block = "<!-- WEATHER:BEGIN v2 -->\n<!-- WEATHER:END -->\n";
+    assert!(stamp(block, fetched).contains("FETCHED 2025-10-25T22:00:00+02:00"));
+    // Calendar days in Rome, across the end of DST in between.
+    assert_eq!(now.to_rfc3339(), "2025-10-27T09:00:00+01:00");
+    assert!(banner(fetched, now).contains("2 days ago"));
+}
@@ -0,0 +1,149 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use weather_helper::columns::{Column, Measure, measures, merge, parse_vars};
+use weather_helper::config::{Config, Origin};
+use weather_helper::i18n::Languages;
+use weather_helper::{
+    DayNote, DayTemp, RunOptions, Units, render_table_columns, render_table_split, summarize_with,
+};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn day(d: &str, tmax: f64, tmin: f64) -> DayTemp {
+    DayTemp {
+        date: date(d),
+        tmax,
+        tmin,
+        tmax_f: tmax * 9.0 / 5.0 + 32.0,
+        tmin_f: tmin * 9.0 / 5.0 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn variable_lists_are_validated_and_deduplicated() {
+    assert_eq!(
+        parse_vars("precipitation_sum, wind_speed_10m_max,precipitation_sum").unwrap(),
+        ["precipitation_sum", "wind_speed_10m_max"]
+    );
+    assert!(parse_vars("").unwrap().is_empty());
+    assert!(parse_vars("rain&daily=x").is_err());
+    assert!(parse_vars("Precipitation Sum").is_err());
+}
+
+#[test]
+fn frontmatter_lists_set_the_note_vars() {
+    let yaml: serde_yaml::Value =
+        serde_yaml::from_str("weather-vars: [precipitation_sum, wind_speed_10m_max]").unwrap();
+    let value = weather_helper::config::yaml_scalar(&yaml["weather-vars"]);
+    assert_eq!(value, "precipitation_sum, wind_speed_10m_max");
+    let mut config = Config::default();
+    config.set("vars", &value, Origin::Flag).unwrap();
+    let opts = config.run_options(&RunOptions::default()).unwrap();
+    assert_eq!(opts.vars, ["precipitation_sum", "wind_speed_10m_max"]);
+}
+
+#[test]
+fn columns_are_rendered_with_the_day_table() {
+    let data = [day("2025-08-09", 30.0, 20.0), day("2025-08-10", 31.0, 21.0)];
+    let notes = BTreeMap::from([(
+        date("2025-08-10"),
+        DayNote {
+            text: "arrive 22:30".to_string(),
+            counted: false,
+        },
+    )]);
+    let today = Some(date("2025-08-10"));
+    let snow = Column {
+        name: "snowfall_sum".to_string(),
+        unit: Some("cm".to_string()),
+        values: BTreeMap::from([(date("2025-08-09"), 2.25), (date("2025-08-10"), 0.0)]),
+    };
+    let gusts = Column {
+        name: "wind_gusts_10m_max".to_string(),
+        unit: None,
+        values: BTreeMap::new(),
+    };
+    assert_eq!(
+        render_table_columns(&data, &notes, today, &[snow, gusts]),
+        "| Date | High (°F) | Low (°F) | High (°C) | Low (°C) | Notes | snowfall_sum (cm) | wind_gusts_10m_max |\n\
+         |---|---:|---:|---:|---:|---|---:|---:|\n\
+         | 2025-08-09 | 86 | 68 | 30 | 20 |  | 2.2 |  |\n\
+         | **— today —** | | | | | | | |\n\
+         | 2025-08-10 | 88 | 70 | 31 | 21 | arrive 22:30 | 0 |  |\n"
+    );
+    assert_eq!(
+        render_table_columns(&data, &notes, today, &[]),
+        render_table_split(&data, &notes, today)
+    );
+}
+
+#[test]
+fn common_variables_are_kept_on_the_days_and_summarized() {
+    assert_eq!(
+        parse_vars("rain, rain-chance, Wind, uv").unwrap(),
+        [
+            "precipitation_sum",
+            "precipitation_probability_max",
+            "wind_speed_10m_max",
+            "uv_index_max"
+        ]
+    );
+    let column = |name: &str, unit: &str, values: &[f64]| Column {
+        name: name.to_string(),
+        unit: Some(unit.to_string()),
+        values: values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (date("2025-08-09") + chrono::Days::new(i as u64), *v))
+            .collect(),
+    };
+    let columns = vec![
+        column("precipitation_sum", "mm", &[2.25, 0.0, 10.1]),
+        column("precipitation_probability_max", "%", &[40.0, 85.0, 10.0]),
+        column("wind_speed_10m_max", "km/h", &[12.0, 35.4, 20.0]),
+        column("uv_index_max", "", &[6.5, 8.0]),
+        column("wind_gusts_10m_max", "km/h", &[50.0, 60.0, 55.0]),
+    ];
+    let mut data = [
+        day("2025-08-09", 30.0, 20.0),
+        day("2025-08-10", 31.0, 21.0),
+        day("2025-08-11", 29.0, 19.0),
+    ];
+    let rest = merge(&mut data, columns);
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].name, "wind_gusts_10m_max");
+    assert_eq!(data[0].precipitation, Some(2.25));
+    assert_eq!(data[1].precipitation_probability, Some(85.0));
+    assert_eq!(data[1].wind_speed, Some(35.4));
+    assert_eq!(data[2].uv_index, None);
+    assert_eq!(measures(&data), Measure::ALL);
+
+    assert_eq!(
+        render_table_columns(&data, &BTreeMap::new(), None, &rest),
+        "| Date | High (°F) | Low (°F) | High (°C) | Low (°C) \
+         | Rain (mm) | Rain chance (%) | Wind (km/h) | UV | wind_gusts_10m_max (km/h) |\n\
+         |---|---:|---:|---:|---:|---:|---:|---:|---:|---:|\n\
+         | 2025-08-09 | 86 | 68 | 30 | 20 | 2.2 | 40 | 12 | 6.5 | 50 |\n\
+         | 2025-08-10 | 88 | 70 | 31 | 21 | 0 | 85 | 35.4 | 8 | 60 |\n\
+         | 2025-08-11 | 84 | 66 | 29 | 19 | 10.1 | 10 | 20 |  | 55 |\n"
+    );
+
+    let langs = Languages::parse("en, it").unwrap();
+    assert_eq!(
+        summarize_with(&data, Units::Celsius, &langs).note,
+        "_3 days • High range 29° → 31° • Low range 19° → 21° \
+         • Rain 12.4 mm in total • Rain chance up to 85% • Wind up to 35.4 km/h • UV up to 8_  \n\
+         _3 giorni • Massime 29° → 31° • Minime 19° → 21° \
+         • Pioggia 12.4 mm in totale • Probabilità di pioggia fino a 85% \
+         • Vento fino a 35.4 km/h • UV fino a 8_"
+    );
+    let plain = [day("2025-08-09", 30.0, 20.0)];
+    assert!(measures(&plain).is_empty());
+    assert_eq!(
+        summarize_with(&plain, Units::Celsius, &Languages::default()).note,
+        "_1 days • High range 30° → 30° • Low range 20° → 20°_"
+    );
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDate;
+use std::fs;
+use weather_helper::Units;
+use weather_helper::compare::{render_block, render_table};
+use weather_helper::roadtrip::RoadDay;
+use weather_helper::route::{Stop, Waypoint};
+
+fn d(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn stop(name: &str) -> Stop {
+    Stop {
+        name: name.into(),
+        latitude: 0.0,
+        longitude: 0.0,
+        timezone: "auto".into(),
+    }
+}
+
+fn day(date: &str, code: Option<u8>, tmax: Option<f64>, tmin: Option<f64>) -> RoadDay {
+    RoadDay {
+        date: d(date),
+        weather_code: code,
+        tmax,
+        tmin,
+    }
+}
+
+#[test]
+fn candidates_replace_a_missing_city() {
+    let dir = std::env::temp_dir().join(format!("wh-compare-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Portugal.md");
+    fs::write(
+        &path,
+        "---\ncandidates: [Lisbon, '41.15, -8.61']\narrival: 2025-08-20\ndeparture: 2025-08-22\n---\n",
+    )
+    .unwrap();
+    let meta = weather_helper::extract_meta(&path).unwrap();
+    assert!(meta.is_comparison());
+    assert_eq!(meta.candidates[0], Waypoint::Named("Lisbon".into()));
+    assert_eq!(meta.candidates[1].label(), "41.15, -8.61");
+
+    // Once a city is chosen the note is an ordinary trip again.
+    fs::write(
+        &path,
+        "---\ncity: Porto\ncandidates: [Lisbon, Porto]\narrival: 2025-08-20\ndeparture: 2025-08-22\n---\n",
+    )
+    .unwrap();
+    assert!(!weather_helper::extract_meta(&path).unwrap().is_comparison());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn candidates_render_side_by_side() {
+    let candidates = vec![
+        (
+            stop("Lisbon"),
+            vec![
+                day("2025-08-20", Some(0), Some(30.0), Some(20.0)),
+                day("2025-08-21", Some(61), Some(28.0), Some(19.0)),
+            ],
+        ),
+        (
+            stop("Porto"),
+            vec![day("2025-08-20", Some(3), Some(24.0), None)],
+        ),
+    ];
+    assert_eq!(
+        render_table(&candidates, Units::Celsius),
+        "| Date | Lisbon | Porto |\n|---|---|---|\n\
+         | 2025-08-20 | 30°C / 20°C, clear | 24°C, overcast |\n\
+         | 2025-08-21 | 28°C / 19°C, rain | – |\n\
+         | **Avg high** | 29°C | 24°C |\n\
+         | **Wet days** | 1 | 0 |\n"
+    );
+    assert!(render_table(&candidates, Units::Fahrenheit).contains("| **Avg high** | 84°F |"));
+
+    let block = render_block(
+        "Forecast: Lisbon vs Porto 2025-08-20 → 2025-08-21",
+        &candidates,
+        Units::Celsius,
+    );
+    assert!(block.starts_with("## Weather Forecast\n"));
+    assert!(block.contains("**Forecast: Lisbon vs Porto 2025-08-20 → 2025-08-21**"));
+    assert_eq!(weather_helper::count_weather_blocks(&block), 1);
+
+    assert_eq!(render_table(&[], Units::Celsius), "_(no rows)_");
+}
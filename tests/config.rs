@@ -0,0 +1,171 @@
+use std::collections::BTreeMap;
+use weather_helper::config::{Config, Origin, env_var};
+use weather_helper::{RunOptions, Units};
+
+#[test]
+fn later_layers_win_and_keep_their_origin() {
+    let mut config = Config::default();
+    config
+        .apply_yaml(
+            "units: celsius\ntimeout: 5s\n",
+            Origin::File("config.yaml".into()),
+        )
+        .unwrap();
+    config
+        .set("units", "fahrenheit", Origin::Env(env_var("units")))
+        .unwrap();
+    config.set("timeout", "10s", Origin::Flag).unwrap();
+
+    let (units, origin) = config.get("units").unwrap();
+    assert_eq!(units, "fahrenheit");
+    assert_eq!(origin.to_string(), "env WEATHER_HELPER_UNITS");
+    assert_eq!(config.get("timeout").unwrap().1, &Origin::Flag);
+    assert_eq!(config.get("geocoder").unwrap().1, &Origin::Default);
+
+    let opts = config.run_options(&RunOptions::default()).unwrap();
+    assert_eq!(opts.timeout, Some(std::time::Duration::from_secs(10)));
+    assert_eq!(opts.units, Units::Fahrenheit);
+}
+
+#[test]
+fn invalid_keys_and_values_are_rejected() {
+    let mut config = Config::default();
+    assert!(config.set("colour", "never", Origin::Flag).is_err());
+    assert!(config.set("units", "kelvin", Origin::Flag).is_err());
+    assert!(config.apply_yaml("- a list", Origin::Flag).is_err());
+}
+
+#[test]
+fn frontmatter_overrides_are_limited_to_note_keys() {
+    let mut config = Config::default();
+    let mut overrides = BTreeMap::new();
+    overrides.insert("units".to_string(), "celsius".to_string());
+    config.apply_note(&overrides, "trip.md").unwrap();
+    let shown = config.show(true);
+    assert!(
+        shown.contains("units         = celsius      # frontmatter of trip.md"),
+        "{shown}"
+    );
+
+    overrides.insert("timeout".to_string(), "1s".to_string());
+    assert!(config.apply_note(&overrides, "trip.md").is_err());
+}
+
+#[test]
+fn day_durations_round_up_to_whole_days() {
+    let mut config = Config::default();
+    assert_eq!(config.get("retention").unwrap().0, "30d");
+    config.set("retention", "2w", Origin::Flag).unwrap();
+    config.set("only-upcoming", "36h", Origin::Flag).unwrap();
+    let opts = config.run_options(&RunOptions::default()).unwrap();
+    assert_eq!(opts.retention, Some(14));
+    assert_eq!(opts.only_upcoming, Some(2));
+}
+
+#[test]
+fn today_overrides_the_clock() {
+    let mut config = Config::default();
+    assert_eq!(config.get("today").unwrap().0, "");
+    assert!(config.set("today", "next tuesday", Origin::Flag).is_err());
+    config.set("today", "2025-08-01", Origin::Flag).unwrap();
+    let opts = config.run_options(&RunOptions::default()).unwrap();
+    assert_eq!(opts.today(), "2025-08-01".parse().unwrap());
+}
+
+#[tokio::test]
+async fn runs_pick_notes_by_the_overridden_day() {
+    let dir = std::env::temp_dir().join(format!("wh-today-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("rome.md"),
+        "---\ncity: Rome\narrival: 2025-08-01\ndeparture: 2025-08-05\n---\n",
+    )
+    .unwrap();
+    // Long past on the chosen day, so it is skipped without any request.
+    let opts = RunOptions {
+        today: Some("2026-01-01".parse().unwrap()),
+        verbosity: weather_helper::telemetry::Verbosity::Quiet,
+        ..RunOptions::default()
+    };
+    let report = weather_helper::run(&dir.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    assert!(report.notes.is_empty());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn runs_over_max_notes_need_confirmation() {
+    let dir = std::env::temp_dir().join(format!("wh-max-notes-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // An unknown `weather-*` key fails each note before any request.
+    for city in ["Rome", "Milan", "Turin"] {
+        std::fs::write(
+            dir.join(format!("{city}.md")),
+            format!("---\ncity: {city}\narrival: 2030-08-01\ndeparture: 2030-08-05\nweather-nope: x\n---\n"),
+        )
+        .unwrap();
+    }
+    let root = dir.to_string_lossy().to_string();
+    let opts = RunOptions {
+        max_notes: Some(2),
+        verbosity: weather_helper::telemetry::Verbosity::Quiet,
+        ..RunOptions::default()
+    };
+    let err = weather_helper::run(&root, &opts).await.unwrap_err();
+    assert!(
+        err.to_string().contains("refusing to update 3 notes"),
+        "{err}"
+    );
+    let declined = RunOptions {
+        confirm: Some(|_, _| false),
+        ..opts.clone()
+    };
+    assert!(weather_helper::run(&root, &declined).await.is_err());
+    for opts in [
+        RunOptions {
+            confirm: Some(|count, _| count == 3),
+            ..opts.clone()
+        },
+        RunOptions {
+            yes: true,
+            ..opts.clone()
+        },
+        RunOptions {
+            max_notes: Some(0),
+            ..opts.clone()
+        },
+    ] {
+        let report = weather_helper::run(&root, &opts).await.unwrap();
+        assert_eq!(report.notes.len(), 3);
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn max_notes_reads_numbers_and_off() {
+    let mut config = Config::default();
+    let limit = |config: &Config| {
+        config
+            .run_options(&RunOptions::default())
+            .unwrap()
+            .max_notes()
+    };
+    assert_eq!(limit(&config), Some(weather_helper::DEFAULT_MAX_NOTES));
+    config.set("max-notes", "500", Origin::Flag).unwrap();
+    assert_eq!(limit(&config), Some(500));
+    config.set("max-notes", "off", Origin::Flag).unwrap();
+    assert_eq!(limit(&config), None);
+    assert!(config.set("max-notes", "lots", Origin::Flag).is_err());
+}
+
+#[test]
+fn alert_rules_round_trip_through_config() {
+    let mut config = Config::default();
+    config
+        .set("alert-if", "tmax_f>95,rain_prob>60", Origin::Flag)
+        .unwrap();
+    assert!(config.set("alert-if", "snow > 1", Origin::Flag).is_err());
+    let opts = config.run_options(&RunOptions::default()).unwrap();
+    assert_eq!(opts.alert_if.unwrap().name(), "tmax_f > 95, rain_prob > 60");
+}
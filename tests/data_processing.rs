@@ -43,6 +43,7 @@ fn summarize_formats() {
             tmin: 0.0,
             tmax_f: 52.0,
             tmin_f: 32.0,
+            ..Default::default()
         },
         DayTemp {
             date: NaiveDate::parse_from_str("2025-01-02", "%Y-%m-%d").unwrap(),
@@ -50,6 +51,7 @@ fn summarize_formats() {
             tmin: 1.0,
             tmax_f: 56.0,
             tmin_f: 3.0,
+            ..Default::default()
         },
     ];
     let s: Summary = summarize(&data);
@@ -67,6 +69,7 @@ fn render_table_outputs_rows() {
             tmin: 0.0,
             tmax_f: 52.0,
             tmin_f: 32.0,
+            ..Default::default()
         },
         DayTemp {
             date: NaiveDate::parse_from_str("2025-01-02", "%Y-%m-%d").unwrap(),
@@ -74,9 +77,80 @@ fn render_table_outputs_rows() {
             tmin: 1.0,
             tmax_f: 56.0,
             tmin_f: 3.0,
+            ..Default::default()
         },
     ];
     let table = render_table(&data);
     assert!(table.contains("| 2025-01-01 | 52 | 32 |"));
     assert!(table.contains("| 2025-01-02 | 56 | 3 |"));
 }
+
+#[test]
+fn parse_daily_skips_null_days() {
+    let resp = serde_json::json!({
+        "daily": {
+            "time": ["2025-01-01", "2025-01-02", "2025-01-03"],
+            "temperature_2m_max": [10.0, null, 12.0],
+            "temperature_2m_min": [1.0, 2.0, null]
+        }
+    });
+    let resp: weather_helper::ForecastResp = serde_json::from_value(resp).unwrap();
+    let out = parse_daily(resp).unwrap();
+    assert_eq!(out.len(), 1);
+    assert_eq!(out[0].date.to_string(), "2025-01-01");
+}
+
+#[test]
+fn parse_daily_all_null_errs() {
+    let resp = serde_json::json!({
+        "daily": {
+            "time": ["2025-01-01", "2025-01-02"],
+            "temperature_2m_max": [null, null],
+            "temperature_2m_min": [null, 2.0]
+        }
+    });
+    let resp: weather_helper::ForecastResp = serde_json::from_value(resp).unwrap();
+    let err = parse_daily(resp).unwrap_err();
+    assert!(err.to_string().contains("missing temperatures"));
+}
+
+#[test]
+fn responses_without_a_full_day_have_no_temperatures() {
+    let resp = |max: &str, min: &str| {
+        let json = format!(
+            r#"{{"daily":{{"time":["2025-01-01","2025-01-02"],"temperature_2m_max":{max},"temperature_2m_min":{min}}}}}"#
+        );
+        serde_json::from_str::<weather_helper::ForecastResp>(&json).unwrap()
+    };
+    assert!(resp("[null, 3.0]", "[null, 1.0]").has_temperatures());
+    assert!(!resp("[null, 3.0]", "[1.0, null]").has_temperatures());
+    assert!(!resp("[]", "[]").has_temperatures());
+    let empty: weather_helper::ForecastResp = serde_json::from_str("{}").unwrap();
+    assert!(!empty.has_temperatures());
+}
+
+#[test]
+fn temperatures_format_deterministically() {
+    use weather_helper::fmt_temp;
+    assert_eq!(fmt_temp(-0.4), "0");
+    assert_eq!(fmt_temp(-0.0), "0");
+    assert_eq!(fmt_temp(0.5), "1");
+    assert_eq!(fmt_temp(-0.5), "-1");
+    assert_eq!(fmt_temp(2.5), "3");
+    assert_eq!(fmt_temp(-12.6), "-13");
+}
+
+#[test]
+fn summarize_in_celsius() {
+    use weather_helper::{Units, summarize_in};
+    let data = vec![DayTemp {
+        date: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        tmax: 30.4,
+        tmin: 19.6,
+        tmax_f: 86.7,
+        tmin_f: 67.3,
+        ..Default::default()
+    }];
+    let s = summarize_in(&data, Units::Celsius);
+    assert_eq!((s.max.as_str(), s.min.as_str()), ("30°C", "20°C"));
+}
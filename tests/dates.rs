@@ -0,0 +1,35 @@
+//! Trip date parsing tests
+
+use weather_helper::dates::{DateOrder, parse_trip_date};
+
+#[test]
+fn parses_supported_formats() {
+    let cases = [
+        ("2025-08-20", "YYYY-MM-DD"),
+        ("2025.08.20", "YYYY.MM.DD"),
+        ("20/08/2025", "DD/MM/YYYY"),
+        ("20-08-2025", "DD-MM-YYYY"),
+        ("2025-08-20T22:30", "YYYY-MM-DDTHH:MM"),
+        ("2025-08-20T22:30:00+02:00", "RFC 3339"),
+    ];
+    for (raw, format) in cases {
+        let parsed = parse_trip_date(raw, DateOrder::DayFirst).unwrap();
+        assert_eq!(parsed.date.to_string(), "2025-08-20", "{raw}");
+        assert_eq!(parsed.format, format, "{raw}");
+    }
+}
+
+#[test]
+fn month_first_order_disambiguates() {
+    let parsed = parse_trip_date("08/20/2025", DateOrder::MonthFirst).unwrap();
+    assert_eq!(parsed.date.to_string(), "2025-08-20");
+    assert_eq!(parsed.format, "MM/DD/YYYY");
+    assert!(parse_trip_date("08/20/2025", DateOrder::DayFirst).is_err());
+}
+
+#[test]
+fn datetime_keeps_time() {
+    let parsed = parse_trip_date("2025-08-20T22:30", DateOrder::DayFirst).unwrap();
+    assert_eq!(parsed.time.unwrap().to_string(), "22:30:00");
+    assert!(!parsed.is_iso());
+}
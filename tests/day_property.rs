@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use weather_helper::day_property::{MAX_DAYS, apply, render};
+use weather_helper::{DayTemp, Units};
+
+fn day(d: u32, tmax: f64, tmin: f64) -> DayTemp {
+    DayTemp {
+        date: NaiveDate::from_ymd_opt(2025, 8, d).unwrap(),
+        tmax,
+        tmin,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: tmin * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn days_are_written_as_stable_arrays() {
+    let data = [day(20, 31.4, 20.1), day(21, 33.0, 21.7)];
+    let note = "---\ncity: Rome # capital\narrival: 2025-08-20\n---\n\n# Rome\n";
+    let out = apply(note, "forecast", &data, Units::Celsius).unwrap();
+    assert_eq!(
+        out,
+        "---\ncity: Rome # capital\narrival: 2025-08-20\nforecast:\n  dates: [2025-08-20, 2025-08-21]\n  high: [31.4, 33.0]\n  low: [20.1, 21.7]\n  unit: °C\n---\n\n# Rome\n"
+    );
+    // A re-run with new numbers replaces the property where it is.
+    let again = apply(&out, "forecast", &data[..1], Units::Fahrenheit).unwrap();
+    assert!(again.contains(
+        "forecast:\n  dates: [2025-08-20]\n  high: [88.5]\n  low: [68.2]\n  unit: °F\n---"
+    ));
+    assert_eq!(again.matches("forecast:").count(), 1);
+}
+
+#[test]
+fn long_trips_and_unsafe_edits_are_limited() {
+    let data: Vec<DayTemp> = (0..80)
+        .map(|i| DayTemp {
+            date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + chrono::Duration::days(i),
+            ..day(1, 10.0, 5.0)
+        })
+        .collect();
+    let dates = render(&data, Units::Celsius)
+        .lines()
+        .next()
+        .unwrap()
+        .to_string();
+    assert_eq!(dates.matches(", ").count() + 1, MAX_DAYS);
+
+    let note = "---\ncity: Rome\n---\n";
+    assert!(apply(note, "weather-days", &data, Units::Celsius).is_err());
+    // A quoted key is not found, and appending a second one would not
+    // read back.
+    let quoted = "---\n\"forecast\": old\ncity: Rome\n---\n";
+    assert!(apply(quoted, "forecast", &data, Units::Celsius).is_err());
+}
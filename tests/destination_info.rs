@@ -0,0 +1,29 @@
+use weather_helper::destination_info::{lookup, plug_types, render_footer};
+
+#[test]
+fn bundled_facts_render_as_a_footer() {
+    let italy = lookup("it").unwrap();
+    assert_eq!(italy.plugs, ["C", "F", "L"]);
+    assert_eq!(
+        render_footer(&italy),
+        "_Italy: plugs C/F/L, 230 V • emergency 112 • tap water safe to drink_\n"
+    );
+    let thailand = lookup("TH").unwrap();
+    assert!(render_footer(&thailand).contains("drink bottled or treated water"));
+    assert!(lookup("XX").is_none());
+    assert!(plug_types("XX").is_empty());
+}
+
+#[test]
+fn every_row_is_well_formed() {
+    let data = include_str!("../data/countries.csv");
+    for line in data.lines().skip(1) {
+        let code = line.split(',').next().unwrap();
+        let info = lookup(code).unwrap_or_else(|| panic!("bad row: {line}"));
+        assert!(!info.plugs.is_empty(), "{line}");
+        assert!(
+            ["safe", "varies", "unsafe"].contains(&info.tap_water),
+            "{line}"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use weather_helper::frontmatter::{parse, set_block, set_keys};
+
+#[test]
+fn set_keys_appends_and_replaces() {
+    let note = "---\ncity: Venice\nlat: 1.0 # old\narrival: 2025-08-20\n---\n\n# Trip\n";
+    let out = set_keys(
+        note,
+        &[
+            ("lat", "45.4371".to_string()),
+            ("lon", "12.3326".to_string()),
+        ],
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        "---\ncity: Venice\nlat: 45.4371\narrival: 2025-08-20\nlon: 12.3326\n---\n\n# Trip\n"
+    );
+}
+
+#[test]
+fn set_keys_ignores_prefixed_keys_and_body() {
+    let note = "---\nlatitude_note: x\n---\nlat: body text\n";
+    let out = set_keys(note, &[("lat", "1".to_string())]).unwrap();
+    assert_eq!(out, "---\nlatitude_note: x\nlat: 1\n---\nlat: body text\n");
+    assert!(set_keys("# no frontmatter\n", &[("lat", "1".to_string())]).is_err());
+}
+
+#[test]
+fn set_block_replaces_nested_values_in_place() {
+    let note =
+        "---\ncity: Rome\nforecast:\n  high: [1.0]\n  low:\n    - 2.0\ntags: [trip]\n---\nbody\n";
+    let out = set_block(note, "forecast", "  high: [3.0]\n").unwrap();
+    assert_eq!(
+        out,
+        "---\ncity: Rome\nforecast:\n  high: [3.0]\ntags: [trip]\n---\nbody\n"
+    );
+    let added = set_block("---\ncity: Rome\n---\n", "forecast", "  high: []\n").unwrap();
+    assert_eq!(added, "---\ncity: Rome\nforecast:\n  high: []\n---\n");
+    assert_eq!(parse(&out).unwrap().len(), 3);
+}
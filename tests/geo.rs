@@ -0,0 +1,40 @@
+use weather_helper::geo::{
+    distance_km, format_coords, format_distance, grid_point, parse_coords, record_grid_point,
+};
+
+#[test]
+fn distances_are_great_circle() {
+    let paris = (48.8566, 2.3522);
+    let london = (51.5074, -0.1278);
+    let d = distance_km(paris, london);
+    assert!((340.0..=345.0).contains(&d), "{d}");
+    assert_eq!(distance_km(paris, paris), 0.0);
+    // Across the antimeridian is the short way round.
+    assert!(distance_km((0.0, 179.9), (0.0, -179.9)) < 23.0);
+}
+
+#[test]
+fn coordinates_round_trip() {
+    assert_eq!(format_coords(41.9028, 12.4964, 2), "41.90, 12.50");
+    assert_eq!(parse_coords("41.9028, 12.4964"), Some((41.9028, 12.4964)));
+    assert_eq!(parse_coords("33.87 S, 151.21 E"), Some((-33.87, 151.21)));
+    assert_eq!(parse_coords("40.7°N, 74.0°w"), Some((40.7, -74.0)));
+    assert_eq!(parse_coords("-33.87S, 151.21"), None);
+    assert_eq!(parse_coords("91, 0"), None);
+    assert_eq!(parse_coords("Paris, France"), None);
+    assert_eq!(parse_coords("41.9"), None);
+}
+
+#[test]
+fn distances_read_naturally() {
+    assert_eq!(format_distance(0.85), "850 m");
+    assert_eq!(format_distance(3.24), "3.2 km");
+    assert_eq!(format_distance(41.4), "41 km");
+}
+
+#[test]
+fn reported_grid_points_are_remembered() {
+    record_grid_point((40.7081, -73.95712), (40.72, -73.94));
+    assert_eq!(grid_point((40.70810001, -73.9571)), Some((40.72, -73.94)));
+    assert_eq!(grid_point((10.0, 10.0)), None);
+}
@@ -0,0 +1,53 @@
+use weather_helper::geocoder::{
+    Place, edit_distance, location_details, normalize_query, rank_suggestions,
+};
+
+#[test]
+fn normalize_strips_accents_punctuation_and_prefixes() {
+    assert_eq!(normalize_query("Città di Forlì"), "forli");
+    assert_eq!(
+        normalize_query("  City of  Reggio-Emilia! "),
+        "reggio emilia"
+    );
+    assert_eq!(normalize_query("Reykjavík"), "reykjavik");
+}
+
+#[test]
+fn edit_distance_counts_characters() {
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("forlì", "forli"), 1);
+    assert_eq!(edit_distance("same", "same"), 0);
+}
+
+#[test]
+fn suggestions_rank_close_names_first() {
+    let candidates = vec![
+        "Reykjahlíð".to_string(),
+        "Reykjavík".to_string(),
+        "Reykjavík".to_string(),
+        "Rekavice".to_string(),
+    ];
+    assert_eq!(rank_suggestions("Reykjavk", &candidates), vec!["Reykjavík"]);
+    assert!(rank_suggestions("Milan", &candidates).is_empty());
+}
+
+#[test]
+fn location_details_show_where_the_forecast_is() {
+    let mut place = Place {
+        name: "Paris".into(),
+        latitude: 33.6609,
+        longitude: -95.5555,
+        timezone: "America/Chicago".into(),
+        country_code: Some("US".into()),
+        elevation: Some(180.4),
+    };
+    assert_eq!(
+        location_details(&place, true),
+        "33.66, -95.56 · 180 m · America/Chicago"
+    );
+    place.elevation = None;
+    place.timezone = "auto".into();
+    assert_eq!(location_details(&place, true), "33.66, -95.56");
+    assert_eq!(location_details(&place, false), "");
+}
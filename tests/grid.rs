@@ -0,0 +1,42 @@
+use weather_helper::geo::distance_km;
+use weather_helper::grid::{format_radius, parse_radius, snap};
+
+#[test]
+fn radii_parse_in_km() {
+    assert_eq!(parse_radius("5").unwrap(), 5.0);
+    assert_eq!(parse_radius("2.5 km").unwrap(), 2.5);
+    assert_eq!(parse_radius(&format_radius(7.5)).unwrap(), 7.5);
+    assert!(parse_radius("0").is_err());
+    assert!(parse_radius("5mi").is_err());
+    assert!(parse_radius("near").is_err());
+}
+
+#[test]
+fn neighborhoods_share_a_grid_point() {
+    let williamsburg = (40.7081, -73.9571);
+    let greenpoint = (40.7304, -73.9515);
+    let boston = (42.3601, -71.0589);
+    let point = |(lat, lon)| snap(lat, lon, 10.0);
+    assert_eq!(point(williamsburg), point(greenpoint));
+    assert_ne!(point(williamsburg), point(boston));
+    assert_ne!(snap(40.7081, -73.9571, 1.0), snap(40.7304, -73.9515, 1.0));
+}
+
+#[test]
+fn grid_points_stay_within_the_cell() {
+    for (lat, lon) in [
+        (40.7081, -73.9571),
+        (-33.8688, 151.2093),
+        (64.1466, -21.9426),
+        (0.0, 0.0),
+        (-16.5, 179.99),
+    ] {
+        let point = snap(lat, lon, 5.0);
+        let off = distance_km((lat, lon), point);
+        assert!(
+            off <= 5.0 * 0.72,
+            "{lat},{lon} → {point:?} is {off} km away"
+        );
+        assert!((-180.0..=180.0).contains(&point.1), "{point:?}");
+    }
+}
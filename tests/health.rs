@@ -0,0 +1,61 @@
+use reqwest::StatusCode;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use weather_helper::health::{
+    ErrorClass, ServiceHealth, record_attempt, record_error, snapshot, summary,
+};
+
+#[test]
+fn statuses_are_classified_by_who_is_at_fault() {
+    let class = ErrorClass::of_status;
+    assert_eq!(class(StatusCode::BAD_REQUEST), ErrorClass::Rejected);
+    assert_eq!(
+        class(StatusCode::TOO_MANY_REQUESTS),
+        ErrorClass::RateLimited
+    );
+    assert_eq!(class(StatusCode::BAD_GATEWAY), ErrorClass::ServerError);
+    assert_eq!(class(StatusCode::REQUEST_TIMEOUT), ErrorClass::Timeout);
+    assert_eq!(ErrorClass::Rejected.blame(), "your configuration");
+    assert_eq!(ErrorClass::Network.blame(), "your network");
+    assert_eq!(ErrorClass::ServerError.blame(), "the API");
+}
+
+#[test]
+fn attempts_and_errors_are_counted_per_service() {
+    let url = "https://health-test.example/v1/forecast?latitude=1";
+    record_attempt(url, 1, Duration::from_millis(100));
+    record_error(url, ErrorClass::ServerError);
+    record_attempt(url, 2, Duration::from_millis(300));
+    let health = snapshot()["health-test.example"].clone();
+    assert_eq!(health.requests, 2);
+    assert_eq!(health.retries, 1);
+    assert_eq!(health.average_latency(), Some(Duration::from_millis(200)));
+    assert_eq!(health.failures(), 1);
+}
+
+#[test]
+fn summary_lists_services_and_the_likely_culprit() {
+    let service = |requests, retries, ms, errors: &[(ErrorClass, u64)]| ServiceHealth {
+        requests,
+        retries,
+        latency: Duration::from_millis(ms),
+        errors: errors.iter().copied().collect(),
+    };
+    let mut health = BTreeMap::new();
+    health.insert(
+        "open-meteo".to_string(),
+        service(12, 1, 2880, &[(ErrorClass::ServerError, 1)]),
+    );
+    health.insert("nominatim".to_string(), service(3, 0, 1830, &[]));
+    assert_eq!(
+        summary(&health),
+        "Provider health:\n  nominatim: 3 requests, avg 610 ms\n  open-meteo: 12 requests (1 retry), avg 240 ms, 1 server error (5xx)\nMost failures point at the API.\n"
+    );
+
+    // A tie between the network and the API is reported as the network.
+    health.insert(
+        "nominatim".to_string(),
+        service(3, 2, 30, &[(ErrorClass::Timeout, 1)]),
+    );
+    assert!(summary(&health).ends_with("Most failures point at your network.\n"));
+}
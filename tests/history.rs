@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use std::fs;
+use weather_helper::Units;
+use weather_helper::history::{
+    self, Day, Entry, HistoryMode, format_size, note_path, parse_size, rotated_path,
+};
+
+fn d(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn entry(fetched: &str, note: &str, days: &[(&str, f64, f64)]) -> Entry {
+    Entry {
+        fetched: fetched.into(),
+        note: note.into(),
+        city: "Rome".into(),
+        label: "Forecast".into(),
+        days: days
+            .iter()
+            .map(|(date, tmax_c, tmin_c)| Day {
+                date: d(date),
+                tmax_c: *tmax_c,
+                tmin_c: *tmin_c,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn sizes_and_modes_parse() {
+    assert_eq!(parse_size("5MB").unwrap(), 5 * 1024 * 1024);
+    assert_eq!(parse_size("512k").unwrap(), 512 * 1024);
+    assert_eq!(parse_size("1.5 KB").unwrap(), 1536);
+    assert_eq!(parse_size("300").unwrap(), 300);
+    assert!(parse_size("lots").is_err());
+    assert!(parse_size("5 parsecs").is_err());
+    assert_eq!(format_size(history::DEFAULT_LIMIT), "5MB");
+    assert_eq!(format_size(2048), "2KB");
+    assert_eq!(format_size(1500), "1500");
+
+    assert_eq!(
+        HistoryMode::parse("per-note").unwrap(),
+        HistoryMode::PerNote
+    );
+    assert_eq!(HistoryMode::parse("").unwrap(), HistoryMode::Off);
+    assert!(HistoryMode::parse("sometimes").is_err());
+    assert_eq!(
+        note_path("Trips/Rome.md"),
+        std::path::Path::new("Trips/Rome.history.jsonl")
+    );
+    assert_eq!(HistoryMode::Off.log_path("Trips/Rome.md"), None);
+}
+
+#[test]
+fn appends_rotate_and_read_back_in_order() {
+    let dir = std::env::temp_dir().join(format!("wh-history-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let log = dir.join("logs").join("history.jsonl");
+
+    let first = entry(
+        "2025-08-01T08:00:00+02:00",
+        "Rome.md",
+        &[("2025-08-20", 30.0, 20.0)],
+    );
+    let second = entry(
+        "2025-08-10T08:00:00+02:00",
+        "Rome.md",
+        &[("2025-08-20", 32.5, 21.0)],
+    );
+    history::append(&log, &first, 1024).unwrap();
+    // A write cut short leaves a torn line; reading skips it.
+    fs::write(&log, fs::read_to_string(&log).unwrap() + "{\"fetched\":\n").unwrap();
+    history::append(&log, &second, 1024).unwrap();
+    assert_eq!(history::read(&log), [first.clone(), second.clone()]);
+    assert!(!rotated_path(&log).exists());
+
+    // Past the limit the log moves aside; both halves are still read.
+    history::append(&log, &first, 1).unwrap();
+    assert!(rotated_path(&log).exists());
+    assert_eq!(history::read(&log), [first.clone(), second, first]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn show_filters_by_note_and_date() {
+    let entries = [
+        entry(
+            "2025-08-01T08:00:00+02:00",
+            "Trips/Rome.md",
+            &[("2025-08-20", 30.0, 20.0), ("2025-08-21", 31.0, 21.0)],
+        ),
+        entry(
+            "2025-08-02T08:00:00+02:00",
+            "Trips/Oslo.md",
+            &[("2025-08-20", 18.0, 9.0)],
+        ),
+        entry(
+            "2025-08-10T08:00:00+02:00",
+            "Trips/Rome.md",
+            &[("2025-08-20", 32.5, 21.0)],
+        ),
+    ];
+    let rome = history::for_note(&entries, "Rome.md");
+    assert_eq!(rome.len(), 2);
+    assert_eq!(
+        history::show(&rome, Some(d("2025-08-20")), Units::Celsius),
+        "2025-08-01T08:00:00+02:00 Trips/Rome.md: 2025-08-20 30.0/20.0 °C\n\
+         2025-08-10T08:00:00+02:00 Trips/Rome.md: 2025-08-20 32.5/21.0 °C\n"
+    );
+    assert_eq!(
+        history::show(&rome[..1], None, Units::Fahrenheit),
+        "2025-08-01T08:00:00+02:00 Trips/Rome.md: 2025-08-20 86.0/68.0, 2025-08-21 87.8/69.8 °F\n"
+    );
+    assert_eq!(
+        history::show(&rome, Some(d("2025-09-01")), Units::Celsius),
+        "No matching history.\n"
+    );
+}
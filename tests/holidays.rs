@@ -0,0 +1,22 @@
+use weather_helper::holidays::{Holiday, during, render_holidays};
+
+#[test]
+fn holidays_in_the_trip_are_listed() {
+    let json = r#"[
+        {"date":"2025-08-15","localName":"Ferragosto","name":"Assumption Day","countryCode":"IT","global":true,"types":["Public"]},
+        {"date":"2025-06-02","localName":"Festa della Repubblica","name":"Republic Day","countryCode":"IT","global":true},
+        {"date":"2025-08-16","localName":"San Rocco","name":"San Rocco","global":false}
+    ]"#;
+    let all: Vec<Holiday> = serde_json::from_str(json).unwrap();
+    let found = during(
+        all,
+        "2025-08-14".parse().unwrap(),
+        "2025-08-20".parse().unwrap(),
+    );
+    assert_eq!(found.len(), 2);
+    assert_eq!(
+        render_holidays(&found),
+        "**Public holidays**\n\n- Aug 15 Ferragosto (Assumption Day) — expect closures\n- Aug 16 San Rocco — expect closures, some regions\n"
+    );
+    assert_eq!(render_holidays(&[]), "");
+}
@@ -0,0 +1,87 @@
+//! Hook commands run through `sh`, so these only run on Unix.
+#![cfg(unix)]
+
+use std::fs;
+use weather_helper::hooks::{
+    self, Hooks, after_note, after_run, notify_alerts, read_note, write_note,
+};
+use weather_helper::migrate::{Migration, backup_path, migrate_vault};
+
+#[test]
+fn hooks_wrap_note_reads_and_writes() {
+    let dir = std::env::temp_dir().join(format!("wh-hooks-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let note = dir.join("Rome.md");
+    // A stand-in for encryption: the file on disk is reversed text.
+    fs::write(&note, "emoR #\n").unwrap();
+    hooks::set(Hooks {
+        pre_read: Some(r#"rev "$1""#.into()),
+        post_write: Some(r#"rev "$WEATHER_HELPER_NOTE" > "$1.tmp" && mv "$1.tmp" "$1""#.into()),
+        ..Hooks::default()
+    });
+    let text = read_note(&note).unwrap();
+    assert_eq!(text, "# Rome\n");
+    write_note(&note, "# Roma\n").unwrap();
+    assert_eq!(fs::read_to_string(&note).unwrap(), "amoR #\n");
+    assert_eq!(read_note(&note).unwrap(), "# Roma\n");
+
+    // Migrations go through the same hooks; the backup is the stored file.
+    let vault = dir.join("vault");
+    fs::create_dir_all(&vault).unwrap();
+    let trip = vault.join("Oslo.md");
+    let stored = "---\nolsO :ytic\n---\n";
+    fs::write(&trip, stored).unwrap();
+    let rename = Migration::RenameKey {
+        from: "city".into(),
+        to: "city-place".into(),
+    };
+    let done = migrate_vault(vault.to_str().unwrap(), &[rename], false, true);
+    assert_eq!(done[0].result.as_ref().unwrap(), &["city → city-place"]);
+    assert_eq!(
+        fs::read_to_string(&trip).unwrap(),
+        "---\nolsO :ecalp-ytic\n---\n"
+    );
+    assert_eq!(read_note(&trip).unwrap(), "---\ncity-place: Oslo\n---\n");
+    assert_eq!(fs::read_to_string(backup_path(&trip)).unwrap(), stored);
+
+    hooks::set(Hooks {
+        pre_read: Some("echo locked >&2; exit 3".into()),
+        ..Hooks::default()
+    });
+    let err = read_note(&note).unwrap_err().to_string();
+    assert!(err.contains("pre-read hook failed"), "{err}");
+    assert!(err.contains("locked"), "{err}");
+
+    hooks::set(Hooks {
+        on_alert: Some(r#"printf '%s' "$WEATHER_HELPER_ALERTS" > "$1.alerts""#.into()),
+        ..Hooks::default()
+    });
+    notify_alerts(&note, "2025-08-21 tmax_f 97.2 > 95");
+    let sent = fs::read_to_string(dir.join("Rome.md.alerts")).unwrap();
+    assert_eq!(sent, "2025-08-21 tmax_f 97.2 > 95");
+
+    hooks::set(Hooks {
+        after_note: Some(r#"cat > "$1.outcome""#.into()),
+        after_run: Some(r#"cat > "$1/run.json""#.into()),
+        ..Hooks::default()
+    });
+    after_note(&note, r#"{"outcome":"updated"}"#);
+    assert_eq!(
+        fs::read_to_string(dir.join("Rome.md.outcome")).unwrap(),
+        r#"{"outcome":"updated"}"#
+    );
+    after_run(&dir, r#"{"notes":[]}"#);
+    assert_eq!(
+        fs::read_to_string(dir.join("run.json")).unwrap(),
+        r#"{"notes":[]}"#
+    );
+
+    // A failing hook is only logged; nothing it could undo has gone wrong.
+    hooks::set(Hooks {
+        after_run: Some("exit 1".into()),
+        ..Hooks::default()
+    });
+    after_run(&dir, "{}");
+    hooks::set(Hooks::default());
+}
@@ -0,0 +1,100 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use weather_helper::i18n::{Languages, messages};
+use weather_helper::{DayTemp, NoteMeta, Place, PreparedNote, RunOptions, Units, summarize_with};
+
+fn day(d: u32, tmax: f64, tmin: f64) -> DayTemp {
+    DayTemp {
+        date: NaiveDate::from_ymd_opt(2025, 8, d).unwrap(),
+        tmax,
+        tmin,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: tmin * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn languages_parse_from_codes_and_locales() {
+    assert_eq!(Languages::default().name(), "en");
+    assert_eq!(Languages::parse("en, it").unwrap().name(), "en, it");
+    assert_eq!(Languages::parse("it-IT+EN+it").unwrap().name(), "it, en");
+    assert_eq!(messages("de_AT").unwrap().lang, "de");
+    let err = Languages::parse("en, tlh").unwrap_err().to_string();
+    assert!(err.contains("'tlh'") && err.contains("en, it, de"), "{err}");
+    assert!(Languages::parse(" , ").is_err());
+}
+
+#[test]
+fn summary_lines_follow_the_languages_in_order() {
+    let data = [day(20, 29.0, 15.0), day(21, 24.0, 18.0)];
+    let english = summarize_with(&data, Units::Celsius, &Languages::default());
+    assert_eq!(
+        english.note,
+        "_2 days • High range 24° → 29° • Low range 15° → 18°_"
+    );
+    let both = summarize_with(&data, Units::Celsius, &Languages::parse("it, de").unwrap());
+    assert_eq!(
+        both.note,
+        "_2 giorni • Massime 24° → 29° • Minime 15° → 18°_  \n\
+         _2 Tage • Höchstwerte 24° → 29° • Tiefstwerte 15° → 18°_"
+    );
+    assert_eq!(both.max, english.max);
+    let none = summarize_with(&[], Units::Celsius, &Languages::parse("en, fr").unwrap());
+    assert_eq!(none.note, "_No data returned_  \n_Aucune donnée reçue_");
+}
+
+#[test]
+fn blocks_carry_one_summary_line_per_language() {
+    let note = PreparedNote {
+        meta: NoteMeta {
+            city: "Rome".into(),
+            path: "Rome.md".into(),
+            ..NoteMeta::default()
+        },
+        opts: RunOptions {
+            units: Units::Celsius,
+            summary_lang: Languages::parse("en, it").unwrap(),
+            ..RunOptions::default()
+        },
+        place: Place {
+            name: "Rome".into(),
+            latitude: 41.89,
+            longitude: 12.48,
+            timezone: "Europe/Rome".into(),
+            country_code: Some("IT".into()),
+            elevation: None,
+        },
+        label: "Forecast for Rome 2025-08-20 → 2025-08-21".into(),
+        data: vec![day(20, 30.0, 20.0), day(21, 28.0, 19.0)],
+        notes: BTreeMap::new(),
+        extra: vec!["**Pack**: sunscreen".into()],
+        sections: vec![],
+        divider: None,
+        trip: None,
+        content: String::new(),
+        separate: None,
+        alerts: vec![],
+        columns: vec![],
+        map_image: None,
+    };
+    let block = note.block();
+    assert!(
+        block.contains(
+            "_2 days • High range 28° → 30° • Low range 19° → 20°_  \n\
+             _2 giorni • Massime 28° → 30° • Minime 19° → 20°_  \n\
+             **Pack**: sunscreen"
+        ),
+        "{block}"
+    );
+    let italian = PreparedNote {
+        opts: RunOptions {
+            summary_lang: Languages::parse("it").unwrap(),
+            ..note.opts.clone()
+        },
+        ..note
+    };
+    let block = italian.block();
+    assert!(block.contains("_2 giorni •"), "{block}");
+    assert!(!block.contains("_2 days"), "{block}");
+}
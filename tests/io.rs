@@ -0,0 +1,66 @@
+use std::fs;
+use weather_helper::io::{SyncPolicy, temp_path, write, write_new};
+
+fn scratch(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("wh-io-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn write_replaces_the_file_and_leaves_no_temp_behind() {
+    let dir = scratch("write");
+    let note = dir.join("Rome.md");
+    write(&note, "old").unwrap();
+    write(&note, "new").unwrap();
+    assert_eq!(fs::read_to_string(&note).unwrap(), "new");
+    let names: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(names, vec!["Rome.md"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn write_keeps_the_mode_bits() {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = scratch("mode");
+    let note = dir.join("Rome.md");
+    fs::write(&note, "old").unwrap();
+    fs::set_permissions(&note, fs::Permissions::from_mode(0o640)).unwrap();
+    write(&note, "new").unwrap();
+    assert_eq!(
+        fs::metadata(&note).unwrap().permissions().mode() & 0o777,
+        0o640
+    );
+}
+
+#[test]
+fn write_new_refuses_to_overwrite() {
+    let dir = scratch("new");
+    let note = dir.join("Rome.md");
+    write_new(&note, "first").unwrap();
+    assert!(write_new(&note, "second").is_err());
+    assert_eq!(fs::read_to_string(&note).unwrap(), "first");
+}
+
+#[test]
+fn temp_paths_are_hidden_unique_and_keep_the_extension() {
+    let chart = std::path::Path::new("notes/Rome-forecast.png");
+    let (a, b) = (temp_path(chart), temp_path(chart));
+    assert_ne!(a, b);
+    assert_eq!(a.parent(), chart.parent());
+    let name = a.file_name().unwrap().to_string_lossy().to_string();
+    assert!(name.starts_with(".Rome-forecast."), "{name}");
+    assert!(name.ends_with(".png"), "{name}");
+}
+
+#[test]
+fn sync_policy_names_round_trip() {
+    for policy in [SyncPolicy::Off, SyncPolicy::File, SyncPolicy::Full] {
+        assert_eq!(SyncPolicy::parse(policy.name()).unwrap(), policy);
+    }
+    assert!(SyncPolicy::parse("always").is_err());
+}
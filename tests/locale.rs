@@ -0,0 +1,66 @@
+use std::fs;
+use weather_helper::locale::{hints, parse_locale};
+
+fn yaml(s: &str) -> serde_yaml::Value {
+    serde_yaml::from_str(s).unwrap()
+}
+
+fn pairs(list: &[(&str, &str)]) -> Vec<(String, String)> {
+    list.iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn locales_split_into_language_and_region() {
+    assert_eq!(
+        parse_locale("de-DE"),
+        Some(("de".into(), Some("DE".into())))
+    );
+    assert_eq!(
+        parse_locale("zh_Hant_tw"),
+        Some(("zh".into(), Some("TW".into())))
+    );
+    assert_eq!(parse_locale("fr"), Some(("fr".into(), None)));
+    assert_eq!(parse_locale("German"), None);
+    assert_eq!(parse_locale(""), None);
+}
+
+#[test]
+fn properties_imply_units_and_language() {
+    assert_eq!(
+        hints(&yaml("locale: en-US")),
+        pairs(&[("units", "fahrenheit"), ("language", "en")])
+    );
+    assert_eq!(
+        hints(&yaml("locale: de-DE")),
+        pairs(&[("units", "celsius"), ("language", "de")])
+    );
+    // An explicit unit property beats the locale's region.
+    assert_eq!(
+        hints(&yaml("lang: en-US\nunits: metric")),
+        pairs(&[("units", "celsius"), ("language", "en")])
+    );
+    assert_eq!(
+        hints(&yaml("temperature-unit: °F")),
+        pairs(&[("units", "fahrenheit")])
+    );
+    // Properties that mean something else are ignored.
+    assert_eq!(hints(&yaml("units: 3\nlanguage: Italian")), vec![]);
+}
+
+#[test]
+fn hints_yield_to_weather_overrides() {
+    let dir = std::env::temp_dir().join(format!("wh-locale-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Boston.md");
+    fs::write(
+        &path,
+        "---\ncity: Boston\narrival: 2025-08-20\ndeparture: 2025-08-22\nlocale: en-US\nweather-units: celsius\n---\n",
+    )
+    .unwrap();
+    let meta = weather_helper::extract_meta(&path).unwrap();
+    assert_eq!(meta.overrides["units"], "celsius");
+    assert_eq!(meta.overrides["language"], "en");
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use weather_helper::http::Client;
+use weather_helper::map::{MapMode, embed, fetch_image, image_path, map_line, save_image};
+
+#[test]
+fn map_modes_and_links() {
+    assert_eq!(MapMode::parse("on").unwrap(), MapMode::Link);
+    assert_eq!(MapMode::parse("Image").unwrap(), MapMode::Image);
+    assert_eq!(MapMode::parse("false").unwrap(), MapMode::Off);
+    assert!(MapMode::parse("satellite").is_err());
+    assert_eq!(
+        map_line(41.90278, 12.49636),
+        "**Map**: [41.9028, 12.4964](https://www.openstreetmap.org/?mlat=41.9028&mlon=12.4964#map=12/41.9028/12.4964)"
+    );
+}
+
+#[test]
+fn images_go_to_attachments_next_to_the_note() {
+    let image = image_path("trips/Rome trip.md", 41.90278, 12.49636);
+    assert_eq!(
+        image,
+        Path::new("trips/attachments/Rome trip-map-41.903_12.496.png")
+    );
+    assert_eq!(
+        embed(&image),
+        "![Map](attachments/Rome%20trip-map-41.903_12.496.png)\n"
+    );
+}
+
+#[tokio::test]
+async fn image_is_downloaded_once() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            let mut buf = [0u8; 2048];
+            let _ = sock.read(&mut buf).await;
+            let body = b"\x89PNG fake";
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: image/png\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = sock.write_all(head.as_bytes()).await;
+            let _ = sock.write_all(body).await;
+        }
+    });
+    // SAFETY: no other test in this binary reads the variable.
+    unsafe { std::env::set_var("STATIC_MAP_BASE", format!("http://{addr}/staticmap")) };
+
+    let dir = std::env::temp_dir().join(format!("wh-map-{}", std::process::id()));
+    let note = dir.join("rome.md");
+    let image = image_path(note.to_str().unwrap(), 41.9, 12.5);
+    let client = Client::new(None).unwrap();
+    let bytes = fetch_image(&client, 41.9, 12.5, &image).await.unwrap();
+    assert_eq!(bytes.as_deref(), Some(&b"\x89PNG fake"[..]));
+    // Fetching writes nothing; the image is saved with the note.
+    assert!(!image.exists());
+    save_image(&image, &bytes.unwrap()).unwrap();
+    assert_eq!(
+        fetch_image(&client, 41.9, 12.5, &image).await.unwrap(),
+        None
+    );
+    assert_eq!(std::fs::read(&image).unwrap(), b"\x89PNG fake");
+    assert_eq!(hits.load(Ordering::SeqCst), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,43 @@
+use weather_helper::markers::{BEGIN, VERSION, check_supported, migrate, version, versions};
+use weather_helper::{render_error_block, upsert_weather_block};
+
+const V1: &str = "## Weather Forecast\n<!-- WEATHER:BEGIN -->\nold\n<!-- WEATHER:END -->\n";
+
+#[test]
+fn begin_marker_carries_the_current_version() {
+    assert_eq!(version(BEGIN), Some(VERSION));
+    let block = render_error_block(chrono::NaiveDate::MIN, "HTTP 500");
+    assert_eq!(version(&block), Some(VERSION));
+}
+
+#[test]
+fn unversioned_blocks_are_v1() {
+    assert_eq!(version(V1), Some(1));
+    assert_eq!(version("# Rome\n"), None);
+    assert_eq!(
+        versions(&format!(
+            "{V1}\n<!-- WEATHER:BEGIN v7 -->\n<!-- WEATHER:END -->"
+        )),
+        vec![1, 7]
+    );
+}
+
+#[test]
+fn migration_upgrades_every_block_and_keeps_the_rest() {
+    let note = format!("# Rome\n\n{V1}\ntext\n\n{V1}");
+    let migrated = migrate(&note).unwrap();
+    assert_eq!(migrated, note.replace("<!-- WEATHER:BEGIN -->", BEGIN));
+    assert_eq!(migrate(&migrated).unwrap(), migrated);
+}
+
+#[test]
+fn blocks_from_a_newer_version_are_left_alone() {
+    let newer = "## Weather Forecast\n<!-- WEATHER:BEGIN v99 -->\nfuture\n<!-- WEATHER:END -->\n";
+    assert!(check_supported(V1).is_ok());
+    assert!(check_supported(newer).is_err());
+    assert!(migrate(newer).is_err());
+    let mut content = format!("# Rome\n\n{newer}");
+    let before = content.clone();
+    assert!(upsert_weather_block(&mut content, V1).is_err());
+    assert_eq!(content, before);
+}
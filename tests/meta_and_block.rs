@@ -3,6 +3,7 @@
 //! Covers:
 //! - extract_meta success and error paths
 //! - upsert_weather_block append/insert/replace idempotency
+//! - marker examples in code are left alone
 
 use std::fs;
 use std::path::PathBuf;
@@ -71,3 +72,233 @@ fn upsert_block_variants() {
     assert!(content3.contains("NEW"));
     assert!(!content3.contains("OLD"));
 }
+
+#[test]
+fn upsert_merges_duplicate_sections() {
+    let old = |text: &str| {
+        format!("## Weather Forecast\n<!-- WEATHER:BEGIN -->\n{text}\n<!-- WEATHER:END -->\n")
+    };
+    let new_block = "## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\nNEW\n<!-- WEATHER:END -->\n";
+    let mut content = format!(
+        "# Trip\n\n{}\n## Notes\nbring hat\n\n{}\n\n{}",
+        old("first"),
+        old("second"),
+        old("third")
+    );
+    assert_eq!(weather_helper::count_weather_blocks(&content), 3);
+    weather_helper::upsert_weather_block(&mut content, new_block).unwrap();
+    assert_eq!(
+        content,
+        format!("# Trip\n\n{new_block}\n## Notes\nbring hat\n")
+    );
+    assert_eq!(weather_helper::count_weather_blocks(&content), 1);
+}
+
+#[test]
+fn upsert_ignores_marker_examples_in_code() {
+    let example = "## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\nEXAMPLE\n<!-- WEATHER:END -->\n";
+    let new_block = "## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\nNEW\n<!-- WEATHER:END -->\n";
+    let note = format!(
+        "# Setup\n\nThe block looks like:\n\n```md\n{example}```\n\n    {}\n\nInline: `<!-- WEATHER:END -->`.\n",
+        example.lines().nth(1).unwrap()
+    );
+    assert_eq!(weather_helper::count_weather_blocks(&note), 0);
+
+    let mut content = note.clone();
+    weather_helper::upsert_weather_block(&mut content, new_block).unwrap();
+    assert_eq!(content, format!("{note}\n\n{new_block}"));
+
+    // Once a real section exists it is the one replaced.
+    let mut again = content.clone();
+    weather_helper::upsert_weather_block(&mut again, new_block).unwrap();
+    assert_eq!(again, content);
+    assert_eq!(weather_helper::count_weather_blocks(&again), 1);
+}
+
+#[test]
+fn arrival_and_departure_times_annotate_days() {
+    let path = write_temp_file(
+        "meta_times.md",
+        r#"---
+city: Rome
+arrival: 2025-08-20T22:30
+departure: 2025-08-25T10:00
+---
+"#,
+    );
+    let meta = extract_meta(&path).expect("meta ok");
+    let _ = fs::remove_file(&path);
+    assert_eq!(meta.arrival_time.unwrap().to_string(), "22:30:00");
+
+    let notes = weather_helper::day_notes(&meta);
+    let arrival = &notes[&meta.arrival];
+    assert!(!arrival.counted);
+    assert!(arrival.text.contains("late"));
+    let departure = &notes[&meta.departure];
+    assert!(departure.counted);
+    assert!(departure.text.contains("half day"));
+
+    let data = vec![weather_helper::DayTemp {
+        date: meta.departure,
+        tmax: 30.0,
+        tmin: 20.0,
+        tmax_f: 86.0,
+        tmin_f: 68.0,
+        ..Default::default()
+    }];
+    let table = weather_helper::render_table_with_notes(&data, &notes);
+    assert!(table.contains("| Notes |"));
+    assert!(table.contains("| 2025-08-25 | 86 | 68 | 30 | 20 | depart 10:00 (half day) |"));
+}
+
+#[test]
+fn coordinate_only_note() {
+    let path = write_temp_file(
+        "meta_coords.md",
+        r#"---
+lat: 45.83
+lon: "7.73"
+arrival: 2025-02-01
+departure: 2025-02-07
+---
+"#,
+    );
+    let meta = extract_meta(&path).expect("meta ok");
+    let _ = fs::remove_file(&path);
+    assert_eq!(meta.coords, Some((45.83, 7.73)));
+    assert!(meta.city.is_empty());
+
+    let half = write_temp_file(
+        "meta_half_coords.md",
+        "---\nlat: 45.83\narrival: 2025-02-01\ndeparture: 2025-02-07\n---\n",
+    );
+    assert!(extract_meta(&half).is_err());
+    let _ = fs::remove_file(&half);
+}
+
+#[test]
+fn in_progress_table_has_today_divider() {
+    let day = |d: &str, c: f64| weather_helper::DayTemp {
+        date: d.parse().unwrap(),
+        tmax: c,
+        tmin: c - 10.0,
+        tmax_f: c * 1.8 + 32.0,
+        tmin_f: (c - 10.0) * 1.8 + 32.0,
+        ..Default::default()
+    };
+    let data = vec![
+        day("2025-08-20", 30.0),
+        day("2025-08-21", 31.0),
+        day("2025-08-22", 32.0),
+    ];
+    let notes = std::collections::BTreeMap::new();
+    let today = "2025-08-21".parse().ok();
+    let table = weather_helper::render_table_split(&data, &notes, today);
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[2].starts_with("| 2025-08-20"));
+    assert_eq!(lines[3], "| **— today —** | | | | |");
+    assert!(lines[4].starts_with("| 2025-08-21"));
+
+    // No divider when every day is still ahead.
+    let ahead = weather_helper::render_table_split(&data, &notes, "2025-08-01".parse().ok());
+    assert!(!ahead.contains("today"));
+}
+
+#[test]
+fn refresh_marks_forecast_trends() {
+    let old = "## Weather Forecast\n<!-- WEATHER:BEGIN -->\n**Forecast**\n\n| Date | High (°F) | Low (°F) | High (°C) | Low (°C) |\n|---|---:|---:|---:|---:|\n| 2025-08-20 | 86 | 68 | 30 | 20 |\n| 2025-08-21 | 88 | 70 | 31 | 21 |\n<!-- WEATHER:END -->\n";
+    let previous = weather_helper::previous_temps(old);
+    assert_eq!(previous.len(), 2);
+
+    let day = |d: &str, c: f64, lo: f64| weather_helper::DayTemp {
+        date: d.parse().unwrap(),
+        tmax: c,
+        tmin: lo,
+        tmax_f: c * 1.8 + 32.0,
+        tmin_f: lo * 1.8 + 32.0,
+        ..Default::default()
+    };
+    let data = vec![day("2025-08-20", 32.0, 19.0), day("2025-08-21", 31.2, 21.0)];
+    let trends = weather_helper::trend_notes(&data, &previous, weather_helper::Units::Celsius);
+    assert_eq!(trends.len(), 1, "unchanged days get no marker");
+    assert_eq!(trends[&data[0].date], "high ↑2°, low ↓1°");
+}
+
+#[test]
+fn roadtrip_waypoints_are_read() {
+    let path = write_temp_file(
+        "meta_roadtrip.md",
+        "---\ncity: Bolzano\ntrip-type: Roadtrip\nwaypoints: [Innsbruck, \" Brenner \"]\narrival: 2025-01-10\ndeparture: 2025-01-12\n---\n",
+    );
+    let meta = extract_meta(&path).expect("meta ok");
+    let _ = fs::remove_file(&path);
+    assert!(meta.is_roadtrip());
+    let names: Vec<&str> = meta.waypoints.iter().map(|w| w.label()).collect();
+    assert_eq!(names, ["Innsbruck", "Brenner"]);
+}
+
+#[test]
+fn provider_and_model_shorthands_apply_per_note() {
+    use weather_helper::RunOptions;
+    use weather_helper::provider::Provider;
+    let path = write_temp_file(
+        "meta_model.md",
+        "---\ncity: Bergen\nprovider: met-no\nmodel: ecmwf_ifs025\nweather-model: icon_seamless\narrival: 2025-01-10\ndeparture: 2025-01-12\n---\n",
+    );
+    let meta = extract_meta(&path).expect("meta ok");
+    let _ = fs::remove_file(&path);
+    let opts = RunOptions::default().for_note(&meta).unwrap();
+    assert_eq!(opts.provider, Provider::MetNo);
+    // The `weather-` form wins over the shorthand.
+    assert_eq!(opts.model.as_deref(), Some("icon_seamless"));
+}
+
+#[test]
+fn failed_update_leaves_a_replaceable_error_block() {
+    use chrono::NaiveDate;
+    use weather_helper::{RunOptions, upsert_weather_block, write_error_block};
+
+    let path = write_temp_file(
+        "error_block.md",
+        "---\ncity: Rome\narrival: 2025-08-20\ndeparture: 2025-08-25\n---\n\n## Weather Forecast\n<!-- WEATHER:BEGIN -->\nold table\n<!-- WEATHER:END -->\n",
+    );
+    let meta = extract_meta(&path).unwrap();
+    let today = NaiveDate::from_ymd_opt(2025, 8, 1).unwrap();
+    write_error_block(
+        &meta,
+        &RunOptions::default(),
+        today,
+        "geocoding failed:\n  no match",
+    )
+    .unwrap();
+    let mut text = fs::read_to_string(&path).unwrap();
+    assert!(
+        text.contains("> ⚠️ weather update failed on 2025-08-01: geocoding failed: no match\n"),
+        "{text}"
+    );
+    assert!(!text.contains("old table"));
+
+    upsert_weather_block(
+        &mut text,
+        "## Weather Forecast\n<!-- WEATHER:BEGIN -->\nnew\n<!-- WEATHER:END -->\n",
+    )
+    .unwrap();
+    assert!(!text.contains("failed"));
+}
+
+#[tokio::test]
+async fn pinned_notes_are_reported_and_left_alone() {
+    use weather_helper::{NoteOutcome, PINNED, RunOptions, process_note};
+
+    let text = "---\ncity: Rome\narrival: 2025-08-20\ndeparture: 2025-08-25\nweather-pin: true\n---\n\n## Weather Forecast\n<!-- WEATHER:BEGIN -->\nwhat the forecast said\n<!-- WEATHER:END -->\n";
+    let path = write_temp_file("pinned.md", text);
+    let meta = extract_meta(&path).unwrap();
+    assert!(meta.pinned);
+    // The pin is not a setting, so it does not trip the per-note key check.
+    assert!(meta.overrides.is_empty());
+    let client = weather_helper::http::Client::default();
+    let outcome = process_note(&meta, &client, &RunOptions::default()).await;
+    assert_eq!(outcome, NoteOutcome::Skipped(PINNED.to_string()));
+    assert_eq!(fs::read_to_string(&path).unwrap(), text);
+    let _ = fs::remove_file(&path);
+}
@@ -0,0 +1,43 @@
+//! Metrics rendering tests (no network)
+
+use weather_helper::metrics;
+
+#[test]
+fn render_includes_counters_and_histogram() {
+    metrics::CACHE_HITS.inc();
+    metrics::REQUEST_LATENCY.observe(std::time::Duration::from_millis(200));
+    let text = metrics::render();
+    assert!(text.contains("# TYPE weather_helper_cache_hits_total counter"));
+    assert!(text.contains("weather_helper_request_duration_seconds_bucket{le=\"0.25\"} 1"));
+    assert!(text.contains("weather_helper_request_duration_seconds_bucket{le=\"0.1\"} 0"));
+    assert!(text.contains("weather_helper_request_duration_seconds_count 1"));
+}
+
+#[tokio::test]
+async fn serve_runs_answers_metrics_between_runs() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    let dir = std::env::temp_dir().join(format!("wh-serve-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let listener = metrics::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let opts = weather_helper::RunOptions {
+        verbosity: weather_helper::telemetry::Verbosity::Quiet,
+        ..Default::default()
+    };
+    let root = dir.to_str().unwrap().to_string();
+    let server = tokio::spawn(async move {
+        metrics::serve_runs(&root, &opts, std::time::Duration::from_millis(50), listener).await
+    });
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("weather_helper_cache_hits_total"));
+    server.abort();
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use weather_helper::dates::DateOrder;
+use weather_helper::migrate::{Migration, backup_path, migrate_content, migrate_vault};
+
+fn rename(from: &str, to: &str) -> Migration {
+    Migration::RenameKey {
+        from: from.into(),
+        to: to.into(),
+    }
+}
+
+fn iso_dates(order: DateOrder) -> Migration {
+    Migration::IsoDates {
+        keys: vec!["arrival".into(), "departure".into()],
+        order,
+    }
+}
+
+#[test]
+fn renames_keep_values_comments_and_order() {
+    let note = "---\ncity: \"Rome\" # capital\narrival: 2025-08-20\n---\n\ncity: not frontmatter\n";
+    let migrated = migrate_content(note, &[rename("city", "city-place")]).unwrap();
+    assert_eq!(
+        migrated.content,
+        "---\ncity-place: \"Rome\" # capital\narrival: 2025-08-20\n---\n\ncity: not frontmatter\n"
+    );
+    assert_eq!(migrated.changes, ["city → city-place"]);
+
+    let both = "---\ncity: Rome\ncity-place: Roma\n---\n";
+    assert!(migrate_content(both, &[rename("city", "city-place")]).is_err());
+    let none = migrate_content("# No frontmatter\n", &[rename("city", "city-place")]).unwrap();
+    assert!(none.changes.is_empty());
+}
+
+#[test]
+fn dates_become_iso_in_the_notes_own_order() {
+    let note = "---\narrival: '08/20/2025'\ndeparture: 2025-08-25T10:00\ndate-order: mdy\n---\n";
+    let migrated = migrate_content(note, &[iso_dates(DateOrder::DayFirst)]).unwrap();
+    assert_eq!(
+        migrated.content,
+        "---\narrival: 2025-08-20\ndeparture: 2025-08-25T10:00\ndate-order: mdy\n---\n"
+    );
+    assert_eq!(migrated.changes, ["arrival 08/20/2025 → 2025-08-20"]);
+
+    let bad = "---\narrival: someday\n---\n";
+    assert!(migrate_content(bad, &[iso_dates(DateOrder::DayFirst)]).is_err());
+}
+
+#[test]
+fn vault_migration_dry_runs_and_backs_up() {
+    let dir = std::env::temp_dir().join(format!("wh-migrate-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let note = dir.join("Rome.md");
+    let original = "---\ncity: Rome\narrival: 20/08/2025\n---\n";
+    fs::write(&note, original).unwrap();
+    fs::write(dir.join("Other.md"), "---\ncity-place: Oslo\n---\n").unwrap();
+    let root = dir.to_str().unwrap();
+    let migrations = [rename("city", "city-place"), iso_dates(DateOrder::DayFirst)];
+
+    let dry = migrate_vault(root, &migrations, true, true);
+    assert_eq!(dry.len(), 1);
+    assert_eq!(fs::read_to_string(&note).unwrap(), original);
+    assert!(!backup_path(&note).exists());
+
+    let done = migrate_vault(root, &migrations, false, true);
+    assert_eq!(
+        done[0].result.as_ref().unwrap(),
+        &["city → city-place", "arrival 20/08/2025 → 2025-08-20"]
+    );
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "---\ncity-place: Rome\narrival: 2025-08-20\n---\n"
+    );
+    assert_eq!(fs::read_to_string(backup_path(&note)).unwrap(), original);
+
+    // A second run finds nothing left to change.
+    assert!(migrate_vault(root, &migrations, false, true).is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
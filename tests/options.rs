@@ -0,0 +1,42 @@
+//! CLI option parsing tests (no network)
+
+use std::time::Duration;
+
+use weather_helper::parse_duration;
+
+#[test]
+fn parse_duration_units() {
+    assert_eq!(parse_duration("10s").unwrap(), Duration::from_secs(10));
+    assert_eq!(parse_duration("5m").unwrap(), Duration::from_secs(300));
+    assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+    assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+    assert_eq!(parse_duration("30").unwrap(), Duration::from_secs(30));
+    assert_eq!(
+        parse_duration("2d").unwrap(),
+        Duration::from_secs(2 * 86400)
+    );
+    assert_eq!(
+        parse_duration("1w").unwrap(),
+        Duration::from_secs(7 * 86400)
+    );
+}
+
+#[test]
+fn parse_duration_rejects_garbage() {
+    assert!(parse_duration("soon").is_err());
+    assert!(parse_duration("10x").is_err());
+    assert!(parse_duration("99999999999999999999999w").is_err());
+    assert!(parse_duration(&"9".repeat(400)).is_err());
+}
+
+#[test]
+fn verbosity_tiers() {
+    use weather_helper::telemetry::Verbosity;
+    assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+    assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+    assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+    assert_eq!(Verbosity::from_flags(false, 3), Verbosity::Trace);
+    assert!(!Verbosity::Quiet.shows_progress());
+    assert!(Verbosity::Verbose.shows_progress());
+    assert_eq!(Verbosity::Normal.default_filter(), "error");
+}
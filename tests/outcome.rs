@@ -0,0 +1,80 @@
+use weather_helper::output::Status;
+use weather_helper::{NoteOutcome, RunReport};
+
+fn report(outcomes: &[(&str, &[&str], NoteOutcome)]) -> RunReport {
+    let mut report = RunReport::default();
+    for (path, tags, outcome) in outcomes {
+        let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+        report.push(path, &tags, outcome.clone());
+    }
+    report
+}
+
+#[test]
+fn outcomes_map_to_status_lines() {
+    assert_eq!(NoteOutcome::Updated.status(), Status::Updated);
+    assert_eq!(NoteOutcome::Unchanged.status(), Status::Unchanged);
+    assert_eq!(NoteOutcome::Unchanged.detail(), None);
+    let failed = NoteOutcome::Failed("HTTP 500".into());
+    assert_eq!(failed.status(), Status::Failed);
+    assert_eq!(failed.detail(), Some("HTTP 500"));
+    assert!(!NoteOutcome::Skipped("no YAML frontmatter".into()).is_success());
+}
+
+#[test]
+fn skipped_notes_stay_out_of_the_tag_report() {
+    let report = report(&[
+        ("a.md", &["work"], NoteOutcome::Updated),
+        ("b.md", &["work"], NoteOutcome::Unchanged),
+        (
+            "c.md",
+            &[],
+            NoteOutcome::Skipped("no YAML frontmatter".into()),
+        ),
+        ("d.md", &[], NoteOutcome::Failed("HTTP 500".into())),
+    ]);
+    assert_eq!(
+        report.by_tag(),
+        vec![
+            (vec!["work".to_string()], true),
+            (vec!["work".to_string()], true),
+            (vec![], false),
+        ]
+    );
+    assert_eq!(report.count(&NoteOutcome::Skipped(String::new())), 1);
+}
+
+#[test]
+fn only_failures_and_deadlines_fail_the_run() {
+    let ok = report(&[
+        ("a.md", &[], NoteOutcome::Unchanged),
+        ("b.md", &[], NoteOutcome::Skipped("duration is -1".into())),
+    ]);
+    assert!(ok.clone().into_result().is_ok());
+    let late = RunReport {
+        deadline_reached: true,
+        ..ok
+    };
+    assert!(late.into_result().is_err());
+    let failed = report(&[("a.md", &[], NoteOutcome::Failed("timeout".into()))]);
+    assert!(failed.into_result().is_err());
+}
+
+#[test]
+fn reports_serialize_for_hooks() {
+    let report = report(&[
+        ("a.md", &["work"], NoteOutcome::Updated),
+        ("b.md", &[], NoteOutcome::Failed("HTTP 500".into())),
+    ]);
+    assert_eq!(
+        report.notes[1].to_json(),
+        serde_json::json!({"path": "b.md", "tags": [], "outcome": "failed", "detail": "HTTP 500"})
+    );
+    let json = report.to_json();
+    assert_eq!(json["notes"][0]["outcome"], "updated");
+    assert_eq!(json["notes"][0]["detail"], serde_json::Value::Null);
+    assert_eq!(json["updated"], 1);
+    assert_eq!(json["failed"], 1);
+    assert_eq!(json["skipped"], 0);
+    assert_eq!(json["deadline_reached"], false);
+}
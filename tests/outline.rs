@@ -0,0 +1,88 @@
+use weather_helper::outline::{
+    check_untouched, find, headings, protected_ranges, relevel, section_end,
+};
+use weather_helper::upsert_weather_block_under;
+
+const BLOCK: &str = "## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\nNEW\n<!-- WEATHER:END -->\n";
+
+#[test]
+fn headings_skip_frontmatter_and_code_fences() {
+    let note = "---\ntitle: '# not a heading'\n---\n# Trip\n\n```md\n## Example\n```\n\n## Logistics ##\n#hashtag\n";
+    let found: Vec<(usize, &str)> = headings(note)
+        .iter()
+        .map(|h| (h.level, &note[h.line.clone()]))
+        .collect();
+    assert_eq!(found, vec![(1, "# Trip\n"), (2, "## Logistics ##\n")]);
+    assert_eq!(headings(note)[1].title, "Logistics");
+}
+
+#[test]
+fn sections_end_at_the_next_heading_of_the_same_level() {
+    let note = "# Trip\n## Logistics\n### Flights\ntext\n## Packing\n";
+    let all = headings(note);
+    let logistics = find(&all, "logistics").unwrap();
+    assert_eq!(
+        &note[..section_end(note, &all, logistics)],
+        "# Trip\n## Logistics\n### Flights\ntext\n"
+    );
+    assert!(find(&all, "### Logistics").is_none());
+    assert_eq!(relevel(BLOCK, 3), BLOCK.replacen("##", "###", 1));
+}
+
+#[test]
+fn new_sections_go_at_the_end_of_the_parent_one_level_down() {
+    let mut note = "# Trip\n\n## Logistics\nTrain at 9.\n\n## Packing\n- hat\n".to_string();
+    upsert_weather_block_under(&mut note, BLOCK, Some("Logistics")).unwrap();
+    assert_eq!(
+        note,
+        format!("# Trip\n\n## Logistics\nTrain at 9.\n\n#{BLOCK}\n## Packing\n- hat\n")
+    );
+    // The next run replaces it where it is.
+    let again = note.clone();
+    upsert_weather_block_under(&mut note, BLOCK, Some("Logistics")).unwrap();
+    assert_eq!(note, again);
+}
+
+#[test]
+fn sections_outside_the_parent_are_moved_into_it() {
+    let old = "## Weather Forecast\n<!-- WEATHER:BEGIN -->\nOLD\n<!-- WEATHER:END -->\n";
+    let mut note = format!("# Trip\n\n{old}\n## Logistics\nTrain at 9.\n");
+    upsert_weather_block_under(&mut note, BLOCK, Some("## Logistics")).unwrap();
+    assert_eq!(
+        note,
+        format!("# Trip\n\n## Logistics\nTrain at 9.\n\n#{BLOCK}")
+    );
+}
+
+#[test]
+fn without_the_parent_heading_the_section_is_appended() {
+    let mut note = "# Trip\n\n```\n## Logistics\n```\n".to_string();
+    upsert_weather_block_under(&mut note, BLOCK, Some("Logistics")).unwrap();
+    assert_eq!(
+        note,
+        format!("# Trip\n\n```\n## Logistics\n```\n\n\n{BLOCK}")
+    );
+}
+
+#[test]
+fn code_blocks_and_frontmatter_are_never_rewritten() {
+    let example = "```md\n## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\nEXAMPLE\n<!-- WEATHER:END -->\n```\n";
+    let mut note = format!("---\ncity: Rome\n---\n# Trip\n\n## Logistics\n{example}");
+    upsert_weather_block_under(&mut note, BLOCK, Some("Logistics")).unwrap();
+    assert_eq!(
+        note,
+        format!("---\ncity: Rome\n---\n# Trip\n\n## Logistics\n{example}\n#{BLOCK}")
+    );
+    assert_eq!(protected_ranges(&note).len(), 2);
+
+    // Below a fence that is never closed the block would land in the code.
+    let open = "# Trip\n\n```md\nnotes\n";
+    let mut note = open.to_string();
+    let err = upsert_weather_block_under(&mut note, BLOCK, None).unwrap_err();
+    assert!(err.to_string().contains("code block"), "{err}");
+    assert_eq!(note, open);
+
+    let edited = "---\ncity: Roma\n---\n# Trip\n";
+    assert!(check_untouched("---\ncity: Rome\n---\n# Trip\n", edited, false).is_err());
+    assert!(check_untouched("---\ncity: Rome\n---\n# Trip\n", edited, true).is_ok());
+}
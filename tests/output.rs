@@ -0,0 +1,40 @@
+use weather_helper::output::{ColorChoice, Status, hyperlink, obsidian_uri, status_line};
+
+#[test]
+fn status_lines_align_without_color() {
+    assert_eq!(
+        status_line(Status::Updated, "trips/rome.md", None, false),
+        "updated  trips/rome.md"
+    );
+    assert_eq!(
+        status_line(Status::Failed, "trips/x.md", Some("HTTP 500"), false),
+        "failed   trips/x.md: HTTP 500"
+    );
+}
+
+#[test]
+fn colored_status_wraps_only_the_label() {
+    let line = status_line(Status::Skipped, "a.md", None, true);
+    assert_eq!(line, "\x1b[33mskipped \x1b[0m a.md");
+}
+
+#[test]
+fn color_choice_parsing_and_overrides() {
+    assert_eq!(ColorChoice::parse("Always").unwrap(), ColorChoice::Always);
+    assert!(ColorChoice::parse("sometimes").is_err());
+    assert!(ColorChoice::Always.enabled(false));
+    assert!(!ColorChoice::Never.enabled(true));
+    assert!(!ColorChoice::Auto.enabled(false));
+}
+
+#[test]
+fn obsidian_links_encode_the_path() {
+    assert_eq!(
+        obsidian_uri("/vault/trips/São Paulo.md"),
+        "obsidian://open?path=%2Fvault%2Ftrips%2FS%C3%A3o%20Paulo.md"
+    );
+    assert_eq!(
+        hyperlink("a.md", "obsidian://open?path=a.md"),
+        "\x1b]8;;obsidian://open?path=a.md\x1b\\a.md\x1b]8;;\x1b\\"
+    );
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use weather_helper::NoteMeta;
+use weather_helper::overlaps::find_overlaps;
+
+fn d(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn trip(path: &str, arrival: &str, departure: &str) -> NoteMeta {
+    NoteMeta {
+        city: "Rome".to_string(),
+        arrival: d(arrival),
+        departure: d(departure),
+        path: path.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn overlapping_trips_are_reported() {
+    let notes = vec![
+        trip("a.md", "2025-08-20", "2025-08-25"),
+        trip("b.md", "2025-08-23", "2025-08-30"),
+        // Arriving on the day the previous trip ends is fine.
+        trip("c.md", "2025-08-30", "2025-09-02"),
+    ];
+    let found = find_overlaps(&notes, d("2025-08-01"));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].first, "a.md");
+    assert_eq!(found[0].second, "b.md");
+    assert_eq!(
+        (found[0].from, found[0].to),
+        (d("2025-08-23"), d("2025-08-25"))
+    );
+}
+
+#[test]
+fn past_and_opted_out_trips_are_ignored() {
+    let mut side = trip("side.md", "2025-08-21", "2025-08-22");
+    side.overlap_ok = true;
+    let notes = vec![
+        trip("old.md", "2025-01-01", "2025-01-10"),
+        trip("older.md", "2025-01-05", "2025-01-08"),
+        trip("main.md", "2025-08-20", "2025-08-25"),
+        side,
+    ];
+    assert!(find_overlaps(&notes, d("2025-08-01")).is_empty());
+}
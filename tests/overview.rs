@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use std::fs;
+use weather_helper::RunOptions;
+use weather_helper::overview::{Overview, TripOverview, overview, report};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+#[tokio::test]
+async fn overview_lists_trips_under_way_and_to_come_without_writing() {
+    let dir = std::env::temp_dir().join(format!("wh-overview-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    // An unknown `weather-*` key fails each note before any request.
+    let mut notes = Vec::new();
+    for (name, arrival, departure) in [
+        ("oslo", "2025-12-20", "2025-12-24"),
+        ("rome", "2025-08-08", "2025-08-12"),
+        ("lima", "2025-08-01", "2025-08-03"),
+    ] {
+        let note = format!(
+            "---\ncity: {name}\narrival: {arrival}\ndeparture: {departure}\nweather-nope: x\n---\n\n# {name}\n"
+        );
+        fs::write(dir.join(format!("{name}.md")), &note).unwrap();
+        notes.push((name, note));
+    }
+    let opts = RunOptions {
+        today: Some(date("2025-08-10")),
+        ..RunOptions::default()
+    };
+    let overview = overview(dir.to_str().unwrap(), &opts).await.unwrap();
+    let listed: Vec<(&str, bool)> = overview
+        .trips
+        .iter()
+        .map(|t| (t.note.as_str(), t.under_way))
+        .collect();
+    assert_eq!(listed, [("rome.md", true), ("oslo.md", false)]);
+    assert_eq!(overview.trips[0].place, "rome");
+    assert!(overview.trips[0].error.is_some());
+    for (name, note) in notes {
+        assert_eq!(
+            fs::read_to_string(dir.join(format!("{name}.md"))).unwrap(),
+            note
+        );
+    }
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn report_shows_summaries_alerts_and_failures() {
+    let trip = |note: &str, place: &str| TripOverview {
+        note: note.to_string(),
+        place: place.to_string(),
+        arrival: date("2025-08-08"),
+        departure: date("2025-08-12"),
+        under_way: true,
+        summary: Vec::new(),
+        alerts: Vec::new(),
+        error: None,
+    };
+    let overview = Overview {
+        on: date("2025-08-10"),
+        trips: vec![
+            TripOverview {
+                summary: vec![
+                    "5 days • High range 24° → 29° • Low range 15° → 18°".to_string(),
+                    "5 giorni • Massime 24° → 29° • Minime 15° → 18°".to_string(),
+                ],
+                alerts: vec!["2025-08-11 tmax_f 97 > 95".to_string()],
+                ..trip("trips/rome.md", "Roma")
+            },
+            TripOverview {
+                under_way: false,
+                error: Some("geocoding failed".to_string()),
+                ..trip("oslo.md", "oslo")
+            },
+        ],
+    };
+    assert_eq!(
+        report(&overview),
+        "2 trip(s) under way or to come as of 2025-08-10:\n\
+         - Roma (2025-08-08 → 2025-08-12, under way) in trips/rome.md\n  \
+         5 days • High range 24° → 29° • Low range 15° → 18°\n  \
+         5 giorni • Massime 24° → 29° • Minime 15° → 18°\n  \
+         alert: 2025-08-11 tmax_f 97 > 95\n\
+         - oslo (2025-08-08 → 2025-08-12) in oslo.md\n  \
+         failed: geocoding failed\n"
+    );
+    let empty = Overview {
+        on: date("2025-08-10"),
+        trips: Vec::new(),
+    };
+    assert_eq!(
+        report(&empty),
+        "No trips under way or to come as of 2025-08-10.\n"
+    );
+    let json = serde_json::to_value(&overview).unwrap();
+    assert_eq!(json["trips"][0]["arrival"], "2025-08-08");
+    assert_eq!(json["trips"][1]["error"], "geocoding failed");
+}
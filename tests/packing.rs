@@ -0,0 +1,96 @@
+use weather_helper::DayTemp;
+use weather_helper::packing::{
+    Rule, Trip, checklist, default_outfit_rules, default_rules, eval_count, insert_once, matches,
+    outfit, outfit_line, render_checklist,
+};
+
+fn day(date: &str, tmax: f64, tmin: f64) -> DayTemp {
+    DayTemp {
+        date: date.parse().unwrap(),
+        tmax,
+        tmin,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: tmin * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn default_rules_follow_weather_and_country() {
+    let data = [day("2025-08-20", 33.0, 21.0), day("2025-08-21", 29.0, 19.0)];
+    let trip = Trip::new(2, &data, Some("it")).unwrap();
+    let items = checklist(&default_rules(), &trip).unwrap();
+    assert_eq!(
+        items,
+        ["sunscreen", "sun hat", "power adapter (Type C/F/L)"]
+    );
+
+    let cold = Trip::new(3, &[day("2025-01-10", 4.0, -2.0)], Some("US")).unwrap();
+    let items = checklist(&default_rules(), &cold).unwrap();
+    assert_eq!(items, ["warm jacket", "warm hat and gloves"]);
+}
+
+#[test]
+fn conditions_combine_and_accept_fahrenheit() {
+    let trip = Trip::new(8, &[day("2025-08-20", 28.0, 18.0)], Some("GB")).unwrap();
+    assert!(matches("high >= 80F, days > 7", &trip).unwrap());
+    assert!(!matches("high >= 85F", &trip).unwrap());
+    assert!(matches("country in IE GB", &trip).unwrap());
+    assert!(matches("high > 20C, plug not A B", &trip).unwrap());
+    assert!(matches("humidity > 3", &trip).is_err());
+
+    let rules = [Rule {
+        item: "adapter for {country}".into(),
+        when: "plug not G".into(),
+        count: None,
+    }];
+    assert!(checklist(&rules, &trip).unwrap().is_empty());
+}
+
+#[test]
+fn checklist_is_inserted_once_after_the_weather_block() {
+    let mut note = String::from(
+        "# Trip\n\n## Weather Forecast\n<!-- WEATHER:BEGIN -->\nx\n<!-- WEATHER:END -->\n\n## Notes\n",
+    );
+    let block = render_checklist(&["sunscreen".to_string()]);
+    assert!(insert_once(&mut note, &block));
+    assert!(note.contains(
+        "<!-- WEATHER:END -->\n\n## Packing List\n<!-- PACKING:BEGIN -->\n- [ ] sunscreen\n"
+    ));
+
+    // A ticked box is left alone on refresh.
+    let ticked = note.replace("- [ ] sunscreen", "- [x] sunscreen");
+    let mut again = ticked.clone();
+    assert!(!insert_once(
+        &mut again,
+        &render_checklist(&["sun hat".to_string()])
+    ));
+    assert_eq!(again, ticked);
+}
+
+#[test]
+fn outfit_counts_follow_formulas() {
+    let trip = Trip::new(10, &[day("2025-08-20", 27.0, 11.0)], Some("IT")).unwrap();
+    let counts = outfit(&default_outfit_rules(), &trip).unwrap();
+    let line = outfit_line(&counts).unwrap();
+    assert_eq!(
+        line,
+        "_Pack: 7 t-shirts, 4 pairs of shorts, 2 warm layers, 8 pairs of socks_"
+    );
+
+    assert_eq!(eval_count("max(nights - 2, 1) * 2", &trip).unwrap(), 14.0);
+    assert_eq!(eval_count("-(1 + 2)", &trip).unwrap(), -3.0);
+    assert!(eval_count("days / 0", &trip).is_err());
+    assert!(eval_count("min(days 3)", &trip).is_err());
+    assert!(eval_count("weeks", &trip).is_err());
+
+    let rules = [Rule {
+        item: "{n} rain shell{s}".into(),
+        when: "low < 15".into(),
+        count: Some("1".into()),
+    }];
+    assert_eq!(
+        outfit(&rules, &trip).unwrap(),
+        [(1, "1 rain shell".to_string())]
+    );
+}
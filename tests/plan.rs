@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+use weather_helper::plan::{Applied, Change, apply_change, block_hash, current_block, parse};
+
+const OLD: &str = "## Weather Forecast\n<!-- WEATHER:BEGIN -->\nold\n<!-- WEATHER:END -->";
+const NEW: &str = "## Weather Forecast\n<!-- WEATHER:BEGIN -->\nnew\n<!-- WEATHER:END -->\n";
+
+fn vault(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("wh-plan-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("Trips")).unwrap();
+    fs::write(dir.join("Trips/Rome.md"), format!("# Rome\n\n{OLD}\n")).unwrap();
+    dir
+}
+
+fn change(old: Option<&str>) -> Change {
+    Change {
+        note: "Trips/Rome.md".into(),
+        old_block_hash: old.map(block_hash),
+        new_block: NEW.into(),
+    }
+}
+
+#[test]
+fn current_block_finds_the_marked_section() {
+    assert_eq!(current_block(&format!("# Rome\n\n{OLD}\nafter")), Some(OLD));
+    assert_eq!(current_block("# Rome\n"), None);
+}
+
+#[test]
+fn apply_writes_the_planned_block() {
+    let dir = vault("apply");
+    assert_eq!(
+        apply_change(&dir, &change(Some(OLD))).unwrap(),
+        Applied::Written
+    );
+    let text = fs::read_to_string(dir.join("Trips/Rome.md")).unwrap();
+    assert!(text.contains("\nnew\n"), "{text}");
+    assert!(!text.contains("\nold\n"));
+}
+
+#[test]
+fn apply_refuses_a_note_changed_since_the_plan() {
+    let dir = vault("conflict");
+    let planned = change(Some(&OLD.replace("old", "older")));
+    assert_eq!(apply_change(&dir, &planned).unwrap(), Applied::Conflict);
+    let text = fs::read_to_string(dir.join("Trips/Rome.md")).unwrap();
+    assert!(text.contains("\nold\n"));
+}
+
+#[test]
+fn apply_stays_inside_the_root() {
+    let mut outside = change(None);
+    outside.note = "../elsewhere.md".into();
+    assert!(apply_change(Path::new("."), &outside).is_err());
+}
+
+#[test]
+fn plans_from_another_version_are_rejected() {
+    let json = r#"{"version": 2, "created": "2025-07-01T00:00:00Z", "changes": []}"#;
+    assert!(parse(json).is_err());
+    let json = r#"{"version": 1, "created": "2025-07-01T00:00:00Z", "changes": []}"#;
+    assert!(parse(json).unwrap().changes.is_empty());
+}
+
+#[test]
+fn apply_creates_a_planned_weather_note() {
+    let dir = vault("create");
+    let mut new_note = change(None);
+    new_note.note = "Trips/Weather - Rome Aug 2025.md".into();
+    assert_eq!(apply_change(&dir, &new_note).unwrap(), Applied::Written);
+    let text = fs::read_to_string(dir.join("Trips/Weather - Rome Aug 2025.md")).unwrap();
+    assert_eq!(text, NEW);
+}
@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+use weather_helper::NoteMeta;
+use weather_helper::priority::{is_long_past, is_upcoming, sort_by_urgency, urgency};
+
+fn d(m: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2025, m, day).unwrap()
+}
+
+fn trip(path: &str, arrival: NaiveDate, departure: NaiveDate) -> NoteMeta {
+    NoteMeta {
+        city: path.into(),
+        path: path.into(),
+        arrival,
+        departure,
+        ..NoteMeta::default()
+    }
+}
+
+#[test]
+fn trips_under_way_then_soonest_then_most_recent_past() {
+    let today = d(8, 1);
+    let mut notes = vec![
+        trip("old", d(3, 1), d(3, 5)),
+        trip("later", d(9, 10), d(9, 12)),
+        trip("recent", d(7, 20), d(7, 25)),
+        trip("now", d(7, 30), d(8, 3)),
+        trip("soon", d(8, 5), d(8, 9)),
+    ];
+    sort_by_urgency(&mut notes, today);
+    let order: Vec<&str> = notes.iter().map(|n| n.path.as_str()).collect();
+    assert_eq!(order, vec!["now", "soon", "later", "recent", "old"]);
+    assert_eq!(urgency(&notes[1], today), (1, 4));
+}
+
+#[test]
+fn only_upcoming_keeps_trips_under_way() {
+    let today = d(8, 1);
+    assert!(is_upcoming(&trip("now", d(7, 30), d(8, 3)), today, 0));
+    assert!(is_upcoming(&trip("soon", d(8, 31), d(9, 3)), today, 30));
+    assert!(!is_upcoming(&trip("later", d(9, 1), d(9, 3)), today, 30));
+    assert!(!is_upcoming(&trip("past", d(7, 1), d(7, 3)), today, 30));
+}
+
+#[test]
+fn retention_only_skips_trips_long_over() {
+    let today = d(8, 1);
+    assert!(is_long_past(&trip("spring", d(3, 1), d(3, 5)), today, 30));
+    assert!(!is_long_past(&trip("july", d(7, 1), d(7, 3)), today, 30));
+    assert!(!is_long_past(&trip("now", d(7, 30), d(8, 3)), today, 0));
+    assert!(!is_long_past(&trip("soon", d(9, 1), d(9, 3)), today, 0));
+}
@@ -0,0 +1,103 @@
+use weather_helper::provider::{
+    MetNoForecast, NwsForecast, Provider, VisualCrossingTimeline, metno_days, nws_days,
+    visual_crossing_days,
+};
+
+#[test]
+fn nws_periods_pair_day_highs_with_following_night_lows() {
+    let json = serde_json::json!({
+        "properties": { "periods": [
+            { "startTime": "2025-07-01T18:00:00-04:00", "isDaytime": false, "temperature": 68, "temperatureUnit": "F" },
+            { "startTime": "2025-07-02T06:00:00-04:00", "isDaytime": true, "temperature": 86, "temperatureUnit": "F" },
+            { "startTime": "2025-07-02T18:00:00-04:00", "isDaytime": false, "temperature": 70, "temperatureUnit": "F" },
+            { "startTime": "2025-07-03T06:00:00-04:00", "isDaytime": true, "temperature": 90, "temperatureUnit": "F" }
+        ]}
+    });
+    let forecast: NwsForecast = serde_json::from_value(json).unwrap();
+    let days = nws_days(forecast);
+    assert_eq!(days.len(), 1, "only 2025-07-02 has both halves");
+    assert_eq!(days[0].date.to_string(), "2025-07-02");
+    assert!((days[0].tmax_f - 86.0).abs() < 1e-9);
+    assert!((days[0].tmin - 21.111).abs() < 1e-3);
+}
+
+#[test]
+fn auto_picks_nws_only_in_the_us() {
+    let auto = Provider::Auto;
+    assert_eq!(auto.resolve(40.71, -74.0, None), Provider::Nws);
+    assert_eq!(auto.resolve(41.9, 12.5, None), Provider::OpenMeteo);
+    assert_eq!(auto.resolve(41.9, 12.5, Some("US")), Provider::Nws);
+    assert_eq!(Provider::Nws.resolve(41.9, 12.5, None), Provider::Nws);
+    assert!(Provider::parse("weather.gov").is_ok());
+}
+
+#[test]
+fn metno_timeseries_folds_into_local_days() {
+    let json = serde_json::json!({
+        "properties": { "timeseries": [
+            { "time": "2025-07-01T21:00:00Z", "data": { "instant": { "details": { "air_temperature": 14.0 } } } },
+            { "time": "2025-07-01T23:00:00Z", "data": { "instant": { "details": { "air_temperature": 12.5 } } } },
+            { "time": "2025-07-02T06:00:00Z", "data": {
+                "instant": { "details": { "air_temperature": 13.0 } },
+                "next_6_hours": { "details": { "air_temperature_max": 19.4, "air_temperature_min": 12.8 } }
+            } }
+        ]}
+    });
+    let forecast: MetNoForecast = serde_json::from_value(json).unwrap();
+    let days = metno_days(forecast, "Europe/Oslo", 10.75);
+    // 23:00Z is already 2025-07-02 in Oslo (UTC+2).
+    assert_eq!(days.len(), 2);
+    assert_eq!(days[0].date.to_string(), "2025-07-01");
+    assert!((days[0].tmax - 14.0).abs() < 1e-9);
+    assert!((days[1].tmax - 19.4).abs() < 1e-9);
+    assert!((days[1].tmin - 12.5).abs() < 1e-9);
+    assert_eq!(Provider::parse("yr").unwrap(), Provider::MetNo);
+}
+
+#[test]
+fn http_dates_parse() {
+    assert!(weather_helper::http::parse_http_date("Tue, 01 Jul 2025 10:00:00 GMT").is_some());
+    assert!(weather_helper::http::parse_http_date("soon").is_none());
+}
+
+#[test]
+fn visual_crossing_units_map_to_celsius() {
+    let json = serde_json::json!({
+        "queryCost": 2,
+        "days": [
+            { "datetime": "2025-07-01", "tempmax": 86.0, "tempmin": 68.0 },
+            { "datetime": "2025-07-02", "tempmax": 90.0 }
+        ]
+    });
+    let timeline: VisualCrossingTimeline = serde_json::from_value(json.clone()).unwrap();
+    let days = visual_crossing_days(timeline, "us").unwrap();
+    assert_eq!(days.len(), 1, "days missing a value are dropped");
+    assert!((days[0].tmax - 30.0).abs() < 1e-9);
+    assert!((days[0].tmin_f - 68.0).abs() < 1e-9);
+
+    let timeline: VisualCrossingTimeline = serde_json::from_value(json).unwrap();
+    let days = visual_crossing_days(timeline, "metric").unwrap();
+    assert!((days[0].tmax - 86.0).abs() < 1e-9);
+    assert_eq!(
+        Provider::parse("visualcrossing").unwrap(),
+        Provider::VisualCrossing
+    );
+}
+
+#[test]
+fn a_model_implies_open_meteo() {
+    let source = |p: Provider, model| p.source(model, 40.7, -74.0, Some("US"));
+    assert_eq!(
+        source(Provider::Auto, None).unwrap().provider,
+        Provider::Nws
+    );
+    let pinned = source(Provider::Auto, Some("ecmwf_ifs025")).unwrap();
+    assert_eq!(pinned.provider, Provider::OpenMeteo);
+    assert_eq!(pinned.label(), " (ecmwf_ifs025)");
+    let err = source(Provider::Nws, Some("ecmwf_ifs025")).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "model 'ecmwf_ifs025' is an Open-Meteo model but the provider is nws"
+    );
+    assert_eq!(Provider::parse("met-no").unwrap(), Provider::MetNo);
+}
@@ -0,0 +1,241 @@
+use chrono::NaiveDate;
+use weather_helper::range_planner::{
+    ActualsSource, FetchKind, PAST_DAYS_MAX, PlannedFetch, actuals_source, plan_ranges,
+    years_earlier,
+};
+
+fn d(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn recent_trips_use_past_days_back_to_the_start() {
+    let today = d("2025-08-20");
+    assert_eq!(
+        actuals_source(d("2025-08-19"), today),
+        ActualsSource::PastDays(1)
+    );
+    assert_eq!(
+        actuals_source(d("2025-08-01"), today),
+        ActualsSource::PastDays(19)
+    );
+}
+
+#[test]
+fn the_window_ends_at_the_api_limit() {
+    let today = d("2025-08-20");
+    let edge = today - chrono::Duration::days(PAST_DAYS_MAX);
+    assert_eq!(actuals_source(edge, today), ActualsSource::PastDays(92));
+    assert_eq!(
+        actuals_source(edge.pred_opt().unwrap(), today),
+        ActualsSource::Archive
+    );
+}
+
+#[test]
+fn old_trips_use_the_archive() {
+    assert_eq!(
+        actuals_source(d("2024-06-01"), d("2025-08-20")),
+        ActualsSource::Archive
+    );
+}
+
+#[test]
+fn a_start_of_today_needs_no_past_days() {
+    let today = d("2025-08-20");
+    assert_eq!(actuals_source(today, today), ActualsSource::PastDays(0));
+}
+
+fn fetch(kind: FetchKind, start: &str, end: &str) -> PlannedFetch {
+    PlannedFetch {
+        kind,
+        start: d(start),
+        end: d(end),
+    }
+}
+
+const TODAY: &str = "2025-08-20";
+const HORIZON: &str = "2025-09-05";
+
+fn plan(arrival: &str, departure: &str) -> Vec<PlannedFetch> {
+    plan_ranges(d(arrival), d(departure), d(TODAY), d(HORIZON))
+}
+
+#[test]
+fn past_trip_is_all_actuals() {
+    assert_eq!(
+        plan("2025-08-01", "2025-08-05"),
+        vec![fetch(FetchKind::Actuals, "2025-08-01", "2025-08-05")]
+    );
+}
+
+#[test]
+fn trip_ending_yesterday_is_all_actuals() {
+    assert_eq!(
+        plan("2025-08-15", "2025-08-19"),
+        vec![fetch(FetchKind::Actuals, "2025-08-15", "2025-08-19")]
+    );
+}
+
+#[test]
+fn trip_under_way_splits_at_today() {
+    assert_eq!(
+        plan("2025-08-17", "2025-08-24"),
+        vec![
+            fetch(FetchKind::Actuals, "2025-08-17", "2025-08-19"),
+            fetch(FetchKind::Forecast, "2025-08-20", "2025-08-24"),
+        ]
+    );
+}
+
+#[test]
+fn trip_ending_today_keeps_today_as_forecast() {
+    assert_eq!(
+        plan("2025-08-18", "2025-08-20"),
+        vec![
+            fetch(FetchKind::Actuals, "2025-08-18", "2025-08-19"),
+            fetch(FetchKind::Forecast, "2025-08-20", "2025-08-20"),
+        ]
+    );
+}
+
+#[test]
+fn trip_under_way_past_the_horizon_is_clamped() {
+    assert_eq!(
+        plan("2025-08-10", "2025-09-30"),
+        vec![
+            fetch(FetchKind::Actuals, "2025-08-10", "2025-08-19"),
+            fetch(FetchKind::Forecast, "2025-08-20", "2025-09-05"),
+        ]
+    );
+}
+
+#[test]
+fn trip_starting_today_is_all_forecast() {
+    assert_eq!(
+        plan("2025-08-20", "2025-08-22"),
+        vec![fetch(FetchKind::Forecast, "2025-08-20", "2025-08-22")]
+    );
+}
+
+#[test]
+fn upcoming_trip_within_the_horizon_is_forecast() {
+    assert_eq!(
+        plan("2025-08-25", "2025-08-30"),
+        vec![fetch(FetchKind::Forecast, "2025-08-25", "2025-08-30")]
+    );
+}
+
+#[test]
+fn upcoming_trip_crossing_the_horizon_is_clamped() {
+    assert_eq!(
+        plan("2025-09-01", "2025-09-12"),
+        vec![fetch(FetchKind::Forecast, "2025-09-01", "2025-09-05")]
+    );
+}
+
+#[test]
+fn trip_starting_on_the_horizon_gets_one_forecast_day() {
+    assert_eq!(
+        plan("2025-09-05", "2025-09-08"),
+        vec![fetch(FetchKind::Forecast, "2025-09-05", "2025-09-05")]
+    );
+}
+
+#[test]
+fn trip_after_the_horizon_uses_last_year() {
+    assert_eq!(
+        plan("2025-09-06", "2025-09-10"),
+        vec![fetch(FetchKind::HistoricProxy, "2024-09-06", "2024-09-10")]
+    );
+}
+
+#[test]
+fn proxy_spanning_new_year_shifts_both_ends() {
+    assert_eq!(
+        plan("2025-12-28", "2026-01-03"),
+        vec![fetch(FetchKind::HistoricProxy, "2024-12-28", "2025-01-03")]
+    );
+}
+
+#[test]
+fn leap_day_proxy_falls_back_to_the_28th() {
+    let today = d("2027-12-01");
+    let horizon = d("2027-12-17");
+    assert_eq!(
+        plan_ranges(d("2028-02-27"), d("2028-02-29"), today, horizon),
+        vec![fetch(FetchKind::HistoricProxy, "2027-02-27", "2027-02-28")]
+    );
+    assert_eq!(
+        plan_ranges(d("2028-02-29"), d("2028-03-02"), today, horizon),
+        vec![fetch(FetchKind::HistoricProxy, "2027-02-28", "2027-03-02")]
+    );
+}
+
+#[test]
+fn older_proxy_years_keep_the_calendar_day() {
+    assert_eq!(years_earlier(d("2025-08-01"), 3), d("2022-08-01"));
+    assert_eq!(years_earlier(d("2028-02-29"), 1), d("2027-02-28"));
+    assert_eq!(years_earlier(d("2028-02-29"), 4), d("2024-02-29"));
+    assert_eq!(years_earlier(d("2025-08-01"), 0), d("2025-08-01"));
+}
+
+#[test]
+fn reversed_dates_are_put_in_order() {
+    assert_eq!(
+        plan("2025-08-24", "2025-08-17"),
+        plan("2025-08-17", "2025-08-24")
+    );
+    assert_eq!(
+        plan("2025-09-10", "2025-09-06"),
+        plan("2025-09-06", "2025-09-10")
+    );
+}
+
+#[test]
+fn single_day_trips() {
+    assert_eq!(
+        plan("2025-08-19", "2025-08-19"),
+        vec![fetch(FetchKind::Actuals, "2025-08-19", "2025-08-19")]
+    );
+    assert_eq!(
+        plan(TODAY, TODAY),
+        vec![fetch(FetchKind::Forecast, TODAY, TODAY)]
+    );
+    assert_eq!(
+        plan("2025-09-20", "2025-09-20"),
+        vec![fetch(FetchKind::HistoricProxy, "2024-09-20", "2024-09-20")]
+    );
+}
+
+#[test]
+fn every_day_is_planned_once_and_in_order() {
+    let today = d(TODAY);
+    let horizon = d(HORIZON);
+    for offset in -40..40 {
+        for len in 0..30 {
+            let start = today + chrono::Duration::days(offset);
+            let end = start + chrono::Duration::days(len);
+            let plan = plan_ranges(start, end, today, horizon);
+            assert!(!plan.is_empty(), "{start} → {end}");
+            for r in &plan {
+                assert!(r.start <= r.end, "{start} → {end}: {r:?}");
+            }
+            for pair in plan.windows(2) {
+                assert_eq!(pair[0].end.succ_opt(), Some(pair[1].start));
+            }
+            if start <= horizon {
+                assert_eq!(plan[0].start, start);
+                assert_eq!(plan.last().unwrap().end, end.min(horizon));
+                assert!(plan.iter().all(|r| match r.kind {
+                    FetchKind::Actuals => r.end < today,
+                    FetchKind::Forecast => r.start >= today,
+                    FetchKind::HistoricProxy => false,
+                }));
+            } else {
+                assert_eq!(plan.len(), 1);
+                assert_eq!(plan[0].kind, FetchKind::HistoricProxy);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Recurring trip scheduling tests
+
+use chrono::NaiveDate;
+use weather_helper::recurrence::Recurrence;
+
+fn d(s: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn monthly_moves_to_next_unfinished_occurrence() {
+    let r = Recurrence::parse("monthly").unwrap();
+    let next = r.next_occurrence(d("2025-01-10"), d("2025-01-12"), d("2025-04-11"));
+    assert_eq!(next, Some((d("2025-04-10"), d("2025-04-12"))));
+    let next = r.next_occurrence(d("2025-01-10"), d("2025-01-12"), d("2025-04-13"));
+    assert_eq!(next, Some((d("2025-05-10"), d("2025-05-12"))));
+}
+
+#[test]
+fn future_trip_is_unchanged() {
+    let r = Recurrence::parse("every 2 weeks").unwrap();
+    let next = r.next_occurrence(d("2025-06-01"), d("2025-06-03"), d("2025-05-01"));
+    assert_eq!(next, Some((d("2025-06-01"), d("2025-06-03"))));
+}
+
+#[test]
+fn cron_every_monday() {
+    let r = Recurrence::parse("0 9 * * 1").unwrap();
+    // 2025-03-10 is a Monday: that two-day trip is still running on the 11th.
+    let next = r.next_occurrence(d("2025-01-06"), d("2025-01-07"), d("2025-03-11"));
+    assert_eq!(next, Some((d("2025-03-10"), d("2025-03-11"))));
+    let next = r.next_occurrence(d("2025-01-06"), d("2025-01-07"), d("2025-03-12"));
+    assert_eq!(next, Some((d("2025-03-17"), d("2025-03-18"))));
+}
+
+#[test]
+fn rejects_unknown_recurrence() {
+    assert!(Recurrence::parse("sometimes").is_err());
+    assert!(Recurrence::parse("every 0 days").is_err());
+}
+
+#[test]
+fn huge_intervals_find_no_occurrence_instead_of_overflowing() {
+    for spec in [
+        "every 4000000000 weeks",
+        "every 4000000000 years",
+        "every 3000000000 days",
+    ] {
+        let r = Recurrence::parse(spec).unwrap();
+        let next = r.next_occurrence(d("2025-01-10"), d("2025-01-12"), d("2025-04-11"));
+        assert_eq!(next, None, "{spec}");
+    }
+}
@@ -0,0 +1,27 @@
+use weather_helper::roadtrip::{RoadDay, hazard, hazards};
+
+fn day(date: &str, code: Option<u8>, tmin: Option<f64>) -> RoadDay {
+    RoadDay {
+        date: date.parse().unwrap(),
+        weather_code: code,
+        tmax: None,
+        tmin,
+    }
+}
+
+#[test]
+fn snow_ice_and_frost_days_are_flagged() {
+    let days = [
+        day("2025-01-10", Some(73), Some(-3.4)),
+        day("2025-01-11", Some(67), Some(1.0)),
+        day("2025-01-12", Some(3), Some(2.0)),
+        day("2025-01-13", None, Some(-0.2)),
+    ];
+    let flagged = hazards(&days);
+    assert_eq!(flagged.len(), 3);
+    assert_eq!(flagged[&days[0].date], ["snow", "low -3°C"]);
+    assert_eq!(flagged[&days[1].date], ["freezing rain"]);
+    // Rounds to 0 but is still below freezing.
+    assert_eq!(flagged[&days[3].date], ["low 0°C"]);
+    assert_eq!(hazard(61), None, "plain rain is not flagged");
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use weather_helper::Units;
+use weather_helper::roadtrip::RoadDay;
+use weather_helper::route::{Stop, Waypoint, fetch_route, render_origin, render_route};
+
+#[test]
+fn waypoints_accept_names_and_coordinates() {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(
+        "[Innsbruck, '47.00, 11.51', {name: Brenner Pass, lat: 47.0, lon: '11.51'}, 3]",
+    )
+    .unwrap();
+    let parsed: Vec<Waypoint> = yaml
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .filter_map(Waypoint::parse)
+        .collect();
+    assert_eq!(parsed.len(), 3);
+    assert_eq!(parsed[0], Waypoint::Named("Innsbruck".into()));
+    assert_eq!(parsed[1].label(), "47.00, 11.51");
+    assert_eq!(
+        parsed[2],
+        Waypoint::Coords {
+            lat: 47.0,
+            lon: 11.51,
+            label: "Brenner Pass".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn stops_at_the_same_place_share_one_request() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+            let mut buf = [0u8; 2048];
+            let _ = sock.read(&mut buf).await;
+            let body = r#"{"daily":{"time":["2025-01-10","2025-01-11","2025-01-12"],"weather_code":[73,3,0],"temperature_2m_max":[1.0,4.0,6.0],"temperature_2m_min":[-4.0,-1.0,0.5]}}"#;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = sock.write_all(head.as_bytes()).await;
+            let _ = sock.write_all(body.as_bytes()).await;
+        }
+    });
+    // SAFETY: no other test in this binary reads the variable.
+    unsafe { std::env::set_var("OPEN_METEO_FORECAST_BASE", format!("http://{addr}")) };
+
+    let stop = |name: &str, lat: f64| Stop {
+        name: name.into(),
+        latitude: lat,
+        longitude: 11.51,
+        timezone: "Europe/Vienna".into(),
+    };
+    let stops = [
+        stop("Brenner", 47.0),
+        stop("Brennero", 47.00001),
+        stop("Innsbruck", 47.26),
+    ];
+    let client = weather_helper::http::Client::new(None).unwrap();
+    let (start, end) = ("2025-01-10".parse().unwrap(), "2025-01-12".parse().unwrap());
+    let route = fetch_route(&client, &stops, start, end, true)
+        .await
+        .unwrap();
+    assert_eq!(hits.load(Ordering::SeqCst), 2);
+    assert_eq!(route.len(), 3);
+    assert_eq!(route[1].0.name, "Brennero");
+    assert_eq!(route[1].1.len(), 3);
+
+    let table = render_route(&route, &[start, end], Units::Celsius);
+    assert!(
+        table.contains("| Brenner | 2025-01-10 | 1°C | -4°C | snow |"),
+        "{table}"
+    );
+    assert!(
+        table.contains("| Innsbruck | 2025-01-12 | 6°C | 1°C | clear |"),
+        "{table}"
+    );
+    assert!(!table.contains("2025-01-11"), "only travel days are shown");
+}
+
+#[test]
+fn origin_gets_one_row_for_the_day_of_setting_off() {
+    let dir = std::env::temp_dir().join(format!("wh-origin-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Rome.md");
+    std::fs::write(
+        &path,
+        "---\ncity: Rome\norigin: Milan\narrival: 2025-08-20\ndeparture: 2025-08-25\n---\n",
+    )
+    .unwrap();
+    let meta = weather_helper::extract_meta(&path).unwrap();
+    assert_eq!(meta.origin, Some(Waypoint::Named("Milan".into())));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let milan = Stop {
+        name: "Milan".into(),
+        latitude: 45.46,
+        longitude: 9.19,
+        timezone: "Europe/Rome".into(),
+    };
+    let day = RoadDay {
+        date: "2025-08-20".parse().unwrap(),
+        weather_code: Some(63),
+        tmax: Some(18.0),
+        tmin: Some(11.4),
+    };
+    assert_eq!(
+        render_origin(&milan, &[day], Units::Fahrenheit),
+        "**Leaving from**\n\n| Origin | Date | High | Low | Conditions |\n|---|---|---:|---:|---|\n\
+         | Milan | 2025-08-20 | 64°F | 53°F | rain |\n"
+    );
+    assert_eq!(render_origin(&milan, &[], Units::Celsius), "");
+}
@@ -0,0 +1,38 @@
+use weather_helper::config::{Config, Origin};
+use weather_helper::schema::{SchemaFormat, export};
+
+#[test]
+fn json_schema_lists_trip_keys_and_current_overrides() {
+    let mut config = Config::default();
+    config.set("units", "fahrenheit", Origin::Flag).unwrap();
+    config.set("packing", "true", Origin::Flag).unwrap();
+    let schema: serde_json::Value =
+        serde_json::from_str(&export(&config, SchemaFormat::JsonSchema)).unwrap();
+
+    assert_eq!(
+        schema["allOf"][0]["anyOf"][0]["required"],
+        serde_json::json!(["arrival", "departure"])
+    );
+    let props = &schema["properties"];
+    assert_eq!(props["arrival"]["type"], "string");
+    assert_eq!(props["overlap-ok"]["type"], "boolean");
+    assert_eq!(props["weather-units"]["default"], "fahrenheit");
+    assert_eq!(
+        props["weather-units"]["enum"],
+        serde_json::json!(["celsius", "fahrenheit"])
+    );
+    assert_eq!(props["weather-packing"]["default"], true);
+    assert!(props.get("weather-timeout").is_none());
+}
+
+#[test]
+fn obsidian_types_map_keys_to_property_types() {
+    let types: serde_json::Value =
+        serde_json::from_str(&export(&Config::default(), SchemaFormat::Obsidian)).unwrap();
+    let types = &types["types"];
+    assert_eq!(types["arrival"], "date");
+    assert_eq!(types["lat"], "number");
+    assert_eq!(types["weather-sidecar"], "checkbox");
+    assert_eq!(types["route"], "multitext");
+    assert_eq!(types["tags"], "tags");
+}
@@ -0,0 +1,22 @@
+use weather_helper::secrets::{Secret, env_var, redact};
+
+#[test]
+fn secrets_never_format_their_value() {
+    let key = Secret::new("abc123");
+    assert_eq!(key.to_string(), "***");
+    assert_eq!(format!("{key:?}"), "Secret(***)");
+    assert_eq!(key.expose(), "abc123");
+}
+
+#[test]
+fn redact_masks_every_occurrence() {
+    let key = Secret::new("abc123");
+    assert_eq!(
+        redact("GET /v1?key=abc123 failed (abc123)", &[&key]),
+        "GET /v1?key=*** failed (***)"
+    );
+    assert_eq!(
+        env_var("visual-crossing"),
+        "WEATHER_HELPER_VISUAL_CROSSING_API_KEY"
+    );
+}
@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use std::path::Path;
+use weather_helper::separate::{embed_block, note_name, note_path};
+use weather_helper::{NoteMeta, upsert_weather_block};
+
+fn meta(city: &str, path: &str) -> NoteMeta {
+    NoteMeta {
+        city: city.into(),
+        path: path.into(),
+        arrival: NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(),
+        departure: NaiveDate::from_ymd_opt(2025, 8, 25).unwrap(),
+        ..NoteMeta::default()
+    }
+}
+
+#[test]
+fn weather_note_is_named_after_city_and_month() {
+    let m = meta("Rome", "Trips/Rome trip.md");
+    assert_eq!(note_name(&m), "Weather - Rome Aug 2025");
+    assert_eq!(note_path(&m), Path::new("Trips/Weather - Rome Aug 2025.md"));
+}
+
+#[test]
+fn characters_obsidian_rejects_are_dropped() {
+    let m = meta("Frankfurt/Main #1", "Frankfurt.md");
+    assert_eq!(note_name(&m), "Weather - FrankfurtMain 1 Aug 2025");
+    assert_eq!(
+        note_name(&meta("", "Trips/Oslo.md")),
+        "Weather - Oslo Aug 2025"
+    );
+}
+
+#[test]
+fn trip_note_block_is_only_the_embed() {
+    let mut content = "# Rome\n".to_string();
+    upsert_weather_block(&mut content, &embed_block(&meta("Rome", "Rome.md"))).unwrap();
+    assert_eq!(
+        content,
+        "# Rome\n\n\n## Weather Forecast\n<!-- WEATHER:BEGIN v2 -->\n![[Weather - Rome Aug 2025]]\n<!-- WEATHER:END -->\n"
+    );
+}
+
+#[test]
+fn a_new_weather_note_is_just_the_block() {
+    let mut content = String::new();
+    upsert_weather_block(&mut content, "## Weather Forecast\nblock\n").unwrap();
+    assert_eq!(content, "## Weather Forecast\nblock\n");
+}
@@ -0,0 +1,137 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use weather_helper::shortcode::{TableStyle, render};
+use weather_helper::{DayNote, DayTemp, Units, render_block_styled};
+
+fn day(date: &str, tmax: f64, tmin: f64) -> DayTemp {
+    DayTemp {
+        date: date.parse().unwrap(),
+        tmax,
+        tmin,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: tmin * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+fn notes() -> BTreeMap<NaiveDate, DayNote> {
+    BTreeMap::from([(
+        "2025-08-21".parse().unwrap(),
+        DayNote {
+            text: "arrive \"late\"".into(),
+            counted: true,
+        },
+    )])
+}
+
+#[test]
+fn styles_parse_by_name() {
+    for style in [
+        TableStyle::Markdown,
+        TableStyle::Hugo,
+        TableStyle::Zola,
+        TableStyle::Html,
+    ] {
+        assert_eq!(TableStyle::parse(style.name()).unwrap(), style);
+    }
+    assert!(TableStyle::parse("jekyll").is_err());
+}
+
+#[test]
+fn shortcodes_carry_both_units_and_notes() {
+    let data = [day("2025-08-20", 30.0, 20.0), day("2025-08-21", 25.0, 15.0)];
+    assert_eq!(
+        render(TableStyle::Hugo, &data, &notes(), Units::Celsius, None, &[]),
+        "{{< weather >}}\n\
+         {{< weather-day date=\"2025-08-20\" high-c=\"30\" low-c=\"20\" high-f=\"86\" low-f=\"68\" note=\"\" >}}\n\
+         {{< weather-day date=\"2025-08-21\" high-c=\"25\" low-c=\"15\" high-f=\"77\" low-f=\"59\" note=\"arrive &quot;late&quot;\" >}}\n\
+         {{< /weather >}}"
+    );
+    let zola = render(TableStyle::Zola, &data, &notes(), Units::Celsius, None, &[]);
+    assert!(zola.starts_with("{% weather() %}\n"), "{zola}");
+    assert!(
+        zola.contains(
+            "{{ weather_day(date=\"2025-08-20\", high_c=30, low_c=20, high_f=86, low_f=68, note=\"\") }}\n"
+        ),
+        "{zola}"
+    );
+    assert!(zola.ends_with("{% end %}"), "{zola}");
+}
+
+#[test]
+fn html_partials_show_the_chosen_units() {
+    let data = [day("2025-08-20", 30.0, 20.0), day("2025-08-21", 25.0, 15.0)];
+    let today = "2025-08-21".parse().ok();
+    let html = render(
+        TableStyle::Html,
+        &data,
+        &notes(),
+        Units::Fahrenheit,
+        today,
+        &[],
+    );
+    assert!(
+        html.starts_with("<div class=\"weather-widget\">\n"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<div class=\"weather-day observed\" data-date=\"2025-08-20\" data-high-c=\"30\" data-low-c=\"20\" data-high-f=\"86\" data-low-f=\"68\"><span class=\"date\">2025-08-20</span> <span class=\"high\">86°F</span> <span class=\"low\">68°F</span></div>\n"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<span class=\"note\">arrive &quot;late&quot;</span></div>"),
+        "{html}"
+    );
+}
+
+#[test]
+fn styled_blocks_keep_the_header_and_summary() {
+    let data = [day("2025-08-20", 30.0, 20.0)];
+    let block = render_block_styled(
+        "Forecast for Rome",
+        &data,
+        &BTreeMap::new(),
+        Units::Celsius,
+        None,
+        &[],
+        TableStyle::Hugo,
+    );
+    assert!(block.contains("**Forecast for Rome**"), "{block}");
+    assert!(block.contains("**Range**"), "{block}");
+    assert!(block.contains("{{< weather >}}"), "{block}");
+    assert!(!block.contains("| Date |"), "{block}");
+    assert_eq!(weather_helper::count_weather_blocks(&block), 1);
+}
+
+#[test]
+fn every_style_carries_rain_wind_and_uv() {
+    let mut data = [day("2025-08-20", 30.0, 20.0), day("2025-08-21", 25.0, 15.0)];
+    data[0].precipitation = Some(2.25);
+    data[0].uv_index = Some(7.5);
+    data[1].precipitation = Some(0.0);
+    data[1].wind_speed = Some(35.0);
+    let none = BTreeMap::new();
+    let hugo = render(TableStyle::Hugo, &data, &none, Units::Celsius, None, &[]);
+    assert!(
+        hugo.contains("low-f=\"68\" rain-mm=\"2.2\" uv=\"7.5\" note=\"\" >}}"),
+        "{hugo}"
+    );
+    assert!(
+        hugo.contains("low-f=\"59\" rain-mm=\"0\" wind-kmh=\"35\" note=\"\" >}}"),
+        "{hugo}"
+    );
+    let zola = render(TableStyle::Zola, &data, &none, Units::Celsius, None, &[]);
+    assert!(
+        zola.contains("low_f=68, rain_mm=2.2, uv=7.5, note=\"\") }}"),
+        "{zola}"
+    );
+    let html = render(TableStyle::Html, &data, &none, Units::Celsius, None, &[]);
+    assert!(
+        html.contains("data-low-f=\"59\" data-rain-mm=\"0\" data-wind-kmh=\"35\">"),
+        "{html}"
+    );
+    assert!(
+        html.contains("<span class=\"low\">15°C</span> <span class=\"rain\">0 mm</span> <span class=\"wind\">35 km/h</span></div>"),
+        "{html}"
+    );
+}
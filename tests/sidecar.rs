@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use weather_helper::sidecar::{build, comment, embedded, link_line, sidecar_path};
+use weather_helper::{DayNote, DayTemp, NoteMeta, Place, PreparedNote, RunOptions};
+
+#[test]
+fn sidecar_sits_next_to_the_note() {
+    assert_eq!(
+        sidecar_path("Trips/Rome Aug.md"),
+        std::path::Path::new("Trips/Rome Aug.weather.json")
+    );
+    assert_eq!(
+        link_line("Trips/Rome Aug.md"),
+        "**Data**: [Rome Aug.weather.json](Rome%20Aug.weather.json)"
+    );
+}
+
+fn prepared() -> PreparedNote {
+    let date = NaiveDate::from_ymd_opt(2025, 8, 20).unwrap();
+    let mut notes = BTreeMap::new();
+    notes.insert(
+        date,
+        DayNote {
+            text: "arrive 22:30".into(),
+            counted: false,
+        },
+    );
+    PreparedNote {
+        meta: NoteMeta {
+            city: "Rome".into(),
+            path: "Rome.md".into(),
+            ..NoteMeta::default()
+        },
+        opts: RunOptions::default(),
+        place: Place {
+            name: "Rome".into(),
+            latitude: 41.89,
+            longitude: 12.48,
+            timezone: "Europe/Rome".into(),
+            country_code: Some("IT".into()),
+            elevation: None,
+        },
+        label: "Forecast for Rome 2025-08-20 → 2025-08-20".into(),
+        data: vec![DayTemp {
+            date,
+            tmax: 30.0,
+            tmin: 20.0,
+            tmax_f: 86.0,
+            tmin_f: 68.0,
+            ..Default::default()
+        }],
+        notes,
+        extra: vec![],
+        sections: vec![],
+        divider: None,
+        trip: None,
+        content: String::new(),
+        separate: None,
+        alerts: vec![],
+        columns: vec![],
+        map_image: None,
+    }
+}
+
+#[test]
+fn sidecar_carries_both_units_and_day_notes() {
+    let note = prepared();
+    let json = serde_json::to_value(build(&note, "2025-08-18T09:00:00+02:00".into())).unwrap();
+    assert_eq!(json["units"], "fahrenheit");
+    assert_eq!(json["timezone"], "Europe/Rome");
+    assert_eq!(json["days"][0]["date"], "2025-08-20");
+    assert_eq!(json["days"][0]["tmax_c"], 30.0);
+    assert_eq!(json["days"][0]["tmin_f"], 68.0);
+    assert_eq!(json["days"][0]["note"], "arrive 22:30");
+}
+
+#[test]
+fn embedded_data_reads_back_from_the_block() {
+    let mut note = prepared();
+    note.notes
+        .values_mut()
+        .for_each(|n| n.text = "ends --> here".into());
+    let line = comment(&note);
+    assert!(
+        line.starts_with("<!-- WEATHER:DATA {\"units\":\"fahrenheit\""),
+        "{line}"
+    );
+    assert_eq!(line.matches("-->").count(), 1, "{line}");
+
+    note.opts.embed_data = true;
+    let block = note.block();
+    let data = embedded(&block).unwrap();
+    assert_eq!(data.days.len(), 1);
+    assert_eq!(data.days[0].tmax_c, 30.0);
+    assert_eq!(data.days[0].note.as_deref(), Some("ends --> here"));
+    assert!(block.ends_with("-->\n<!-- WEATHER:END -->\n"), "{block}");
+
+    // Trend arrows use the exact figures rather than the rounded table.
+    let previous = weather_helper::previous_temps(&block);
+    assert_eq!(previous.values().next().unwrap().tmax, 30.0);
+    assert_eq!(embedded("<!-- WEATHER:BEGIN v2 -->\n"), None);
+}
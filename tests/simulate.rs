@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use std::fs;
+use weather_helper::RunOptions;
+use weather_helper::range_planner::FetchKind;
+use weather_helper::simulate::{report, simulate};
+
+fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn vault() -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("wh-simulate-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (name, arrival, departure) in [
+        ("rome", "2025-08-08", "2025-08-12"),
+        ("oslo", "2025-12-20", "2025-12-24"),
+        ("lima", "2025-01-02", "2025-01-06"),
+    ] {
+        let note = format!(
+            "---\ncity_place: {name}\narrival: {arrival}\ndeparture: {departure}\n---\n\n# {name}\n"
+        );
+        fs::write(dir.join(format!("{name}.md")), note).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn simulate_plans_each_note_as_of_the_given_day() {
+    let dir = vault();
+    let opts = RunOptions {
+        today: Some(date("2025-08-10")),
+        ..RunOptions::default()
+    };
+    let sim = simulate(dir.to_str().unwrap(), &opts);
+    assert_eq!(sim.on, date("2025-08-10"));
+    assert_eq!(sim.long_past, 1);
+
+    let rome = sim.notes.iter().find(|n| n.note == "rome.md").unwrap();
+    let kinds: Vec<FetchKind> = rome.fetches.iter().map(|f| f.kind).collect();
+    assert_eq!(kinds, [FetchKind::Actuals, FetchKind::Forecast]);
+    let oslo = sim.notes.iter().find(|n| n.note == "oslo.md").unwrap();
+    assert_eq!(oslo.fetches[0].kind, FetchKind::HistoricProxy);
+    assert_eq!(oslo.fetches[0].start, date("2024-12-20"));
+
+    let text = report(&sim);
+    assert!(text.starts_with("On 2025-08-10, a run would refresh 2 note(s):\n"));
+    assert!(
+        text.contains(
+            "- rome.md (2025-08-08 → 2025-08-12): actuals 2025-08-08 → 2025-08-09 \
+             (forecast API, past_days=2); forecast 2025-08-10 → 2025-08-12\n"
+        ),
+        "{text}"
+    );
+    assert!(
+        text.contains("last year 2024-12-20 → 2024-12-24 (ERA5 archive, as a proxy)"),
+        "{text}"
+    );
+    assert!(text.ends_with("1 trip(s) would be skipped as long past.\n"));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,49 @@
+//! Synthetic vault generation (only with `--features simulator`)
+#![cfg(feature = "simulator")]
+
+use weather_helper::extract_meta;
+use weather_helper::simulator::{MockProvider, bench_options, generate_vault};
+use weather_helper::telemetry::Verbosity;
+
+#[test]
+fn generated_notes_are_valid_and_reproducible() {
+    let start = "2025-01-01".parse().unwrap();
+    let a = std::env::temp_dir().join(format!("sim_a_{}", std::process::id()));
+    let b = std::env::temp_dir().join(format!("sim_b_{}", std::process::id()));
+    generate_vault(&a, 5, start, 7).unwrap();
+    generate_vault(&b, 5, start, 7).unwrap();
+    for i in 0..5 {
+        let name = format!("trip-{i:05}.md");
+        let meta = extract_meta(&a.join(&name)).expect("valid note");
+        assert!(meta.overlap_ok);
+        assert!(meta.departure > meta.arrival);
+        assert_eq!(
+            std::fs::read_to_string(a.join(&name)).unwrap(),
+            std::fs::read_to_string(b.join(&name)).unwrap()
+        );
+    }
+    let _ = std::fs::remove_dir_all(&a);
+    let _ = std::fs::remove_dir_all(&b);
+}
+
+#[tokio::test]
+async fn bench_runs_vaults_larger_than_max_notes() {
+    let dir = std::env::temp_dir().join(format!("sim_bench_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let count = weather_helper::DEFAULT_MAX_NOTES + 20;
+    let today = chrono::Local::now().date_naive();
+    generate_vault(&dir, count, today, 3).unwrap();
+    let mock = MockProvider::start().await.unwrap();
+    // SAFETY: the only test in this binary that touches the environment.
+    unsafe {
+        mock.install_env();
+        std::env::set_var("WEATHER_HELPER_USAGE_FILE", dir.join("usage.json"));
+        std::env::set_var("WEATHER_HELPER_CACHE_DIR", dir.join("cache"));
+    }
+    let root = dir.to_string_lossy().to_string();
+    let report = weather_helper::run(&root, &bench_options(Verbosity::Quiet))
+        .await
+        .unwrap();
+    assert_eq!(report.notes.len(), count);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,34 @@
+//! Golden-file tests for rendered blocks (no network)
+//!
+//! Each `tests/fixtures/<name>.json` API payload must render to
+//! `tests/fixtures/<name>.md`. Regenerate with `UPDATE_SNAPSHOTS=1 cargo test`
+//! or `weather-helper render --fixture <json>`.
+
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn fixtures_match_snapshots() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut checked = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let json = entry.unwrap().path();
+        if json.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let rendered = weather_helper::render_fixture(&fs::read(&json).unwrap(), None).unwrap();
+        let snapshot = json.with_extension("md");
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&snapshot, &rendered).unwrap();
+        }
+        let expected = fs::read_to_string(&snapshot).unwrap_or_default();
+        assert_eq!(
+            rendered,
+            expected,
+            "snapshot mismatch for {}",
+            json.display()
+        );
+        checked += 1;
+    }
+    assert!(checked > 0, "no fixtures found");
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, FixedOffset};
+use std::sync::Arc;
+use weather_helper::stale::{Refresh, age, fetched_at, mark_stale, stamp, without_stamp};
+use weather_helper::{NoteOutcome, RunOptions, clock::FixedClock, telemetry::Verbosity};
+
+fn at(s: &str) -> DateTime<FixedOffset> {
+    DateTime::parse_from_rfc3339(s).unwrap()
+}
+
+const BLOCK: &str =
+    "## Weather Forecast\n<!-- WEATHER:BEGIN -->\n**Forecast**  \n| Date |\n<!-- WEATHER:END -->\n";
+
+#[test]
+fn stamp_goes_after_the_begin_marker_and_reads_back() {
+    let fetched = at("2025-08-01T10:00:00+02:00");
+    let stamped = stamp(BLOCK, fetched);
+    assert!(stamped.contains(
+        "<!-- WEATHER:BEGIN -->\n<!-- WEATHER:FETCHED 2025-08-01T10:00:00+02:00 -->\n**Forecast**"
+    ));
+    assert_eq!(fetched_at(&stamped), Some(fetched));
+    assert_eq!(without_stamp(&stamped), BLOCK);
+    assert_eq!(fetched_at(BLOCK), None);
+}
+
+#[test]
+fn age_reads_naturally() {
+    let fetched = at("2025-08-01T10:00:00+02:00");
+    assert_eq!(
+        age(fetched, at("2025-08-01T10:20:00+02:00")),
+        "less than an hour ago"
+    );
+    assert_eq!(age(fetched, at("2025-08-01T13:00:00+02:00")), "3 hours ago");
+    assert_eq!(age(fetched, at("2025-08-02T08:00:00+02:00")), "yesterday");
+    assert_eq!(age(fetched, at("2025-08-04T08:00:00+02:00")), "3 days ago");
+}
+
+#[test]
+fn stale_banner_is_added_once_and_only_to_stamped_blocks() {
+    let note = format!(
+        "# Rome\n\n{}",
+        stamp(BLOCK, at("2025-08-01T10:00:00+02:00"))
+    );
+    let once = mark_stale(&note, at("2025-08-04T09:00:00+02:00")).unwrap();
+    assert!(once.contains(
+        "-->\n⏳ _data from 3 days ago (last updated 2025-08-01); the latest update failed_  \n**Forecast**"
+    ), "{once}");
+    assert!(once.contains("<!-- WEATHER:BEGIN v2 -->"), "{once}");
+    let twice = mark_stale(&once, at("2025-08-05T09:00:00+02:00")).unwrap();
+    assert_eq!(twice.matches("⏳").count(), 1);
+    assert!(twice.contains("4 days ago"));
+    assert_eq!(mark_stale(BLOCK, at("2025-08-04T09:00:00+02:00")), None);
+}
+
+#[test]
+fn stamped_examples_in_code_fences_are_left_alone() {
+    let example = format!(
+        "```md\n{}```\n",
+        stamp(BLOCK, at("2025-07-01T10:00:00+02:00"))
+    );
+    assert_eq!(fetched_at(&example), None);
+    assert_eq!(mark_stale(&example, at("2025-08-04T09:00:00+02:00")), None);
+
+    let note = format!(
+        "# Rome\n\n{example}\n{}",
+        stamp(BLOCK, at("2025-08-01T10:00:00+02:00"))
+    );
+    assert_eq!(fetched_at(&note), Some(at("2025-08-01T10:00:00+02:00")));
+    let marked = mark_stale(&note, at("2025-08-04T09:00:00+02:00")).unwrap();
+    assert!(
+        marked.starts_with(&format!("# Rome\n\n{example}")),
+        "{marked}"
+    );
+    assert_eq!(marked.matches("⏳").count(), 1);
+    assert_eq!(marked.matches("<!-- WEATHER:BEGIN -->").count(), 1);
+}
+
+#[test]
+fn refresh_cadences_wait_for_old_enough_data() {
+    let fetched = Some(at("2025-08-01T10:00:00+02:00"));
+    let later = |s: &str| Refresh::Hourly.not_due(fetched, at(s));
+    assert!(later("2025-08-01T10:30:00+02:00").is_some());
+    assert_eq!(later("2025-08-01T11:00:00+02:00"), None);
+
+    let daily = |s: &str| Refresh::Daily.not_due(fetched, at(s));
+    assert_eq!(
+        daily("2025-08-01T23:00:00+02:00").as_deref(),
+        Some("not due (refresh: daily, last updated 13 hours ago)")
+    );
+    assert_eq!(daily("2025-08-02T00:10:00+02:00"), None);
+
+    let weekly = |s: &str| Refresh::Weekly.not_due(fetched, at(s));
+    assert!(weekly("2025-08-07T10:00:00+02:00").is_some());
+    assert_eq!(weekly("2025-08-08T10:00:00+02:00"), None);
+
+    let now = at("2025-09-01T10:00:00+02:00");
+    assert_eq!(Refresh::Always.not_due(fetched, now), None);
+    assert_eq!(Refresh::Daily.not_due(None, now), None);
+    assert!(Refresh::Manual.not_due(None, now).is_some());
+    for refresh in ["always", "hourly", "daily", "weekly", "manual"] {
+        assert_eq!(Refresh::parse(refresh).unwrap().name(), refresh);
+    }
+    assert!(Refresh::parse("monthly").is_err());
+}
+
+#[tokio::test]
+async fn runs_skip_notes_that_are_not_due() {
+    let dir = std::env::temp_dir().join(format!("wh-refresh-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let block = stamp(BLOCK, at("2025-08-01T10:00:00+02:00"));
+    let note = |refresh: &str| {
+        format!(
+            "---\ncity: Rome\narrival: 2025-08-10\ndeparture: 2025-08-12\nrefresh: {refresh}\n---\n{block}"
+        )
+    };
+    std::fs::write(dir.join("archived.md"), note("manual")).unwrap();
+    std::fs::write(dir.join("weekly.md"), note("weekly")).unwrap();
+    let opts = RunOptions {
+        verbosity: Verbosity::Quiet,
+        clock: Some(Arc::new(FixedClock::new(
+            at("2025-08-03T09:00:00+02:00").to_utc(),
+            None,
+        ))),
+        ..RunOptions::default()
+    };
+    let report = weather_helper::run(&dir.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    let outcome = |name: &str| {
+        report
+            .notes
+            .iter()
+            .find(|n| n.path.ends_with(name))
+            .map(|n| n.outcome.clone())
+    };
+    assert!(
+        matches!(outcome("archived.md"), Some(NoteOutcome::Skipped(why)) if why.contains("manual"))
+    );
+    assert_eq!(
+        outcome("weekly.md"),
+        Some(NoteOutcome::Skipped(
+            "not due (refresh: weekly, last updated 2 days ago)".into()
+        ))
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+use weather_helper::Units;
+use weather_helper::stats::{StatDay, TripActuals, report};
+
+fn d(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn day(date: &str, tmax: f64, tmin: f64, precip: f64) -> StatDay {
+    StatDay {
+        date: d(date),
+        tmax,
+        tmin,
+        precip: Some(precip),
+    }
+}
+
+#[test]
+fn report_summarizes_the_year() {
+    let trips = vec![
+        TripActuals {
+            path: "rome.md".into(),
+            place: "Rome".into(),
+            start: d("2025-07-10"),
+            end: d("2025-07-12"),
+            days: vec![
+                day("2025-07-10", 34.2, 22.0, 0.0),
+                day("2025-07-11", 36.0, 23.1, 1.5),
+                day("2025-07-12", 33.0, 21.0, 0.0),
+            ],
+        },
+        TripActuals {
+            path: "milan.md".into(),
+            place: "Milan".into(),
+            start: d("2025-07-12"),
+            end: d("2025-07-13"),
+            days: vec![
+                day("2025-07-12", 29.0, 18.0, 12.0),
+                day("2025-07-13", 27.0, 17.5, 8.4),
+            ],
+        },
+    ];
+    let text = report(2025, &trips, Units::Celsius, false);
+    assert!(text.starts_with("# Travel weather 2025\n\n"));
+    assert!(text.contains("- Trips: 2\n"));
+    // 2025-07-12 is shared by both trips and counted once.
+    assert!(text.contains("- Days traveled: 4\n"));
+    assert!(text.contains("- Hottest day: 36°C on 2025-07-11 in Rome\n"));
+    assert!(text.contains("- Coldest night: 18°C on 2025-07-13 in Milan\n"));
+    assert!(text.contains("- Rainiest trip: Milan (2025-07-12 → 2025-07-13), 20 mm\n"));
+    assert!(text.contains("| Rome | 2025-07-10 → 2025-07-12 | 3 | 36°C | 21°C | 2 mm |\n"));
+
+    let linked = report(2025, &trips[..1], Units::Celsius, true);
+    assert!(linked.contains("| [Rome](obsidian://open?path=rome.md) | 2025-07-10"));
+}
+
+#[test]
+fn report_without_trips_says_so() {
+    assert_eq!(
+        report(2024, &[], Units::Fahrenheit, false),
+        "# Travel weather 2024\n\n_No past trips found for this year._\n"
+    );
+}
@@ -0,0 +1,45 @@
+use weather_helper::tags::{parse_tags, report, selected, tag_matches};
+
+fn yaml(text: &str) -> serde_yaml::Value {
+    serde_yaml::from_str(text).unwrap()
+}
+
+#[test]
+fn tags_come_as_lists_or_strings() {
+    assert_eq!(
+        parse_tags(&yaml("tags: [work-travel, '#conference']")),
+        vec!["work-travel", "conference"]
+    );
+    assert_eq!(
+        parse_tags(&yaml("tags: '#work, personal'")),
+        vec!["work", "personal"]
+    );
+    assert_eq!(parse_tags(&yaml("tag: family")), vec!["family"]);
+    assert!(parse_tags(&yaml("city: Rome")).is_empty());
+}
+
+#[test]
+fn filters_match_case_insensitively_and_nested_tags() {
+    assert!(tag_matches("Work/Conference", "work"));
+    assert!(tag_matches("work", "#WORK"));
+    assert!(!tag_matches("workshop", "work"));
+    assert!(selected(&[], &[]));
+    assert!(!selected(&[], &["work".into()]));
+    assert!(selected(
+        &["family".into(), "work".into()],
+        &["work".into()]
+    ));
+}
+
+#[test]
+fn report_groups_outcomes_by_tag() {
+    let outcomes = vec![
+        (vec!["work".to_string()], true),
+        (vec!["work".to_string(), "Rome".to_string()], false),
+        (vec![], true),
+    ];
+    assert_eq!(
+        report(&outcomes),
+        "By tag:\n  rome: 0 updated, 1 failed\n  work: 1 updated, 1 failed\n  (untagged): 1 updated\n"
+    );
+}
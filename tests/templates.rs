@@ -0,0 +1,48 @@
+//! Template rendering tests
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use weather_helper::templates::{list_templates, new_note, render_template};
+
+fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn substitutes_placeholders() {
+    let out = render_template(
+        "---\ncity: {{city}}\narrival: {{ arrival }}\n---\n",
+        &vars(&[("city", "Rome"), ("arrival", "2025-08-20")]),
+    )
+    .unwrap();
+    assert_eq!(out, "---\ncity: Rome\narrival: 2025-08-20\n---\n");
+}
+
+#[test]
+fn undefined_placeholders_error() {
+    let err = render_template("{{city}} {{hotel}}", &vars(&[("city", "Rome")])).unwrap_err();
+    assert!(err.to_string().contains("hotel"));
+}
+
+#[test]
+fn new_note_from_directory() {
+    let dir = std::env::temp_dir().join(format!("wh_templates_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("business-trip.md"),
+        "# {{city}}\n\n## Weather Forecast\n<!-- WEATHER:BEGIN -->\n<!-- WEATHER:END -->\n",
+    )
+    .unwrap();
+    assert_eq!(list_templates(&dir).unwrap(), vec!["business-trip"]);
+
+    let out = dir.join("Milan.md");
+    new_note(&dir, "business-trip", &vars(&[("city", "Milan")]), &out).unwrap();
+    assert!(fs::read_to_string(&out).unwrap().starts_with("# Milan\n"));
+    // Existing notes are never overwritten.
+    assert!(new_note(&dir, "business-trip", &vars(&[("city", "Milan")]), &out).is_err());
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,26 @@
+use weather_helper::terminal::{plain, section, table};
+
+#[test]
+fn table_pads_by_display_width_and_rules_the_header() {
+    let rows = vec![
+        vec!["東京".to_string(), "7".to_string()],
+        vec!["Oslo".to_string(), "-12".to_string()],
+    ];
+    let out = table(&["City", "Max"], &rows, &[false, true]);
+    assert_eq!(out, "City  Max\n────  ───\n東京    7\nOslo  -12\n");
+}
+
+#[test]
+fn plain_drops_markdown_emphasis() {
+    assert_eq!(plain("**Packing**: umbrella"), "Packing: umbrella");
+    assert_eq!(plain("_Historical proxy_"), "Historical proxy");
+}
+
+#[test]
+fn section_relays_markdown_tables() {
+    let md = "**Holidays**\n| Date | Name |\n|---|:---|\n| 2025-05-01 | Labour Day |\n";
+    assert_eq!(
+        section(md),
+        "Holidays\nDate        Name\n──────────  ──────────\n2025-05-01  Labour Day\n"
+    );
+}
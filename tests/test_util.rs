@@ -0,0 +1,205 @@
+//! End-to-end runs against the embedded mock (only with `--features test-util`)
+#![cfg(feature = "test-util")]
+
+use chrono::{Datelike, Days, Local};
+use weather_helper::test_util::{MockResponse, MockServer};
+use weather_helper::{NoteOutcome, RunOptions, telemetry::Verbosity};
+
+#[tokio::test]
+async fn a_run_against_the_mock_updates_notes_and_reports_failures() {
+    let mock = MockServer::start().await.unwrap();
+    let dir = std::env::temp_dir().join(format!("wh-test-util-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // SAFETY: the only test in this binary; nothing else touches the
+    // environment.
+    unsafe {
+        mock.install_env();
+        std::env::set_var("WEATHER_HELPER_USAGE_FILE", dir.join("usage.json"));
+        std::env::set_var("WEATHER_HELPER_CACHE_DIR", dir.join("cache"));
+    }
+    let today = Local::now().date_naive();
+    let note = |city: &str| {
+        let (arrival, departure) = (today + Days::new(3), today + Days::new(5));
+        format!("---\ncity: {city}\narrival: {arrival}\ndeparture: {departure}\n---\n# {city}\n")
+    };
+    std::fs::write(dir.join("rome.md"), note("Rome")).unwrap();
+    std::fs::write(dir.join("nowhere.md"), note("Nowhere")).unwrap();
+    // The geocoder retries a normalized "nowhere" before giving up.
+    mock.on_fn("/search", |req| match req.param("name") {
+        Some(name) if name.eq_ignore_ascii_case("nowhere") => {
+            MockResponse::json(r#"{"results":[]}"#)
+        }
+        _ => weather_helper::test_util::canned(req),
+    });
+
+    let opts = RunOptions {
+        verbosity: Verbosity::Quiet,
+        ..RunOptions::default()
+    };
+    let report = weather_helper::run(&dir.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    let outcome = |name: &str| {
+        report
+            .notes
+            .iter()
+            .find(|n| n.path.ends_with(name))
+            .map(|n| n.outcome.clone())
+    };
+    assert_eq!(outcome("rome.md"), Some(NoteOutcome::Updated));
+    assert!(matches!(
+        outcome("nowhere.md"),
+        Some(NoteOutcome::Failed(_))
+    ));
+    let rome = std::fs::read_to_string(dir.join("rome.md")).unwrap();
+    assert!(rome.contains("<!-- WEATHER:BEGIN v2 -->"), "{rome}");
+
+    let requests = mock.requests();
+    assert!(requests.iter().any(|r| r.path.ends_with("/forecast")));
+    assert!(
+        requests
+            .iter()
+            .any(|r| r.param("name").as_deref() == Some("Nowhere"))
+    );
+
+    // A request the API rejects leaves a note with earlier data as it was;
+    // a server error marks that data as stale.
+    let failing = dir.join("failing");
+    std::fs::create_dir_all(&failing).unwrap();
+    std::fs::write(failing.join("rome.md"), &rome).unwrap();
+    mock.on("/forecast", MockResponse::error(400, "invalid date range"));
+    let report = weather_helper::run(&failing.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    let NoteOutcome::Failed(error) = &report.notes[0].outcome else {
+        panic!("{:?}", report.notes[0].outcome);
+    };
+    assert!(
+        error.contains("HTTP 400 Bad Request, API said: 'invalid date range'"),
+        "{error}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(failing.join("rome.md")).unwrap(),
+        rome
+    );
+    // Payloads the API should never send are described in the report.
+    for (body, diagnostic) in [
+        ("<html>busy</html>", "response is not valid JSON"),
+        (r#"{"daily": 5}"#, "unexpected payload shape"),
+    ] {
+        mock.on("/forecast", MockResponse::json(body));
+        let report = weather_helper::run(&failing.to_string_lossy(), &opts)
+            .await
+            .unwrap();
+        let NoteOutcome::Failed(error) = &report.notes[0].outcome else {
+            panic!("{:?}", report.notes[0].outcome);
+        };
+        assert!(error.contains("bad response from"), "{error}");
+        assert!(error.contains(diagnostic), "{error}");
+    }
+    mock.on(
+        "/forecast",
+        MockResponse::error(503, "down for maintenance"),
+    );
+    let report = weather_helper::run(&failing.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    assert!(matches!(report.notes[0].outcome, NoteOutcome::Failed(_)));
+    let stale = std::fs::read_to_string(failing.join("rome.md")).unwrap();
+    assert!(stale.contains("the latest update failed"), "{stale}");
+    let meta = weather_helper::extract_meta(&failing.join("rome.md")).unwrap();
+    let client = opts.client().unwrap();
+    let outcome = weather_helper::process_note(&meta, &client, &opts).await;
+    let NoteOutcome::Failed(error) = outcome else {
+        panic!("{outcome:?}");
+    };
+    assert!(error.starts_with("request failed: "), "{error}");
+    assert!(
+        error.contains("API said: 'down for maintenance'"),
+        "{error}"
+    );
+
+    // Last year's proxy walks back past a year the archive fails for.
+    let span = (today + Days::new(3), today + Days::new(5));
+    let year = span.0.year();
+    mock.on_fn("/era5", move |req| match req.date("start_date") {
+        Some(start) if start.year() == year => MockResponse::error(400, "no such year"),
+        _ => weather_helper::test_util::canned(req),
+    });
+    let client = weather_helper::http::Client::new(None).unwrap();
+    let (data, proxied) = weather_helper::fetch_proxy(&client, (41.9, 12.5), span, "Europe/Rome")
+        .await
+        .unwrap();
+    assert_eq!(proxied.0.year(), year - 1);
+    assert!(!data.is_empty());
+    mock.on("/era5", MockResponse::error(400, "no such year"));
+    let err = weather_helper::fetch_proxy(&client, (41.9, 12.5), span, "Europe/Rome")
+        .await
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("no such year"), "{err:#}");
+
+    // A forecast still pending at the deadline leaves the note untouched.
+    let slow = dir.join("slow");
+    std::fs::create_dir_all(&slow).unwrap();
+    std::fs::write(slow.join("rome.md"), &rome).unwrap();
+    mock.on(
+        "/forecast",
+        MockResponse::json("{}").delayed(std::time::Duration::from_secs(10)),
+    );
+    let opts = RunOptions {
+        deadline: Some(std::time::Duration::from_millis(500)),
+        ..opts
+    };
+    let report = weather_helper::run(&slow.to_string_lossy(), &opts)
+        .await
+        .unwrap();
+    assert!(report.deadline_reached);
+    assert_eq!(
+        report.notes[0].outcome,
+        NoteOutcome::Skipped("not attempted (deadline reached)".to_string())
+    );
+    assert_eq!(std::fs::read_to_string(slow.join("rome.md")).unwrap(), rome);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn routes_can_be_overridden_and_reset() {
+    let mock = MockServer::start().await.unwrap();
+    let client = weather_helper::http::Client::new(None).unwrap();
+    let url = format!(
+        "{}/v1/era5?start_date=2025-01-01&end_date=2025-01-02",
+        mock.base_url()
+    );
+    mock.on("/era5", MockResponse::error(400, "end_date out of range"));
+    let err = client
+        .get_json::<serde_json::Value>(&url)
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("end_date out of range"),
+        "{err:#}"
+    );
+
+    mock.reset();
+    let body: serde_json::Value = client.get_json(&url).await.unwrap();
+    assert_eq!(body["daily"]["time"][1], "2025-01-02");
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn shared_clients_fetch_each_url_once() {
+    let mock = MockServer::start().await.unwrap();
+    let client = weather_helper::http::Client::new(None)
+        .unwrap()
+        .with_shared(true);
+    let url = |end: &str| {
+        format!(
+            "{}/v1/era5?start_date=2025-01-01&end_date={end}",
+            mock.base_url()
+        )
+    };
+    for end in ["2025-01-02", "2025-01-02", "2025-01-03"] {
+        let _: serde_json::Value = client.clone().get_json(&url(end)).await.unwrap();
+    }
+    assert_eq!(mock.requests().len(), 2);
+}
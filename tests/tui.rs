@@ -0,0 +1,33 @@
+//! Dashboard diffing (only with `--features tui`)
+#![cfg(feature = "tui")]
+
+use weather_helper::tui::{DiffLine, line_diff};
+
+#[test]
+fn diff_shows_changed_lines_with_context() {
+    let old = "a\nb\nc\nold\nd\ne\nf\n";
+    let new = "a\nb\nc\nnew\nextra\nd\ne\nf\n";
+    assert_eq!(
+        line_diff(old, new, 1),
+        vec![
+            DiffLine::Same("c".into()),
+            DiffLine::Removed("old".into()),
+            DiffLine::Added("new".into()),
+            DiffLine::Added("extra".into()),
+            DiffLine::Same("d".into()),
+        ]
+    );
+}
+
+#[test]
+fn identical_text_has_no_diff() {
+    assert!(line_diff("a\nb\n", "a\nb\n", 3).is_empty());
+}
+
+#[test]
+fn appended_block_diffs_against_the_end() {
+    assert_eq!(
+        line_diff("a\n", "a\nb\n", 2),
+        vec![DiffLine::Same("a".into()), DiffLine::Added("b".into())]
+    );
+}
@@ -0,0 +1,92 @@
+use weather_helper::tz::{
+    clock_change_line, clock_changes, fmt_offset, home_difference, mismatch, parse_zone,
+};
+
+fn date(s: &str) -> chrono::NaiveDate {
+    s.parse().unwrap()
+}
+
+#[test]
+fn difference_is_reported_from_home() {
+    let chicago = parse_zone("America/Chicago").unwrap();
+    let rome = parse_zone("Europe/Rome").unwrap();
+    let text = home_difference(Some(chicago), rome, date("2025-07-01"), date("2025-07-05"));
+    assert_eq!(text.as_deref(), Some("+7h ahead of home"));
+
+    let text = home_difference(Some(rome), chicago, date("2025-07-01"), date("2025-07-05"));
+    assert_eq!(text.as_deref(), Some("7h behind home"));
+
+    assert_eq!(
+        home_difference(Some(rome), rome, date("2025-07-01"), date("2025-07-05")),
+        None
+    );
+}
+
+#[test]
+fn dst_change_during_the_trip_is_called_out() {
+    // Europe leaves DST on 2025-10-26, the US a week later.
+    let new_york = parse_zone("America/New_York").unwrap();
+    let london = parse_zone("Europe/London").unwrap();
+    let text = home_difference(
+        Some(new_york),
+        london,
+        date("2025-10-24"),
+        date("2025-11-04"),
+    );
+    assert_eq!(
+        text.as_deref(),
+        Some("+5h ahead of home, +4h from 2025-10-26, +5h from 2025-11-02")
+    );
+}
+
+#[test]
+fn offsets_format_with_minutes() {
+    assert_eq!(fmt_offset(19800), "+5h30m");
+    assert_eq!(fmt_offset(-10800), "-3h");
+    assert!(parse_zone("Mars/Olympus").is_err());
+}
+
+#[test]
+fn clock_changes_during_the_trip_are_found() {
+    let rome = parse_zone("Europe/Rome").unwrap();
+    let changes = clock_changes(rome, date("2025-03-28"), date("2025-04-02"));
+    assert_eq!(changes, vec![(date("2025-03-30"), 3600)]);
+    assert_eq!(
+        clock_change_line(&changes).as_deref(),
+        Some("🕑 _Clocks change Mar 30 (forward 1h) during your trip_")
+    );
+
+    let sydney = parse_zone("Australia/Sydney").unwrap();
+    let year = clock_changes(sydney, date("2025-01-01"), date("2025-12-31"));
+    assert_eq!(
+        clock_change_line(&year).as_deref(),
+        Some("🕑 _Clocks change Apr 6 (back 1h) and Oct 5 (forward 1h) during your trip_")
+    );
+
+    // A change early on the day of arrival happened before the trip.
+    assert!(clock_changes(rome, date("2025-03-30"), date("2025-04-02")).is_empty());
+    assert_eq!(clock_change_line(&[]), None);
+}
+
+#[test]
+fn timezones_that_disagree_with_the_place_are_reported() {
+    let (start, end) = (date("2025-07-01"), date("2025-07-05"));
+    assert_eq!(mismatch("Europe/Vatican", "Europe/Rome", start, end), None);
+    assert_eq!(
+        mismatch("America/New_York", "America/Chicago", start, end).as_deref(),
+        Some(
+            "timezone America/New_York differs from America/Chicago found for the place (+1h during the trip); using America/Chicago"
+        )
+    );
+    // Zones that only disagree outside the trip are fine.
+    let winter = (date("2025-01-10"), date("2025-01-12"));
+    assert_eq!(
+        mismatch("Africa/Lagos", "Europe/Paris", winter.0, winter.1),
+        None
+    );
+    assert!(
+        mismatch("Mars/Olympus", "Europe/Rome", start, end)
+            .unwrap()
+            .contains("not a known zone")
+    );
+}
@@ -0,0 +1,38 @@
+use weather_helper::validate::validate_fixture;
+
+#[test]
+fn recorded_fixture_validates() {
+    let json = std::fs::read("tests/fixtures/forecast_rome.json").unwrap();
+    let report = validate_fixture(&json).unwrap();
+    assert!(report.starts_with("ok: "), "{report}");
+}
+
+#[test]
+fn failures_name_the_field_and_index() {
+    let bad_type = br#"{"daily":{"time":["2025-10-01","2025-10-02"],"temperature_2m_max":[20.0,"hot"],"temperature_2m_min":[10.0,11.0]}}"#;
+    let err = validate_fixture(bad_type).unwrap_err().to_string();
+    assert!(
+        err.starts_with("daily.temperature_2m_max[1]: invalid type"),
+        "{err}"
+    );
+
+    let bad_date = br#"{"daily":{"time":["2025-10-01","01/10/2025"],"temperature_2m_max":[20.0,21.0],"temperature_2m_min":[10.0,11.0]}}"#;
+    let err = validate_fixture(bad_date).unwrap_err().to_string();
+    assert_eq!(err, "daily.time[1]: '01/10/2025' is not a YYYY-MM-DD date");
+
+    let short = br#"{"daily":{"time":["2025-10-01","2025-10-02"],"temperature_2m_max":[20.0],"temperature_2m_min":[10.0,11.0]}}"#;
+    let err = validate_fixture(short).unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "daily.temperature_2m_max: 1 values for 2 days in daily.time"
+    );
+}
+
+#[test]
+fn nulls_are_warnings() {
+    let json = br#"{"daily":{"time":["2025-10-01","2025-10-02"],"temperature_2m_max":[20.0,null],"temperature_2m_min":[10.0,11.0]}}"#;
+    assert_eq!(
+        validate_fixture(json).unwrap(),
+        "ok: 1 day, 2025-10-01 → 2025-10-01\nwarning: daily.temperature_2m_max[1] is null; 2025-10-02 is skipped\n"
+    );
+}
@@ -0,0 +1,37 @@
+use serde_json::json;
+use weather_helper::variables::{Endpoint, restore_names, translate};
+
+#[test]
+fn forecast_only_variables_are_dropped_for_the_archive() {
+    let t = translate(&["temperature_2m_max", "uv_index_max"], Endpoint::Archive);
+    assert_eq!(t.query(), "temperature_2m_max");
+    assert_eq!(t.unsupported, vec!["uv_index_max"]);
+    let t = translate(&["temperature_2m_max", "uv_index_max"], Endpoint::Forecast);
+    assert_eq!(t.query(), "temperature_2m_max,uv_index_max");
+    assert!(t.unsupported.is_empty());
+}
+
+#[test]
+fn legacy_names_are_sent_as_current_ones_and_renamed_back() {
+    let t = translate(&["weathercode", "made_up_variable"], Endpoint::Archive);
+    assert_eq!(t.query(), "weather_code,made_up_variable");
+    let mut resp =
+        json!({"daily": {"time": ["2025-08-01"], "weather_code": [3], "made_up_variable": [1]}});
+    restore_names(&mut resp, &t);
+    assert_eq!(resp["daily"]["weathercode"], json!([3]));
+    assert!(resp["daily"].get("weather_code").is_none());
+}
+
+#[test]
+fn unsupported_variables_read_as_missing_data() {
+    let t = translate(
+        &["temperature_2m_max", "precipitation_probability_max"],
+        Endpoint::Archive,
+    );
+    let mut resp = json!({"daily": {"time": ["2025-08-01", "2025-08-02"], "temperature_2m_max": [30.0, 31.0]}});
+    restore_names(&mut resp, &t);
+    assert_eq!(
+        resp["daily"]["precipitation_probability_max"],
+        json!([null, null])
+    );
+}
@@ -0,0 +1,49 @@
+use weather_helper::geocoder::normalize_query;
+use weather_helper::width::{align_tables, display_width, isolate, pad};
+use weather_helper::{DayTemp, previous_temps, render_table_with_notes};
+
+#[test]
+fn width_counts_columns_not_bytes() {
+    assert_eq!(display_width("東京"), 4);
+    assert_eq!(display_width("תל אביב"), 7);
+    assert_eq!(display_width("Forlì"), 5);
+    assert_eq!(pad("東京", 6, true), "東京  ");
+    assert_eq!(pad("7", 3, false), "  7");
+}
+
+#[test]
+fn rtl_names_are_isolated() {
+    assert_eq!(isolate("תל אביב"), "\u{2068}תל אביב\u{2069}");
+    assert_eq!(isolate("東京"), "東京");
+}
+
+#[test]
+fn tables_align_by_display_width() {
+    let text = "**Along the route**\n\n| Waypoint | High |\n|---|---:|\n| 東京 | 12°C |\n| Tel Aviv | 9°C |\ntrailing\n";
+    assert_eq!(
+        align_tables(text),
+        "**Along the route**\n\n| Waypoint | High |\n| -------- | ---: |\n| 東京     | 12°C |\n| Tel Aviv |  9°C |\ntrailing\n"
+    );
+}
+
+#[test]
+fn aligned_tables_still_feed_trends() {
+    let data = vec![DayTemp {
+        date: "2025-10-01".parse().unwrap(),
+        tmax: 5.0,
+        tmin: -3.0,
+        tmax_f: 41.0,
+        tmin_f: 26.6,
+        ..Default::default()
+    }];
+    let table = align_tables(&render_table_with_notes(&data, &Default::default()));
+    let block = format!("<!-- WEATHER:BEGIN -->\n{table}<!-- WEATHER:END -->");
+    let previous = previous_temps(&block);
+    assert_eq!(previous.values().next().unwrap().tmin, -3.0);
+}
+
+#[test]
+fn pointed_hebrew_normalizes_to_whole_words() {
+    assert_eq!(normalize_query("תֵּל אָבִיב"), "תל אביב");
+    assert_eq!(normalize_query("\u{200F}東京"), "東京");
+}
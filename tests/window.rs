@@ -0,0 +1,107 @@
+use chrono::{Duration, NaiveDate};
+use std::collections::BTreeMap;
+use std::fs;
+use weather_helper::window::{Score, Window, best, line};
+use weather_helper::{DayTemp, Units};
+
+fn d(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+fn day(date: &str, tmax: f64) -> DayTemp {
+    DayTemp {
+        date: d(date),
+        tmax,
+        tmin: tmax - 10.0,
+        tmax_f: tmax * 1.8 + 32.0,
+        tmin_f: (tmax - 10.0) * 1.8 + 32.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn scores_parse_and_print_canonically() {
+    assert_eq!(Score::parse("dry, mild").unwrap(), Score::default());
+    assert_eq!(Score::parse("dry, mild").unwrap().name(), "dry, mild=22");
+    let warm = Score::parse("mild=77F").unwrap();
+    assert!(!warm.dry);
+    assert_eq!(warm.mild, Some(25.0));
+    assert_eq!(Score::parse("dry").unwrap().mild, None);
+    assert!(Score::parse("sunny").is_err());
+    assert!(Score::parse("mild=warm").is_err());
+    assert!(Score::parse("").is_err());
+}
+
+#[test]
+fn best_window_prefers_dry_mild_days() {
+    let data = [
+        day("2025-09-01", 30.0),
+        day("2025-09-02", 23.0),
+        day("2025-09-03", 22.0),
+        day("2025-09-04", 21.0),
+        day("2025-09-05", 22.0),
+    ];
+    let rain = BTreeMap::from([(d("2025-09-03"), 25.0), (d("2025-09-04"), 0.5)]);
+
+    // Mild alone picks the days closest to 22°C, rain or not.
+    let mild = Score::parse("mild").unwrap();
+    let w = best(&data, &rain, 2, &mild).unwrap();
+    assert_eq!((w.start, w.end), (d("2025-09-02"), d("2025-09-03")));
+
+    // A downpour counts as 10 mm at most, still enough to move the window.
+    let w = best(&data, &rain, 2, &Score::default()).unwrap();
+    assert_eq!((w.start, w.end), (d("2025-09-04"), d("2025-09-05")));
+    assert_eq!(w.rain_mm, Some(0.5));
+    assert_eq!(w.highs, (21.0, 22.0));
+
+    assert_eq!(best(&data, &rain, 6, &Score::default()), None);
+}
+
+#[test]
+fn the_recommendation_line_names_the_days() {
+    let w = Window {
+        start: d("2024-09-04"),
+        end: d("2024-09-05"),
+        rain_mm: Some(0.5),
+        highs: (21.0, 22.0),
+    };
+    assert_eq!(
+        line(Some(&w), 2, 5, Duration::zero(), Units::Celsius),
+        "**Best window**: 2024-09-04 → 2024-09-05 (0.5 mm rain, highs 21–22°C)"
+    );
+    assert_eq!(
+        line(Some(&w), 2, 5, Duration::days(365), Units::Fahrenheit),
+        "**Best window**: 2025-09-04 → 2025-09-05 (0.5 mm rain, highs 70–72°F, going by last year)"
+    );
+    assert_eq!(
+        line(None, 7, 3, Duration::zero(), Units::Celsius),
+        "**Best window**: only 3 of the 7 days needed have weather data yet"
+    );
+}
+
+#[test]
+fn flexible_notes_use_their_window() {
+    let dir = std::env::temp_dir().join(format!("wh-window-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Lisbon.md");
+    fs::write(
+        &path,
+        "---\ncity: Lisbon\nflexible: true\nwindow-start: 2025-09-01\nwindow-end: 2025-09-20\nestimated-days: 5\n---\n",
+    )
+    .unwrap();
+    let meta = weather_helper::extract_meta(&path).unwrap();
+    assert_eq!(meta.flexible, Some(5));
+    assert_eq!(
+        (meta.arrival, meta.departure),
+        (d("2025-09-01"), d("2025-09-20"))
+    );
+
+    fs::write(
+        &path,
+        "---\ncity: Lisbon\nflexible: true\nwindow-start: 2025-09-01\nwindow-end: 2025-09-20\n---\n",
+    )
+    .unwrap();
+    let err = weather_helper::extract_meta(&path).unwrap_err();
+    assert!(err.to_string().contains("estimated-days"), "{err}");
+    let _ = fs::remove_dir_all(&dir);
+}